@@ -1,7 +1,7 @@
 use criterion::{
     criterion_group, criterion_main, AxisScale, BenchmarkId, Criterion, PlotConfiguration,
 };
-use delaunator::{Point, Triangulation};
+use delaunator::{builder::TriangulationBuilder, Point, Triangulation};
 use rand::{Rng, SeedableRng};
 use rand_xorshift::XorShiftRng;
 use std::iter::repeat_with;
@@ -30,6 +30,22 @@ fn bench(c: &mut Criterion) {
         .plot_config(PlotConfiguration::default().summary_scale(AxisScale::Logarithmic));
 
     group.finish();
+
+    let mut group = c.benchmark_group("hull_only");
+
+    for &count in COUNTS {
+        group.bench_function(BenchmarkId::from_parameter(count), |b| {
+            let points = &all_points[..count];
+            let builder = TriangulationBuilder::new().hull_only(true);
+            b.iter(move || builder.build::<u32, _, _>(points))
+        });
+    }
+
+    group
+        .sample_size(20)
+        .plot_config(PlotConfiguration::default().summary_scale(AxisScale::Logarithmic));
+
+    group.finish();
 }
 
 criterion_group!(benches, bench);
@@ -0,0 +1,190 @@
+//! Steps through the construction of a triangulation, one point at a time.
+//!
+//!     cargo run --example debugger --features debugger -- tests/fixtures/ukraine.json
+//!
+//! The fixture is a JSON array of `[x, y]` pairs, like the test fixtures.
+//! Right and left arrows step forward and back, Home and End jump to the seed
+//! triangle and the finished triangulation, Space plays and pauses, and Escape
+//! quits. The point of the current step is drawn in red (green if it was
+//! skipped), the hull in yellow, and points that are still to come in gray.
+
+use delaunator::{
+    builder::TriangulationBuilder,
+    trace::{Event, PointStatus, Replay, Step},
+    Point, Triangulation,
+};
+use minifb::{Key, KeyRepeat, Window, WindowOptions};
+
+const SIZE: usize = 800;
+const MARGIN: f64 = 20.0;
+
+const BACKGROUND: u32 = 0x20_20_20;
+const EDGE: u32 = 0x70_70_70;
+const HULL: u32 = 0xe0_c0_40;
+const DONE: u32 = 0xf0_f0_f0;
+const PENDING: u32 = 0x50_50_50;
+const CURRENT: u32 = 0xff_40_40;
+const SKIPPED: u32 = 0x40_ff_40;
+
+fn main() {
+    let path = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "tests/fixtures/ukraine.json".to_string());
+    let json = std::fs::read_to_string(&path).expect("failed to read the fixture");
+    let coords: Vec<(f64, f64)> = serde_json::from_str(&json).expect("malformed fixture");
+    let points: Vec<Point<f64>> = coords.into_iter().map(|(x, y)| Point { x, y }).collect();
+
+    let (_, steps): (Triangulation<usize>, _) = TriangulationBuilder::new()
+        .build_with_steps(&points)
+        .expect("No triangulation exists for this input.");
+
+    let screen = Screen::fit(&points);
+    let mut replay = Replay::new(&steps);
+    let mut window = Window::new("delaunator debugger", SIZE, SIZE, WindowOptions::default())
+        .expect("failed to open a window");
+    window.set_target_fps(60);
+
+    let mut buffer = vec![BACKGROUND; SIZE * SIZE];
+    let (mut current, mut playing, mut drawn) = (0, false, None);
+    while window.is_open() && !window.is_key_down(Key::Escape) {
+        for key in window.get_keys_pressed(KeyRepeat::Yes) {
+            match key {
+                Key::Right => current = (current + 1).min(steps.len() - 1),
+                Key::Left => current = current.saturating_sub(1),
+                Key::Home => current = 0,
+                Key::End => current = steps.len() - 1,
+                Key::Space => playing = !playing,
+                _ => {}
+            }
+        }
+        if playing {
+            current = (current + 1).min(steps.len() - 1);
+            playing = current + 1 < steps.len();
+        }
+
+        if drawn != Some(current) {
+            replay.seek(current);
+            draw(&mut buffer, &screen, &points, &steps, &replay, current);
+            window.set_title(&title(&steps[current], &replay, current, steps.len()));
+            drawn = Some(current);
+        }
+        window
+            .update_with_buffer(&buffer, SIZE, SIZE)
+            .expect("failed to draw the window");
+    }
+}
+
+fn title(step: &Step, replay: &Replay, current: usize, len: usize) -> String {
+    let event = match step.event() {
+        Event::Seed(a, b, c) => format!("seed triangle {} {} {}", a, b, c),
+        Event::Point(i, status) => format!("point {}: {:?}", i, status),
+        event => format!("{:?}", event),
+    };
+    format!(
+        "step {}/{}: {}, {} flips, {} triangles",
+        current,
+        len - 1,
+        event,
+        step.flips(),
+        replay.triangles().len() / 3
+    )
+}
+
+fn draw(
+    buffer: &mut [u32],
+    screen: &Screen,
+    points: &[Point<f64>],
+    steps: &[Step],
+    replay: &Replay,
+    current: usize,
+) {
+    buffer.fill(BACKGROUND);
+    let step = &steps[current];
+
+    // points seen so far are done, the rest are still to come
+    let mut done = vec![false; points.len()];
+    for step in &steps[..=current] {
+        match step.event() {
+            Event::Seed(a, b, c) => [a, b, c].iter().for_each(|&i| done[i] = true),
+            Event::Point(i, _) => done[i] = true,
+            _ => {}
+        }
+    }
+    for (i, &p) in points.iter().enumerate() {
+        let color = if done[i] { DONE } else { PENDING };
+        screen.dot(buffer, p, 1, color);
+    }
+
+    for t in replay.triangles().chunks_exact(3) {
+        for k in 0..3 {
+            screen.line(buffer, points[t[k]], points[t[(k + 1) % 3]], EDGE);
+        }
+    }
+    for (a, b) in replay.hull() {
+        screen.line(buffer, points[a], points[b], HULL);
+    }
+
+    match step.event() {
+        Event::Seed(a, b, c) => [a, b, c]
+            .iter()
+            .for_each(|&i| screen.dot(buffer, points[i], 3, CURRENT)),
+        Event::Point(i, PointStatus::Inserted) | Event::Point(i, PointStatus::Seed) => {
+            screen.dot(buffer, points[i], 3, CURRENT)
+        }
+        Event::Point(i, _) => screen.dot(buffer, points[i], 3, SKIPPED),
+        _ => {}
+    }
+}
+
+/// Maps points to pixels, keeping the aspect ratio.
+struct Screen {
+    min: Point<f64>,
+    scale: f64,
+}
+
+impl Screen {
+    fn fit(points: &[Point<f64>]) -> Self {
+        let (mut min, mut max) = (points[0], points[0]);
+        for p in points {
+            min = Point::new(min.x.min(p.x), min.y.min(p.y));
+            max = Point::new(max.x.max(p.x), max.y.max(p.y));
+        }
+        let extent = (max.x - min.x).max(max.y - min.y).max(f64::MIN_POSITIVE);
+        Screen {
+            min,
+            scale: (SIZE as f64 - 2.0 * MARGIN) / extent,
+        }
+    }
+
+    /// The pixel of `p`, with y pointing up.
+    fn pixel(&self, p: Point<f64>) -> (f64, f64) {
+        let x = MARGIN + (p.x - self.min.x) * self.scale;
+        let y = SIZE as f64 - MARGIN - (p.y - self.min.y) * self.scale;
+        (x, y)
+    }
+
+    fn set(buffer: &mut [u32], x: i64, y: i64, color: u32) {
+        if (0..SIZE as i64).contains(&x) && (0..SIZE as i64).contains(&y) {
+            buffer[y as usize * SIZE + x as usize] = color;
+        }
+    }
+
+    fn dot(&self, buffer: &mut [u32], p: Point<f64>, radius: i64, color: u32) {
+        let (x, y) = self.pixel(p);
+        for dy in -radius..=radius {
+            for dx in -radius..=radius {
+                Self::set(buffer, x as i64 + dx, y as i64 + dy, color);
+            }
+        }
+    }
+
+    fn line(&self, buffer: &mut [u32], a: Point<f64>, b: Point<f64>, color: u32) {
+        let ((x0, y0), (x1, y1)) = (self.pixel(a), self.pixel(b));
+        let steps = (x1 - x0).abs().max((y1 - y0).abs()).ceil().max(1.0) as usize;
+        for s in 0..=steps {
+            let t = s as f64 / steps as f64;
+            let (x, y) = (x0 + (x1 - x0) * t, y0 + (y1 - y0) * t);
+            Self::set(buffer, x.round() as i64, y.round() as i64, color);
+        }
+    }
+}
@@ -0,0 +1,155 @@
+//! The alpha complex filtration of a point set, for topological data analysis.
+//!
+//! Every simplex of the Delaunay triangulation enters the alpha complex at a
+//! filtration value: the squared radius of the smallest empty circle through
+//! its vertices. Persistent homology libraries build persistence diagrams from
+//! the simplices in order of these values.
+//!
+//! ```rust
+//! use delaunator::{alpha::{self, Simplex}, Point, Triangulation};
+//!
+//! let points = vec![
+//!     Point { x: 0., y: 0. },
+//!     Point { x: 2., y: 0. },
+//!     Point { x: 1., y: 1. },
+//! ];
+//! let triangulation = Triangulation::<usize>::new(&points).unwrap();
+//!
+//! let filtration = alpha::alpha_filtration(&triangulation, &points);
+//! assert_eq!(filtration.triangles, [1.0]);
+//!
+//! let simplices = filtration.simplices(&triangulation);
+//! assert_eq!(simplices.len(), 7);
+//! assert_eq!(simplices.last(), Some(&(Simplex::Triangle(0, 1, 2), 1.0)));
+//! ```
+
+use crate::{
+    traits::{HasPosition, Index, Scalar},
+    util::{next_halfedge, prev_halfedge},
+    Point, Triangulation,
+};
+
+/// The alpha filtration values of all simplices of a [Triangulation], as
+/// squared radii.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AlphaFiltration<T> {
+    /// The value of every point, zero for points of the triangulation and
+    /// infinity for points that aren't part of it.
+    pub vertices: Vec<T>,
+    /// The value of every edge, indexed by half-edge id; both half-edges of an
+    /// edge have the same value.
+    pub halfedges: Vec<T>,
+    /// The value of every triangle, its squared circumradius, indexed by
+    /// triangle id.
+    pub triangles: Vec<T>,
+}
+
+/// A simplex of a triangulation, by its point indices.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Simplex {
+    /// A point.
+    Vertex(usize),
+    /// An edge, with the lower point index first.
+    Edge(usize, usize),
+    /// A triangle, counter-clockwise starting at its lowest point index.
+    Triangle(usize, usize, usize),
+}
+
+/// Computes the alpha filtration value of every simplex of `triangulation`.
+///
+/// A triangle's value is its squared circumradius. An edge's value is its
+/// squared half-length if its diametral circle is empty; otherwise it is
+/// attached to the triangle whose opposite point lies in that circle and
+/// enters with the smallest such triangle.
+pub fn alpha_filtration<T: Scalar, I: Index, P: HasPosition<T>>(
+    triangulation: &Triangulation<I>,
+    points: &[P],
+) -> AlphaFiltration<T> {
+    let zero = T::from_f64(0.0);
+    let pos = |e: usize| points[triangulation.triangles[e].as_usize()].pos();
+
+    let triangles: Vec<T> = (0..triangulation.len())
+        .map(|t| pos(3 * t).circumradius_squared(pos(3 * t + 1), pos(3 * t + 2)))
+        .collect();
+
+    let mut vertices = vec![T::infinity(); points.len()];
+    for &i in &triangulation.triangles {
+        vertices[i.as_usize()] = zero;
+    }
+
+    let halfedges = (0..triangulation.halfedges.len())
+        .map(|e| {
+            let (a, b) = (pos(e), pos(next_halfedge(e)));
+            // the opposite point is inside the diametral circle if it sees
+            // the edge at an obtuse angle
+            let attached = |f: usize| {
+                let c = pos(prev_halfedge(f));
+                let (u, v) = (a - c, b - c);
+                u.x * v.x + u.y * v.y < zero
+            };
+            let twin = triangulation.halfedges[e].get().map(I::as_usize);
+            let value = [Some(e), twin]
+                .iter()
+                .flatten()
+                .filter(|&&f| attached(f))
+                .map(|&f| triangles[f / 3])
+                .fold(None, |min: Option<T>, v| Some(min.map_or(v, |m| m.min(v))));
+            value.unwrap_or_else(|| {
+                let half = Point::new(
+                    (b.x - a.x) / T::from_f64(2.0),
+                    (b.y - a.y) / T::from_f64(2.0),
+                );
+                // no larger than the triangles around it, even after rounding
+                [Some(e), twin]
+                    .iter()
+                    .flatten()
+                    .fold(half.length_squared(), |v, &f| v.min(triangles[f / 3]))
+            })
+        })
+        .collect();
+
+    AlphaFiltration {
+        vertices,
+        halfedges,
+        triangles,
+    }
+}
+
+impl<T: Scalar> AlphaFiltration<T> {
+    /// Every simplex of `triangulation` with its value, in filtration order:
+    /// by increasing value, and faces before the simplices they bound.
+    pub fn simplices<I: Index>(&self, triangulation: &Triangulation<I>) -> Vec<(Simplex, T)> {
+        let point = |e: usize| triangulation.triangles[e].as_usize();
+        let mut simplices: Vec<(Simplex, T)> = Vec::new();
+        simplices.extend(
+            self.vertices
+                .iter()
+                .enumerate()
+                .filter(|&(_, &v)| v < T::infinity())
+                .map(|(i, &v)| (Simplex::Vertex(i), v)),
+        );
+        simplices.extend(triangulation.unique_edges().map(|e| {
+            let (a, b) = (point(e), point(next_halfedge(e)));
+            (Simplex::Edge(a.min(b), a.max(b)), self.halfedges[e])
+        }));
+        simplices.extend((0..triangulation.len()).map(|t| {
+            let [a, b, c] = [point(3 * t), point(3 * t + 1), point(3 * t + 2)];
+            let triangle = if a < b && a < c {
+                Simplex::Triangle(a, b, c)
+            } else if b < c {
+                Simplex::Triangle(b, c, a)
+            } else {
+                Simplex::Triangle(c, a, b)
+            };
+            (triangle, self.triangles[t])
+        }));
+
+        let dimension = |s: &Simplex| match s {
+            Simplex::Vertex(_) => 0,
+            Simplex::Edge(..) => 1,
+            Simplex::Triangle(..) => 2,
+        };
+        simplices.sort_by(|(s, v), (r, w)| v.total_cmp(w).then(dimension(s).cmp(&dimension(r))));
+        simplices
+    }
+}
@@ -0,0 +1,13 @@
+use arbitrary::{size_hint, Arbitrary, Result, Unstructured};
+
+use crate::{traits::Scalar, Point};
+
+impl<'a, T: Scalar + Arbitrary<'a>> Arbitrary<'a> for Point<T> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Point::new(T::arbitrary(u)?, T::arbitrary(u)?))
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        size_hint::and(T::size_hint(depth), T::size_hint(depth))
+    }
+}
@@ -0,0 +1,245 @@
+//! Per-point data that follows a triangulation as new points are inserted.
+//!
+//! Points added by splitting edges and triangles, e.g. Steiner points during
+//! refinement, have no data of their own. [Attributes] interpolates the data of
+//! the edge or triangle a new point was inserted into, with any type that
+//! implements [Lerp], and remembers that [Parent] simplex for every new point.
+//!
+//! ```rust
+//! use delaunator::{attributes::{Attributes, Parent}, Point, Triangulation};
+//!
+//! let mut points = vec![
+//!     Point { x: 0., y: 0. },
+//!     Point { x: 2., y: 0. },
+//!     Point { x: 0., y: 2. },
+//! ];
+//! let mut triangulation = Triangulation::<usize>::new(&points).unwrap();
+//! let mut heights = Attributes::new(vec![0f64, 3., 6.]);
+//!
+//! // insert the centroid
+//! points.push(Point { x: 2. / 3., y: 2. / 3. });
+//! let mut edit = triangulation.edit();
+//! heights.split_triangle(&mut edit, &points, 0, 3).unwrap();
+//!
+//! assert!((heights.values[3] - 3.).abs() < 1e-12);
+//! assert!(matches!(heights.parents()[0], (3, Parent::Triangle { .. })));
+//! ```
+
+use crate::{
+    edit::{EditError, TriangulationMut},
+    traits::{HasPosition, Index, Scalar},
+    Point,
+};
+
+/// Data that can be interpolated linearly, such as heights, colors or weights.
+pub trait Lerp: Sized {
+    /// The value a fraction `t` of the way from `self` to `other`.
+    fn lerp(&self, other: &Self, t: f64) -> Self;
+
+    /// The weighted combination of `a`, `b` and `c`, with barycentric
+    /// `weights` that sum to one.
+    ///
+    /// The default combines two [lerp](Self::lerp)s; types with a cheaper
+    /// direct formula can override it.
+    fn barycentric(a: &Self, b: &Self, c: &Self, weights: [f64; 3]) -> Self {
+        let [wa, wb, wc] = weights;
+        let ab = if wa + wb == 0.0 {
+            a.lerp(b, 0.5)
+        } else {
+            a.lerp(b, wb / (wa + wb))
+        };
+        ab.lerp(c, wc)
+    }
+}
+
+impl Lerp for f64 {
+    fn lerp(&self, other: &Self, t: f64) -> Self {
+        self + (other - self) * t
+    }
+
+    fn barycentric(a: &Self, b: &Self, c: &Self, [wa, wb, wc]: [f64; 3]) -> Self {
+        a * wa + b * wb + c * wc
+    }
+}
+
+impl Lerp for f32 {
+    fn lerp(&self, other: &Self, t: f64) -> Self {
+        (*self as f64).lerp(&(*other as f64), t) as f32
+    }
+}
+
+impl<T: Scalar> Lerp for Point<T> {
+    fn lerp(&self, other: &Self, t: f64) -> Self {
+        *self + (*other - *self) * T::from_f64(t)
+    }
+}
+
+impl<A: Lerp, const N: usize> Lerp for [A; N] {
+    fn lerp(&self, other: &Self, t: f64) -> Self {
+        std::array::from_fn(|k| self[k].lerp(&other[k], t))
+    }
+
+    fn barycentric(a: &Self, b: &Self, c: &Self, weights: [f64; 3]) -> Self {
+        std::array::from_fn(|k| A::barycentric(&a[k], &b[k], &c[k], weights))
+    }
+}
+
+impl<A: Lerp, B: Lerp> Lerp for (A, B) {
+    fn lerp(&self, other: &Self, t: f64) -> Self {
+        (self.0.lerp(&other.0, t), self.1.lerp(&other.1, t))
+    }
+
+    fn barycentric(a: &Self, b: &Self, c: &Self, weights: [f64; 3]) -> Self {
+        (
+            A::barycentric(&a.0, &b.0, &c.0, weights),
+            B::barycentric(&a.1, &b.1, &c.1, weights),
+        )
+    }
+}
+
+/// The simplex a new point was inserted into, with the position of the point
+/// relative to it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Parent {
+    /// On the edge from point `a` to point `b`, a fraction `t` of the way
+    /// along it.
+    Edge { a: usize, b: usize, t: f64 },
+    /// Inside the triangle with the points `corners`, with barycentric
+    /// `weights` that sum to one.
+    Triangle {
+        corners: [usize; 3],
+        weights: [f64; 3],
+    },
+}
+
+impl Parent {
+    /// The edge from `a` to `b` as the parent of point `m`, which is projected
+    /// onto the edge.
+    pub fn edge<T: Scalar, P: HasPosition<T>>(points: &[P], a: usize, b: usize, m: usize) -> Self {
+        let [pa, pb, pm] = [a, b, m].map(|i| points[i].pos());
+        let d = pb - pa;
+        let len = d.length_squared().to_f64();
+        let t = if len > 0.0 {
+            let along = pm - pa;
+            ((along.x * d.x + along.y * d.y).to_f64() / len).clamp(0.0, 1.0)
+        } else {
+            0.5
+        };
+        Parent::Edge { a, b, t }
+    }
+
+    /// The triangle with the points `corners` as the parent of point `m`.
+    /// The weights of a point outside the triangle are clamped to the
+    /// triangle, and a degenerate triangle gets equal weights.
+    pub fn triangle<T: Scalar, P: HasPosition<T>>(
+        points: &[P],
+        corners: [usize; 3],
+        m: usize,
+    ) -> Self {
+        let [a, b, c] = corners.map(|i| points[i].pos());
+        let p = points[m].pos();
+        let area = (b - a).perp_dot(c - a).to_f64();
+        let weights = if area != 0.0 {
+            let wa = ((b - p).perp_dot(c - p).to_f64() / area).max(0.0);
+            let wb = ((c - p).perp_dot(a - p).to_f64() / area).max(0.0);
+            let wc = ((a - p).perp_dot(b - p).to_f64() / area).max(0.0);
+            let sum = wa + wb + wc;
+            [wa / sum, wb / sum, wc / sum]
+        } else {
+            [1.0 / 3.0; 3]
+        };
+        Parent::Triangle { corners, weights }
+    }
+
+    /// Interpolates `values`, indexed by point, at the position of the new
+    /// point.
+    pub fn interpolate<A: Lerp>(&self, values: &[A]) -> A {
+        match *self {
+            Parent::Edge { a, b, t } => values[a].lerp(&values[b], t),
+            Parent::Triangle { corners, weights } => {
+                let [a, b, c] = corners;
+                A::barycentric(&values[a], &values[b], &values[c], weights)
+            }
+        }
+    }
+}
+
+/// Data for every point of a triangulation, extended by interpolation as
+/// points are inserted.
+#[derive(Clone, Debug, Default)]
+pub struct Attributes<A> {
+    /// The data of every point, by point index.
+    pub values: Vec<A>,
+    parents: Vec<(usize, Parent)>,
+}
+
+impl<A: Lerp> Attributes<A> {
+    /// Starts with the data of the existing points.
+    pub fn new(values: Vec<A>) -> Self {
+        Self {
+            values,
+            parents: Vec::new(),
+        }
+    }
+
+    /// The new points in the order they were inserted, each with its parent
+    /// simplex.
+    pub fn parents(&self) -> &[(usize, Parent)] {
+        &self.parents
+    }
+
+    /// Records that point `m` was inserted into `parent` by other means than
+    /// the methods below, and interpolates its data.
+    ///
+    /// # Panics
+    /// Panics unless `m` is the next point, i.e. the number of values so far.
+    pub fn record(&mut self, m: usize, parent: Parent) -> &A {
+        assert_eq!(m, self.values.len(), "points must be inserted in order");
+        let value = parent.interpolate(&self.values);
+        self.values.push(value);
+        self.parents.push((m, parent));
+        &self.values[m]
+    }
+
+    /// Splits the edge of half-edge `e` at the new point `m`, see
+    /// [TriangulationMut::split_edge], and interpolates the data of `m` along
+    /// the edge.
+    ///
+    /// # Panics
+    /// Panics unless `m` is the next point, i.e. the number of values so far.
+    pub fn split_edge<I: Index, T: Scalar, P: HasPosition<T>>(
+        &mut self,
+        edit: &mut TriangulationMut<'_, I>,
+        points: &[P],
+        e: usize,
+        m: usize,
+    ) -> Result<usize, EditError> {
+        assert_eq!(m, self.values.len(), "points must be inserted in order");
+        let edge = edit.view().get_half_edge(e).ok_or(EditError::OutOfBounds)?;
+        let (a, b) = (edge.start().id(), edge.end().id());
+        let n = edit.split_edge(e, m)?;
+        self.record(m, Parent::edge(points, a, b, m));
+        Ok(n)
+    }
+
+    /// Splits triangle `t` around the new point `m`, see
+    /// [TriangulationMut::split_triangle], and interpolates the data of `m`
+    /// across the triangle.
+    ///
+    /// # Panics
+    /// Panics unless `m` is the next point, i.e. the number of values so far.
+    pub fn split_triangle<I: Index, T: Scalar, P: HasPosition<T>>(
+        &mut self,
+        edit: &mut TriangulationMut<'_, I>,
+        points: &[P],
+        t: usize,
+        m: usize,
+    ) -> Result<usize, EditError> {
+        assert_eq!(m, self.values.len(), "points must be inserted in order");
+        let triangle = edit.view().get_triangle(t).ok_or(EditError::OutOfBounds)?;
+        let corners = [triangle.a().id(), triangle.b().id(), triangle.c().id()];
+        let e = edit.split_triangle(t, m)?;
+        self.record(m, Parent::triangle(points, corners, m));
+        Ok(e)
+    }
+}
@@ -0,0 +1,397 @@
+//! Options for constructing a [Triangulation].
+
+use std::marker::PhantomData;
+
+use crate::{
+    hull::Scratch,
+    kernel::{FloatKernel, Kernel},
+    observer::StreamingVisitor,
+    profile::{Profile, Profiler},
+    trace::{ConstructionReport, Reporter, Step, Stepper, Trace, Tracer},
+    traits::{ApproxEq, HasPosition, Index, PointSource, Scalar},
+    triangulation::Seed,
+    Triangulation,
+};
+
+/// How to choose between the two possible diagonals of four exactly cocircular points.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TieBreak {
+    /// Keep whichever diagonal is created first. The result depends on the order
+    /// in which points are inserted.
+    None,
+    /// Prefer the diagonal that touches the lowest point index, and insert points
+    /// at equal distances from the seed in index order. Regular grids and other
+    /// cocircular inputs then triangulate identically on every platform and for
+    /// every index type.
+    LowestIndex,
+}
+
+/// Configures how a [Triangulation] is constructed.
+///
+/// ```rust
+/// use delaunator::{builder::{TieBreak, TriangulationBuilder}, Point, Triangulation};
+///
+/// let points = vec![
+///     Point { x: 0., y: 0. },
+///     Point { x: 1., y: 0. },
+///     Point { x: 1., y: 1. },
+///     Point { x: 0., y: 1. },
+/// ];
+///
+/// let result: Triangulation<usize> = TriangulationBuilder::new()
+///     .tie_break(TieBreak::LowestIndex)
+///     .build(&points)
+///     .expect("No triangulation exists.");
+/// ```
+pub struct TriangulationBuilder<K = FloatKernel> {
+    pub(crate) tie_break: TieBreak,
+    pub(crate) dedup: Option<f64>,
+    pub(crate) hull_only: bool,
+    pub(crate) collinear_hull_points: bool,
+    kernel: PhantomData<fn() -> K>,
+}
+
+impl TriangulationBuilder {
+    /// Creates a builder with the default options.
+    pub fn new() -> Self {
+        TriangulationBuilder {
+            tie_break: TieBreak::None,
+            dedup: None,
+            hull_only: false,
+            collinear_hull_points: true,
+            kernel: PhantomData,
+        }
+    }
+}
+
+impl<K> TriangulationBuilder<K> {
+    /// Sets the geometric [Kernel] used for predicates. Defaults to [FloatKernel].
+    pub fn kernel<L>(self) -> TriangulationBuilder<L> {
+        TriangulationBuilder {
+            tie_break: self.tie_break,
+            dedup: self.dedup,
+            hull_only: self.hull_only,
+            collinear_hull_points: self.collinear_hull_points,
+            kernel: PhantomData,
+        }
+    }
+
+    /// Sets the rule used for exactly cocircular points. Defaults to [TieBreak::None].
+    pub fn tie_break(mut self, tie_break: TieBreak) -> Self {
+        self.tie_break = tie_break;
+        self
+    }
+
+    /// Removes duplicate points before triangulating: any point within `epsilon`
+    /// of an earlier point that is kept is left out of the triangulation. A
+    /// chain of points closer than `epsilon` to each other may therefore keep
+    /// more than one of them. An `epsilon` of zero removes exact duplicates
+    /// only.
+    ///
+    /// This runs in linear time using a hash grid. Without it, only duplicates
+    /// that happen to be adjacent in insertion order are detected, and inputs
+    /// with many duplicates triangulate slowly.
+    pub fn dedup(mut self, epsilon: f64) -> Self {
+        self.dedup = Some(epsilon);
+        self
+    }
+
+    /// Only computes the convex hull. The result has no triangles and only its
+    /// `hull` is filled in, exactly as a full triangulation would fill it.
+    ///
+    /// The hull is found by the same advancing front as the triangulation, but
+    /// without creating triangles or flipping edges, which is several times
+    /// faster and needs no memory beyond the hull itself.
+    ///
+    /// ```rust
+    /// use delaunator::{builder::TriangulationBuilder, Point};
+    ///
+    /// let points = vec![
+    ///     Point { x: 0., y: 0. },
+    ///     Point { x: 2., y: 0. },
+    ///     Point { x: 1., y: 1. },
+    ///     Point { x: 2., y: 2. },
+    ///     Point { x: 0., y: 2. },
+    /// ];
+    ///
+    /// let result = TriangulationBuilder::new()
+    ///     .hull_only(true)
+    ///     .build::<usize, _, _>(&points)
+    ///     .unwrap();
+    /// assert!(result.is_empty());
+    /// assert_eq!(result.hull.len(), 4);
+    /// ```
+    pub fn hull_only(mut self, hull_only: bool) -> Self {
+        self.hull_only = hull_only;
+        self
+    }
+
+    /// Sets whether points lying exactly on an edge of the convex hull are
+    /// listed in `hull`. Defaults to `true`, which preserves every boundary
+    /// point, as cartographic data needs. With `false`, `hull` only lists the
+    /// corners of the hull. Either way such points stay vertices of the
+    /// triangles.
+    pub fn collinear_hull_points(mut self, collinear_hull_points: bool) -> Self {
+        self.collinear_hull_points = collinear_hull_points;
+        self
+    }
+
+    /// Triangulate a set of 2D points.
+    /// Returns `None` if no triangulation exists for the input (e.g. all points are collinear),
+    /// or if `I` is too small to index its half-edges.
+    pub fn build<I: Index, T: Scalar + ApproxEq, P: HasPosition<T>>(
+        &self,
+        points: &[P],
+    ) -> Option<Triangulation<I>>
+    where
+        K: Kernel<T>,
+    {
+        self.build_from_source(points)
+    }
+
+    /// Triangulate points read from a [PointSource], such as a raw coordinate buffer.
+    /// Returns `None` if no triangulation exists for the input (e.g. all points are collinear).
+    pub fn build_from_source<I, T, S>(&self, points: &S) -> Option<Triangulation<I>>
+    where
+        I: Index,
+        T: Scalar + ApproxEq,
+        K: Kernel<T>,
+        S: PointSource<T> + ?Sized,
+    {
+        Triangulation::construct(self, points, Seed::Find, &mut (), &mut Scratch::new())
+    }
+
+    /// Triangulate a set of 2D points starting from the given counter-clockwise seed triangle.
+    pub fn build_with_seed_triangle<I: Index, T: Scalar + ApproxEq, P: HasPosition<T>>(
+        &self,
+        points: &[P],
+        seed_triangle: (usize, usize, usize),
+    ) -> Triangulation<I>
+    where
+        K: Kernel<T>,
+    {
+        Triangulation::construct(
+            self,
+            points,
+            Seed::Triangle(seed_triangle.0, seed_triangle.1, seed_triangle.2),
+            &mut (),
+            &mut Scratch::new(),
+        )
+        .unwrap()
+    }
+
+    /// Triangulate points that are already in insertion order, skipping the
+    /// sort by distance that dominates construction time for large inputs.
+    /// Returns `None` if there are fewer than three points or the first three
+    /// are collinear.
+    ///
+    /// The first three points form the seed triangle, and the rest must follow
+    /// in order of increasing distance from its circumcenter, with no point
+    /// inside its circumcircle. Points that break the order may be left out of
+    /// the triangulation, or make it not Delaunay. This suits re-triangulating
+    /// points after tiny perturbations, reordered by the
+    /// [order](crate::trace::Trace::order) of an earlier construction.
+    pub fn build_with_sorted_points<I: Index, T: Scalar + ApproxEq, P: HasPosition<T>>(
+        &self,
+        points: &[P],
+    ) -> Option<Triangulation<I>>
+    where
+        K: Kernel<T>,
+    {
+        Triangulation::construct(self, points, Seed::Sorted, &mut (), &mut Scratch::new())
+    }
+
+    /// Triangulate a set of 2D points, calling `visit` with each triangle as
+    /// soon as it is final. See [Triangulation::with_visitor].
+    pub fn build_with_visitor<I, T, P, F>(&self, points: &[P], visit: F) -> Option<Triangulation<I>>
+    where
+        I: Index,
+        T: Scalar + ApproxEq,
+        K: Kernel<T>,
+        P: HasPosition<T>,
+        F: FnMut([usize; 3]),
+    {
+        let mut visitor = StreamingVisitor::new(visit);
+        Triangulation::construct(self, points, Seed::Find, &mut visitor, &mut Scratch::new())
+    }
+
+    /// Triangulate a set of 2D points, also returning counters and timings of
+    /// the construction for diagnosing performance.
+    /// Returns `None` if no triangulation exists for the input (e.g. all points are collinear).
+    pub fn build_with_profile<I, T, P>(&self, points: &[P]) -> Option<(Triangulation<I>, Profile)>
+    where
+        I: Index,
+        T: Scalar + ApproxEq,
+        K: Kernel<T>,
+        P: HasPosition<T>,
+    {
+        let mut profiler = Profiler::new();
+        let triangulation =
+            Triangulation::construct(self, points, Seed::Find, &mut profiler, &mut Scratch::new())?;
+        Some((triangulation, profiler.profile))
+    }
+
+    /// Triangulate a set of 2D points, also returning the seed triangle, the
+    /// insertion order and what happened to every point, for finding out why
+    /// a point is missing or reproducing the construction.
+    /// Returns `None` if no triangulation exists for the input (e.g. all points are collinear).
+    pub fn build_with_trace<I, T, P>(&self, points: &[P]) -> Option<(Triangulation<I>, Trace<T>)>
+    where
+        I: Index,
+        T: Scalar + ApproxEq,
+        K: Kernel<T>,
+        P: HasPosition<T>,
+    {
+        let mut tracer = Tracer::new(points.len());
+        let triangulation =
+            Triangulation::construct(self, points, Seed::Find, &mut tracer, &mut Scratch::new())?;
+        Some((triangulation, tracer.trace))
+    }
+
+    /// Triangulate a set of 2D points, also returning a [ConstructionReport]
+    /// of the points that were skipped, the flips performed and any
+    /// degeneracies in the input.
+    /// Returns `None` if no triangulation exists for the input (e.g. all points are collinear).
+    pub fn build_with_report<I, T, P>(
+        &self,
+        points: &[P],
+    ) -> Option<(Triangulation<I>, ConstructionReport)>
+    where
+        I: Index,
+        T: Scalar + ApproxEq,
+        K: Kernel<T>,
+        P: HasPosition<T>,
+    {
+        let mut reporter = Reporter::new();
+        let triangulation =
+            Triangulation::construct(self, points, Seed::Find, &mut reporter, &mut Scratch::new())?;
+        Some((triangulation, reporter.report))
+    }
+
+    /// Triangulate a set of 2D points, also returning what the seed triangle
+    /// and every point changed, for stepping through the construction in a
+    /// debugger with a [Replay](crate::trace::Replay).
+    /// Returns `None` if no triangulation exists for the input (e.g. all points are collinear).
+    pub fn build_with_steps<I, T, P>(&self, points: &[P]) -> Option<(Triangulation<I>, Vec<Step>)>
+    where
+        I: Index,
+        T: Scalar + ApproxEq,
+        K: Kernel<T>,
+        P: HasPosition<T>,
+    {
+        let mut stepper = Stepper::new();
+        let triangulation =
+            Triangulation::construct(self, points, Seed::Find, &mut stepper, &mut Scratch::new())?;
+        Some((triangulation, stepper.steps))
+    }
+
+    /// Creates a [Triangulator] that triangulates many point sets with these options.
+    pub fn triangulator<I: Index, T: Scalar>(&self) -> Triangulator<T, I, K> {
+        Triangulator {
+            options: *self,
+            scratch: Scratch::new(),
+        }
+    }
+}
+
+impl Default for TriangulationBuilder {
+    fn default() -> Self {
+        TriangulationBuilder::new()
+    }
+}
+
+impl<K> Clone for TriangulationBuilder<K> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<K> Copy for TriangulationBuilder<K> {}
+
+impl<K> std::fmt::Debug for TriangulationBuilder<K> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TriangulationBuilder")
+            .field("tie_break", &self.tie_break)
+            .field("dedup", &self.dedup)
+            .field("hull_only", &self.hull_only)
+            .field("collinear_hull_points", &self.collinear_hull_points)
+            .field("kernel", &std::any::type_name::<K>())
+            .finish()
+    }
+}
+
+/// Triangulates many point sets in a row, reusing its internal buffers.
+///
+/// Construction needs several temporary arrays proportional to the number of
+/// points. A `Triangulator` keeps them between calls, which avoids repeated
+/// allocation when triangulating lots of small point sets.
+///
+/// ```rust
+/// use delaunator::{builder::Triangulator, Point};
+///
+/// let mut triangulator = Triangulator::<f64, u32>::new();
+/// for i in 1..10 {
+///     let s = i as f64;
+///     let points = [
+///         Point { x: 0., y: 0. },
+///         Point { x: s, y: 0. },
+///         Point { x: 0., y: s },
+///     ];
+///     let triangulation = triangulator.triangulate(&points).unwrap();
+///     assert_eq!(triangulation.len(), 1);
+/// }
+/// ```
+pub struct Triangulator<T: Scalar, I, K = FloatKernel> {
+    options: TriangulationBuilder<K>,
+    scratch: Scratch<T, I>,
+}
+
+impl<T: Scalar + ApproxEq, I: Index> Triangulator<T, I> {
+    /// Creates a triangulator with the default options.
+    /// Use [TriangulationBuilder::triangulator] to customize them.
+    pub fn new() -> Self {
+        TriangulationBuilder::new().triangulator()
+    }
+}
+
+impl<T: Scalar + ApproxEq, I: Index, K: Kernel<T>> Triangulator<T, I, K> {
+    /// Triangulate a set of 2D points.
+    /// Returns `None` if no triangulation exists for the input (e.g. all points are collinear).
+    pub fn triangulate<P: HasPosition<T>>(&mut self, points: &[P]) -> Option<Triangulation<I>> {
+        self.triangulate_source(points)
+    }
+
+    /// Triangulate points read from a [PointSource].
+    /// Returns `None` if no triangulation exists for the input (e.g. all points are collinear).
+    pub fn triangulate_source<S: PointSource<T> + ?Sized>(
+        &mut self,
+        points: &S,
+    ) -> Option<Triangulation<I>> {
+        Triangulation::construct(
+            &self.options,
+            points,
+            Seed::Find,
+            &mut (),
+            &mut self.scratch,
+        )
+    }
+
+    /// Triangulate a set of 2D points, returning only the point indices of
+    /// the triangles, as in [Triangulation::triangles]. This suits rendering,
+    /// which needs nothing else.
+    ///
+    /// The half-edges that flipping needs during construction stay in the
+    /// triangulator's buffers, and the hull is not collected, so after the
+    /// first call only the triangles are allocated. That halves the memory
+    /// a [Triangulation] would take.
+    /// Returns `None` if no triangulation exists for the input (e.g. all points are collinear).
+    pub fn triangulate_indices<P: HasPosition<T>>(&mut self, points: &[P]) -> Option<Vec<I>> {
+        Triangulation::construct_triangles(&self.options, points, &mut self.scratch)
+    }
+}
+
+impl<T: Scalar + ApproxEq, I: Index> Default for Triangulator<T, I> {
+    fn default() -> Self {
+        Triangulator::new()
+    }
+}
@@ -0,0 +1,679 @@
+//! Constrained Delaunay triangulations, which contain given segments as edges.
+//!
+//! A segment is inserted by flipping away the edges it crosses, which
+//! re-triangulates the cavity along it, and then flipping the new edges until
+//! every edge that isn't a segment is locally Delaunay again. The result is
+//! as close to a Delaunay triangulation as the segments allow.
+//!
+//! Segments can also form closed rings, such as the outer boundary of a
+//! polygon and its holes. The triangles enclosed by the rings by the even-odd
+//! rule make up the domain of the triangulation:
+//!
+//! ```rust
+//! use delaunator::{Point, Triangulation};
+//!
+//! // a square with a square hole
+//! let points = vec![
+//!     Point { x: 0., y: 0. },
+//!     Point { x: 3., y: 0. },
+//!     Point { x: 3., y: 3. },
+//!     Point { x: 0., y: 3. },
+//!     Point { x: 1., y: 1. },
+//!     Point { x: 2., y: 1. },
+//!     Point { x: 2., y: 2. },
+//!     Point { x: 1., y: 2. },
+//! ];
+//! let rings = [vec![0, 1, 2, 3], vec![4, 5, 6, 7]];
+//! let cdt = Triangulation::<usize>::from_pslg(&points, &rings, &[]).unwrap();
+//!
+//! assert_eq!(cdt.triangulation().len(), 10);
+//! assert_eq!(cdt.domain_triangles().len(), 3 * 8);
+//! ```
+//!
+//! ```rust
+//! use delaunator::{Point, Triangulation};
+//!
+//! // a thin rhombus, whose Delaunay triangulation uses the short diagonal
+//! let points = vec![
+//!     Point { x: 0., y: 0. },
+//!     Point { x: 2., y: -1. },
+//!     Point { x: 4., y: 0. },
+//!     Point { x: 2., y: 1. },
+//! ];
+//! let cdt = Triangulation::<usize>::with_constraints(&points, &[(0, 2)]).unwrap();
+//!
+//! let e = cdt.find_edge(0, 2).unwrap();
+//! assert!(cdt.is_constrained(e));
+//! assert!(cdt.find_edge(1, 3).is_none());
+//! ```
+
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+};
+
+use crate::{
+    attributes::{Lerp, Parent},
+    traits::{ApproxEq, HasPosition, Index, Scalar},
+    util::{key, next_halfedge, prev_halfedge},
+    Point, Triangulation,
+};
+
+/// The reasons a segment can't be inserted into a
+/// [ConstrainedTriangulation].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConstraintError {
+    /// The points have no triangulation, e.g. because they are collinear.
+    NoTriangulation,
+    /// A segment refers to a point that doesn't exist.
+    OutOfBounds(usize),
+    /// A segment refers to a point that isn't part of the triangulation,
+    /// e.g. a skipped duplicate.
+    NotTriangulated(usize),
+    /// The segment crosses a segment that was inserted before.
+    Crossing {
+        segment: (usize, usize),
+        other: (usize, usize),
+    },
+}
+
+impl fmt::Display for ConstraintError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            ConstraintError::NoTriangulation => write!(f, "no triangulation exists"),
+            ConstraintError::OutOfBounds(i) => write!(f, "point {} out of bounds", i),
+            ConstraintError::NotTriangulated(i) => {
+                write!(f, "point {} is not part of the triangulation", i)
+            }
+            ConstraintError::Crossing { segment, other } => {
+                write!(f, "segment {:?} crosses segment {:?}", segment, other)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConstraintError {}
+
+/// A triangulation with segments that are kept as edges, see
+/// [Triangulation::with_constraints].
+///
+/// Like [EdgeMarkers](crate::markers::EdgeMarkers), segments are stored as
+/// pairs of points rather than half-edges. A segment that passes through
+/// other points is stored as the chain of segments between them.
+pub struct ConstrainedTriangulation<I: Index> {
+    pub(crate) triangulation: Triangulation<I>,
+    /// The segments, with the number of rings each belongs to.
+    segments: HashMap<(usize, usize), u32>,
+    /// For every point, a half-edge starting at it, or `usize::MAX` for
+    /// points that aren't part of the triangulation.
+    outgoing: Vec<usize>,
+}
+
+impl<I: Index> Triangulation<I> {
+    /// Triangulates `points` so that the segments between the pairs of points
+    /// in `edges` are edges of the triangulation, a constrained Delaunay
+    /// triangulation.
+    ///
+    /// Segments may share end points but must not cross. A segment that passes
+    /// through another point is split there.
+    pub fn with_constraints<T: Scalar + ApproxEq, P: HasPosition<T>>(
+        points: &[P],
+        edges: &[(usize, usize)],
+    ) -> Result<ConstrainedTriangulation<I>, ConstraintError> {
+        let triangulation = Triangulation::new(points).ok_or(ConstraintError::NoTriangulation)?;
+        let mut cdt = ConstrainedTriangulation::new(triangulation, points.len());
+        for &(a, b) in edges {
+            cdt.insert_segment(points, a, b, false)?;
+        }
+
+        #[cfg(feature = "vertices")]
+        cdt.triangulation.update_vertices(points.len());
+
+        Ok(cdt)
+    }
+
+    /// Triangulates the planar straight-line graph made of `points`, the
+    /// closed `rings` of point indices and the other `segments`, like
+    /// [with_constraints](Self::with_constraints).
+    ///
+    /// The rings bound the domain by the even-odd rule, e.g. an outer boundary
+    /// and its holes, in either orientation. A ring may repeat its first point
+    /// at the end. The triangles of the domain are
+    /// [domain_triangles](ConstrainedTriangulation::domain_triangles).
+    pub fn from_pslg<T: Scalar + ApproxEq, P: HasPosition<T>, V: AsRef<[usize]>>(
+        points: &[P],
+        rings: &[V],
+        segments: &[(usize, usize)],
+    ) -> Result<ConstrainedTriangulation<I>, ConstraintError> {
+        let triangulation = Triangulation::new(points).ok_or(ConstraintError::NoTriangulation)?;
+        let mut cdt = ConstrainedTriangulation::new(triangulation, points.len());
+        for ring in rings {
+            cdt.insert_ring_segments(points, ring.as_ref())?;
+        }
+        for &(a, b) in segments {
+            cdt.insert_segment(points, a, b, false)?;
+        }
+
+        #[cfg(feature = "vertices")]
+        cdt.triangulation.update_vertices(points.len());
+
+        Ok(cdt)
+    }
+}
+
+impl<I: Index> ConstrainedTriangulation<I> {
+    /// Starts with `triangulation`, a triangulation of `n` points, and no
+    /// segments.
+    pub fn new(triangulation: Triangulation<I>, n: usize) -> Self {
+        let mut outgoing = vec![usize::MAX; n];
+        for (e, &i) in triangulation.triangles.iter().enumerate() {
+            outgoing[i.as_usize()] = e;
+        }
+        ConstrainedTriangulation {
+            triangulation,
+            segments: HashMap::new(),
+            outgoing,
+        }
+    }
+
+    /// The triangulation.
+    pub fn triangulation(&self) -> &Triangulation<I> {
+        &self.triangulation
+    }
+
+    /// Returns the triangulation, dropping the segments.
+    pub fn into_triangulation(self) -> Triangulation<I> {
+        self.triangulation
+    }
+
+    /// Inserts the segment between points `a` and `b` into a triangulation
+    /// of `points`.
+    ///
+    /// If the segment passes through other points, the parts before the
+    /// first one that fails are kept.
+    pub fn insert<T: Scalar, P: HasPosition<T>>(
+        &mut self,
+        points: &[P],
+        a: usize,
+        b: usize,
+    ) -> Result<(), ConstraintError> {
+        let result = self.insert_segment(points, a, b, false);
+
+        #[cfg(feature = "vertices")]
+        self.triangulation.update_vertices(points.len());
+
+        result
+    }
+
+    /// Inserts the segments of the closed `ring` of point indices, a boundary
+    /// of the domain, see [Triangulation::from_pslg].
+    pub fn insert_ring<T: Scalar, P: HasPosition<T>>(
+        &mut self,
+        points: &[P],
+        ring: &[usize],
+    ) -> Result<(), ConstraintError> {
+        let result = self.insert_ring_segments(points, ring);
+
+        #[cfg(feature = "vertices")]
+        self.triangulation.update_vertices(points.len());
+
+        result
+    }
+
+    /// Whether the edge of half-edge `e` is a segment.
+    pub fn is_constrained(&self, e: usize) -> bool {
+        let t = &self.triangulation.triangles;
+        self.segments
+            .contains_key(&key(t[e].as_usize(), t[next_halfedge(e)].as_usize()))
+    }
+
+    /// The segments, each from its smaller point index, in order.
+    pub fn segments(&self) -> Vec<(usize, usize)> {
+        let mut segments: Vec<_> = self.segments.keys().copied().collect();
+        segments.sort_unstable();
+        segments
+    }
+
+    /// Whether each triangle, by id, is inside the domain bounded by the
+    /// rings, by the even-odd rule. Without rings, every triangle is inside.
+    pub fn inside(&self) -> Vec<bool> {
+        let t = &self.triangulation;
+        if self.segments.values().all(|&rings| rings == 0) {
+            return vec![true; t.len()];
+        }
+        let crosses_ring = |e: usize| {
+            let (a, b) = (
+                t.triangles[e].as_usize(),
+                t.triangles[next_halfedge(e)].as_usize(),
+            );
+            self.segments
+                .get(&key(a, b))
+                .map_or(false, |&rings| rings % 2 == 1)
+        };
+
+        // walk in from the hull, switching sides at every ring
+        let mut inside = vec![None; t.len()];
+        let mut stack: Vec<usize> = (0..t.halfedges.len())
+            .filter(|&e| t.halfedges[e].is_none())
+            .take(1)
+            .collect();
+        if let Some(&e) = stack.first() {
+            inside[e / 3] = Some(crosses_ring(e));
+        }
+        while let Some(e) = stack.pop() {
+            let here = inside[e / 3].unwrap();
+            for h in 3 * (e / 3)..3 * (e / 3) + 3 {
+                if let Some(f) = t.halfedges[h].get() {
+                    let f = f.as_usize();
+                    if inside[f / 3].is_none() {
+                        inside[f / 3] = Some(here != crosses_ring(h));
+                        stack.push(f);
+                    }
+                }
+            }
+        }
+        inside.into_iter().map(|t| t == Some(true)).collect()
+    }
+
+    /// The triangles inside the domain, see [inside](Self::inside), as
+    /// triples of point indices in the order of their ids.
+    pub fn domain_triangles(&self) -> Vec<I> {
+        let t = &self.triangulation;
+        self.inside()
+            .into_iter()
+            .enumerate()
+            .filter(|&(_, inside)| inside)
+            .flat_map(|(k, _)| t.triangles[3 * k..3 * k + 3].iter().copied())
+            .collect()
+    }
+
+    /// Splits segments with new points until the triangulation is Delaunay,
+    /// a conforming Delaunay triangulation in which every segment is a chain
+    /// of Delaunay edges.
+    ///
+    /// The new points are appended to `points`, which this is a triangulation
+    /// of, and returned with the segment each one split as its [Parent], e.g.
+    /// for [Attributes::record](crate::attributes::Attributes::record), or
+    /// [EdgeMarkers::record](crate::markers::EdgeMarkers::record) to carry
+    /// the markers of split segments over to their halves.
+    /// Segments between two points of the input are split at their midpoint,
+    /// other segments at a power of two from the input point at their end, so
+    /// that segments meeting at small angles don't split each other forever.
+    ///
+    /// Stops after `max_points` new points; the result is conforming if fewer
+    /// were needed, see [Triangulation::is_delaunay].
+    pub fn conform<T: Scalar>(
+        &mut self,
+        points: &mut Vec<Point<T>>,
+        max_points: usize,
+    ) -> Vec<(usize, Parent)> {
+        let n = points.len();
+        let mut inserted = Vec::new();
+        let mut queue = self.segments();
+        while let Some((a, b)) = queue.pop() {
+            if inserted.len() >= max_points {
+                break;
+            }
+            let e = match self.find_edge(a, b).or_else(|| self.find_edge(b, a)) {
+                Some(e) if self.triangulation.halfedges[e].is_some() => e,
+                _ => continue,
+            };
+            if !self.triangulation.is_illegal(e, points) {
+                continue;
+            }
+            let (a, b) = (
+                self.triangulation.triangles[e].as_usize(),
+                self.triangulation.triangles[next_halfedge(e)].as_usize(),
+            );
+            let length = points[a].distance(points[b]).to_f64();
+            let t = match (a < n, b < n) {
+                (true, false) => shell(length),
+                (false, true) => 1.0 - shell(length),
+                _ => 0.5,
+            };
+            let m = self.split_segment(points, e, t);
+            inserted.push((m, Parent::Edge { a, b, t }));
+
+            // only the segments of the triangles around `m` have changed
+            for h in self.around(m) {
+                for g in 3 * (h / 3)..3 * (h / 3) + 3 {
+                    if self.is_constrained(g) {
+                        let t = &self.triangulation.triangles;
+                        queue.push(key(t[g].as_usize(), t[next_halfedge(g)].as_usize()));
+                    }
+                }
+            }
+        }
+
+        #[cfg(feature = "vertices")]
+        self.triangulation.update_vertices(points.len());
+
+        inserted
+    }
+
+    /// The half-edge from point `a` to point `b`, if there is one.
+    pub fn find_edge(&self, a: usize, b: usize) -> Option<usize> {
+        let t = &self.triangulation.triangles;
+        self.around(a)
+            .into_iter()
+            .find(|&e| t[next_halfedge(e)].as_usize() == b)
+    }
+
+    fn insert_ring_segments<T: Scalar, P: HasPosition<T>>(
+        &mut self,
+        points: &[P],
+        mut ring: &[usize],
+    ) -> Result<(), ConstraintError> {
+        if ring.len() > 1 && ring.first() == ring.last() {
+            ring = &ring[..ring.len() - 1];
+        }
+        for k in 0..ring.len() {
+            self.insert_segment(points, ring[k], ring[(k + 1) % ring.len()], true)?;
+        }
+        Ok(())
+    }
+
+    /// Inserts the segment from `a` to `b`, as part of a ring if `ring`.
+    fn insert_segment<T: Scalar, P: HasPosition<T>>(
+        &mut self,
+        points: &[P],
+        a: usize,
+        b: usize,
+        ring: bool,
+    ) -> Result<(), ConstraintError> {
+        for i in [a, b] {
+            match self.outgoing.get(i) {
+                None => return Err(ConstraintError::OutOfBounds(i)),
+                Some(&usize::MAX) => return Err(ConstraintError::NotTriangulated(i)),
+                Some(_) => {}
+            }
+        }
+        let mut start = a;
+        while start != b {
+            let (end, crossed) = self.crossings(points, start, b)?;
+            *self.segments.entry(key(start, end)).or_insert(0) += u32::from(ring);
+            if !crossed.is_empty() {
+                let created = self.flip_crossings(points, start, end, crossed);
+                let created = created
+                    .into_iter()
+                    .filter_map(|(u, v)| self.find_edge(u, v))
+                    .collect();
+                self.legalize(points, created);
+            }
+            start = end;
+        }
+        Ok(())
+    }
+
+    /// Splits the edge of half-edge `e` at the new point a fraction `t` of
+    /// the way along it, appended to `points`, and flips the edges around the
+    /// new point until they are locally Delaunay. Both halves of a segment are
+    /// segments. Returns the new point.
+    ///
+    /// The triangles on either side of `e` are split, adding a triangle for
+    /// each at the end.
+    pub(crate) fn split_segment<T: Scalar>(
+        &mut self,
+        points: &mut Vec<Point<T>>,
+        e: usize,
+        t: f64,
+    ) -> usize {
+        let tr = &self.triangulation;
+        let (a, b) = (
+            tr.triangles[e].as_usize(),
+            tr.triangles[next_halfedge(e)].as_usize(),
+        );
+        let m = points.len();
+        points.push(points[a].lerp(&points[b], t));
+        let n = self
+            .triangulation
+            .edit()
+            .split_edge(e, m)
+            .expect("segments are edges of the triangulation");
+        if let Some(rings) = self.segments.remove(&key(a, b)) {
+            self.segments.insert(key(a, m), rings);
+            self.segments.insert(key(m, b), rings);
+        }
+        self.settle(points, m, n);
+        m
+    }
+
+    /// Inserts the new point `p` into triangle `t`, which contains it, and
+    /// flips the edges around it until they are locally Delaunay. A point on
+    /// an edge of `t` splits that edge, which must not be a segment. Returns
+    /// the index of the new point in `points`.
+    ///
+    /// The new triangles, one for a point on a hull edge and two otherwise,
+    /// are added at the end.
+    pub(crate) fn insert_point<T: Scalar>(
+        &mut self,
+        points: &mut Vec<Point<T>>,
+        p: Point<T>,
+        t: usize,
+    ) -> usize {
+        let m = points.len();
+        let tr = &self.triangulation;
+        let pos = |e: usize| points[tr.triangles[e].as_usize()];
+        let on_edge = (3 * t..3 * t + 3)
+            .find(|&e| orient(pos(e), pos(next_halfedge(e)), p) == T::from_f64(0.0));
+        points.push(p);
+        let mut edit = self.triangulation.edit();
+        let n = match on_edge {
+            Some(e) => edit.split_edge(e, m),
+            None => edit.split_triangle(t, m),
+        }
+        .expect("the triangle exists");
+        self.settle(points, m, n);
+        m
+    }
+
+    /// Updates `outgoing` after inserting point `m`, which starts half-edge
+    /// `n`, and legalizes the triangles around it.
+    fn settle<T: Scalar>(&mut self, points: &[Point<T>], m: usize, n: usize) {
+        // the insertion moved half-edges between the triangles around `m`
+        self.outgoing.resize(m + 1, usize::MAX);
+        self.outgoing[m] = n;
+        let around = self.around(m);
+        for &h in &around {
+            for g in 3 * (h / 3)..3 * (h / 3) + 3 {
+                self.outgoing[self.triangulation.triangles[g].as_usize()] = g;
+            }
+        }
+        let stack = around
+            .iter()
+            .flat_map(|&h| 3 * (h / 3)..3 * (h / 3) + 3)
+            .collect();
+        self.legalize(points, stack);
+    }
+
+    /// The edges crossed by the segment from `a` towards `b`, as pairs of
+    /// points from the right of the segment to its left, up to `b` or the
+    /// first point on the segment, which is returned as well.
+    fn crossings<T: Scalar, P: HasPosition<T>>(
+        &self,
+        points: &[P],
+        a: usize,
+        b: usize,
+    ) -> Result<(usize, Vec<(usize, usize)>), ConstraintError> {
+        let t = &self.triangulation;
+        let point = |e: usize| t.triangles[e].as_usize();
+        let (pa, pb) = (points[a].pos(), points[b].pos());
+        let side = |i: usize| orient(pa, pb, points[i].pos());
+        let zero = T::from_f64(0.0);
+        let ahead = |i: usize| {
+            let (d, s) = (points[i].pos() - pa, pb - pa);
+            side(i) == zero && d.x * s.x + d.y * s.y > zero
+        };
+
+        // the first edge crossed, across a triangle around `a`
+        let around = self.around(a);
+        let corners = around
+            .iter()
+            .flat_map(|&e| [point(next_halfedge(e)), point(prev_halfedge(e))]);
+        if let Some(end) = corners.clone().find(|&i| i == b) {
+            return Ok((end, Vec::new()));
+        }
+        if let Some(end) = corners.clone().find(|&i| ahead(i)) {
+            return Ok((end, Vec::new()));
+        }
+        let mut h = around
+            .iter()
+            .map(|&e| next_halfedge(e))
+            .find(|&h| side(point(h)) < zero && side(point(next_halfedge(h))) > zero)
+            .expect("the segment leaves the triangulation");
+
+        let mut crossed = Vec::new();
+        loop {
+            let (right, left) = (point(h), point(next_halfedge(h)));
+            if self.segments.contains_key(&key(right, left)) {
+                return Err(ConstraintError::Crossing {
+                    segment: key(a, b),
+                    other: key(right, left),
+                });
+            }
+            crossed.push((right, left));
+            let f = t.halfedges[h]
+                .get()
+                .expect("the segment leaves the triangulation")
+                .as_usize();
+            let s = point(prev_halfedge(f));
+            let o = side(s);
+            if s == b || o == zero {
+                return Ok((s, crossed));
+            }
+            h = if o > zero {
+                next_halfedge(f)
+            } else {
+                prev_halfedge(f)
+            };
+        }
+    }
+
+    /// Flips the `crossed` edges until none of them crosses the segment from
+    /// `a` to `b`, and returns the new edges other than the segment.
+    fn flip_crossings<T: Scalar, P: HasPosition<T>>(
+        &mut self,
+        points: &[P],
+        a: usize,
+        b: usize,
+        crossed: Vec<(usize, usize)>,
+    ) -> Vec<(usize, usize)> {
+        let pos = |i: usize| points[i].pos();
+        let zero = T::from_f64(0.0);
+        let mut queue = VecDeque::from(crossed);
+        let mut created = Vec::new();
+        while let Some((u, v)) = queue.pop_front() {
+            let h = self
+                .find_edge(u, v)
+                .expect("crossed edges are interior edges");
+            let g = self.triangulation.halfedges[h].unwrap().as_usize();
+            let p0 = self.triangulation.triangles[prev_halfedge(h)].as_usize();
+            let p1 = self.triangulation.triangles[prev_halfedge(g)].as_usize();
+
+            // only flip if both new triangles are counter-clockwise
+            if orient(pos(p1), pos(v), pos(p0)) <= zero || orient(pos(p0), pos(u), pos(p1)) <= zero
+            {
+                queue.push_back((u, v));
+                continue;
+            }
+            self.flip(h);
+            if p0 != a && p0 != b && p1 != a && p1 != b && crosses(pos(a), pos(b), pos(p0), pos(p1))
+            {
+                // keep the right-to-left order of crossed edges
+                if orient(pos(a), pos(b), pos(p0)) < zero {
+                    queue.push_back((p0, p1));
+                } else {
+                    queue.push_back((p1, p0));
+                }
+            } else if key(p0, p1) != key(a, b) {
+                created.push((p0, p1));
+            }
+        }
+        created
+    }
+
+    /// Flips the edges of the half-edges on the `stack` and the edges around
+    /// them until every edge that isn't a segment is locally Delaunay.
+    fn legalize<T: Scalar, P: HasPosition<T>>(&mut self, points: &[P], mut stack: Vec<usize>) {
+        while let Some(a) = stack.pop() {
+            let b = match self.triangulation.halfedges[a].get() {
+                None => continue,
+                Some(b) => b.as_usize(),
+            };
+            if self.is_constrained(a) || !self.triangulation.is_illegal(a, points) {
+                continue;
+            }
+
+            // only flip if both new triangles are counter-clockwise
+            let pos = |e: usize| points[self.triangulation.triangles[e].as_usize()].pos();
+            let (p0, pr) = (pos(prev_halfedge(a)), pos(a));
+            let (pl, p1) = (pos(b), pos(prev_halfedge(b)));
+            if !p0.is_clockwise(pl, p1) || !p1.is_clockwise(pr, p0) {
+                continue;
+            }
+
+            self.flip(a);
+            stack.push(a);
+            stack.push(next_halfedge(a));
+            stack.push(b);
+            stack.push(next_halfedge(b));
+        }
+    }
+
+    /// Flips the interior edge of half-edge `a`, keeping `outgoing` valid.
+    fn flip(&mut self, a: usize) {
+        let t = &mut self.triangulation;
+        let b = t.halfedges[a].unwrap().as_usize();
+        let (pr, pl) = (t.triangles[a].as_usize(), t.triangles[b].as_usize());
+        t.flip(a);
+        // `a` and `b` now start at the new diagonal's far points
+        self.outgoing[pr] = next_halfedge(b);
+        self.outgoing[pl] = next_halfedge(a);
+    }
+
+    /// The half-edges starting at point `i`, or none if it isn't part of the
+    /// triangulation.
+    pub(crate) fn around(&self, i: usize) -> Vec<usize> {
+        let start = match self.outgoing.get(i) {
+            Some(&e) if e != usize::MAX => e,
+            _ => return Vec::new(),
+        };
+        let halfedges = &self.triangulation.halfedges;
+        let mut edges = vec![start];
+        // counter-clockwise until the hull or back at the start
+        let mut e = start;
+        while let Some(f) = halfedges[prev_halfedge(e)].get() {
+            e = f.as_usize();
+            if e == start {
+                return edges;
+            }
+            edges.push(e);
+        }
+        // then clockwise from the start to the other side of the hull
+        let mut e = start;
+        while let Some(f) = halfedges[e].get() {
+            e = next_halfedge(f.as_usize());
+            edges.push(e);
+        }
+        edges
+    }
+}
+
+/// The power of two closest to half of `length`, as a fraction of `length`.
+pub(crate) fn shell(length: f64) -> f64 {
+    (length / 2.0).log2().round().exp2() / length
+}
+
+/// Twice the signed area of the triangle `a`, `b`, `c`, positive if it is
+/// counter-clockwise.
+pub(crate) fn orient<T: Scalar>(a: Point<T>, b: Point<T>, c: Point<T>) -> T {
+    (b - a).perp_dot(c - a)
+}
+
+/// Whether the segments from `a` to `b` and from `p` to `q` cross at a point
+/// inside both.
+fn crosses<T: Scalar>(a: Point<T>, b: Point<T>, p: Point<T>, q: Point<T>) -> bool {
+    let zero = T::from_f64(0.0);
+    let opposite = |s: T, t: T| (s > zero && t < zero) || (s < zero && t > zero);
+    opposite(orient(a, b, p), orient(a, b, q)) && opposite(orient(p, q, a), orient(p, q, b))
+}
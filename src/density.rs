@@ -0,0 +1,70 @@
+//! Density estimation from the Delaunay triangulation of a point sample.
+
+use crate::{
+    traits::{HasPosition, Index, Scalar},
+    Triangulation,
+};
+
+/// Estimates the density of the points at every point with the Delaunay
+/// tessellation field estimator (DTFE), counting each point with unit mass.
+///
+/// See [dtfe_weighted].
+pub fn dtfe<T: Scalar, I: Index, P: HasPosition<T>>(
+    triangulation: &Triangulation<I>,
+    points: &[P],
+) -> Vec<T> {
+    let masses = vec![T::from_f64(1.0); points.len()];
+    dtfe_weighted(triangulation, points, &masses)
+}
+
+/// Estimates the density of the mass of the points at every point with the
+/// Delaunay tessellation field estimator (DTFE).
+///
+/// The density at a point is three times its mass over the area of its
+/// contiguous Voronoi cell, the triangles around it. Interpolating these
+/// values linearly across each triangle, e.g. with
+/// [tin::interpolate](crate::tin::interpolate), gives a continuous density
+/// field that conserves mass. Points on the convex hull have incomplete cells,
+/// so their density is overestimated. Points that aren't part of the
+/// triangulation get zero.
+///
+/// ```rust
+/// use delaunator::{density, tin, Point, Triangulation};
+///
+/// let points: Vec<_> = (0..100)
+///     .map(|i| Point { x: (i % 10) as f64, y: (i / 10) as f64 })
+///     .collect();
+/// let triangulation = Triangulation::<usize>::new(&points).unwrap();
+///
+/// // a unit grid has one point per unit area
+/// let densities = density::dtfe(&triangulation, &points);
+/// let center = tin::interpolate(&triangulation, &points, &densities, Point { x: 4.5, y: 4.5 });
+/// assert!((center.unwrap() - 1.0).abs() < 1e-9);
+/// ```
+pub fn dtfe_weighted<T: Scalar, I: Index, P: HasPosition<T>>(
+    triangulation: &Triangulation<I>,
+    points: &[P],
+    masses: &[T],
+) -> Vec<T> {
+    let zero = T::from_f64(0.0);
+    let mut areas = vec![zero; points.len()];
+    for t in triangulation.triangles.chunks_exact(3) {
+        let [a, b, c] = [t[0], t[1], t[2]].map(I::as_usize);
+        let (pa, pb, pc) = (points[a].pos(), points[b].pos(), points[c].pos());
+        let area = (pb - pa).perp_dot(pc - pa).abs() / T::from_f64(2.0);
+        for i in [a, b, c] {
+            areas[i] = areas[i] + area;
+        }
+    }
+    areas
+        .iter()
+        .zip(masses)
+        .map(|(&area, &mass)| {
+            if area > zero {
+                T::from_f64(3.0) * mass / area
+            } else {
+                zero
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,180 @@
+//! A Delaunay triangulation and its Voronoi diagram as one linked mesh.
+//!
+//! Procedural map generators use the two graphs side by side: Voronoi cells
+//! as regions, their corners for rivers and elevation, and the Delaunay edges
+//! to move between neighboring regions. [DualMesh] exposes both, with
+//! cross-links in every direction:
+//!
+//! * a *site* is a point of the triangulation and the Voronoi cell around it;
+//! * a *corner* is a Delaunay triangle and the Voronoi vertex at its circumcenter,
+//!   both identified by the triangle id;
+//! * an *edge* is a Delaunay half-edge, identified by its half-edge id, and the
+//!   Voronoi edge between the corners on either side of it.
+//!
+//! ```rust
+//! use delaunator::{dual::DualMesh, Point};
+//!
+//! let points = vec![
+//!     Point { x: 0., y: 0. },
+//!     Point { x: 2., y: 0. },
+//!     Point { x: 2., y: 2. },
+//!     Point { x: 0., y: 2. },
+//!     Point { x: 1., y: 1. },
+//! ];
+//! let mesh = DualMesh::<f64, usize>::new(&points).unwrap();
+//!
+//! // the center is surrounded by four corners and four neighboring sites
+//! assert!(!mesh.is_boundary_site(4));
+//! assert_eq!(mesh.site_corners(4).count(), 4);
+//! assert_eq!(mesh.site_neighbors(4).count(), 4);
+//! ```
+
+use std::iter;
+
+use crate::{
+    traits::{ApproxEq, HasPosition, Index, Scalar},
+    util::{next_halfedge, prev_halfedge},
+    Point, Triangulation,
+};
+
+/// A Delaunay triangulation together with its Voronoi diagram.
+pub struct DualMesh<T: Scalar, I> {
+    /// The underlying triangulation.
+    pub triangulation: Triangulation<I>,
+    /// The position of every site, indexed like the input points.
+    pub sites: Vec<Point<T>>,
+    /// The position of every corner, the circumcenter of its triangle, indexed
+    /// by triangle id.
+    pub corners: Vec<Point<T>>,
+    /// For every site, the first outgoing half-edge in counter-clockwise order,
+    /// or `usize::MAX` for points that aren't part of the triangulation.
+    spokes: Vec<usize>,
+}
+
+impl<T: Scalar, I: Index> DualMesh<T, I> {
+    /// Triangulates `points` and links the result with its Voronoi diagram.
+    /// Returns `None` if no triangulation exists for the input.
+    pub fn new<P: HasPosition<T>>(points: &[P]) -> Option<Self>
+    where
+        T: ApproxEq,
+    {
+        Triangulation::new(points)
+            .map(|triangulation| Self::from_triangulation(triangulation, points))
+    }
+
+    /// Links an existing triangulation of `points` with its Voronoi diagram.
+    pub fn from_triangulation<P: HasPosition<T>>(
+        triangulation: Triangulation<I>,
+        points: &[P],
+    ) -> Self {
+        let mut spokes = vec![usize::MAX; points.len()];
+        for (e, &i) in triangulation.triangles.iter().enumerate() {
+            let i = i.as_usize();
+            // on the hull, start at the outgoing hull edge so walks don't wrap
+            if spokes[i] == usize::MAX || triangulation.halfedges[e].is_none() {
+                spokes[i] = e;
+            }
+        }
+        DualMesh {
+            corners: triangulation.circumcenters(points),
+            sites: points.iter().map(|p| p.pos()).collect(),
+            triangulation,
+            spokes,
+        }
+    }
+
+    /// The number of sites, including points that aren't part of the triangulation.
+    pub fn site_count(&self) -> usize {
+        self.sites.len()
+    }
+
+    /// The number of corners, which is the number of triangles.
+    pub fn corner_count(&self) -> usize {
+        self.corners.len()
+    }
+
+    /// The number of edges, which is the number of half-edges.
+    pub fn edge_count(&self) -> usize {
+        self.triangulation.halfedges.len()
+    }
+
+    /// The site that edge `e` starts from.
+    pub fn edge_start_site(&self, e: usize) -> usize {
+        self.triangulation.triangles[e].as_usize()
+    }
+
+    /// The site that edge `e` ends at.
+    pub fn edge_end_site(&self, e: usize) -> usize {
+        self.triangulation.triangles[next_halfedge(e)].as_usize()
+    }
+
+    /// The corner of the triangle that edge `e` belongs to.
+    pub fn edge_inner_corner(&self, e: usize) -> usize {
+        e / 3
+    }
+
+    /// The corner on the other side of edge `e`, or `None` on the convex hull,
+    /// where the dual Voronoi edge is unbounded.
+    pub fn edge_outer_corner(&self, e: usize) -> Option<usize> {
+        self.edge_twin(e).map(|twin| twin / 3)
+    }
+
+    /// The half-edge running the opposite way along edge `e`, or `None` on the
+    /// convex hull.
+    pub fn edge_twin(&self, e: usize) -> Option<usize> {
+        self.triangulation.halfedges[e].get().map(I::as_usize)
+    }
+
+    /// The sites at the vertices of the triangle of corner `t`, counter-clockwise.
+    pub fn corner_sites(&self, t: usize) -> [usize; 3] {
+        [0, 1, 2].map(|k| self.triangulation.triangles[3 * t + k].as_usize())
+    }
+
+    /// The edges of the triangle of corner `t`, counter-clockwise.
+    pub fn corner_edges(&self, t: usize) -> [usize; 3] {
+        [3 * t, 3 * t + 1, 3 * t + 2]
+    }
+
+    /// The corners of the triangles across each edge of corner `t`, or `None`
+    /// across hull edges.
+    pub fn corner_neighbors(&self, t: usize) -> [Option<usize>; 3] {
+        self.corner_edges(t).map(|e| self.edge_outer_corner(e))
+    }
+
+    /// Whether site `s` lies on the convex hull, so its Voronoi cell is unbounded.
+    pub fn is_boundary_site(&self, s: usize) -> bool {
+        self.spokes[s] != usize::MAX && self.triangulation.halfedges[self.spokes[s]].is_none()
+    }
+
+    /// The edges starting from site `s`, counter-clockwise, one per adjacent triangle.
+    ///
+    /// On the convex hull this starts at the outgoing hull edge; the incoming
+    /// hull edge has no outgoing twin and isn't included.
+    pub fn site_edges(&self, s: usize) -> impl Iterator<Item = usize> + '_ {
+        let start = Some(self.spokes[s]).filter(|&e| e != usize::MAX);
+        iter::successors(start, move |&e| {
+            self.edge_twin(prev_halfedge(e))
+                .filter(|&twin| Some(twin) != start)
+        })
+    }
+
+    /// The corners around site `s`, counter-clockwise. These are the vertices
+    /// of its Voronoi cell, which is open on the convex hull.
+    pub fn site_corners(&self, s: usize) -> impl Iterator<Item = usize> + '_ {
+        self.site_edges(s).map(|e| e / 3)
+    }
+
+    /// The sites whose Voronoi cells share an edge with that of site `s`,
+    /// counter-clockwise.
+    pub fn site_neighbors(&self, s: usize) -> impl Iterator<Item = usize> + '_ {
+        // on the hull, the far end of the incoming hull edge comes last
+        let hull = if self.is_boundary_site(s) {
+            self.site_edges(s).last()
+        } else {
+            None
+        };
+        self.site_edges(s)
+            .map(move |e| self.edge_end_site(e))
+            .chain(hull.map(|e| self.edge_start_site(prev_halfedge(e))))
+    }
+}
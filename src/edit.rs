@@ -0,0 +1,570 @@
+//! Editing a triangulation with local mesh operations.
+//!
+//! [TriangulationMut] applies flips, splits and collapses while keeping the
+//! half-edges, the convex hull and (with the `vertices` feature) the vertex
+//! index consistent. The operations are purely topological: the caller adds
+//! points to its own array and checks that the new triangles are
+//! counter-clockwise, e.g. by only flipping convex quadrilaterals.
+//!
+//! ```rust
+//! use delaunator::{Point, Triangulation};
+//!
+//! let mut points = vec![
+//!     Point { x: 0., y: 0. },
+//!     Point { x: 2., y: 0. },
+//!     Point { x: 0., y: 2. },
+//! ];
+//! let mut triangulation = Triangulation::<usize>::new(&points).unwrap();
+//!
+//! // add the centroid, then the midpoint of the hull edge across from point 2
+//! points.push(Point { x: 2. / 3., y: 2. / 3. });
+//! let mut edit = triangulation.edit();
+//! edit.split_triangle(0, 3).unwrap();
+//! let e = edit
+//!     .view()
+//!     .half_edges()
+//!     .find(|e| e.start().id() == 0 && e.end().id() == 1)
+//!     .unwrap();
+//! let e = e.id();
+//! points.push(Point { x: 1., y: 0. });
+//! edit.split_edge(e, 4).unwrap();
+//!
+//! assert_eq!(triangulation.len(), 4);
+//! assert_eq!(triangulation.hull.len(), 4);
+//! ```
+//!
+//! Edits made through [Triangulation::edit_with_journal] are recorded in a
+//! [Journal], which restores the triangulation to any earlier [Snapshot] at a
+//! cost proportional to the edits since then:
+//!
+//! ```rust
+//! use delaunator::{edit::Journal, Point, Triangulation};
+//!
+//! let points = vec![
+//!     Point { x: 0., y: 0. },
+//!     Point { x: 1., y: 0. },
+//!     Point { x: 1., y: 1. },
+//!     Point { x: 0., y: 1. },
+//! ];
+//! let mut triangulation = Triangulation::<usize>::new(&points).unwrap();
+//! let before = triangulation.triangles.clone();
+//!
+//! let mut journal = Journal::new();
+//! let snapshot = journal.snapshot();
+//! let e = triangulation.halfedges.iter().position(|e| e.is_some()).unwrap();
+//! triangulation.edit_with_journal(&mut journal).flip_edge(e).unwrap();
+//! assert_ne!(triangulation.triangles, before);
+//!
+//! journal.rollback(&mut triangulation, snapshot);
+//! assert_eq!(triangulation.triangles, before);
+//! ```
+
+use crate::{
+    traits::Index,
+    util::{next_halfedge, prev_halfedge, OptionIndex},
+    view::TriangulationRef,
+    Triangulation,
+};
+
+/// The reasons an edit of a [TriangulationMut] is refused. The triangulation
+/// is left unchanged.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EditError {
+    /// The half-edge or triangle does not exist.
+    OutOfBounds,
+    /// The edge is on the convex hull and has no triangle on its other side.
+    HullEdge,
+    /// The edit would create an edge that already exists.
+    DuplicateEdge,
+    /// The edit would make the mesh non-manifold or leave fewer than one
+    /// triangle.
+    NonManifold,
+}
+
+impl std::fmt::Display for EditError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            EditError::OutOfBounds => write!(f, "no such half-edge or triangle"),
+            EditError::HullEdge => write!(f, "edge is on the convex hull"),
+            EditError::DuplicateEdge => write!(f, "edge already exists"),
+            EditError::NonManifold => write!(f, "edit would make the mesh non-manifold"),
+        }
+    }
+}
+
+impl std::error::Error for EditError {}
+
+/// A mutable handle to a [Triangulation], created by [Triangulation::edit].
+pub struct TriangulationMut<'a, I: Index> {
+    triangulation: &'a mut Triangulation<I>,
+    journal: Option<&'a mut Journal<I>>,
+}
+
+/// The edits made to a triangulation, for undoing them. See
+/// [Triangulation::edit_with_journal].
+///
+/// The journal holds the previous contents of every triangle, hull entry and
+/// vertex entry that an edit overwrote, so it grows with the number of edits,
+/// not with the size of the triangulation.
+#[derive(Clone, Debug)]
+pub struct Journal<I: Index> {
+    entries: Vec<Entry<I>>,
+}
+
+/// A point in the history of a [Journal] to roll back to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Snapshot(usize);
+
+/// A change recorded by a [Journal], holding what it overwrote.
+#[derive(Clone, Debug)]
+enum Entry<I: Index> {
+    Triangle {
+        t: usize,
+        triangles: [I; 3],
+        halfedges: [OptionIndex<I>; 3],
+    },
+    Len(usize),
+    HullInsert(usize),
+    HullRemove(usize, I),
+    HullSet(usize, I),
+    #[cfg(feature = "vertices")]
+    Vertex(usize, I),
+    #[cfg(feature = "vertices")]
+    VerticesLen(usize),
+}
+
+impl<I: Index> Journal<I> {
+    /// Creates a journal with an empty history.
+    pub fn new() -> Self {
+        Journal {
+            entries: Vec::new(),
+        }
+    }
+
+    /// The current point in the history.
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot(self.entries.len())
+    }
+
+    /// Undoes the edits made to `triangulation` since `snapshot` was taken,
+    /// restoring its arrays exactly, including triangle ids.
+    ///
+    /// Later snapshots can no longer be rolled back to.
+    ///
+    /// # Panics
+    /// Panics if `snapshot` is newer than the history, i.e. it was taken
+    /// after an earlier snapshot that was rolled back to or before [Journal::clear].
+    pub fn rollback(&mut self, triangulation: &mut Triangulation<I>, snapshot: Snapshot) {
+        assert!(
+            snapshot.0 <= self.entries.len(),
+            "snapshot is not part of the journal"
+        );
+        let t = triangulation;
+        for entry in self.entries.drain(snapshot.0..).rev() {
+            match entry {
+                Entry::Triangle {
+                    t: id,
+                    triangles,
+                    halfedges,
+                } => {
+                    t.triangles[3 * id..3 * id + 3].copy_from_slice(&triangles);
+                    t.halfedges[3 * id..3 * id + 3].copy_from_slice(&halfedges);
+                }
+                Entry::Len(len) => {
+                    t.triangles.resize(3 * len, I::max_value());
+                    t.halfedges.resize(3 * len, OptionIndex::none());
+                }
+                Entry::HullInsert(k) => {
+                    t.hull.remove(k);
+                }
+                Entry::HullRemove(k, p) => t.hull.insert(k, p),
+                Entry::HullSet(k, p) => t.hull[k] = p,
+                #[cfg(feature = "vertices")]
+                Entry::Vertex(p, e) => t.vertices[p] = e,
+                #[cfg(feature = "vertices")]
+                Entry::VerticesLen(len) => t.vertices.truncate(len),
+            }
+        }
+    }
+
+    /// Forgets the history, keeping the triangulation as it is.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// The number of changes recorded.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether no changes are recorded.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl<I: Index> Default for Journal<I> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<I: Index> Triangulation<I> {
+    /// Edits the triangulation with local mesh operations.
+    pub fn edit(&mut self) -> TriangulationMut<'_, I> {
+        TriangulationMut {
+            triangulation: self,
+            journal: None,
+        }
+    }
+
+    /// Edits the triangulation with local mesh operations, recording them in
+    /// `journal` so they can be rolled back.
+    pub fn edit_with_journal<'a>(
+        &'a mut self,
+        journal: &'a mut Journal<I>,
+    ) -> TriangulationMut<'a, I> {
+        TriangulationMut {
+            triangulation: self,
+            journal: Some(journal),
+        }
+    }
+}
+
+impl<'a, I: Index> TriangulationMut<'a, I> {
+    /// Borrows the triangulation being edited.
+    pub fn view(&self) -> TriangulationRef<'_, I> {
+        self.triangulation.view()
+    }
+
+    /// Flips the interior edge of half-edge `e`, replacing it by the other
+    /// diagonal of the quadrilateral formed by its two triangles. Returns a
+    /// half-edge of the new diagonal.
+    ///
+    /// The quadrilateral should be convex; otherwise the new triangles overlap.
+    pub fn flip_edge(&mut self, e: usize) -> Result<usize, EditError> {
+        let t = &*self.triangulation;
+        let f = t
+            .halfedges
+            .get(e)
+            .ok_or(EditError::OutOfBounds)?
+            .get()
+            .ok_or(EditError::HullEdge)?
+            .as_usize();
+        let p1 = t.triangles[prev_halfedge(f)].as_usize();
+        if self.neighbors(prev_halfedge(e)).contains(&p1) {
+            return Err(EditError::DuplicateEdge);
+        }
+        self.save_around(e / 3);
+        self.save_around(f / 3);
+        self.triangulation.flip(e);
+        self.touch(e / 3);
+        self.touch(f / 3);
+        Ok(prev_halfedge(e))
+    }
+
+    /// Splits the edge of half-edge `e` at the new point `m`, which must not be
+    /// part of the triangulation yet. The triangles on either side of the edge
+    /// are each split in two; a split edge on the convex hull adds `m` to the
+    /// hull. Returns the half-edge from `m` to the end of `e`.
+    ///
+    /// `m` should lie on the edge, or at least see both halves of it from the
+    /// opposite points.
+    pub fn split_edge(&mut self, e: usize, m: usize) -> Result<usize, EditError> {
+        let twin = *self
+            .triangulation
+            .halfedges
+            .get(e)
+            .ok_or(EditError::OutOfBounds)?;
+        self.save_around(e / 3);
+        if let Some(f) = twin.get() {
+            self.save_around(f.as_usize() / 3);
+        }
+        self.save_len();
+        let t = &mut *self.triangulation;
+        let a = t.triangles[e].as_usize();
+        let b = t.triangles[next_halfedge(e)].as_usize();
+
+        // a->m stays in `e`, m->b goes into a new triangle (m, b, c)
+        let n = split_half(t, e, m);
+        match twin.get() {
+            Some(f) => {
+                // likewise b->m stays in `f`, m->a goes into (m, a, d)
+                let f = f.as_usize();
+                let w = split_half(t, f, m);
+                link(t, e, w);
+                link(t, f, n);
+                self.touch(f / 3);
+                self.touch(w / 3);
+            }
+            None => {
+                let hull = &t.hull;
+                let len = hull.len();
+                if let Some(k) = (0..len)
+                    .find(|&k| hull[k].as_usize() == a && hull[(k + 1) % len].as_usize() == b)
+                {
+                    t.hull.insert(k + 1, I::from_usize(m));
+                    self.record(Entry::HullInsert(k + 1));
+                }
+            }
+        }
+        self.touch(e / 3);
+        self.touch(n / 3);
+        Ok(n)
+    }
+
+    /// Splits triangle `t` into three triangles around the new point `m`,
+    /// which must not be part of the triangulation yet and should lie inside
+    /// the triangle. The triangle keeps its id for the part along its first
+    /// edge. Returns the half-edge from `m` to the first point of `t`.
+    pub fn split_triangle(&mut self, t: usize, m: usize) -> Result<usize, EditError> {
+        if 3 * t >= self.triangulation.triangles.len() {
+            return Err(EditError::OutOfBounds);
+        }
+        self.save_around(t);
+        self.save_len();
+        let tr = &mut *self.triangulation;
+        let (e0, e1, e2) = (3 * t, 3 * t + 1, 3 * t + 2);
+        let [a, b, c] = [e0, e1, e2].map(|e| tr.triangles[e].as_usize());
+        let (h1, h2) = (tr.halfedges[e1], tr.halfedges[e2]);
+
+        // (a, b, m) in place, then (b, c, m) and (c, a, m)
+        tr.triangles[e2] = I::from_usize(m);
+        let n = tr.add_triangle(b, c, m, h1, OptionIndex::none(), OptionIndex::none());
+        let o = tr.add_triangle(c, a, m, h2, OptionIndex::none(), OptionIndex::none());
+        // the edge from `b` to `m` is now `e1`, from `m` to `a` is `e2`
+        link(tr, e1, n + 2);
+        link(tr, n + 1, o + 2);
+        link(tr, o + 1, e2);
+
+        self.touch(t);
+        self.touch(n / 3);
+        self.touch(o / 3);
+        Ok(e2)
+    }
+
+    /// Collapses the edge of half-edge `e` by merging its end point into its
+    /// start point, removing the one or two triangles on the edge. Returns the
+    /// point that was removed.
+    ///
+    /// The merged point keeps the position of the start point, so it should
+    /// see all of the end point's neighbors. Triangles are removed by moving
+    /// the last triangles into their slots, which changes those triangles' ids.
+    pub fn collapse_edge(&mut self, e: usize) -> Result<usize, EditError> {
+        let t = &*self.triangulation;
+        if e >= t.triangles.len() {
+            return Err(EditError::OutOfBounds);
+        }
+        let f = t.halfedges[e].get().map(I::as_usize);
+        let a = t.triangles[e].as_usize();
+        let b = t.triangles[next_halfedge(e)].as_usize();
+        let on_hull = |p: usize| t.hull.iter().any(|&q| q.as_usize() == p);
+
+        // the link condition: `a` and `b` only share the points across the edge
+        let mut opposite = vec![t.triangles[prev_halfedge(e)].as_usize()];
+        opposite.extend(f.map(|f| t.triangles[prev_halfedge(f)].as_usize()));
+        let around_b = self.neighbors(next_halfedge(e));
+        let shared = self
+            .neighbors(e)
+            .into_iter()
+            .filter(|p| around_b.contains(p))
+            .count();
+        let pinched = f.is_some() && on_hull(a) && on_hull(b);
+        let empty = (f.is_none() && t.hull.len() <= 3) || t.len() <= opposite.len();
+        if shared != opposite.len() || pinched || empty {
+            return Err(EditError::NonManifold);
+        }
+
+        // every half-edge starting at `b` starts at `a` now
+        let from_b: Vec<usize> = self
+            .view()
+            .get_half_edge(next_halfedge(e))
+            .unwrap()
+            .start()
+            .edges()
+            .map(|h| h.id())
+            .collect();
+        for &h in &from_b {
+            self.save(h / 3);
+        }
+        self.save_around(e / 3);
+        if let Some(f) = f {
+            self.save_around(f / 3);
+        }
+        let t = &mut *self.triangulation;
+        for h in from_b {
+            t.triangles[h] = I::from_usize(a);
+        }
+
+        // close the gaps left by the removed triangles
+        let mut kept = Vec::with_capacity(4);
+        for g in std::iter::once(e).chain(f) {
+            let (g1, g2) = (t.halfedges[next_halfedge(g)], t.halfedges[prev_halfedge(g)]);
+            for h in [g1, g2].iter().filter_map(|h| h.get()) {
+                kept.push(h.as_usize());
+            }
+            match (g1.get(), g2.get()) {
+                (Some(g1), Some(g2)) => link(t, g1.as_usize(), g2.as_usize()),
+                (Some(h), None) | (None, Some(h)) => {
+                    t.halfedges[h.as_usize()] = OptionIndex::none()
+                }
+                (None, None) => {}
+            }
+        }
+
+        if let Some(k) = t.hull.iter().position(|p| p.as_usize() == b) {
+            let entry = match f {
+                None => Entry::HullRemove(k, t.hull.remove(k)),
+                Some(_) => Entry::HullSet(k, std::mem::replace(&mut t.hull[k], I::from_usize(a))),
+            };
+            self.record(entry);
+        }
+
+        let mut removed: Vec<usize> = std::iter::once(e / 3).chain(f.map(|f| f / 3)).collect();
+        removed.sort_unstable();
+        for &r in removed.iter().rev() {
+            if let Some(moved) = self.remove_triangle(r) {
+                for h in &mut kept {
+                    if *h / 3 == moved {
+                        *h = 3 * r + *h % 3;
+                    }
+                }
+                self.touch(r);
+            }
+        }
+
+        #[cfg(feature = "vertices")]
+        self.set_vertex(b, I::max_value());
+        for h in kept {
+            self.touch(h / 3);
+        }
+        Ok(b)
+    }
+
+    /// The points connected to the start point of half-edge `e`.
+    fn neighbors(&self, e: usize) -> Vec<usize> {
+        let start = self.triangulation.triangles[e];
+        self.view()
+            .get_half_edge(e)
+            .unwrap()
+            .start()
+            .incident_edges()
+            .map(|h| {
+                let (s, t) = (h.start().id(), h.end().id());
+                if I::from_usize(s) == start {
+                    t
+                } else {
+                    s
+                }
+            })
+            .collect()
+    }
+
+    /// Removes triangle `t` by moving the last triangle into its slot. Returns
+    /// the old id of the moved triangle, if any.
+    fn remove_triangle(&mut self, t: usize) -> Option<usize> {
+        let last = self.triangulation.len() - 1;
+        self.save_around(last);
+        self.save_len();
+        let tr = &mut *self.triangulation;
+        let moved = if last != t {
+            for k in 0..3 {
+                let e = 3 * t + k;
+                tr.triangles[e] = tr.triangles[3 * last + k];
+                tr.halfedges[e] = tr.halfedges[3 * last + k];
+                if let Some(twin) = tr.halfedges[e].get() {
+                    tr.halfedges[twin.as_usize()] = OptionIndex::some(I::from_usize(e));
+                }
+            }
+            Some(last)
+        } else {
+            None
+        };
+        tr.triangles.truncate(3 * last);
+        tr.halfedges.truncate(3 * last);
+        moved
+    }
+
+    /// Records a change in the journal, if any.
+    fn record(&mut self, entry: Entry<I>) {
+        if let Some(journal) = &mut self.journal {
+            journal.entries.push(entry);
+        }
+    }
+
+    /// Records the contents of triangle `t` before it is changed.
+    fn save(&mut self, t: usize) {
+        if self.journal.is_some() {
+            let tr = &*self.triangulation;
+            let entry = Entry::Triangle {
+                t,
+                triangles: [0, 1, 2].map(|k| tr.triangles[3 * t + k]),
+                halfedges: [0, 1, 2].map(|k| tr.halfedges[3 * t + k]),
+            };
+            self.record(entry);
+        }
+    }
+
+    /// Records triangle `t` and its neighbors, whose twins change along with it.
+    fn save_around(&mut self, t: usize) {
+        self.save(t);
+        for e in 3 * t..3 * t + 3 {
+            if let Some(twin) = self.triangulation.halfedges[e].get() {
+                self.save(twin.as_usize() / 3);
+            }
+        }
+    }
+
+    /// Records the number of triangles before triangles are added or removed.
+    fn save_len(&mut self) {
+        let len = self.triangulation.len();
+        self.record(Entry::Len(len));
+    }
+
+    /// Points the vertex index of the points of triangle `t` at its half-edges.
+    #[cfg(feature = "vertices")]
+    fn touch(&mut self, t: usize) {
+        for e in 3 * t..3 * t + 3 {
+            let p = self.triangulation.triangles[e].as_usize();
+            self.set_vertex(p, I::from_usize(e));
+        }
+    }
+
+    #[cfg(not(feature = "vertices"))]
+    fn touch(&mut self, _t: usize) {}
+
+    #[cfg(feature = "vertices")]
+    fn set_vertex(&mut self, p: usize, e: I) {
+        let vertices = &mut self.triangulation.vertices;
+        let len = vertices.len();
+        if p >= len {
+            vertices.resize(p + 1, I::max_value());
+            self.record(Entry::VerticesLen(len));
+        }
+        let old = std::mem::replace(&mut self.triangulation.vertices[p], e);
+        self.record(Entry::Vertex(p, old));
+    }
+}
+
+/// Splits the triangle of half-edge `e` at the new point `m` on `e`: the
+/// triangle keeps the half from the start of `e` to `m`, and a new triangle
+/// takes the other half. Returns the new triangle's half-edge from `m` to the
+/// end of `e`, which has no twin yet.
+fn split_half<I: Index>(t: &mut Triangulation<I>, e: usize, m: usize) -> usize {
+    let (e1, e2) = (next_halfedge(e), prev_halfedge(e));
+    let b = t.triangles[e1].as_usize();
+    let c = t.triangles[e2].as_usize();
+    let h1 = t.halfedges[e1];
+
+    t.triangles[e1] = I::from_usize(m);
+    let n = t.add_triangle(m, b, c, OptionIndex::none(), h1, OptionIndex::none());
+    link(t, e1, n + 2);
+    t.halfedges[e] = OptionIndex::none();
+    n
+}
+
+/// Makes half-edges `a` and `b` twins.
+fn link<I: Index>(t: &mut Triangulation<I>, a: usize, b: usize) {
+    t.halfedges[a] = OptionIndex::some(I::from_usize(b));
+    t.halfedges[b] = OptionIndex::some(I::from_usize(a));
+}
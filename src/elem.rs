@@ -1,18 +1,18 @@
 use super::iter::*;
-use super::Triangulation;
+use crate::view::TriangulationRef;
 use crate::{
     traits::Index,
     util::{next_halfedge, prev_halfedge},
 };
 
-/// One triangle within a [Triangulation]
+/// One triangle within a [Triangulation](crate::Triangulation)
 pub struct Triangle<'a, I> {
-    pub(crate) triangulation: &'a Triangulation<I>,
+    pub(crate) triangulation: TriangulationRef<'a, I>,
     pub(crate) index: usize,
 }
 
 impl<'a, I: Index> Triangle<'a, I> {
-    /// A fixed identifier for this triangle which can be used to get it from its [Triangulation].
+    /// A fixed identifier for this triangle which can be used to get it from its [Triangulation](crate::Triangulation).
     pub fn id(&self) -> usize {
         self.index / 3
     }
@@ -84,15 +84,15 @@ impl<'a, I: Index> Triangle<'a, I> {
     }
 }
 
-/// One half-edge within a [Triangulation]
+/// One half-edge within a [Triangulation](crate::Triangulation)
 #[derive(Clone, Copy)]
 pub struct HalfEdge<'a, I> {
-    pub(crate) triangulation: &'a Triangulation<I>,
+    pub(crate) triangulation: TriangulationRef<'a, I>,
     pub(crate) index: usize,
 }
 
 impl<'a, I: Index> HalfEdge<'a, I> {
-    /// A fixed identifier for this half-edge which can be used to get it from its [Triangulation].
+    /// A fixed identifier for this half-edge which can be used to get it from its [Triangulation](crate::Triangulation).
     pub fn id(&self) -> usize {
         self.index
     }
@@ -165,15 +165,15 @@ impl<'a, I: Index> HalfEdge<'a, I> {
     }
 }
 
-/// One vertex within a [Triangulation]
+/// One vertex within a [Triangulation](crate::Triangulation)
 #[derive(Clone, Copy)]
 pub struct Vertex<'a, I> {
-    pub(crate) triangulation: &'a Triangulation<I>,
+    pub(crate) triangulation: TriangulationRef<'a, I>,
     pub(crate) index: usize,
 }
 
 impl<'a, I: Index> Vertex<'a, I> {
-    /// A fixed identifier for this vertex which can be used to get it from its [Triangulation].
+    /// A fixed identifier for this vertex which can be used to get it from its [Triangulation](crate::Triangulation).
     pub fn id(&self) -> usize {
         self.triangulation.triangles[self.index].as_usize()
     }
@@ -188,10 +188,48 @@ impl<'a, I: Index> Vertex<'a, I> {
         }
     }
 
+    /// An iterator over the [HalfEdge]s that end at this vertex.
+    pub fn incoming_edges(&self) -> VertexIncomingEdgeIter<'a, I> {
+        VertexIncomingEdgeIter {
+            inner: self.edges(),
+        }
+    }
+
+    /// An iterator over every [HalfEdge] that starts or ends at this vertex,
+    /// visiting each edge exactly once.
+    ///
+    /// Unlike [Vertex::edges], this includes the incoming half-edge on the
+    /// convex hull, which has no outgoing twin.
+    pub fn incident_edges(&self) -> VertexIncidentEdgeIter<'a, I> {
+        VertexIncidentEdgeIter {
+            inner: self.edges(),
+            hull_edge: None,
+        }
+    }
+
     /// An iterator over the [Triangle]s that are adjacent to this vertex.
     pub fn triangles(&self) -> VertexTriangleIter<'a, I> {
         VertexTriangleIter {
             inner: self.edges(),
         }
     }
+
+    /// Like [edges](Self::edges), with the number of edges looked up in
+    /// `degrees`, the table from [Triangulation::degrees](crate::Triangulation::degrees).
+    pub fn exact_edges(&self, degrees: &[usize]) -> ExactVertexIter<VertexEdgeIter<'a, I>> {
+        ExactVertexIter {
+            inner: self.edges(),
+            len: degrees[self.id()],
+        }
+    }
+
+    /// Like [triangles](Self::triangles), with the number of triangles looked
+    /// up in `degrees`, the table from
+    /// [Triangulation::degrees](crate::Triangulation::degrees).
+    pub fn exact_triangles(&self, degrees: &[usize]) -> ExactVertexIter<VertexTriangleIter<'a, I>> {
+        ExactVertexIter {
+            inner: self.triangles(),
+            len: degrees[self.id()],
+        }
+    }
 }
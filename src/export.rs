@@ -0,0 +1,430 @@
+//! Exporting triangulations as 3D meshes, for rendering and 3D printing.
+//!
+//! Exporters implement [MeshSink] and receive vertices, triangles and
+//! per-vertex attributes from [write_triangulation] or
+//! [MeshBuffers::write_to]. The crate provides sinks for Wavefront OBJ, PLY
+//! and legacy VTK files; other formats only need to implement the trait.
+//!
+//! ```rust
+//! use delaunator::{export, Point, Triangulation};
+//!
+//! let points = vec![
+//!     Point { x: 0., y: 0. },
+//!     Point { x: 1., y: 0. },
+//!     Point { x: 1., y: 1. },
+//!     Point { x: 0., y: 1. },
+//! ];
+//! let triangulation = Triangulation::<usize>::new(&points).unwrap();
+//!
+//! // a unit cube: two triangles each on top and bottom and on four sides
+//! let cube = export::extrude(&points, &triangulation, 1.);
+//! assert_eq!(cube.indices.len(), 3 * 12);
+//! assert_eq!(cube.positions.len(), cube.normals.len());
+//! ```
+
+use std::{
+    convert::TryFrom,
+    io::{self, Write},
+};
+
+use crate::{
+    traits::{HasPosition, Index, Scalar},
+    Triangulation,
+};
+
+/// A destination for meshes, such as a file format or a GPU buffer.
+///
+/// A mesh is written as one call to [begin](MeshSink::begin), then every
+/// vertex, then every triangle, then any per-vertex attributes, and finally
+/// [finish](MeshSink::finish).
+pub trait MeshSink {
+    /// Starts a mesh with the given numbers of vertices and triangles.
+    fn begin(&mut self, _vertices: usize, _triangles: usize) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Adds the next vertex.
+    fn add_vertex(&mut self, position: [f64; 3]) -> io::Result<()>;
+
+    /// Adds a triangle by the indices of its vertices, counter-clockwise seen
+    /// from its front.
+    fn add_triangle(&mut self, vertices: [usize; 3]) -> io::Result<()>;
+
+    /// Adds a named value for every vertex. Sinks that can't store attributes
+    /// ignore them.
+    fn add_attribute(&mut self, _name: &str, _values: &[f64]) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Ends the mesh.
+    fn finish(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<S: MeshSink + ?Sized> MeshSink for &mut S {
+    fn begin(&mut self, vertices: usize, triangles: usize) -> io::Result<()> {
+        (**self).begin(vertices, triangles)
+    }
+
+    fn add_vertex(&mut self, position: [f64; 3]) -> io::Result<()> {
+        (**self).add_vertex(position)
+    }
+
+    fn add_triangle(&mut self, vertices: [usize; 3]) -> io::Result<()> {
+        (**self).add_triangle(vertices)
+    }
+
+    fn add_attribute(&mut self, name: &str, values: &[f64]) -> io::Result<()> {
+        (**self).add_attribute(name, values)
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        (**self).finish()
+    }
+}
+
+/// Writes `triangulation` to `sink`, with a vertex for every point at height
+/// `z[i]`, or zero without `z`, and the named per-point `attributes`.
+///
+/// ```rust
+/// use delaunator::{export::{self, ObjWriter}, Point, Triangulation};
+///
+/// let points = vec![Point { x: 0., y: 0. }, Point { x: 1., y: 0. }, Point { x: 0., y: 1. }];
+/// let triangulation = Triangulation::<usize>::new(&points).unwrap();
+///
+/// let mut obj = ObjWriter::new(Vec::new());
+/// export::write_triangulation(&mut obj, &triangulation, &points, None, &[]).unwrap();
+/// let obj = String::from_utf8(obj.into_inner()).unwrap();
+/// assert!(obj.ends_with("f 1 2 3\n"));
+/// ```
+pub fn write_triangulation<S, T, I, P>(
+    mut sink: S,
+    triangulation: &Triangulation<I>,
+    points: &[P],
+    z: Option<&[T]>,
+    attributes: &[(&str, &[T])],
+) -> io::Result<()>
+where
+    S: MeshSink,
+    T: Scalar,
+    I: Index,
+    P: HasPosition<T>,
+{
+    sink.begin(points.len(), triangulation.len())?;
+    for (i, p) in points.iter().enumerate() {
+        let p = p.pos();
+        let height = z.map_or(0.0, |z| z[i].to_f64());
+        sink.add_vertex([p.x.to_f64(), p.y.to_f64(), height])?;
+    }
+    for t in triangulation.triangles.chunks_exact(3) {
+        sink.add_triangle([t[0], t[1], t[2]].map(I::as_usize))?;
+    }
+    let mut buffer = Vec::with_capacity(points.len());
+    for &(name, values) in attributes {
+        buffer.clear();
+        buffer.extend(values.iter().map(|v| v.to_f64()));
+        sink.add_attribute(name, &buffer)?;
+    }
+    sink.finish()
+}
+
+/// The buffers of a triangle mesh, ready to upload to the GPU or write to a
+/// mesh file.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MeshBuffers {
+    /// The position of every vertex, as a flat `[x, y, z, ...]` array.
+    pub positions: Vec<f32>,
+    /// The unit normal of every vertex, as a flat `[x, y, z, ...]` array.
+    pub normals: Vec<f32>,
+    /// Vertex indices where each triple is a triangle, counter-clockwise when
+    /// seen from outside the solid.
+    pub indices: Vec<u32>,
+}
+
+impl MeshBuffers {
+    /// Writes the mesh to `sink`, with the normals as the attributes `nx`, `ny`
+    /// and `nz`.
+    pub fn write_to<S: MeshSink>(&self, mut sink: S) -> io::Result<()> {
+        let vertices = self.positions.len() / 3;
+        sink.begin(vertices, self.indices.len() / 3)?;
+        for p in self.positions.chunks_exact(3) {
+            sink.add_vertex([p[0], p[1], p[2]].map(f64::from))?;
+        }
+        for t in self.indices.chunks_exact(3) {
+            sink.add_triangle([t[0], t[1], t[2]].map(|i| i as usize))?;
+        }
+        for (k, name) in ["nx", "ny", "nz"].iter().enumerate() {
+            let values: Vec<f64> = (0..vertices)
+                .map(|i| f64::from(self.normals[3 * i + k]))
+                .collect();
+            sink.add_attribute(name, &values)?;
+        }
+        sink.finish()
+    }
+
+    /// Adds a vertex and returns its index.
+    fn vertex(&mut self, position: [f32; 3], normal: [f32; 3]) -> u32 {
+        let index = u32::try_from(self.positions.len() / 3)
+            .expect("Too many vertices to index MeshBuffers by u32");
+        self.positions.extend(position);
+        self.normals.extend(normal);
+        index
+    }
+}
+
+/// Extrudes a triangulation into a closed prism between `z = 0` and
+/// `z = height`: the triangulation forms the top, a mirrored copy the bottom,
+/// and a wall of two triangles stands on every edge of the convex hull.
+///
+/// Vertices are repeated where the top, bottom and walls meet so that every
+/// face gets a flat normal, so the walls don't share vertex indices with the
+/// caps and the mesh as indexed is not a manifold. The repeated positions are
+/// identical, though: welding vertices by position (as most 3D printing tools
+/// do) gives a closed surface. A negative `height` extrudes downwards. Points that
+/// aren't part of the triangulation still get (unused) top and bottom vertices,
+/// at the indices of the point and of the point plus `points.len()`.
+///
+/// # Panics
+/// Panics if the mesh has more vertices than fit in a `u32`.
+pub fn extrude<T: Scalar, I: Index, P: HasPosition<T>>(
+    points: &[P],
+    triangulation: &Triangulation<I>,
+    height: T,
+) -> MeshBuffers {
+    let mut mesh = MeshBuffers::default();
+    let height = height.to_f64();
+    let (bottom, top) = (height.min(0.0) as f32, height.max(0.0) as f32);
+    let xy = |i: usize| {
+        let p = points[i].pos();
+        (p.x.to_f64() as f32, p.y.to_f64() as f32)
+    };
+
+    for i in 0..points.len() {
+        let (x, y) = xy(i);
+        mesh.vertex([x, y, top], [0.0, 0.0, 1.0]);
+    }
+    for i in 0..points.len() {
+        let (x, y) = xy(i);
+        mesh.vertex([x, y, bottom], [0.0, 0.0, -1.0]);
+    }
+    let n = points.len() as u32;
+    for t in triangulation.triangles.chunks_exact(3) {
+        let [a, b, c] = [t[0], t[1], t[2]].map(|i| i.as_usize() as u32);
+        mesh.indices.extend([a, b, c]);
+        mesh.indices.extend([n + a, n + c, n + b]);
+    }
+
+    if triangulation.is_empty() {
+        return mesh;
+    }
+    let hull = &triangulation.hull;
+    for k in 0..hull.len() {
+        let (a, b) = (hull[k].as_usize(), hull[(k + 1) % hull.len()].as_usize());
+        let ((ax, ay), (bx, by)) = (xy(a), xy(b));
+        // the hull is counter-clockwise, so the outside is on the right
+        let (nx, ny) = (by - ay, ax - bx);
+        let length = (nx * nx + ny * ny).sqrt();
+        let normal = if length > 0.0 {
+            [nx / length, ny / length, 0.0]
+        } else {
+            [0.0; 3]
+        };
+        let v = [
+            mesh.vertex([ax, ay, bottom], normal),
+            mesh.vertex([bx, by, bottom], normal),
+            mesh.vertex([bx, by, top], normal),
+            mesh.vertex([ax, ay, top], normal),
+        ];
+        mesh.indices.extend([v[0], v[1], v[2], v[0], v[2], v[3]]);
+    }
+    mesh
+}
+
+/// Writes meshes as Wavefront OBJ files. Attributes are ignored.
+#[derive(Debug)]
+pub struct ObjWriter<W> {
+    writer: W,
+}
+
+impl<W: Write> ObjWriter<W> {
+    /// Creates a writer that writes to `writer`.
+    pub fn new(writer: W) -> Self {
+        ObjWriter { writer }
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write> MeshSink for ObjWriter<W> {
+    fn add_vertex(&mut self, [x, y, z]: [f64; 3]) -> io::Result<()> {
+        writeln!(self.writer, "v {} {} {}", x, y, z)
+    }
+
+    fn add_triangle(&mut self, [a, b, c]: [usize; 3]) -> io::Result<()> {
+        // OBJ numbers vertices from one
+        writeln!(self.writer, "f {} {} {}", a + 1, b + 1, c + 1)
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// Writes meshes as ASCII PLY files, with attributes as vertex properties.
+///
+/// The header lists every vertex property before the vertices, so the mesh is
+/// held in memory until [finish](MeshSink::finish).
+#[derive(Debug)]
+pub struct PlyWriter<W> {
+    writer: W,
+    vertices: Vec<[f64; 3]>,
+    triangles: Vec<[usize; 3]>,
+    attributes: Vec<(String, Vec<f64>)>,
+}
+
+impl<W: Write> PlyWriter<W> {
+    /// Creates a writer that writes to `writer`.
+    pub fn new(writer: W) -> Self {
+        PlyWriter {
+            writer,
+            vertices: Vec::new(),
+            triangles: Vec::new(),
+            attributes: Vec::new(),
+        }
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write> MeshSink for PlyWriter<W> {
+    fn begin(&mut self, vertices: usize, triangles: usize) -> io::Result<()> {
+        self.vertices.clear();
+        self.vertices.reserve(vertices);
+        self.triangles.clear();
+        self.triangles.reserve(triangles);
+        self.attributes.clear();
+        Ok(())
+    }
+
+    fn add_vertex(&mut self, position: [f64; 3]) -> io::Result<()> {
+        self.vertices.push(position);
+        Ok(())
+    }
+
+    fn add_triangle(&mut self, vertices: [usize; 3]) -> io::Result<()> {
+        self.triangles.push(vertices);
+        Ok(())
+    }
+
+    fn add_attribute(&mut self, name: &str, values: &[f64]) -> io::Result<()> {
+        self.attributes.push((name.to_string(), values.to_vec()));
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        let w = &mut self.writer;
+        writeln!(w, "ply\nformat ascii 1.0")?;
+        writeln!(w, "element vertex {}", self.vertices.len())?;
+        writeln!(w, "property double x\nproperty double y\nproperty double z")?;
+        for (name, _) in &self.attributes {
+            writeln!(w, "property double {}", name)?;
+        }
+        writeln!(w, "element face {}", self.triangles.len())?;
+        writeln!(w, "property list uchar uint vertex_indices\nend_header")?;
+        for (i, [x, y, z]) in self.vertices.iter().enumerate() {
+            write!(w, "{} {} {}", x, y, z)?;
+            for (_, values) in &self.attributes {
+                write!(w, " {}", values[i])?;
+            }
+            writeln!(w)?;
+        }
+        for [a, b, c] in &self.triangles {
+            writeln!(w, "3 {} {} {}", a, b, c)?;
+        }
+        w.flush()
+    }
+}
+
+/// Writes meshes as legacy ASCII VTK files, for ParaView and VisIt, with
+/// attributes as point data.
+#[derive(Debug)]
+pub struct VtkWriter<W> {
+    writer: W,
+    vertices: usize,
+    triangles: usize,
+    written: usize,
+    has_attributes: bool,
+}
+
+impl<W: Write> VtkWriter<W> {
+    /// Creates a writer that writes to `writer`.
+    pub fn new(writer: W) -> Self {
+        VtkWriter {
+            writer,
+            vertices: 0,
+            triangles: 0,
+            written: 0,
+            has_attributes: false,
+        }
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write> MeshSink for VtkWriter<W> {
+    fn begin(&mut self, vertices: usize, triangles: usize) -> io::Result<()> {
+        self.vertices = vertices;
+        self.triangles = triangles;
+        self.written = 0;
+        self.has_attributes = false;
+        writeln!(self.writer, "# vtk DataFile Version 3.0\ndelaunator\nASCII")?;
+        writeln!(self.writer, "DATASET POLYDATA\nPOINTS {} double", vertices)
+    }
+
+    fn add_vertex(&mut self, [x, y, z]: [f64; 3]) -> io::Result<()> {
+        writeln!(self.writer, "{} {} {}", x, y, z)
+    }
+
+    fn add_triangle(&mut self, [a, b, c]: [usize; 3]) -> io::Result<()> {
+        if self.written == 0 {
+            writeln!(
+                self.writer,
+                "POLYGONS {} {}",
+                self.triangles,
+                4 * self.triangles
+            )?;
+        }
+        self.written += 1;
+        writeln!(self.writer, "3 {} {} {}", a, b, c)
+    }
+
+    fn add_attribute(&mut self, name: &str, values: &[f64]) -> io::Result<()> {
+        if !self.has_attributes {
+            self.has_attributes = true;
+            writeln!(self.writer, "POINT_DATA {}", self.vertices)?;
+        }
+        writeln!(
+            self.writer,
+            "SCALARS {} double 1\nLOOKUP_TABLE default",
+            name
+        )?;
+        for v in values {
+            writeln!(self.writer, "{}", v)?;
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
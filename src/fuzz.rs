@@ -0,0 +1,145 @@
+//! Entry points for fuzzing the construction, e.g. with `cargo fuzz` or
+//! OSS-Fuzz.
+//!
+//! A fuzz target only needs to pass its input to [triangulate]:
+//!
+//! ```rust,ignore
+//! #![no_main]
+//! libfuzzer_sys::fuzz_target!(|data: &[u8]| delaunator::fuzz::triangulate(data));
+//! ```
+//!
+//! [Point] implements `Arbitrary`, so structured fuzzers can also generate
+//! points directly and check the result with [validate].
+
+use std::collections::HashMap;
+
+use arbitrary::Unstructured;
+
+use crate::{
+    traits::Index,
+    util::{next_halfedge, OptionIndex},
+    Point, Triangulation,
+};
+
+/// A broken invariant of a [Triangulation], found by [validate].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Violation {
+    /// `triangles` and `halfedges` differ in length, or the length isn't a
+    /// multiple of three.
+    Length,
+    /// A half-edge starts at a point that does not exist.
+    IndexOutOfBounds { halfedge: usize },
+    /// A triangle has the same point at two corners.
+    DegenerateTriangle { triangle: usize },
+    /// The twin of a half-edge doesn't point back to it or doesn't connect
+    /// the same points in the opposite direction.
+    BrokenTwin { halfedge: usize },
+    /// The hull doesn't match the half-edges without a twin.
+    BrokenHull,
+}
+
+impl std::fmt::Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Violation::Length => write!(f, "triangles and halfedges don't match in length"),
+            Violation::IndexOutOfBounds { halfedge } => {
+                write!(f, "half-edge {} starts at a missing point", halfedge)
+            }
+            Violation::DegenerateTriangle { triangle } => {
+                write!(f, "triangle {} repeats a point", triangle)
+            }
+            Violation::BrokenTwin { halfedge } => {
+                write!(f, "half-edge {} and its twin don't match", halfedge)
+            }
+            Violation::BrokenHull => write!(f, "hull doesn't match the boundary half-edges"),
+        }
+    }
+}
+
+impl std::error::Error for Violation {}
+
+/// Triangulates points decoded from `data` and panics if construction panics
+/// or produces a broken triangulation.
+///
+/// Coordinates that aren't finite are dropped, since they have no place in a
+/// triangulation. Both `usize` and `u32` indices are exercised.
+pub fn triangulate(data: &[u8]) {
+    let mut u = Unstructured::new(data);
+    let points: Vec<Point<f64>> = match u.arbitrary() {
+        Ok(points) => points,
+        Err(_) => return,
+    };
+    let points: Vec<Point<f64>> = points
+        .into_iter()
+        .filter(|p| p.x.is_finite() && p.y.is_finite())
+        .collect();
+
+    if let Some(triangulation) = Triangulation::<usize>::new(&points) {
+        if let Err(violation) = validate(&triangulation, points.len()) {
+            panic!("invalid triangulation of {:?}: {}", points, violation);
+        }
+    }
+    if let Some(triangulation) = Triangulation::<u32>::new(&points) {
+        if let Err(violation) = validate(&triangulation, points.len()) {
+            panic!("invalid triangulation of {:?}: {}", points, violation);
+        }
+    }
+}
+
+/// Checks the structural invariants of a triangulation of `points` points:
+/// consistent array lengths, valid point indices, triangles with three
+/// distinct corners, matching twins, and a hull that follows the half-edges
+/// without a twin counter-clockwise.
+pub fn validate<I: Index>(
+    triangulation: &Triangulation<I>,
+    points: usize,
+) -> Result<(), Violation> {
+    let Triangulation {
+        triangles,
+        halfedges,
+        hull,
+        ..
+    } = triangulation;
+    if triangles.len() != halfedges.len() || triangles.len() % 3 != 0 {
+        return Err(Violation::Length);
+    }
+
+    let point = |e: usize| triangles[e].as_usize();
+    if let Some(halfedge) = (0..triangles.len()).find(|&e| point(e) >= points) {
+        return Err(Violation::IndexOutOfBounds { halfedge });
+    }
+    for (triangle, t) in triangles.chunks_exact(3).enumerate() {
+        if t[0] == t[1] || t[1] == t[2] || t[2] == t[0] {
+            return Err(Violation::DegenerateTriangle { triangle });
+        }
+    }
+
+    // where each hull edge ends, by its start
+    let mut boundary = HashMap::new();
+    for (e, &twin) in halfedges.iter().enumerate() {
+        match twin.get().map(I::as_usize) {
+            Some(f) => {
+                let matches = f < halfedges.len()
+                    && halfedges[f] == OptionIndex::from(I::from_usize(e))
+                    && point(f) == point(next_halfedge(e))
+                    && point(next_halfedge(f)) == point(e);
+                if !matches {
+                    return Err(Violation::BrokenTwin { halfedge: e });
+                }
+            }
+            None => {
+                if boundary.insert(point(e), point(next_halfedge(e))).is_some() {
+                    return Err(Violation::BrokenHull);
+                }
+            }
+        }
+    }
+    let follows = (0..hull.len()).all(|k| {
+        let (a, b) = (hull[k].as_usize(), hull[(k + 1) % hull.len()].as_usize());
+        boundary.get(&a) == Some(&b)
+    });
+    if boundary.len() != hull.len() || !follows {
+        return Err(Violation::BrokenHull);
+    }
+    Ok(())
+}
@@ -0,0 +1,113 @@
+//! Delaunay triangulations and Voronoi diagrams of [geo_types] geometries.
+//!
+//! ```rust
+//! use delaunator::geo::{TriangulateDelaunay, VoronoiDiagram};
+//! use geo_types::{polygon, MultiPoint, Point};
+//!
+//! let points: MultiPoint<f64> = vec![
+//!     Point::new(0., 0.),
+//!     Point::new(1., 0.),
+//!     Point::new(1., 1.),
+//!     Point::new(0., 1.),
+//! ]
+//! .into();
+//!
+//! let triangles = points.delaunay_triangles();
+//! assert_eq!(triangles.len(), 2);
+//!
+//! let bounds = polygon![(x: -1., y: -1.), (x: 2., y: -1.), (x: 2., y: 2.), (x: -1., y: 2.)];
+//! let cells = points.voronoi_cells(&bounds);
+//! assert_eq!(cells.len(), 4);
+//! ```
+
+use geo_types::{Coord, CoordFloat, LineString, MultiPoint, Polygon, Triangle};
+
+use crate::{
+    traits::{ApproxEq, Scalar},
+    voronoi, Point, Triangulation,
+};
+
+impl<T: Scalar + CoordFloat> From<Coord<T>> for Point<T> {
+    fn from(other: Coord<T>) -> Self {
+        Point {
+            x: other.x,
+            y: other.y,
+        }
+    }
+}
+
+impl<T: Scalar + CoordFloat> From<Point<T>> for Coord<T> {
+    fn from(other: Point<T>) -> Self {
+        Coord {
+            x: other.x,
+            y: other.y,
+        }
+    }
+}
+
+/// Delaunay triangulation of a geometry's points.
+pub trait TriangulateDelaunay<T: CoordFloat> {
+    /// The Delaunay triangulation of the points, indexed like them.
+    /// Returns `None` if no triangulation exists (e.g. all points are collinear).
+    fn delaunay_triangulation(&self) -> Option<Triangulation<usize>>;
+
+    /// The triangles of the Delaunay triangulation, counter-clockwise. Empty if
+    /// no triangulation exists.
+    fn delaunay_triangles(&self) -> Vec<Triangle<T>>;
+}
+
+/// Voronoi diagram of a geometry's points.
+pub trait VoronoiDiagram<T: CoordFloat> {
+    /// The Voronoi cell of every point, clipped to the exterior of `bounds` and
+    /// indexed like the points. Duplicate points and cells outside the bounds
+    /// get an empty polygon. See [voronoi::clip_to_polygon].
+    fn voronoi_cells(&self, bounds: &Polygon<T>) -> Vec<Polygon<T>>;
+}
+
+impl<T: Scalar + ApproxEq + CoordFloat> TriangulateDelaunay<T> for MultiPoint<T> {
+    fn delaunay_triangulation(&self) -> Option<Triangulation<usize>> {
+        Triangulation::new(&points(self))
+    }
+
+    fn delaunay_triangles(&self) -> Vec<Triangle<T>> {
+        let points = points(self);
+        let triangulation = match Triangulation::<usize>::new(&points) {
+            Some(triangulation) => triangulation,
+            None => return Vec::new(),
+        };
+        triangulation
+            .triangles
+            .chunks_exact(3)
+            .map(|t| {
+                let [a, b, c] = [t[0], t[1], t[2]].map(|i| points[i].into());
+                Triangle::new(a, b, c)
+            })
+            .collect()
+    }
+}
+
+impl<T: Scalar + ApproxEq + CoordFloat> VoronoiDiagram<T> for MultiPoint<T> {
+    fn voronoi_cells(&self, bounds: &Polygon<T>) -> Vec<Polygon<T>> {
+        let points = points(self);
+        let triangulation = match Triangulation::<usize>::new(&points) {
+            Some(triangulation) => triangulation,
+            None => return Vec::new(),
+        };
+        // geo rings repeat their first coordinate at the end
+        let mut boundary: Vec<Point<T>> = bounds.exterior().coords().map(|&c| c.into()).collect();
+        if boundary.len() > 1 && boundary.first() == boundary.last() {
+            boundary.pop();
+        }
+        voronoi::clip_to_polygon(&triangulation, &points, &boundary)
+            .into_iter()
+            .map(|cell| {
+                let ring: Vec<Coord<T>> = cell.into_iter().map(Coord::from).collect();
+                Polygon::new(LineString::new(ring), Vec::new())
+            })
+            .collect()
+    }
+}
+
+fn points<T: Scalar + CoordFloat>(points: &MultiPoint<T>) -> Vec<Point<T>> {
+    points.iter().map(|p| p.0.into()).collect()
+}
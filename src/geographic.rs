@@ -0,0 +1,142 @@
+//! Triangulation of longitude/latitude points.
+//!
+//! Delaunay triangulation is defined on the plane, so geographic coordinates
+//! are projected first. Point `x` is the longitude and `y` the latitude, both
+//! in degrees; projected coordinates are in meters. Indices in the result refer
+//! to the original points.
+//!
+//! ```rust
+//! use delaunator::{geographic::{Projection, Projector}, Point, Triangulation};
+//!
+//! // a small survey area that straddles the antimeridian
+//! let lonlat = vec![
+//!     Point { x: 179.9, y: -16.9 },
+//!     Point { x: -179.9, y: -16.9 },
+//!     Point { x: -179.9, y: -16.7 },
+//!     Point { x: 179.9, y: -16.7 },
+//! ];
+//!
+//! let (triangulation, projected): (Triangulation<usize>, _) =
+//!     Projector::new(Projection::AzimuthalEquidistant)
+//!         .unwrap_antimeridian(true)
+//!         .triangulate(&lonlat)
+//!         .expect("No triangulation exists.");
+//! assert_eq!(triangulation.len(), 2);
+//! assert!(projected[0].distance(projected[1]) < 25_000.);
+//! ```
+
+use std::f64::consts::FRAC_PI_4;
+
+use crate::{
+    traits::{HasPosition, Index},
+    Point, Triangulation,
+};
+
+/// The mean radius of the Earth in meters.
+pub const EARTH_RADIUS: f64 = 6_371_008.8;
+
+/// The latitude limit of the web mercator projection, in degrees.
+const MERCATOR_MAX_LATITUDE: f64 = 85.051_128_779_806_59;
+
+/// A map projection from longitude/latitude to the plane.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Projection {
+    /// Azimuthal equidistant projection around the centroid of the points.
+    /// Distances from the centroid are preserved, and distortion stays low for
+    /// regional datasets. The recommended choice.
+    AzimuthalEquidistant,
+    /// Spherical (web) mercator projection. Preserves angles, but strongly
+    /// distorts distances away from the equator. Latitudes are clamped to
+    /// ±85.0511°.
+    WebMercator,
+}
+
+/// Projects longitude/latitude points to the plane.
+#[derive(Clone, Copy, Debug)]
+pub struct Projector {
+    projection: Projection,
+    unwrap_antimeridian: bool,
+}
+
+impl Projector {
+    /// Creates a projector using `projection`.
+    pub fn new(projection: Projection) -> Self {
+        Projector {
+            projection,
+            unwrap_antimeridian: false,
+        }
+    }
+
+    /// If `true`, longitudes are unwrapped around the circular mean longitude
+    /// of the points before projecting, so that datasets crossing the ±180°
+    /// meridian stay contiguous under [Projection::WebMercator] and get a
+    /// sensible center under [Projection::AzimuthalEquidistant].
+    /// Defaults to `false`.
+    pub fn unwrap_antimeridian(mut self, unwrap_antimeridian: bool) -> Self {
+        self.unwrap_antimeridian = unwrap_antimeridian;
+        self
+    }
+
+    /// Projects `lonlat` to the plane, in meters.
+    pub fn project<P: HasPosition<f64>>(&self, lonlat: &[P]) -> Vec<Point<f64>> {
+        let mut points: Vec<Point<f64>> = lonlat.iter().map(|p| p.pos()).collect();
+        if points.is_empty() {
+            return points;
+        }
+
+        if self.unwrap_antimeridian {
+            let (sin, cos) = points.iter().fold((0.0, 0.0), |(sin, cos), p| {
+                let lon = p.x.to_radians();
+                (sin + lon.sin(), cos + lon.cos())
+            });
+            let center = f64::atan2(sin, cos).to_degrees();
+            for p in &mut points {
+                p.x = center + (p.x - center + 180.0).rem_euclid(360.0) - 180.0;
+            }
+        }
+
+        match self.projection {
+            Projection::AzimuthalEquidistant => {
+                let n = points.len() as f64;
+                let lon0 = points.iter().map(|p| p.x).sum::<f64>() / n;
+                let lat0 = points.iter().map(|p| p.y).sum::<f64>() / n;
+                let (sin0, cos0) = lat0.to_radians().sin_cos();
+                for p in &mut points {
+                    let (sin, cos) = p.y.to_radians().sin_cos();
+                    let (sin_d, cos_d) = (p.x - lon0).to_radians().sin_cos();
+                    let x = cos * sin_d;
+                    let y = cos0 * sin - sin0 * cos * cos_d;
+                    // angular distance from the center; atan2 stays accurate near zero
+                    let sin_c = x.hypot(y);
+                    let c = sin_c.atan2(sin0 * sin + cos0 * cos * cos_d);
+                    let k = if sin_c > 0.0 { c / sin_c } else { 1.0 };
+                    *p = Point::new(EARTH_RADIUS * k * x, EARTH_RADIUS * k * y);
+                }
+            }
+            Projection::WebMercator => {
+                for p in &mut points {
+                    let lat =
+                        p.y.clamp(-MERCATOR_MAX_LATITUDE, MERCATOR_MAX_LATITUDE)
+                            .to_radians();
+                    *p = Point::new(
+                        EARTH_RADIUS * p.x.to_radians(),
+                        EARTH_RADIUS * (FRAC_PI_4 + lat / 2.0).tan().ln(),
+                    );
+                }
+            }
+        }
+        points
+    }
+
+    /// Projects and triangulates `lonlat`, returning the triangulation along
+    /// with the projected points.
+    /// Returns `None` if no triangulation exists for the input (e.g. all points are collinear).
+    pub fn triangulate<I: Index, P: HasPosition<f64>>(
+        &self,
+        lonlat: &[P],
+    ) -> Option<(Triangulation<I>, Vec<Point<f64>>)> {
+        let points = self.project(lonlat);
+        let triangulation = Triangulation::new(&points)?;
+        Some((triangulation, points))
+    }
+}
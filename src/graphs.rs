@@ -0,0 +1,386 @@
+//! Graph algorithms on the edges of a [Triangulation].
+
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::BinaryHeap,
+};
+
+use crate::{
+    traits::{HasPosition, Index, Scalar},
+    util::next_halfedge,
+    Triangulation,
+};
+
+/// Which Delaunay edges are too long to connect two points of a cluster.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EdgeCutoff<T> {
+    /// Edges longer than this are removed.
+    Length(T),
+    /// Edges are removed if they are more than this many standard deviations
+    /// longer than the mean length of the edges at either of their endpoints.
+    /// Adapts to clusters of different densities.
+    LocalDeviations(T),
+}
+
+/// Groups points into clusters, the connected components that remain after
+/// removing the Delaunay edges selected by `cutoff`.
+///
+/// Returns a cluster label for every point, numbered from zero in order of the
+/// lowest point index in each cluster. Outliers end up in clusters of their own.
+/// Points that aren't part of the triangulation (e.g. skipped duplicates) get
+/// `None`.
+///
+/// ```rust
+/// use delaunator::{graphs::{self, EdgeCutoff}, Point, Triangulation};
+///
+/// let points = vec![
+///     Point { x: 0., y: 0. },
+///     Point { x: 1., y: 0. },
+///     Point { x: 0., y: 1. },
+///     Point { x: 10., y: 0. },
+///     Point { x: 11., y: 0. },
+///     Point { x: 10., y: 1. },
+/// ];
+/// let triangulation = Triangulation::<usize>::new(&points).unwrap();
+///
+/// let labels = graphs::cluster_by_edge_length(&triangulation, &points, EdgeCutoff::Length(2.));
+/// assert_eq!(labels, [Some(0), Some(0), Some(0), Some(1), Some(1), Some(1)]);
+/// ```
+pub fn cluster_by_edge_length<T: Scalar, I: Index, P: HasPosition<T>>(
+    triangulation: &Triangulation<I>,
+    points: &[P],
+    cutoff: EdgeCutoff<T>,
+) -> Vec<Option<usize>> {
+    let zero = T::from_f64(0.0);
+
+    let edges = edge_lengths(triangulation, points);
+
+    let keep: Vec<bool> = match cutoff {
+        EdgeCutoff::Length(max) => edges.iter().map(|&(_, _, len)| len <= max).collect(),
+        EdgeCutoff::LocalDeviations(k) => {
+            // mean and standard deviation of the incident edge lengths per point
+            let mut count = vec![0usize; points.len()];
+            let mut sum = vec![zero; points.len()];
+            let mut sum_squares = vec![zero; points.len()];
+            for &(u, v, len) in &edges {
+                for i in [u, v] {
+                    count[i] += 1;
+                    sum[i] = sum[i] + len;
+                    sum_squares[i] = sum_squares[i] + len * len;
+                }
+            }
+            let limit: Vec<T> = (0..points.len())
+                .map(|i| {
+                    let n = T::from_f64(count[i].max(1) as f64);
+                    let mean = sum[i] / n;
+                    let variance = (sum_squares[i] / n - mean * mean).max(zero);
+                    mean + k * variance.sqrt()
+                })
+                .collect();
+            edges
+                .iter()
+                .map(|&(u, v, len)| len <= limit[u] && len <= limit[v])
+                .collect()
+        }
+    };
+
+    let mut parent: Vec<usize> = (0..points.len()).collect();
+    for (&(u, v, _), _) in edges.iter().zip(&keep).filter(|(_, &keep)| keep) {
+        let (ru, rv) = (find(&mut parent, u), find(&mut parent, v));
+        // the lower index becomes the root, so labels follow point order
+        parent[ru.max(rv)] = ru.min(rv);
+    }
+
+    let mut used = vec![false; points.len()];
+    for &i in &triangulation.triangles {
+        used[i.as_usize()] = true;
+    }
+    let mut labels = vec![None; points.len()];
+    let mut count = 0;
+    for i in 0..points.len() {
+        if !used[i] {
+            continue;
+        }
+        let root = find(&mut parent, i);
+        labels[i] = match labels[root] {
+            Some(label) => Some(label),
+            None => {
+                count += 1;
+                Some(count - 1)
+            }
+        };
+    }
+    labels
+}
+
+/// The Euclidean minimum spanning tree of the points, as `(a, b, length)`
+/// edges in order of increasing length.
+///
+/// The minimum spanning tree is a subgraph of the Delaunay triangulation, so
+/// only its edges are considered. Points that aren't part of the triangulation
+/// are left out.
+pub fn minimum_spanning_tree<T: Scalar, I: Index, P: HasPosition<T>>(
+    triangulation: &Triangulation<I>,
+    points: &[P],
+) -> Vec<(usize, usize, T)> {
+    let mut edges = edge_lengths(triangulation, points);
+    edges.sort_by(|a, b| a.2.total_cmp(&b.2));
+
+    let mut parent: Vec<usize> = (0..points.len()).collect();
+    edges.retain(|&(u, v, _)| {
+        let (ru, rv) = (find(&mut parent, u), find(&mut parent, v));
+        parent[ru] = rv;
+        ru != rv
+    });
+    edges
+}
+
+/// Prunes the Delaunay edges to a sparser graph in which the shortest path
+/// between the endpoints of every Delaunay edge is at most `stretch` times
+/// its length (the greedy spanner), as `(a, b, length)` edges in order of
+/// increasing length.
+///
+/// Edges are taken shortest first and kept only if the edges kept so far don't
+/// already connect their endpoints closely enough. Since the Delaunay graph
+/// itself is a 1.998-spanner, paths between any two points are at most
+/// `1.998 * stretch` times as long as the straight line. The result always
+/// contains the [minimum_spanning_tree]; a `stretch` of one or less keeps every
+/// edge that isn't matched exactly by a detour.
+///
+/// ```rust
+/// use delaunator::{graphs, Point, Triangulation};
+///
+/// let points: Vec<_> = (0..100)
+///     .map(|i| Point { x: (i % 10) as f64, y: (i / 10) as f64 })
+///     .collect();
+/// let triangulation = Triangulation::<usize>::new(&points).unwrap();
+///
+/// // the grid's diagonals are detours of at most sqrt(2) along its sides
+/// let spanner = graphs::greedy_spanner(&triangulation, &points, 1.5);
+/// assert_eq!(spanner.len(), 180);
+/// ```
+pub fn greedy_spanner<T: Scalar, I: Index, P: HasPosition<T>>(
+    triangulation: &Triangulation<I>,
+    points: &[P],
+    stretch: T,
+) -> Vec<(usize, usize, T)> {
+    let mut edges = edge_lengths(triangulation, points);
+    edges.sort_by(|a, b| a.2.total_cmp(&b.2));
+
+    let mut adjacency: Vec<Vec<(usize, T)>> = vec![Vec::new(); points.len()];
+    let mut search = PathSearch::new(points.len());
+    edges.retain(|&(u, v, length)| {
+        if search.within(&adjacency, u, v, length * stretch) {
+            return false;
+        }
+        adjacency[u].push((v, length));
+        adjacency[v].push((u, length));
+        true
+    });
+    edges
+}
+
+/// Dijkstra's algorithm with its buffers kept between searches.
+struct PathSearch<T> {
+    distance: Vec<T>,
+    touched: Vec<usize>,
+    heap: BinaryHeap<Reverse<(Distance, usize)>>,
+}
+
+impl<T: Scalar> PathSearch<T> {
+    fn new(n: usize) -> Self {
+        PathSearch {
+            distance: vec![T::infinity(); n],
+            touched: Vec::new(),
+            heap: BinaryHeap::new(),
+        }
+    }
+
+    /// Whether a path from `from` to `to` of length at most `limit` exists.
+    fn within(&mut self, adjacency: &[Vec<(usize, T)>], from: usize, to: usize, limit: T) -> bool {
+        self.distance[from] = T::from_f64(0.0);
+        self.touched.push(from);
+        self.heap.push(Reverse((Distance(0.0), from)));
+        let mut found = false;
+        while let Some(Reverse((Distance(d), i))) = self.heap.pop() {
+            if i == to {
+                found = true;
+                break;
+            }
+            // skip entries that were superseded by a shorter path
+            if d > self.distance[i].to_f64() {
+                continue;
+            }
+            for &(j, length) in &adjacency[i] {
+                let next = self.distance[i] + length;
+                if next <= limit && next < self.distance[j] {
+                    if self.distance[j] == T::infinity() {
+                        self.touched.push(j);
+                    }
+                    self.distance[j] = next;
+                    self.heap.push(Reverse((Distance(next.to_f64()), j)));
+                }
+            }
+        }
+
+        for i in self.touched.drain(..) {
+            self.distance[i] = T::infinity();
+        }
+        self.heap.clear();
+        found
+    }
+}
+
+/// A totally ordered path length.
+#[derive(Clone, Copy, Debug)]
+struct Distance(f64);
+
+impl PartialEq for Distance {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Distance {}
+
+impl PartialOrd for Distance {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Distance {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.partial_cmp(&other.0).unwrap_or(Ordering::Equal)
+    }
+}
+
+/// The merge history of single-linkage clustering, see [single_linkage].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Dendrogram<T> {
+    /// The merges in order of increasing distance. Points are the clusters
+    /// `0..n` and the `k`-th merge creates cluster `n + k`, as in SciPy's
+    /// linkage matrices.
+    pub merges: Vec<Merge<T>>,
+    used: Vec<bool>,
+}
+
+/// Two clusters joined into one, see [Dendrogram].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Merge<T> {
+    /// The id of one of the merged clusters.
+    pub left: usize,
+    /// The id of the other merged cluster.
+    pub right: usize,
+    /// The distance between the closest points of the two clusters.
+    pub distance: T,
+    /// The number of points in the merged cluster.
+    pub size: usize,
+}
+
+impl<T: Scalar> Dendrogram<T> {
+    /// Labels the clusters that remain when only merges up to `distance` are
+    /// made, numbered from zero in order of the lowest point index in each
+    /// cluster. Points that aren't part of the triangulation get `None`.
+    ///
+    /// This gives the same clusters as cutting all edges longer than
+    /// `distance` with [cluster_by_edge_length].
+    pub fn cut(&self, distance: T) -> Vec<Option<usize>> {
+        let n = self.used.len();
+        let mut parent: Vec<usize> = (0..n + self.merges.len()).collect();
+        for (k, merge) in self.merges.iter().enumerate() {
+            if merge.distance > distance {
+                break;
+            }
+            parent[merge.left] = n + k;
+            parent[merge.right] = n + k;
+        }
+
+        let mut labels = vec![None; n];
+        let mut root_label = vec![None; parent.len()];
+        let mut count = 0;
+        for i in (0..n).filter(|&i| self.used[i]) {
+            let root = find(&mut parent, i);
+            let label = *root_label[root].get_or_insert_with(|| {
+                count += 1;
+                count - 1
+            });
+            labels[i] = Some(label);
+        }
+        labels
+    }
+}
+
+/// Single-linkage hierarchical clustering of the points, built from their
+/// [minimum_spanning_tree].
+///
+/// ```rust
+/// use delaunator::{graphs, Point, Triangulation};
+///
+/// let points = vec![
+///     Point { x: 0., y: 0. },
+///     Point { x: 1., y: 0. },
+///     Point { x: 0., y: 1. },
+///     Point { x: 10., y: 0. },
+///     Point { x: 11., y: 0. },
+/// ];
+/// let triangulation = Triangulation::<usize>::new(&points).unwrap();
+///
+/// let dendrogram = graphs::single_linkage(&triangulation, &points);
+/// assert_eq!(dendrogram.merges.len(), 4);
+/// assert_eq!(dendrogram.merges[3].size, 5);
+/// assert_eq!(dendrogram.cut(2.), [Some(0), Some(0), Some(0), Some(1), Some(1)]);
+/// ```
+pub fn single_linkage<T: Scalar, I: Index, P: HasPosition<T>>(
+    triangulation: &Triangulation<I>,
+    points: &[P],
+) -> Dendrogram<T> {
+    let n = points.len();
+    let mut used = vec![false; n];
+    for &i in &triangulation.triangles {
+        used[i.as_usize()] = true;
+    }
+
+    // union-find over points, tracking the cluster id and size of each root
+    let mut parent: Vec<usize> = (0..n).collect();
+    let mut cluster: Vec<usize> = (0..n).collect();
+    let mut size = vec![1; n];
+    let mut merges = Vec::new();
+    for (u, v, distance) in minimum_spanning_tree(triangulation, points) {
+        let (ru, rv) = (find(&mut parent, u), find(&mut parent, v));
+        merges.push(Merge {
+            left: cluster[ru],
+            right: cluster[rv],
+            distance,
+            size: size[ru] + size[rv],
+        });
+        parent[ru] = rv;
+        cluster[rv] = n + merges.len() - 1;
+        size[rv] += size[ru];
+    }
+    Dendrogram { merges, used }
+}
+
+/// The root of the union-find set containing `i`, halving the path on the way.
+fn find(parent: &mut [usize], mut i: usize) -> usize {
+    while parent[i] != i {
+        parent[i] = parent[parent[i]];
+        i = parent[i];
+    }
+    i
+}
+
+/// Every edge of `triangulation` once, with its length.
+fn edge_lengths<T: Scalar, I: Index, P: HasPosition<T>>(
+    triangulation: &Triangulation<I>,
+    points: &[P],
+) -> Vec<(usize, usize, T)> {
+    triangulation
+        .unique_edges()
+        .map(|e| {
+            let u = triangulation.triangles[e].as_usize();
+            let v = triangulation.triangles[next_halfedge(e)].as_usize();
+            (u, v, points[u].pos().distance(points[v].pos()))
+        })
+        .collect()
+}
@@ -1,5 +1,7 @@
 use crate::{
-    traits::{HasPosition, Index, Scalar},
+    kernel::Kernel,
+    observer::Observer,
+    traits::{Index, PointSource, Scalar},
     util::OptionIndex,
     Point,
 };
@@ -8,11 +10,11 @@ use crate::{
 /// but doesn't need expensive trigonometry.
 fn pseudo_angle<T: Scalar>(p: Point<T>) -> T {
     let k = p.x / (p.x.abs() + p.y.abs());
-    (if p.y > T::from(0.0) {
-        T::from(3.0) - k
+    (if p.y > T::from_f64(0.0) {
+        T::from_f64(3.0) - k
     } else {
-        T::from(1.0) + k
-    }) / T::from(4.0)
+        T::from_f64(1.0) + k
+    }) / T::from_f64(4.0)
 }
 
 // data structure for tracking the edges of the advancing convex hull
@@ -26,46 +28,59 @@ pub(crate) struct Hull<T: Scalar, I> {
 }
 
 impl<T: Scalar, I: Index> Hull<T, I> {
-    pub fn new<P: HasPosition<T>>(
+    pub fn new() -> Self {
+        Self {
+            start: 0,
+            prev: Vec::new(),
+            next: Vec::new(),
+            tri: Vec::new(),
+            hash: Vec::new(),
+            center: Point::new(T::from_f64(0.0), T::from_f64(0.0)),
+        }
+    }
+
+    /// Resets the hull to the seed triangle of `n` points, reusing its allocations.
+    pub fn init<S: PointSource<T> + ?Sized>(
+        &mut self,
         n: usize,
         center: Point<T>,
         i0: usize,
         i1: usize,
         i2: usize,
-        points: &[P],
-    ) -> Self {
+        points: &S,
+    ) {
         let hash_len = (n as f64).sqrt() as usize;
 
-        let mut hull = Self {
-            prev: vec![Default::default(); n],        // vertex to prev vertex
-            next: vec![Default::default(); n],        // vertex to next vertex
-            tri: vec![Default::default(); n],         // vertex to adjacent halfedge
-            hash: vec![Default::default(); hash_len], // angular edge hash
-            start: i0,
-            center,
-        };
-
-        hull.next[i0] = i1.into();
-        hull.prev[i2] = i1.into();
-        hull.next[i1] = i2.into();
-        hull.prev[i0] = i2.into();
-        hull.next[i2] = i0.into();
-        hull.prev[i1] = i0.into();
+        for v in [&mut self.prev, &mut self.next] {
+            v.clear();
+            v.resize(n, Default::default()); // vertex to prev/next vertex
+        }
+        self.tri.clear();
+        self.tri.resize(n, Default::default()); // vertex to adjacent halfedge
+        self.hash.clear();
+        self.hash.resize(hash_len, Default::default()); // angular edge hash
+        self.start = i0;
+        self.center = center;
 
-        hull.tri[i0] = I::from_usize(0).into();
-        hull.tri[i1] = I::from_usize(1).into();
-        hull.tri[i2] = I::from_usize(2).into();
+        self.next[i0] = i1.into();
+        self.prev[i2] = i1.into();
+        self.next[i1] = i2.into();
+        self.prev[i0] = i2.into();
+        self.next[i2] = i0.into();
+        self.prev[i1] = i0.into();
 
-        hull.hash_edge(points[i0].pos(), i0);
-        hull.hash_edge(points[i1].pos(), i1);
-        hull.hash_edge(points[i2].pos(), i2);
+        self.tri[i0] = I::from_usize(0).into();
+        self.tri[i1] = I::from_usize(1).into();
+        self.tri[i2] = I::from_usize(2).into();
 
-        hull
+        self.hash_edge(points.point(i0), i0);
+        self.hash_edge(points.point(i1), i1);
+        self.hash_edge(points.point(i2), i2);
     }
 
     fn hash_key(&self, p: Point<T>) -> usize {
         let len = self.hash.len();
-        ((T::from(len as f32) * pseudo_angle(p - self.center)).into() as usize) % len
+        ((T::from_f64(len as f64) * pseudo_angle(p - self.center)).to_f64() as usize) % len
     }
 
     pub(crate) fn hash_edge(&mut self, p: Point<T>, i: usize) {
@@ -73,15 +88,23 @@ impl<T: Scalar, I: Index> Hull<T, I> {
         self.hash[key] = i.into();
     }
 
-    pub(crate) fn find_visible_edge<P: HasPosition<T>>(
+    pub(crate) fn find_visible_edge<K, S, O>(
         &self,
         p: Point<T>,
-        points: &[P],
-    ) -> (Option<usize>, bool) {
+        points: &S,
+        observer: &mut O,
+    ) -> (Option<usize>, bool)
+    where
+        K: Kernel<T>,
+        S: PointSource<T> + ?Sized,
+        O: Observer<T, I>,
+    {
         let mut start = OptionIndex::none();
         let key = self.hash_key(p);
         let len = self.hash.len();
+        let mut probes = 0;
         for j in 0..len {
+            probes += 1;
             start = self.hash[(key + j) % len];
             if start.get().and_then(|x| self.next[x].get()).is_some() {
                 break;
@@ -89,13 +112,17 @@ impl<T: Scalar, I: Index> Hull<T, I> {
         }
         let start = self.prev[start.unwrap()].unwrap();
         let mut e = start;
+        let mut steps = 0;
 
-        while !p.is_clockwise(points[e].pos(), points[self.next[e].unwrap()].pos()) {
+        while !K::is_clockwise(p, points.point(e), points.point(self.next[e].unwrap())) {
             e = self.next[e].unwrap();
+            steps += 1;
             if e == start {
+                observer.hull_search(probes, steps, false);
                 return (None, false);
             }
         }
+        observer.hull_search(probes, steps, true);
         (Some(e), e == start)
     }
 
@@ -113,3 +140,24 @@ impl<T: Scalar, I: Index> Hull<T, I> {
         }
     }
 }
+
+/// Buffers used during construction, kept between runs by a
+/// [Triangulator](crate::builder::Triangulator).
+pub(crate) struct Scratch<T: Scalar, I> {
+    pub(crate) hull: Hull<T, I>,
+    pub(crate) dists: Vec<(usize, T)>,
+    pub(crate) stack: Vec<usize>,
+    /// Half-edges of a construction that only returns the triangles.
+    pub(crate) halfedges: Vec<OptionIndex<I>>,
+}
+
+impl<T: Scalar, I: Index> Scratch<T, I> {
+    pub(crate) fn new() -> Self {
+        Scratch {
+            hull: Hull::new(),
+            dists: Vec::new(),
+            stack: Vec::new(),
+            halfedges: Vec::new(),
+        }
+    }
+}
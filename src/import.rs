@@ -0,0 +1,247 @@
+//! Building a [Triangulation] from triangles produced elsewhere.
+
+use std::collections::HashMap;
+
+use crate::{
+    traits::{HasPosition, Index, Scalar},
+    util::{self, OptionIndex},
+    Triangulation,
+};
+
+/// The reasons a list of triangles cannot be turned into a [Triangulation].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TopologyError {
+    /// The length of the triangle array is not a multiple of three.
+    IncompleteTriangle,
+    /// A triangle references a point that does not exist.
+    IndexOutOfBounds { triangle: usize },
+    /// A triangle has zero area.
+    DegenerateTriangle { triangle: usize },
+    /// An edge is shared by more than two triangles, or by two triangles
+    /// that do not agree on its orientation.
+    NonManifoldEdge { start: usize, end: usize },
+    /// The triangles around a point do not form a single fan.
+    NonManifoldVertex { vertex: usize },
+    /// There are no triangles.
+    Empty,
+}
+
+impl std::fmt::Display for TopologyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            TopologyError::IncompleteTriangle => {
+                write!(f, "triangle array length is not a multiple of three")
+            }
+            TopologyError::IndexOutOfBounds { triangle } => {
+                write!(f, "triangle {} references a missing point", triangle)
+            }
+            TopologyError::DegenerateTriangle { triangle } => {
+                write!(f, "triangle {} has zero area", triangle)
+            }
+            TopologyError::NonManifoldEdge { start, end } => {
+                write!(f, "edge {}-{} is not manifold", start, end)
+            }
+            TopologyError::NonManifoldVertex { vertex } => {
+                write!(
+                    f,
+                    "triangles around point {} do not form a single fan",
+                    vertex
+                )
+            }
+            TopologyError::Empty => write!(f, "no triangles"),
+        }
+    }
+}
+
+impl std::error::Error for TopologyError {}
+
+impl<I: Index> Triangulation<I> {
+    /// Builds a Delaunay triangulation from an arbitrary triangle mesh over `points`.
+    ///
+    /// Triangles may be given in either winding order. Half-edges are rebuilt from
+    /// the shared edges, then interior edges are flipped until the mesh satisfies
+    /// the Delaunay condition. The boundary of the mesh is kept as is, so a mesh
+    /// with a non-convex boundary yields a Delaunay triangulation of that domain
+    /// and `hull` lists its outer boundary.
+    pub fn from_triangles<T: Scalar, P: HasPosition<T>>(
+        points: &[P],
+        triangles: Vec<I>,
+    ) -> Result<Self, TopologyError> {
+        let mut triangulation = Triangulation::from_mesh(points, triangles)?;
+        triangulation.legalize_all(points);
+
+        #[cfg(feature = "vertices")]
+        triangulation.update_vertices(points.len());
+
+        Ok(triangulation)
+    }
+
+    /// Builds a triangulation from an existing triangle mesh over `points` without
+    /// changing its triangles, so meshes from other tools can be navigated with the
+    /// element and iterator API.
+    ///
+    /// Triangles may be given in either winding order; clockwise triangles are
+    /// reversed. The mesh must be manifold: every edge is shared by at most two
+    /// triangles and the triangles around each point form a single fan. `hull`
+    /// lists the outer boundary of the mesh, which need not be convex.
+    pub fn from_mesh<T: Scalar, P: HasPosition<T>>(
+        points: &[P],
+        mut triangles: Vec<I>,
+    ) -> Result<Self, TopologyError> {
+        if triangles.len() % 3 != 0 {
+            return Err(TopologyError::IncompleteTriangle);
+        }
+        if triangles.is_empty() {
+            return Err(TopologyError::Empty);
+        }
+
+        for (t, tri) in triangles.chunks_exact_mut(3).enumerate() {
+            if tri.iter().any(|i| i.as_usize() >= points.len()) {
+                return Err(TopologyError::IndexOutOfBounds { triangle: t });
+            }
+            let a = points[tri[0].as_usize()].pos();
+            let b = points[tri[1].as_usize()].pos();
+            let c = points[tri[2].as_usize()].pos();
+            if a.is_clockwise(b, c) {
+                tri.swap(1, 2);
+            } else if !c.is_clockwise(b, a) {
+                return Err(TopologyError::DegenerateTriangle { triangle: t });
+            }
+        }
+
+        let halfedges = link_halfedges(&triangles)?;
+        check_fans(points.len(), &triangles, &halfedges)?;
+        let hull = trace_boundary(points, &triangles, &halfedges)?;
+
+        #[allow(unused_mut)]
+        let mut triangulation = Triangulation {
+            #[cfg(feature = "vertices")]
+            vertices: Vec::new(),
+            triangles,
+            halfedges,
+            hull,
+        };
+
+        #[cfg(feature = "vertices")]
+        triangulation.update_vertices(points.len());
+
+        Ok(triangulation)
+    }
+}
+
+/// Pairs every half-edge with the half-edge running the other way along the same edge.
+fn link_halfedges<I: Index>(triangles: &[I]) -> Result<Vec<OptionIndex<I>>, TopologyError> {
+    let mut halfedges = vec![OptionIndex::none(); triangles.len()];
+    let mut edges = HashMap::with_capacity(triangles.len());
+
+    for e in 0..triangles.len() {
+        let start = triangles[e].as_usize();
+        let end = triangles[util::next_halfedge(e)].as_usize();
+        if edges.insert((start, end), e).is_some() {
+            return Err(TopologyError::NonManifoldEdge { start, end });
+        }
+        if let Some(&twin) = edges.get(&(end, start)) {
+            if halfedges[twin].is_some() {
+                return Err(TopologyError::NonManifoldEdge { start, end });
+            }
+            halfedges[e] = I::from_usize(twin).into();
+            halfedges[twin] = I::from_usize(e).into();
+        }
+    }
+
+    Ok(halfedges)
+}
+
+/// Checks that the triangles around every point form a single fan.
+fn check_fans<I: Index>(
+    n: usize,
+    triangles: &[I],
+    halfedges: &[OptionIndex<I>],
+) -> Result<(), TopologyError> {
+    let mut degree = vec![0usize; n];
+    let mut first = vec![None; n];
+    for (e, &v) in triangles.iter().enumerate() {
+        let v = v.as_usize();
+        degree[v] += 1;
+        // start from a boundary edge if there is one so the walk covers the whole fan
+        if first[v].is_none() || halfedges[util::prev_halfedge(e)].is_none() {
+            first[v] = Some(e);
+        }
+    }
+
+    for (vertex, start) in first.into_iter().enumerate() {
+        let start = match start {
+            None => continue,
+            Some(e) => e,
+        };
+        let mut count = 0;
+        let mut e = start;
+        loop {
+            count += 1;
+            match halfedges[e].get() {
+                Some(twin) => {
+                    e = util::next_halfedge(twin.as_usize());
+                    if e == start {
+                        break;
+                    }
+                }
+                None => break,
+            }
+        }
+        if count != degree[vertex] {
+            return Err(TopologyError::NonManifoldVertex { vertex });
+        }
+    }
+
+    Ok(())
+}
+
+/// Finds the boundary loop enclosing the largest area, counter-clockwise.
+fn trace_boundary<T: Scalar, P: HasPosition<T>, I: Index>(
+    points: &[P],
+    triangles: &[I],
+    halfedges: &[OptionIndex<I>],
+) -> Result<Vec<I>, TopologyError> {
+    let mut next = HashMap::new();
+    let mut starts = Vec::new();
+    for (e, h) in halfedges.iter().enumerate() {
+        if h.is_none() {
+            let start = triangles[e].as_usize();
+            let end = triangles[util::next_halfedge(e)];
+            if next.insert(start, end).is_some() {
+                return Err(TopologyError::NonManifoldVertex { vertex: start });
+            }
+            starts.push(start);
+        }
+    }
+
+    let mut hull = Vec::new();
+    let mut max_area = T::from_f64(0.0);
+    let mut visited = vec![false; points.len()];
+    for start in starts {
+        if visited[start] {
+            continue;
+        }
+
+        let mut boundary = Vec::new();
+        let mut area = T::from_f64(0.0);
+        let mut v = start;
+        loop {
+            visited[v] = true;
+            boundary.push(I::from_usize(v));
+            let w = next[&v].as_usize();
+            area = area + points[v].pos().perp_dot(points[w].pos());
+            v = w;
+            if v == start {
+                break;
+            }
+        }
+
+        if hull.is_empty() || area > max_area {
+            hull = boundary;
+            max_area = area;
+        }
+    }
+
+    Ok(hull)
+}
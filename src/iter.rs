@@ -1,10 +1,12 @@
-use std::iter::FusedIterator;
+use std::{collections::HashSet, iter::FusedIterator};
 
 use super::elem::*;
-use super::Triangulation;
+use crate::view::TriangulationRef;
 use crate::{
-    traits::Index,
+    query::triangle_intersects_bbox,
+    traits::{HasPosition, Index, Scalar},
     util::{next_halfedge, prev_halfedge},
+    Point,
 };
 
 /// Iterates over all [HalfEdge]s that start at a [Vertex].
@@ -14,9 +16,10 @@ use crate::{
 ///
 /// Note that on the convex hull, one half-edge connected to the vertex does
 /// not start at that vertex and therefore will not be visited by this iteration.
+/// Use [VertexIncidentEdgeIter] to visit it as well.
 #[derive(Clone, Copy)]
 pub struct VertexEdgeIter<'a, I> {
-    pub(crate) triangulation: &'a Triangulation<I>,
+    pub(crate) triangulation: TriangulationRef<'a, I>,
     pub(crate) start: Option<usize>,
     pub(crate) index: Option<usize>,
 }
@@ -59,10 +62,82 @@ impl<'a, I: Index> Iterator for VertexEdgeIter<'a, I> {
             }
         }
     }
+
+    /// At least one more edge if the iteration isn't done, and at most one
+    /// per triangle. See [Vertex::exact_edges] for the exact count.
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.index {
+            None => (0, Some(0)),
+            Some(_) => (1, Some(self.triangulation.triangles.len() / 3)),
+        }
+    }
 }
 
 impl<'a, I: Index> FusedIterator for VertexEdgeIter<'a, I> {}
 
+/// Iterates over all [HalfEdge]s that end at a [Vertex].
+///
+/// Visits one half-edge per adjacent [Triangle], in the same order as [VertexEdgeIter].
+/// Unlike [VertexEdgeIter], this includes the half-edge arriving along the
+/// convex hull.
+#[derive(Clone, Copy)]
+pub struct VertexIncomingEdgeIter<'a, I> {
+    pub(crate) inner: VertexEdgeIter<'a, I>,
+}
+
+impl<'a, I: Index> Iterator for VertexIncomingEdgeIter<'a, I> {
+    type Item = HalfEdge<'a, I>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|x| x.prev())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, I: Index> FusedIterator for VertexIncomingEdgeIter<'a, I> {}
+
+/// Iterates over every [HalfEdge] incident to a [Vertex] exactly once.
+///
+/// Yields the same half-edges as [VertexEdgeIter], followed by the incoming
+/// half-edge on the convex hull if the vertex is on the hull.
+#[derive(Clone, Copy)]
+pub struct VertexIncidentEdgeIter<'a, I> {
+    pub(crate) inner: VertexEdgeIter<'a, I>,
+    pub(crate) hull_edge: Option<usize>,
+}
+
+impl<'a, I: Index> Iterator for VertexIncidentEdgeIter<'a, I> {
+    type Item = HalfEdge<'a, I>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.inner.next() {
+            Some(edge) => {
+                let prev = prev_halfedge(edge.index);
+                if self.inner.triangulation.halfedges[prev].is_none() {
+                    self.hull_edge = Some(prev);
+                }
+                Some(edge)
+            }
+            None => self.hull_edge.take().map(|index| HalfEdge {
+                triangulation: self.inner.triangulation,
+                index,
+            }),
+        }
+    }
+
+    /// Like [VertexEdgeIter], plus the incoming half-edge on the convex hull.
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.inner.size_hint();
+        let hull = usize::from(self.hull_edge.is_some());
+        (lower.max(hull), upper.map(|upper| upper + 1))
+    }
+}
+
+impl<'a, I: Index> FusedIterator for VertexIncidentEdgeIter<'a, I> {}
+
 /// Iterates over all [Triangle]s that are adjacent to [Vertex].
 ///
 /// Order of iteration is undefined (generally counter-clockwise, but will
@@ -78,14 +153,46 @@ impl<'a, I: Index> Iterator for VertexTriangleIter<'a, I> {
     fn next(&mut self) -> Option<Self::Item> {
         self.inner.next().map(|x| x.left())
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
 }
 
 impl<'a, I: Index> FusedIterator for VertexTriangleIter<'a, I> {}
 
+/// An iterator around a [Vertex] whose length comes from a table of degrees,
+/// so that it implements [ExactSizeIterator].
+///
+/// Created by [Vertex::exact_edges] and [Vertex::exact_triangles].
+#[derive(Clone, Copy)]
+pub struct ExactVertexIter<It> {
+    pub(crate) inner: It,
+    pub(crate) len: usize,
+}
+
+impl<It: Iterator> Iterator for ExactVertexIter<It> {
+    type Item = It::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.inner.next()?;
+        self.len = self.len.saturating_sub(1);
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<It: FusedIterator> FusedIterator for ExactVertexIter<It> {}
+
+impl<It: Iterator> ExactSizeIterator for ExactVertexIter<It> {}
+
 /// Iterates over the three [HalfEdge]s of a [Triangle]
 #[derive(Clone, Copy)]
 pub struct TriangleEdgeIter<'a, I> {
-    pub(crate) triangulation: &'a Triangulation<I>,
+    pub(crate) triangulation: TriangulationRef<'a, I>,
     pub(crate) index: usize,
     pub(crate) end: usize,
 }
@@ -133,7 +240,7 @@ impl<'a, I> ExactSizeIterator for TriangleEdgeIter<'a, I> {
 /// Iterates over the three [Vertex]s of a [Triangle]
 #[derive(Clone, Copy)]
 pub struct TriangleVertexIter<'a, I> {
-    pub(crate) triangulation: &'a Triangulation<I>,
+    pub(crate) triangulation: TriangulationRef<'a, I>,
     pub(crate) index: usize,
     pub(crate) end: usize,
 }
@@ -183,10 +290,10 @@ impl<'a, I> ExactSizeIterator for TriangleVertexIter<'a, I> {
     }
 }
 
-/// Iterates over the [Triangle]s in a [Triangulation]
+/// Iterates over the [Triangle]s in a [Triangulation](crate::Triangulation)
 #[derive(Clone, Copy)]
 pub struct TriangleIter<'a, I> {
-    pub(crate) triangulation: &'a Triangulation<I>,
+    pub(crate) triangulation: TriangulationRef<'a, I>,
     pub(crate) index: usize,
     pub(crate) end: usize,
 }
@@ -236,10 +343,10 @@ impl<'a, I> ExactSizeIterator for TriangleIter<'a, I> {
     }
 }
 
-/// Iterates over the [HalfEdge]s in a [Triangulation]
+/// Iterates over the [HalfEdge]s in a [Triangulation](crate::Triangulation)
 #[derive(Clone, Copy)]
 pub struct HalfEdgeIter<'a, I> {
-    pub(crate) triangulation: &'a Triangulation<I>,
+    pub(crate) triangulation: TriangulationRef<'a, I>,
     pub(crate) index: usize,
     pub(crate) end: usize,
 }
@@ -290,9 +397,9 @@ impl<'a, I> ExactSizeIterator for HalfEdgeIter<'a, I> {
 }
 
 #[cfg(feature = "vertices")]
-/// Iterates over the [Vertex]es in a [Triangulation]
+/// Iterates over the [Vertex]es in a [Triangulation](crate::Triangulation)
 pub struct VertexIter<'a, I> {
-    pub(crate) triangulation: &'a Triangulation<I>,
+    pub(crate) triangulation: TriangulationRef<'a, I>,
     pub(crate) index: usize,
     pub(crate) end: usize,
 }
@@ -341,6 +448,104 @@ impl<'a, I: Index> ExactSizeIterator for VertexIter<'a, I> {
     }
 }
 
+/// Iterates over the [Triangle]s that intersect an axis-aligned box.
+///
+/// Order of iteration is undefined. Created by [Triangulation::triangles_in_bbox](crate::Triangulation::triangles_in_bbox).
+pub struct BBoxTriangleIter<'a, T: Scalar, I, P> {
+    pub(crate) triangulation: TriangulationRef<'a, I>,
+    pub(crate) points: &'a [P],
+    pub(crate) min: Point<T>,
+    pub(crate) max: Point<T>,
+    pub(crate) stack: Vec<usize>,
+    pub(crate) visited: HashSet<usize>,
+}
+
+impl<'a, T: Scalar, I: Index, P: HasPosition<T>> BBoxTriangleIter<'a, T, I, P> {
+    pub(crate) fn push(&mut self, t: usize) {
+        if self.visited.insert(t) {
+            self.stack.push(t);
+        }
+    }
+
+    pub(crate) fn intersects(&self, t: usize) -> bool {
+        let pos = |e: usize| self.points[self.triangulation.triangles[e].as_usize()].pos();
+        triangle_intersects_bbox(
+            [pos(3 * t), pos(3 * t + 1), pos(3 * t + 2)],
+            self.min,
+            self.max,
+        )
+    }
+}
+
+impl<'a, T: Scalar, I: Index, P: HasPosition<T>> Iterator for BBoxTriangleIter<'a, T, I, P> {
+    type Item = Triangle<'a, I>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let t = self.stack.pop()?;
+        for e in 3 * t..3 * t + 3 {
+            if let Some(twin) = self.triangulation.halfedges[e].get() {
+                let u = twin.as_usize() / 3;
+                if !self.visited.contains(&u) && self.intersects(u) {
+                    self.push(u);
+                }
+            }
+        }
+        Some(Triangle {
+            triangulation: self.triangulation,
+            index: 3 * t,
+        })
+    }
+}
+
+impl<'a, T: Scalar, I: Index, P: HasPosition<T>> FusedIterator for BBoxTriangleIter<'a, T, I, P> {}
+
+/// A space-filling curve used to order elements spatially.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SpatialOrder {
+    /// Hilbert curve order; the best locality.
+    Hilbert,
+    /// Morton (Z-order) curve order; cheaper to compute.
+    Morton,
+}
+
+/// Iterates over the [Triangle]s in a [Triangulation](crate::Triangulation) in the order of a
+/// space-filling curve through their centroids.
+///
+/// Created by [Triangulation::spatially_ordered_triangles](crate::Triangulation::spatially_ordered_triangles).
+#[derive(Clone)]
+pub struct SpatialTriangleIter<'a, I> {
+    pub(crate) triangulation: TriangulationRef<'a, I>,
+    pub(crate) order: std::vec::IntoIter<usize>,
+}
+
+impl<'a, I> Iterator for SpatialTriangleIter<'a, I> {
+    type Item = Triangle<'a, I>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.order.next().map(|t| Triangle {
+            triangulation: self.triangulation,
+            index: 3 * t,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.order.size_hint()
+    }
+}
+
+impl<'a, I> DoubleEndedIterator for SpatialTriangleIter<'a, I> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.order.next_back().map(|t| Triangle {
+            triangulation: self.triangulation,
+            index: 3 * t,
+        })
+    }
+}
+
+impl<'a, I> FusedIterator for SpatialTriangleIter<'a, I> {}
+
+impl<'a, I> ExactSizeIterator for SpatialTriangleIter<'a, I> {}
+
 #[cfg(test)]
 mod test {
     use crate::{Point, Triangulation};
@@ -377,5 +582,74 @@ mod test {
         let vertex = triangle.vertices().find(|x| x.id() == 2).unwrap();
         let edges = vertex.edges().map(|x| x.id()).collect::<Vec<_>>();
         assert_eq!(edges, vec![1, 3]);
+
+        let mut iter = vertex.edges();
+        assert_eq!(iter.size_hint(), (1, Some(4)));
+        iter.next();
+        iter.next();
+        assert_eq!(iter.size_hint(), (0, Some(0)));
+
+        let degrees = triangulation.degrees(points.len());
+        assert_eq!(degrees, vec![4, 2, 2, 2, 2]);
+        let mut iter = vertex.exact_edges(&degrees);
+        assert_eq!(iter.len(), 2);
+        iter.next();
+        assert_eq!(iter.size_hint(), (1, Some(1)));
+        assert_eq!(vertex.exact_triangles(&degrees).len(), 2);
+        let incident = vertex.incident_edges();
+        assert!(incident.size_hint().0 <= 3 && incident.size_hint().1 >= Some(3));
+    }
+
+    #[test]
+    fn test_vertex_incident_edge_iter() {
+        let points = [
+            Point::new(0.0, 0.0),
+            Point::new(1.0, 0.0),
+            Point::new(0.0, 1.0),
+            Point::new(-1.0, 0.0),
+            Point::new(0.0, -1.0),
+        ];
+
+        let triangulation = Triangulation::<usize>::new(&points).unwrap();
+        let triangle = triangulation.get_triangle(0).unwrap();
+
+        // Interior vertices have no incoming hull edge
+        let vertex = triangle.vertices().find(|x| x.id() == 0).unwrap();
+        let edges = vertex.incident_edges().map(|x| x.id()).collect::<Vec<_>>();
+        assert_eq!(edges, vec![2, 5, 8, 9]);
+
+        // Hull vertices also see the incoming hull edge
+        let vertex = triangle.vertices().find(|x| x.id() == 1).unwrap();
+        let edges = vertex.incident_edges().map(|x| x.id()).collect::<Vec<_>>();
+        assert_eq!(edges, vec![0, 11, 10]);
+
+        let vertex = triangle.vertices().find(|x| x.id() == 2).unwrap();
+        let edges = vertex.incident_edges().map(|x| x.id()).collect::<Vec<_>>();
+        assert_eq!(edges, vec![1, 3, 0]);
+    }
+
+    #[test]
+    fn test_vertex_incoming_edge_iter() {
+        let points = [
+            Point::new(0.0, 0.0),
+            Point::new(1.0, 0.0),
+            Point::new(0.0, 1.0),
+            Point::new(-1.0, 0.0),
+            Point::new(0.0, -1.0),
+        ];
+
+        let triangulation = Triangulation::<usize>::new(&points).unwrap();
+        for vertex in triangulation.triangles().flat_map(|t| t.vertices()) {
+            assert!(vertex.incoming_edges().all(|e| e.end().id() == vertex.id()));
+        }
+
+        let triangle = triangulation.get_triangle(0).unwrap();
+        let vertex = triangle.vertices().find(|x| x.id() == 0).unwrap();
+        let edges = vertex.incoming_edges().map(|x| x.id()).collect::<Vec<_>>();
+        assert_eq!(edges, vec![1, 4, 7, 11]);
+
+        let vertex = triangle.vertices().find(|x| x.id() == 1).unwrap();
+        let edges = vertex.incoming_edges().map(|x| x.id()).collect::<Vec<_>>();
+        assert_eq!(edges, vec![2, 10]);
     }
 }
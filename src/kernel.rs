@@ -0,0 +1,276 @@
+//! Geometric kernels: the predicates and constructions used during triangulation.
+//!
+//! The default [FloatKernel] evaluates everything directly in floating point,
+//! which is fast but can misjudge nearly degenerate configurations. For `f32`
+//! inputs, [PromotedKernel] does the same math in `f64`, which avoids most of
+//! these failures at little cost. With the
+//! `robust` feature, [RobustKernel] evaluates the predicates exactly using
+//! adaptive-precision arithmetic, at a small cost in speed, and
+//! [IntervalKernel] first tries to certify their signs with interval
+//! arithmetic and only falls back to the exact evaluation when that fails.
+//!
+//! ```rust
+//! use delaunator::{builder::TriangulationBuilder, kernel::FloatKernel, Point, Triangulation};
+//!
+//! let points = vec![
+//!     Point { x: 0., y: 0. },
+//!     Point { x: 1., y: 0. },
+//!     Point { x: 1., y: 1. },
+//!     Point { x: 0., y: 1. },
+//! ];
+//!
+//! let result: Triangulation<usize> = TriangulationBuilder::new()
+//!     .kernel::<FloatKernel>()
+//!     .build(&points)
+//!     .expect("No triangulation exists.");
+//! ```
+
+use std::cmp::Ordering;
+
+use crate::{traits::Scalar, Point};
+
+/// The geometric predicates and constructions used to build a triangulation.
+pub trait Kernel<T: Scalar> {
+    /// Tests if the path `p` to `q` to `r` goes in a clockwise direction
+    /// (see [Point::is_clockwise]).
+    fn is_clockwise(p: Point<T>, q: Point<T>, r: Point<T>) -> bool;
+
+    /// The sign of the in-circle determinant of `p` with respect to `a`, `b`
+    /// and `c` (see [Point::in_circle]): `Greater` if `p` is inside the circle,
+    /// `Less` if it is outside and `Equal` if all four points are cocircular.
+    fn in_circle(p: Point<T>, a: Point<T>, b: Point<T>, c: Point<T>) -> Ordering;
+
+    /// The center of the circumcircle of `a`, `b` and `c`.
+    fn circumcenter(a: Point<T>, b: Point<T>, c: Point<T>) -> Point<T> {
+        a.circumcenter(b, c)
+    }
+
+    /// The square of the radius of the circumcircle of `a`, `b` and `c`.
+    fn circumradius_squared(a: Point<T>, b: Point<T>, c: Point<T>) -> T {
+        a.circumradius_squared(b, c)
+    }
+}
+
+/// Plain floating point arithmetic. The default kernel.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FloatKernel;
+
+impl<T: Scalar> Kernel<T> for FloatKernel {
+    #[inline(always)]
+    fn is_clockwise(p: Point<T>, q: Point<T>, r: Point<T>) -> bool {
+        p.is_clockwise(q, r)
+    }
+
+    #[inline(always)]
+    fn in_circle(p: Point<T>, a: Point<T>, b: Point<T>, c: Point<T>) -> Ordering {
+        p.in_circle(a, b, c)
+            .partial_cmp(&T::from_f64(0.0))
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+/// Evaluates the predicates and constructions for `f32` points in `f64`.
+///
+/// `f32` has too little precision for the in-circle test on many real-world
+/// datasets, which leads to non-Delaunay or broken triangulations. Promoting
+/// to `f64` internally fixes most of these while keeping `f32` storage.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PromotedKernel;
+
+fn promote(p: Point<f32>) -> Point<f64> {
+    Point::new(p.x.into(), p.y.into())
+}
+
+impl Kernel<f32> for PromotedKernel {
+    #[inline(always)]
+    fn is_clockwise(p: Point<f32>, q: Point<f32>, r: Point<f32>) -> bool {
+        promote(p).is_clockwise(promote(q), promote(r))
+    }
+
+    #[inline(always)]
+    fn in_circle(p: Point<f32>, a: Point<f32>, b: Point<f32>, c: Point<f32>) -> Ordering {
+        FloatKernel::in_circle(promote(p), promote(a), promote(b), promote(c))
+    }
+
+    fn circumcenter(a: Point<f32>, b: Point<f32>, c: Point<f32>) -> Point<f32> {
+        let center = promote(a).circumcenter(promote(b), promote(c));
+        Point::new(center.x as f32, center.y as f32)
+    }
+
+    fn circumradius_squared(a: Point<f32>, b: Point<f32>, c: Point<f32>) -> f32 {
+        promote(a).circumradius_squared(promote(b), promote(c)) as f32
+    }
+}
+
+/// Exact predicates using adaptive-precision arithmetic, from the
+/// [robust](https://crates.io/crates/robust) crate.
+///
+/// Constructions such as circumcenters are still evaluated in floating point.
+#[cfg(feature = "robust")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RobustKernel;
+
+#[cfg(feature = "robust")]
+fn coord<T: Scalar>(p: Point<T>) -> robust::Coord<f64> {
+    robust::Coord {
+        x: p.x.to_f64(),
+        y: p.y.to_f64(),
+    }
+}
+
+#[cfg(feature = "robust")]
+impl<T: Scalar> Kernel<T> for RobustKernel {
+    #[inline]
+    fn is_clockwise(p: Point<T>, q: Point<T>, r: Point<T>) -> bool {
+        robust::orient2d(coord(p), coord(q), coord(r)) < 0.0
+    }
+
+    #[inline]
+    fn in_circle(p: Point<T>, a: Point<T>, b: Point<T>, c: Point<T>) -> Ordering {
+        robust::incircle(coord(a), coord(b), coord(c), coord(p))
+            .partial_cmp(&0.0)
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+/// Exact predicates filtered through interval arithmetic.
+///
+/// Each predicate is first evaluated on intervals that are rounded outwards
+/// after every operation, which bounds the rounding error of the floating
+/// point result. Only if the interval contains zero, so that the sign is in
+/// doubt, is the predicate evaluated exactly as in [RobustKernel]. The results
+/// are the same as with [RobustKernel], and most of the time the exact
+/// evaluation is skipped entirely.
+///
+/// Constructions such as circumcenters are still evaluated in floating point.
+#[cfg(feature = "robust")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct IntervalKernel;
+
+#[cfg(feature = "robust")]
+impl<T: Scalar> Kernel<T> for IntervalKernel {
+    #[inline]
+    fn is_clockwise(p: Point<T>, q: Point<T>, r: Point<T>) -> bool {
+        let [p, q, r] = [p, q, r].map(Interval::point);
+        let det = Interval::perp_dot(r.0 - q.0, r.1 - q.1, q.0 - p.0, q.1 - p.1);
+        match det.sign() {
+            Some(sign) => sign == Ordering::Greater,
+            None => RobustKernel::is_clockwise(p.2, q.2, r.2),
+        }
+    }
+
+    #[inline]
+    fn in_circle(p: Point<T>, a: Point<T>, b: Point<T>, c: Point<T>) -> Ordering {
+        let [p, a, b, c] = [p, a, b, c].map(Interval::point);
+        let (dx, dy) = (a.0 - p.0, a.1 - p.1);
+        let (ex, ey) = (b.0 - p.0, b.1 - p.1);
+        let (fx, fy) = (c.0 - p.0, c.1 - p.1);
+        let ap = dx * dx + dy * dy;
+        let bp = ex * ex + ey * ey;
+        let cp = fx * fx + fy * fy;
+        let (gx, gy) = (ex * cp - fx * bp, ey * cp - fy * bp);
+        let det = Interval::perp_dot(dx, dy, gx, gy) + ap * Interval::perp_dot(ex, ey, fx, fy);
+        det.sign()
+            .unwrap_or_else(|| RobustKernel::in_circle(p.2, a.2, b.2, c.2))
+    }
+}
+
+/// A closed interval of `f64`s that contains the exact result of a
+/// computation.
+#[cfg(feature = "robust")]
+#[derive(Clone, Copy, Debug)]
+struct Interval {
+    lo: f64,
+    hi: f64,
+}
+
+#[cfg(feature = "robust")]
+impl Interval {
+    /// The coordinates of `p` as intervals, along with `p` itself.
+    fn point<T: Scalar>(p: Point<T>) -> (Interval, Interval, Point<T>) {
+        let (x, y) = (p.x.to_f64(), p.y.to_f64());
+        (Interval { lo: x, hi: x }, Interval { lo: y, hi: y }, p)
+    }
+
+    /// Widens `[lo, hi]` by one unit in the last place on either side, which
+    /// covers the rounding error of a single operation.
+    fn rounded(lo: f64, hi: f64) -> Interval {
+        Interval {
+            lo: -next_up(-lo),
+            hi: next_up(hi),
+        }
+    }
+
+    fn perp_dot(ax: Interval, ay: Interval, bx: Interval, by: Interval) -> Interval {
+        ax * by - ay * bx
+    }
+
+    /// The sign of every number in the interval, or `None` if it contains
+    /// zero or isn't finite.
+    fn sign(self) -> Option<Ordering> {
+        if self.lo > 0.0 && self.lo.is_finite() {
+            Some(Ordering::Greater)
+        } else if self.hi < 0.0 && self.hi.is_finite() {
+            Some(Ordering::Less)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(feature = "robust")]
+impl std::ops::Add for Interval {
+    type Output = Interval;
+
+    fn add(self, other: Interval) -> Interval {
+        Interval::rounded(self.lo + other.lo, self.hi + other.hi)
+    }
+}
+
+#[cfg(feature = "robust")]
+impl std::ops::Sub for Interval {
+    type Output = Interval;
+
+    fn sub(self, other: Interval) -> Interval {
+        Interval::rounded(self.lo - other.hi, self.hi - other.lo)
+    }
+}
+
+#[cfg(feature = "robust")]
+impl std::ops::Mul for Interval {
+    type Output = Interval;
+
+    fn mul(self, other: Interval) -> Interval {
+        let products = [
+            self.lo * other.lo,
+            self.lo * other.hi,
+            self.hi * other.lo,
+            self.hi * other.hi,
+        ];
+        // an infinite bound times zero, after an overflow
+        if products.iter().any(|p| p.is_nan()) {
+            return Interval {
+                lo: f64::NEG_INFINITY,
+                hi: f64::INFINITY,
+            };
+        }
+        let lo = products.iter().copied().fold(f64::INFINITY, f64::min);
+        let hi = products.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        Interval::rounded(lo, hi)
+    }
+}
+
+/// The smallest float greater than `x`, or `x` itself if it is NaN or
+/// infinite.
+#[cfg(feature = "robust")]
+fn next_up(x: f64) -> f64 {
+    if x.is_nan() || x == f64::INFINITY {
+        x
+    } else if x == 0.0 {
+        f64::from_bits(1)
+    } else if x > 0.0 {
+        f64::from_bits(x.to_bits() + 1)
+    } else {
+        f64::from_bits(x.to_bits() - 1)
+    }
+}
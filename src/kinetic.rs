@@ -0,0 +1,121 @@
+//! Keeping a triangulation Delaunay while its points move.
+//!
+//! Particle simulations and crowd systems move every point a little each frame.
+//! Instead of rebuilding the triangulation, [Triangulation::repair] fixes it up
+//! in place: as long as no triangle turned inside out, edge flips restore the
+//! Delaunay condition, and their number grows with how far the points moved
+//! rather than with the number of points.
+//!
+//! ```rust
+//! use delaunator::{kinetic::Repair, Point, Triangulation};
+//!
+//! let mut points: Vec<_> = (0..100)
+//!     .map(|i| {
+//!         let (r, angle) = ((i as f64).sqrt(), i as f64 * 2.4);
+//!         Point { x: r * angle.cos(), y: r * angle.sin() }
+//!     })
+//!     .collect();
+//! let mut triangulation = Triangulation::<usize>::new(&points).unwrap();
+//!
+//! // swirl the points, the inner ones faster
+//! for _ in 0..10 {
+//!     for p in &mut points {
+//!         let (sin, cos) = (0.1 / (1. + p.x.hypot(p.y))).sin_cos();
+//!         *p = Point { x: p.x * cos - p.y * sin, y: p.x * sin + p.y * cos };
+//!     }
+//!     let repair = triangulation.repair(&points).unwrap();
+//!     assert!(matches!(repair, Repair::Flipped(_)));
+//!     assert!(triangulation.is_delaunay(&points));
+//! }
+//! ```
+
+use std::collections::HashMap;
+
+use crate::{
+    traits::{ApproxEq, HasPosition, Index, Scalar},
+    util::OptionIndex,
+    Triangulation,
+};
+
+/// How [Triangulation::repair] restored the triangulation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Repair {
+    /// The triangulation was fixed in place. Holds the number of edge flips.
+    Flipped(usize),
+    /// A triangle turned inside out, so the triangulation was rebuilt.
+    Rebuilt,
+}
+
+impl<I: Index> Triangulation<I> {
+    /// Restores the triangulation after its points moved to the positions in
+    /// `points`.
+    ///
+    /// If no triangle turned clockwise, hull points that moved inwards are
+    /// covered with new triangles so the hull is convex again, and then edges
+    /// are flipped until the triangulation is Delaunay. Triangle ids
+    /// are kept, and new hull triangles are appended. If some triangle turned
+    /// inside out, which happens when a point moves past one of its neighbors'
+    /// edges within a single step, all points are triangulated again.
+    ///
+    /// Points that were skipped as duplicates stay skipped unless the
+    /// triangulation is rebuilt. Returns `None` and leaves the triangulation
+    /// unchanged if it has to be rebuilt but no triangulation exists for the
+    /// points.
+    pub fn repair<T: Scalar + ApproxEq, P: HasPosition<T>>(
+        &mut self,
+        points: &[P],
+    ) -> Option<Repair> {
+        let zero = T::from_f64(0.0);
+        let pos = |i: I| points[i.as_usize()].pos();
+        let inverted = self.triangles.chunks_exact(3).any(|t| {
+            let (a, b, c) = (pos(t[0]), pos(t[1]), pos(t[2]));
+            (b - a).perp_dot(c - a) < zero
+        });
+        if inverted {
+            *self = Triangulation::new(points)?;
+            return Some(Repair::Rebuilt);
+        }
+
+        self.fill_hull(points);
+        let flips = self.legalize_all(points);
+
+        #[cfg(feature = "vertices")]
+        self.update_vertices(points.len());
+
+        Some(Repair::Flipped(flips))
+    }
+
+    /// Adds triangles over the hull points that are no longer convex corners.
+    fn fill_hull<T: Scalar, P: HasPosition<T>>(&mut self, points: &[P]) {
+        let zero = T::from_f64(0.0);
+        // the half-edge on the hull starting at each hull point
+        let mut hull_edges: HashMap<usize, OptionIndex<I>> = (0..self.halfedges.len())
+            .filter(|&e| self.halfedges[e].is_none())
+            .map(|e| (self.triangles[e].as_usize(), I::from_usize(e).into()))
+            .collect();
+
+        let mut hull: Vec<usize> = self.hull.iter().map(|&i| i.as_usize()).collect();
+        let (mut k, mut convex) = (0, 0);
+        while convex < hull.len() && hull.len() > 3 {
+            let n = hull.len();
+            let (a, b, c) = (hull[(k + n - 1) % n], hull[k], hull[(k + 1) % n]);
+            let [pa, pb, pc] = [a, b, c].map(|i| points[i].pos());
+            if (pc - pa).perp_dot(pb - pa) > zero {
+                let t =
+                    self.add_triangle(a, c, b, OptionIndex::none(), hull_edges[&b], hull_edges[&a]);
+                hull_edges.insert(a, I::from_usize(t).into());
+                hull.remove(k);
+                // the previous corner may have become concave
+                k = (k + n - 2) % (n - 1);
+                convex = 0;
+            } else {
+                k = (k + 1) % n;
+                convex += 1;
+            }
+        }
+
+        if hull.len() != self.hull.len() {
+            self.hull = hull.into_iter().map(I::from_usize).collect();
+        }
+    }
+}
@@ -1,4 +1,4 @@
-#![allow(clippy::clippy::many_single_char_names)]
+#![allow(clippy::many_single_char_names)]
 
 /*!
 A very fast 2D [Delaunay Triangulation](https://en.wikipedia.org/wiki/Delaunay_triangulation) library for Rust.
@@ -21,13 +21,61 @@ println!("{:?}", result.triangles); // [0, 2, 1, 0, 3, 2]
 ```
 */
 
+pub mod alpha;
+pub mod attributes;
+pub mod builder;
+pub mod constrained;
+pub mod density;
+pub mod dual;
+pub mod edit;
 pub mod elem;
+pub mod export;
+#[cfg(feature = "arbitrary")]
+pub mod fuzz;
+#[cfg(feature = "geo")]
+pub mod geo;
+pub mod geographic;
+pub mod graphs;
 mod hull;
+pub mod import;
 pub mod iter;
+pub mod kernel;
+pub mod kinetic;
+pub mod markers;
+mod measure;
+mod observer;
+pub mod overlay;
 pub mod point;
+pub mod polygon;
+pub mod profile;
+mod query;
+pub mod refine;
+pub mod sampling;
+#[cfg(feature = "serde")]
+pub mod sentinel;
+pub mod shape;
+pub mod snap;
+pub mod soa;
+pub mod source;
+pub mod stats;
+#[cfg(feature = "proptest")]
+pub mod strategy;
+#[cfg(feature = "svg")]
+pub mod svg;
+pub mod tiled;
+pub mod tin;
+pub mod trace;
 pub mod traits;
+pub mod tri;
+pub mod triangle_io;
 pub mod triangulation;
+pub mod update;
 pub mod util;
+pub mod view;
+pub mod voronoi;
+
+#[cfg(feature = "arbitrary")]
+mod arbitrary;
 
 #[cfg(feature = "mint")]
 mod mint;
@@ -35,6 +83,10 @@ mod mint;
 #[cfg(feature = "serde")]
 mod serde;
 
+#[cfg(feature = "spade")]
+mod spade;
+
 pub use elem::{HalfEdge, Triangle, Vertex};
 pub use point::Point;
 pub use triangulation::Triangulation;
+pub use view::TriangulationRef;
@@ -0,0 +1,169 @@
+//! Integer boundary markers on edges, for applying boundary conditions to the
+//! exported mesh.
+//!
+//! Markers are attached to segments between two points, not to half-edges,
+//! so they stay valid when the triangulation is rebuilt or refined. When a
+//! segment is split by a new point, both halves inherit its marker: replay
+//! the points that [Refinement::refine](crate::refine::Refinement::refine) or
+//! [ConstrainedTriangulation::conform](crate::constrained::ConstrainedTriangulation::conform)
+//! return through [EdgeMarkers::record].
+//!
+//! ```rust
+//! use delaunator::{markers::EdgeMarkers, Point, Triangulation};
+//!
+//! let points = vec![
+//!     Point { x: 0., y: 0. },
+//!     Point { x: 1., y: 0. },
+//!     Point { x: 1., y: 1. },
+//!     Point { x: 0., y: 1. },
+//! ];
+//! let triangulation = Triangulation::<usize>::new(&points).unwrap();
+//!
+//! let mut markers = EdgeMarkers::new();
+//! markers.mark_hull(&triangulation, 1);
+//! markers.insert(0, 1, 2); // the bottom edge gets its own condition
+//!
+//! let per_halfedge = markers.halfedge_markers(&triangulation);
+//! assert_eq!(per_halfedge.iter().filter(|m| m.is_some()).count(), 4);
+//! assert_eq!(markers.get(1, 0), Some(2));
+//! ```
+
+use std::{collections::HashMap, iter::FromIterator};
+
+use crate::{
+    attributes::Parent,
+    traits::Index,
+    triangle_io::Segment,
+    util::{key, next_halfedge},
+    Triangulation,
+};
+
+/// Boundary markers on segments between pairs of points.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EdgeMarkers {
+    markers: HashMap<(usize, usize), i32>,
+}
+
+impl EdgeMarkers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Marks the segment between points `a` and `b`, in either direction,
+    /// replacing any previous marker.
+    pub fn insert(&mut self, a: usize, b: usize, marker: i32) {
+        self.markers.insert(key(a, b), marker);
+    }
+
+    /// Removes the marker of the segment between `a` and `b`, returning it.
+    pub fn remove(&mut self, a: usize, b: usize) -> Option<i32> {
+        self.markers.remove(&key(a, b))
+    }
+
+    /// The marker of the segment between `a` and `b`, in either direction.
+    pub fn get(&self, a: usize, b: usize) -> Option<i32> {
+        self.markers.get(&key(a, b)).copied()
+    }
+
+    /// The number of marked segments.
+    pub fn len(&self) -> usize {
+        self.markers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.markers.is_empty()
+    }
+
+    /// Marks every edge of the convex hull of `triangulation` that isn't marked yet.
+    pub fn mark_hull<I: Index>(&mut self, triangulation: &Triangulation<I>, marker: i32) {
+        let hull = &triangulation.hull;
+        for k in 0..hull.len() {
+            let (a, b) = (hull[k].as_usize(), hull[(k + 1) % hull.len()].as_usize());
+            self.markers.entry(key(a, b)).or_insert(marker);
+        }
+    }
+
+    /// Splits the segment between `a` and `b` at point `m`: if it is marked,
+    /// the segments from `a` to `m` and from `m` to `b` take over its marker.
+    /// Returns the marker, if any.
+    pub fn split(&mut self, a: usize, b: usize, m: usize) -> Option<i32> {
+        let marker = self.markers.remove(&key(a, b))?;
+        self.markers.insert(key(a, m), marker);
+        self.markers.insert(key(m, b), marker);
+        Some(marker)
+    }
+
+    /// Records that point `m` was inserted into `parent`, splitting the
+    /// segment if `parent` is an edge, see [split](Self::split). Returns the
+    /// marker of the split segment, if any.
+    ///
+    /// Replaying the new points of a refinement in order carries the markers
+    /// over to the sub-segments:
+    ///
+    /// ```rust
+    /// use delaunator::{markers::EdgeMarkers, refine::Refinement, Point, Triangulation};
+    ///
+    /// let mut points = vec![
+    ///     Point { x: 0., y: 0. },
+    ///     Point { x: 2., y: 0. },
+    ///     Point { x: 2., y: 2. },
+    ///     Point { x: 0., y: 2. },
+    /// ];
+    /// let mut cdt = Triangulation::<usize>::from_pslg(&points, &[vec![0, 1, 2, 3]], &[]).unwrap();
+    /// let mut markers = EdgeMarkers::new();
+    /// markers.insert(0, 1, 7);
+    ///
+    /// let inserted = Refinement::new().max_area(0.1).refine(&mut cdt, &mut points);
+    /// for &(m, parent) in &inserted {
+    ///     markers.record(m, parent);
+    /// }
+    /// assert_eq!(markers.get(0, 1), None);
+    /// assert!(markers.len() > 1);
+    /// ```
+    pub fn record(&mut self, m: usize, parent: Parent) -> Option<i32> {
+        match parent {
+            Parent::Edge { a, b, .. } => self.split(a, b, m),
+            Parent::Triangle { .. } => None,
+        }
+    }
+
+    /// The marker of every half-edge of `triangulation`, indexed by half-edge id.
+    /// Both half-edges of a marked interior edge get its marker.
+    pub fn halfedge_markers<I: Index>(&self, triangulation: &Triangulation<I>) -> Vec<Option<i32>> {
+        (0..triangulation.halfedges.len())
+            .map(|e| {
+                let a = triangulation.triangles[e].as_usize();
+                let b = triangulation.triangles[next_halfedge(e)].as_usize();
+                self.get(a, b)
+            })
+            .collect()
+    }
+
+    /// The marked segments, ordered by their points, e.g. for
+    /// [write_poly](crate::triangle_io::write_poly).
+    pub fn segments(&self) -> Vec<Segment> {
+        let mut segments: Vec<Segment> = self
+            .markers
+            .iter()
+            .map(|(&(start, end), &marker)| Segment { start, end, marker })
+            .collect();
+        segments.sort_unstable_by_key(|s| (s.start, s.end));
+        segments
+    }
+}
+
+impl Extend<Segment> for EdgeMarkers {
+    fn extend<It: IntoIterator<Item = Segment>>(&mut self, segments: It) {
+        for segment in segments {
+            self.insert(segment.start, segment.end, segment.marker);
+        }
+    }
+}
+
+impl FromIterator<Segment> for EdgeMarkers {
+    fn from_iter<It: IntoIterator<Item = Segment>>(segments: It) -> Self {
+        let mut markers = EdgeMarkers::new();
+        markers.extend(segments);
+        markers
+    }
+}
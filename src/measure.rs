@@ -0,0 +1,83 @@
+use crate::{
+    traits::{HasPosition, Index, Scalar},
+    util, Point, Triangulation,
+};
+
+impl<I: Index> Triangulation<I> {
+    /// The points of the convex hull, counter-clockwise.
+    pub fn hull_polygon<T: Scalar, P: HasPosition<T>>(&self, points: &[P]) -> Vec<Point<T>> {
+        self.hull
+            .iter()
+            .map(|&i| points[i.as_usize()].pos())
+            .collect()
+    }
+
+    /// The area enclosed by the convex hull.
+    pub fn hull_area<T: Scalar, P: HasPosition<T>>(&self, points: &[P]) -> T {
+        util::sum(
+            self.hull_edges(points)
+                .map(|(p0, p)| (p.x + p0.x) * (p.y - p0.y)),
+        ) / T::from_f64(2.0)
+    }
+
+    /// The length of the convex hull.
+    pub fn hull_perimeter<T: Scalar, P: HasPosition<T>>(&self, points: &[P]) -> T {
+        util::sum(self.hull_edges(points).map(|(p0, p)| p0.distance(p)))
+    }
+
+    /// The centroid of the area enclosed by the convex hull.
+    pub fn hull_centroid<T: Scalar, P: HasPosition<T>>(&self, points: &[P]) -> Point<T> {
+        let area = self.hull_area(points);
+        let cx = util::sum(
+            self.hull_edges(points)
+                .map(|(p0, p)| (p0.x + p.x) * p0.perp_dot(p)),
+        );
+        let cy = util::sum(
+            self.hull_edges(points)
+                .map(|(p0, p)| (p0.y + p.y) * p0.perp_dot(p)),
+        );
+        let k = T::from_f64(6.0) * area;
+        Point::new(cx / k, cy / k)
+    }
+
+    /// The total area of the triangles, summed with compensation for rounding.
+    ///
+    /// This equals [hull_area](Triangulation::hull_area) for a triangulation of
+    /// points, but also covers meshes with holes or a non-convex boundary.
+    pub fn area<T: Scalar, P: HasPosition<T>>(&self, points: &[P]) -> T {
+        util::sum(self.triangle_areas(points).map(|(_, area)| area))
+    }
+
+    /// The centroid of the area covered by the triangles, weighting the
+    /// centroid of each triangle by its area.
+    pub fn centroid<T: Scalar, P: HasPosition<T>>(&self, points: &[P]) -> Point<T> {
+        let k = T::from_f64(3.0) * self.area(points);
+        let cx = util::sum(self.triangle_areas(points).map(|(c, area)| c.x * area));
+        let cy = util::sum(self.triangle_areas(points).map(|(c, area)| c.y * area));
+        Point::new(cx / k, cy / k)
+    }
+
+    /// The sum of the corners and the area of every triangle.
+    fn triangle_areas<'a, T: Scalar, P: HasPosition<T>>(
+        &'a self,
+        points: &'a [P],
+    ) -> impl Iterator<Item = (Point<T>, T)> + 'a {
+        self.triangles.chunks_exact(3).map(move |t| {
+            let [a, b, c] = [t[0], t[1], t[2]].map(|i| points[i.as_usize()].pos());
+            (a + b + c, (b - a).perp_dot(c - a) / T::from_f64(2.0))
+        })
+    }
+
+    /// The edges of the convex hull as pairs of points, counter-clockwise.
+    fn hull_edges<'a, T: Scalar, P: HasPosition<T>>(
+        &'a self,
+        points: &'a [P],
+    ) -> impl Iterator<Item = (Point<T>, Point<T>)> + 'a {
+        let len = self.hull.len();
+        (0..len).map(move |i| {
+            let p0 = points[self.hull[(i + len - 1) % len].as_usize()].pos();
+            let p = points[self.hull[i].as_usize()].pos();
+            (p0, p)
+        })
+    }
+}
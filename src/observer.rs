@@ -0,0 +1,201 @@
+use std::{cmp::Ordering, cmp::Reverse, collections::BinaryHeap};
+
+use crate::{
+    trace::PointStatus,
+    traits::{Index, PointSource, Scalar},
+    Point, Triangulation,
+};
+
+/// A stage of construction, in the order they run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Phase {
+    Dedup,
+    Seed,
+    Sort,
+    Insert,
+    Finish,
+}
+
+/// Receives progress notifications while a [Triangulation] is constructed.
+///
+/// All methods default to doing nothing; `()` observes nothing.
+pub(crate) trait Observer<T: Scalar, I: Index> {
+    /// Construction enters `phase`.
+    fn phase(&mut self, _phase: Phase) {}
+
+    /// Construction starts from the `seed` triangle; points are inserted in
+    /// order of distance from `center`.
+    fn start(&mut self, _seed: (usize, usize, usize), _center: Point<T>) {}
+
+    /// Point `i`, at squared distance `dist` from the center, is about to be inserted.
+    fn insert<S: PointSource<T> + ?Sized>(
+        &mut self,
+        _triangulation: &Triangulation<I>,
+        _points: &S,
+        _i: usize,
+        _dist: T,
+    ) {
+    }
+
+    /// Construction decided what to do with point `i`.
+    fn status(&mut self, _i: usize, _status: PointStatus) {}
+
+    /// Triangle `t` was added or changed by a flip.
+    fn changed(&mut self, _t: usize) {}
+
+    /// Triangle `t` was added with the given corners.
+    fn added(&mut self, _t: usize, _corners: [usize; 3]) {}
+
+    /// The edge of half-edges `halfedges` was flipped to restore the Delaunay
+    /// condition, from the diagonal between the points `removed` to the one
+    /// between `added`.
+    fn flip(
+        &mut self,
+        _halfedges: (usize, usize),
+        _removed: (usize, usize),
+        _added: (usize, usize),
+    ) {
+    }
+
+    /// The hull now runs from point `from` through the new `point` to `to`,
+    /// leaving out the points that were between `from` and `to`.
+    fn hull(&mut self, _from: usize, _point: usize, _to: usize) {}
+
+    /// An edge was checked whose quadrilateral has four exactly cocircular
+    /// corners, so either diagonal is Delaunay.
+    fn cocircular(&mut self) {}
+
+    /// The hull was searched for an edge visible from a new point, probing
+    /// `probes` hash buckets and walking `steps` hull edges.
+    fn hull_search(&mut self, _probes: usize, _steps: usize, _found: bool) {}
+
+    /// All points have been inserted.
+    fn finish<S: PointSource<T> + ?Sized>(
+        &mut self,
+        _triangulation: &Triangulation<I>,
+        _points: &S,
+    ) {
+    }
+}
+
+impl<T: Scalar, I: Index> Observer<T, I> for () {}
+
+/// Reports triangles to a closure as soon as no later point can change them.
+///
+/// Points are inserted in order of distance from the center, so a triangle
+/// is final once its circumcircle lies inside the disk swept so far.
+pub(crate) struct StreamingVisitor<T: Scalar, F> {
+    center: Point<T>,
+    visit: F,
+    dirty: Vec<usize>,
+    heap: BinaryHeap<Reverse<(Reach, usize)>>,
+    visited: Vec<bool>,
+}
+
+impl<T: Scalar, F: FnMut([usize; 3])> StreamingVisitor<T, F> {
+    pub(crate) fn new(visit: F) -> Self {
+        StreamingVisitor {
+            center: Point::new(T::from_f64(0.0), T::from_f64(0.0)),
+            visit,
+            dirty: Vec::new(),
+            heap: BinaryHeap::new(),
+            visited: Vec::new(),
+        }
+    }
+
+    /// The distance from the center to the far side of the circumcircle of triangle `t`.
+    fn reach<I: Index, S: PointSource<T> + ?Sized>(
+        &self,
+        triangulation: &Triangulation<I>,
+        points: &S,
+        t: usize,
+    ) -> Reach {
+        let [a, b, c] = [3 * t, 3 * t + 1, 3 * t + 2]
+            .map(|e| points.point(triangulation.triangles[e].as_usize()));
+        let r = a.circumradius_squared(b, c).sqrt();
+        let reach = (self.center.distance(a.circumcenter(b, c)) + r).to_f64();
+        Reach(reach)
+    }
+
+    fn visit<I: Index>(&mut self, triangulation: &Triangulation<I>, t: usize) {
+        self.visited[t] = true;
+        (self.visit)([3 * t, 3 * t + 1, 3 * t + 2].map(|e| triangulation.triangles[e].as_usize()));
+    }
+}
+
+impl<T: Scalar, I: Index, F: FnMut([usize; 3])> Observer<T, I> for StreamingVisitor<T, F> {
+    fn start(&mut self, _seed: (usize, usize, usize), center: Point<T>) {
+        self.center = center;
+    }
+
+    fn insert<S: PointSource<T> + ?Sized>(
+        &mut self,
+        triangulation: &Triangulation<I>,
+        points: &S,
+        _i: usize,
+        dist: T,
+    ) {
+        let mut dirty = std::mem::take(&mut self.dirty);
+        for t in dirty.drain(..) {
+            let reach = self.reach(triangulation, points, t);
+            self.heap.push(Reverse((reach, t)));
+        }
+        self.dirty = dirty;
+        self.visited.resize(triangulation.len(), false);
+
+        // leave a small margin for rounding in the in-circle test
+        let swept = dist.sqrt().to_f64();
+        let swept = swept * (1.0 - 1e-6);
+        while let Some(&Reverse((reach, t))) = self.heap.peek() {
+            if reach >= Reach(swept) {
+                break;
+            }
+            self.heap.pop();
+            // skip stale entries for triangles that changed after being queued
+            if !self.visited[t] && self.reach(triangulation, points, t) == reach {
+                self.visit(triangulation, t);
+            }
+        }
+    }
+
+    fn changed(&mut self, t: usize) {
+        self.dirty.push(t);
+    }
+
+    fn finish<S: PointSource<T> + ?Sized>(
+        &mut self,
+        triangulation: &Triangulation<I>,
+        _points: &S,
+    ) {
+        self.visited.resize(triangulation.len(), false);
+        for t in 0..triangulation.len() {
+            if !self.visited[t] {
+                self.visit(triangulation, t);
+            }
+        }
+    }
+}
+
+/// A totally ordered distance.
+#[derive(Clone, Copy, Debug)]
+struct Reach(f64);
+
+impl PartialEq for Reach {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Reach {}
+
+impl PartialOrd for Reach {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Reach {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.partial_cmp(&other.0).unwrap_or(Ordering::Equal)
+    }
+}
@@ -0,0 +1,553 @@
+//! Overlays of triangulated regions: boolean operations on polygons with
+//! [boolean] and the arrangement of two triangulations with [overlay].
+//!
+//! [boolean] splits the boundaries of both inputs where they cross and inserts
+//! them as segments into a [constrained triangulation](crate::constrained).
+//! Every triangle is then labeled with the inputs covering it, so the result
+//! is a half-edge mesh that holds all operations at once. [overlay] clips every
+//! pair of overlapping triangles against each other instead.
+//!
+//! ```rust
+//! use delaunator::{overlay::{self, Operation, Region}, Point};
+//!
+//! let square = |x: f64, y: f64| {
+//!     vec![
+//!         Point { x, y },
+//!         Point { x: x + 2., y },
+//!         Point { x: x + 2., y: y + 2. },
+//!         Point { x, y: y + 2. },
+//!     ]
+//! };
+//! let (a, b) = ([square(0., 0.)], [square(1., 1.)]);
+//!
+//! let union = overlay::boolean(&a, &b, Operation::Union).unwrap();
+//! assert!((union.area() - 7.).abs() < 1e-12);
+//! assert!((union.area_of(Region::Both) - 1.).abs() < 1e-12);
+//!
+//! let difference = overlay::boolean(&a, &b, Operation::Difference).unwrap();
+//! assert!((difference.area() - 3.).abs() < 1e-12);
+//! ```
+
+use std::collections::HashMap;
+
+use crate::{
+    constrained::{orient, ConstraintError},
+    traits::{ApproxEq, HasPosition, Index, Scalar},
+    util::{self, key, next_halfedge},
+    voronoi::clip_half_plane,
+    Point, Triangulation,
+};
+
+/// A boolean operation on two regions, see [boolean].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operation {
+    /// The area covered by either region.
+    Union,
+    /// The area covered by both regions.
+    Intersection,
+    /// The area covered by the first region but not the second.
+    Difference,
+    /// The area covered by exactly one of the regions.
+    SymmetricDifference,
+}
+
+impl Operation {
+    /// Whether the result of the operation includes `region`.
+    pub fn includes(self, region: Region) -> bool {
+        matches!(
+            (self, region),
+            (Operation::Union, _)
+                | (Operation::Intersection, Region::Both)
+                | (Operation::Difference, Region::First)
+                | (Operation::SymmetricDifference, Region::First)
+                | (Operation::SymmetricDifference, Region::Second)
+        )
+    }
+}
+
+/// Which of the two inputs of [boolean] cover a triangle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Region {
+    /// Only the first input.
+    First,
+    /// Only the second input.
+    Second,
+    /// Both inputs.
+    Both,
+}
+
+/// The result of [boolean]: a triangulation in which the boundaries of both
+/// inputs are chains of edges, with every triangle labeled by the inputs
+/// covering it.
+pub struct RegionMesh<T: Scalar> {
+    /// The vertices of both inputs without duplicates, followed by the points
+    /// where their boundaries cross.
+    pub points: Vec<Point<T>>,
+    /// A constrained Delaunay triangulation of `points` with the boundaries of
+    /// both inputs as segments.
+    pub triangulation: Triangulation<usize>,
+    /// The inputs covering every triangle of `triangulation`, by id, or
+    /// `None` for the triangles outside of both.
+    pub regions: Vec<Option<Region>>,
+    /// The operation whose result [triangles](Self::triangles) returns.
+    pub operation: Operation,
+}
+
+impl<T: Scalar> RegionMesh<T> {
+    /// Whether the result of the operation includes triangle `t`.
+    pub fn includes(&self, t: usize) -> bool {
+        self.regions[t].map_or(false, |region| self.operation.includes(region))
+    }
+
+    /// The triangles of the result, as triples of point indices in the order
+    /// of their ids.
+    pub fn triangles(&self) -> Vec<usize> {
+        (0..self.regions.len())
+            .filter(|&t| self.includes(t))
+            .flat_map(|t| {
+                self.triangulation.triangles[3 * t..3 * t + 3]
+                    .iter()
+                    .copied()
+            })
+            .collect()
+    }
+
+    /// The area of the result.
+    pub fn area(&self) -> T {
+        self.sum_areas(|t| self.includes(t))
+    }
+
+    /// The area covered by `region`, whether or not the result includes it.
+    pub fn area_of(&self, region: Region) -> T {
+        self.sum_areas(|t| self.regions[t] == Some(region))
+    }
+
+    fn sum_areas(&self, filter: impl Fn(usize) -> bool) -> T {
+        let t = &self.triangulation.triangles;
+        (0..self.regions.len())
+            .filter(|&k| filter(k))
+            .map(|k| {
+                let [a, b, c] = [t[3 * k], t[3 * k + 1], t[3 * k + 2]].map(|i| self.points[i]);
+                orient(a, b, c) / T::from_f64(2.0)
+            })
+            .fold(T::from_f64(0.0), |sum, area| sum + area)
+    }
+}
+
+/// Computes a boolean operation on two regions, each bounded by rings of
+/// points by the even-odd rule, e.g. outer boundaries and their holes in
+/// either orientation. A ring may repeat its first point at the end.
+///
+/// The boundaries of each region must not cross each other, but may cross
+/// the boundaries of the other region anywhere. Candidate pairs of crossing
+/// edges are found through a grid of their bounding boxes.
+///
+/// Returns an error if there is no triangulation, e.g. because both regions
+/// are empty, or if the boundaries of a region cross each other.
+pub fn boolean<T, P, V>(
+    first: &[V],
+    second: &[V],
+    operation: Operation,
+) -> Result<RegionMesh<T>, ConstraintError>
+where
+    T: Scalar + ApproxEq,
+    P: HasPosition<T>,
+    V: AsRef<[P]>,
+{
+    let mut points = Vec::new();
+    let mut ids = HashMap::new();
+    let mut id = |points: &mut Vec<Point<T>>, p: Point<T>| {
+        *ids.entry((p.x.to_f64().to_bits(), p.y.to_f64().to_bits()))
+            .or_insert_with(|| {
+                points.push(p);
+                points.len() - 1
+            })
+    };
+
+    // the edges of both inputs, by the input they belong to
+    let mut edges = Vec::new();
+    for (operand, rings) in [first, second].iter().enumerate() {
+        for ring in rings.iter() {
+            let mut ring: Vec<usize> = ring
+                .as_ref()
+                .iter()
+                .map(|p| id(&mut points, p.pos()))
+                .collect();
+            if ring.len() > 1 && ring.first() == ring.last() {
+                ring.pop();
+            }
+            for k in 0..ring.len() {
+                let (a, b) = (ring[k], ring[(k + 1) % ring.len()]);
+                if a != b {
+                    edges.push((operand, a, b));
+                }
+            }
+        }
+    }
+
+    // split the edges where they cross or touch another edge
+    let boxes: Vec<[Point<T>; 3]> = edges
+        .iter()
+        .map(|&(_, a, b)| [points[a], points[b], points[b]])
+        .collect();
+    let mut grid = Grid::new(&boxes);
+    let mut candidates = Vec::new();
+    let mut splits = vec![Vec::new(); edges.len()];
+    for i in 0..edges.len() {
+        grid.query(&boxes[i], &mut candidates);
+        for &j in candidates.iter().filter(|&&j| j > i) {
+            let ((first, a, b), (second, c, d)) = (edges[i], edges[j]);
+            let [p, q, r, s] = [a, b, c, d].map(|k| points[k]);
+            let (or, os, op, oq) = (
+                orient(p, q, r),
+                orient(p, q, s),
+                orient(r, s, p),
+                orient(r, s, q),
+            );
+            if first != second && opposite(or, os) && opposite(op, oq) {
+                let x = id(&mut points, p + (q - p) * (op / (op - oq)));
+                splits[i].push((along(p, q, points[x]), x));
+                splits[j].push((along(r, s, points[x]), x));
+                continue;
+            }
+            for (k, (from, to), ends) in
+                [(i, (p, q), [(r, c), (s, d)]), (j, (r, s), [(p, a), (q, b)])]
+            {
+                for (w, w_id) in ends {
+                    let t = along(from, to, w);
+                    if orient(from, to, w) == T::from_f64(0.0) && t > 0.0 && t < 1.0 {
+                        splits[k].push((t, w_id));
+                    }
+                }
+            }
+        }
+    }
+
+    // the segments between consecutive splits, with the number of times each
+    // input's boundaries run along them
+    let mut boundaries: HashMap<(usize, usize), [u32; 2]> = HashMap::new();
+    for (&(operand, a, b), mut split) in edges.iter().zip(splits) {
+        split.sort_by(|u, v| u.0.total_cmp(&v.0));
+        let chain = std::iter::once(a)
+            .chain(split.into_iter().map(|(_, k)| k))
+            .chain(std::iter::once(b));
+        let mut start = a;
+        for end in chain.skip(1) {
+            if end != start {
+                boundaries.entry(key(start, end)).or_insert([0; 2])[operand] += 1;
+                start = end;
+            }
+        }
+    }
+    let mut segments: Vec<_> = boundaries.keys().copied().collect();
+    segments.sort_unstable();
+
+    let triangulation = Triangulation::with_constraints(&points, &segments)?.into_triangulation();
+    let regions = label(&triangulation, &boundaries);
+    Ok(RegionMesh {
+        points,
+        triangulation,
+        regions,
+        operation,
+    })
+}
+
+/// Whether `s` and `t` have opposite signs, neither being zero.
+fn opposite<T: Scalar>(s: T, t: T) -> bool {
+    let zero = T::from_f64(0.0);
+    (s > zero && t < zero) || (s < zero && t > zero)
+}
+
+/// How far along the segment from `p` to `q` the projection of `w` lies, from
+/// 0 at `p` to 1 at `q`.
+fn along<T: Scalar>(p: Point<T>, q: Point<T>, w: Point<T>) -> f64 {
+    let (d, v) = (q - p, w - p);
+    ((d.x * v.x + d.y * v.y) / d.length_squared()).to_f64()
+}
+
+/// The inputs covering every triangle, found by walking in from the hull and
+/// switching sides of an input at every edge that its boundaries run along an
+/// odd number of times.
+fn label(
+    triangulation: &Triangulation<usize>,
+    boundaries: &HashMap<(usize, usize), [u32; 2]>,
+) -> Vec<Option<Region>> {
+    let t = triangulation;
+    let crosses = |e: usize| {
+        let (a, b) = (t.triangles[e], t.triangles[next_halfedge(e)]);
+        boundaries
+            .get(&key(a, b))
+            .map_or([false; 2], |counts| counts.map(|count| count % 2 == 1))
+    };
+
+    let mut sides = vec![None; t.len()];
+    let mut stack: Vec<usize> = (0..t.halfedges.len())
+        .filter(|&e| t.halfedges[e].is_none())
+        .take(1)
+        .collect();
+    if let Some(&e) = stack.first() {
+        sides[e / 3] = Some(crosses(e));
+    }
+    while let Some(e) = stack.pop() {
+        let [first, second] = sides[e / 3].unwrap();
+        for h in 3 * (e / 3)..3 * (e / 3) + 3 {
+            if let Some(f) = t.halfedges[h].get() {
+                if sides[f / 3].is_none() {
+                    let [x, y] = crosses(h);
+                    sides[f / 3] = Some([first != x, second != y]);
+                    stack.push(f);
+                }
+            }
+        }
+    }
+    sides
+        .into_iter()
+        .map(|side| match side {
+            Some([true, true]) => Some(Region::Both),
+            Some([true, false]) => Some(Region::First),
+            Some([false, true]) => Some(Region::Second),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The arrangement of two superimposed triangulations, see [overlay].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Arrangement<T: Scalar> {
+    /// The faces of the arrangement, as convex counter-clockwise polygons.
+    pub faces: Vec<Vec<Point<T>>>,
+    /// For every face, the triangle of the first and of the second
+    /// triangulation that it lies in.
+    pub sources: Vec<(usize, usize)>,
+}
+
+impl<T: Scalar> Arrangement<T> {
+    /// The area of every face.
+    pub fn areas(&self) -> Vec<T> {
+        self.faces
+            .iter()
+            .map(|face| util::signed_area(face) / T::from_f64(2.0))
+            .collect()
+    }
+
+    /// Transfers a field that is constant on every triangle of the first
+    /// triangulation to the `len` triangles of the second, averaging over the
+    /// faces in each, so its integral over the covered area is preserved.
+    ///
+    /// Triangles of the second triangulation that no face covers get `None`.
+    ///
+    /// # Panics
+    /// Panics if `values` doesn't have a value for a triangle of the first
+    /// triangulation that appears in a face.
+    pub fn remap(&self, values: &[T], len: usize) -> Vec<Option<T>> {
+        let zero = T::from_f64(0.0);
+        let mut integral = vec![zero; len];
+        let mut covered = vec![zero; len];
+        for (&(a, b), area) in self.sources.iter().zip(self.areas()) {
+            integral[b] = integral[b] + values[a] * area;
+            covered[b] = covered[b] + area;
+        }
+        integral
+            .into_iter()
+            .zip(covered)
+            .map(|(integral, area)| {
+                if area > zero {
+                    Some(integral / area)
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+/// Superimposes two triangulations and computes the faces they cut each other
+/// into, e.g. for conservative remapping of fields between meshes.
+///
+/// Every face is the intersection of a triangle of each triangulation and is
+/// tagged with both. Together, the faces cover the area where the two
+/// triangulations overlap. Candidate pairs of triangles are found through a grid
+/// of their bounding boxes.
+///
+/// ```rust
+/// use delaunator::{overlay, Point, Triangulation};
+///
+/// let square = |offset: f64| {
+///     vec![
+///         Point { x: offset, y: 0. },
+///         Point { x: 1. + offset, y: 0. },
+///         Point { x: 1. + offset, y: 1. },
+///         Point { x: offset, y: 1. },
+///     ]
+/// };
+/// let (first, second) = (square(0.), square(0.5));
+/// let a = Triangulation::<usize>::new(&first).unwrap();
+/// let b = Triangulation::<usize>::new(&second).unwrap();
+///
+/// let arrangement = overlay::overlay(&a, &first, &b, &second);
+/// let area: f64 = arrangement.areas().iter().sum();
+/// assert!((area - 0.5).abs() < 1e-12);
+///
+/// // a constant field stays constant
+/// let values = arrangement.remap(&[3., 3.], b.len());
+/// assert!(values.iter().all(|v| (v.unwrap() - 3.).abs() < 1e-12));
+/// ```
+pub fn overlay<T, I, P>(
+    first: &Triangulation<I>,
+    first_points: &[P],
+    second: &Triangulation<I>,
+    second_points: &[P],
+) -> Arrangement<T>
+where
+    T: Scalar,
+    I: Index,
+    P: HasPosition<T>,
+{
+    let triangles = |triangulation: &Triangulation<I>, points: &[P]| -> Vec<[Point<T>; 3]> {
+        triangulation
+            .triangles
+            .chunks_exact(3)
+            .map(|t| [t[0], t[1], t[2]].map(|i| points[i.as_usize()].pos()))
+            .collect()
+    };
+    let (a, b) = (
+        triangles(first, first_points),
+        triangles(second, second_points),
+    );
+
+    let mut arrangement = Arrangement {
+        faces: Vec::new(),
+        sources: Vec::new(),
+    };
+    let mut grid = Grid::new(&b);
+    let mut candidates = Vec::new();
+    for (i, t) in a.iter().enumerate() {
+        grid.query(t, &mut candidates);
+        for &j in &candidates {
+            let face = intersect(t, &b[j]);
+            if face.len() >= 3 && util::signed_area(&face) > T::from_f64(0.0) {
+                arrangement.faces.push(face);
+                arrangement.sources.push((i, j));
+            }
+        }
+    }
+    arrangement
+}
+
+/// The intersection of the convex `polygon` with the counter-clockwise
+/// `triangle`.
+fn intersect<T: Scalar>(polygon: &[Point<T>], triangle: &[Point<T>; 3]) -> Vec<Point<T>> {
+    (0..3).fold(polygon.to_vec(), |polygon, k| {
+        let (p, q) = (triangle[k], triangle[(k + 1) % 3]);
+        clip_half_plane(&polygon, p, outward(p, q))
+    })
+}
+
+/// The outward normal of the edge from `p` to `q` of a counter-clockwise polygon.
+fn outward<T: Scalar>(p: Point<T>, q: Point<T>) -> Point<T> {
+    Point::new(q.y - p.y, p.x - q.x)
+}
+
+/// A uniform grid over the bounding boxes of triangles, for finding the ones
+/// that may overlap another triangle. A segment is a triangle with a repeated
+/// corner.
+struct Grid {
+    min: (f64, f64),
+    size: f64,
+    columns: usize,
+    rows: usize,
+    cells: Vec<Vec<usize>>,
+    seen: Vec<usize>,
+    stamp: usize,
+}
+
+impl Grid {
+    fn new<T: Scalar>(triangles: &[[Point<T>; 3]]) -> Self {
+        let bounds = triangles.iter().map(bounds_of).fold(
+            (
+                f64::INFINITY,
+                f64::INFINITY,
+                f64::NEG_INFINITY,
+                f64::NEG_INFINITY,
+            ),
+            |(x0, y0, x1, y1), (a, b, c, d)| (x0.min(a), y0.min(b), x1.max(c), y1.max(d)),
+        );
+        // about one triangle per cell
+        let side = (triangles.len() as f64).sqrt().ceil().max(1.0);
+        let extent = (bounds.2 - bounds.0).max(bounds.3 - bounds.1);
+        let size = if extent > 0.0 { extent / side } else { 1.0 };
+        let columns = (((bounds.2 - bounds.0) / size) as usize + 1).max(1);
+        let rows = (((bounds.3 - bounds.1) / size) as usize + 1).max(1);
+
+        let mut grid = Grid {
+            min: (bounds.0, bounds.1),
+            size,
+            columns,
+            rows,
+            cells: vec![
+                Vec::new();
+                if triangles.is_empty() {
+                    0
+                } else {
+                    columns * rows
+                }
+            ],
+            seen: vec![0; triangles.len()],
+            stamp: 0,
+        };
+        for (t, triangle) in triangles.iter().enumerate() {
+            let (x0, y0, x1, y1) = grid.cell_range(bounds_of(triangle));
+            for y in y0..=y1 {
+                for x in x0..=x1 {
+                    grid.cells[y * columns + x].push(t);
+                }
+            }
+        }
+        grid
+    }
+
+    /// Replaces `candidates` with the triangles whose bounding boxes overlap
+    /// the one of `triangle`.
+    fn query<T: Scalar>(&mut self, triangle: &[Point<T>; 3], candidates: &mut Vec<usize>) {
+        candidates.clear();
+        if self.cells.is_empty() {
+            return;
+        }
+        self.stamp += 1;
+        let (x0, y0, x1, y1) = self.cell_range(bounds_of(triangle));
+        for y in y0..=y1 {
+            for x in x0..=x1 {
+                for &t in &self.cells[y * self.columns + x] {
+                    if self.seen[t] != self.stamp {
+                        self.seen[t] = self.stamp;
+                        candidates.push(t);
+                    }
+                }
+            }
+        }
+    }
+
+    /// The first and last column and row covered by a bounding box.
+    fn cell_range(&self, (x0, y0, x1, y1): (f64, f64, f64, f64)) -> (usize, usize, usize, usize) {
+        let column =
+            |x: f64| (((x - self.min.0) / self.size).max(0.0) as usize).min(self.columns - 1);
+        let row = |y: f64| (((y - self.min.1) / self.size).max(0.0) as usize).min(self.rows - 1);
+        (column(x0), row(y0), column(x1), row(y1))
+    }
+}
+
+/// The bounding box of a triangle as `(min x, min y, max x, max y)`.
+fn bounds_of<T: Scalar>(triangle: &[Point<T>; 3]) -> (f64, f64, f64, f64) {
+    triangle.iter().fold(
+        (
+            f64::INFINITY,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+            f64::NEG_INFINITY,
+        ),
+        |(x0, y0, x1, y1), p| {
+            let (x, y) = (p.x.to_f64(), p.y.to_f64());
+            (x0.min(x), y0.min(y), x1.max(x), y1.max(y))
+        },
+    )
+}
@@ -25,6 +25,11 @@ impl<T: Scalar> Point<T> {
         self.x * self.x + self.y * self.y
     }
 
+    /// The length of `self`.
+    pub fn length(self) -> T {
+        self.length_squared().sqrt()
+    }
+
     /// Returns a `Point` that is equal to `self` rotated by 90 degrees.
     pub fn perp(self) -> Self {
         Self {
@@ -43,10 +48,15 @@ impl<T: Scalar> Point<T> {
         (self - p).length_squared()
     }
 
+    /// The distance between `self` and `p`.
+    pub fn distance(self, p: Self) -> T {
+        (self - p).length()
+    }
+
     /// Tests if the path `self` to `q` to `r` goes in a clockwise direction
     /// (assuming a right-handed coordinate system).
     pub fn is_clockwise(self, q: Self, r: Self) -> bool {
-        (r - q).perp_dot(q - self) > 0.0.into()
+        (r - q).perp_dot(q - self) > T::from_f64(0.0)
     }
 
     fn circumdelta(self, b: Self, c: Self) -> Self {
@@ -55,7 +65,7 @@ impl<T: Scalar> Point<T> {
 
         let bl = d.length_squared();
         let cl = e.length_squared();
-        let k: T = T::from(0.5) / d.perp_dot(e);
+        let k: T = T::from_f64(0.5) / d.perp_dot(e);
 
         (d * cl - e * bl).perp() * k
     }
@@ -72,6 +82,13 @@ impl<T: Scalar> Point<T> {
 
     /// Tests if `self` is in the circumcircle of `a`, `b`, and `c`.
     pub fn is_in_circle(self, a: Self, b: Self, c: Self) -> bool {
+        self.in_circle(a, b, c) > T::from_f64(0.0)
+    }
+
+    /// The in-circle determinant of `self` with respect to the circumcircle of the
+    /// counter-clockwise triangle `a`, `b`, `c`: positive if `self` is inside the
+    /// circle, negative if it is outside and zero if all four points are cocircular.
+    pub fn in_circle(self, a: Self, b: Self, c: Self) -> T {
         let d = a - self;
         let e = b - self;
         let f = c - self;
@@ -82,7 +99,7 @@ impl<T: Scalar> Point<T> {
 
         let g = e * cp - f * bp;
 
-        d.perp_dot(g) + ap * e.perp_dot(f) > 0.0.into()
+        d.perp_dot(g) + ap * e.perp_dot(f)
     }
 
     pub fn nearly_equals(self, p: Self) -> bool
@@ -127,6 +144,7 @@ impl<T: Scalar> Mul<T> for Point<T> {
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod test {
     use super::*;
 
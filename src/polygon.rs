@@ -0,0 +1,182 @@
+//! Triangulation of simple polygons by ear clipping.
+//!
+//! Unlike [Triangulation](crate::Triangulation), which triangulates the convex
+//! hull of a point set, this triangulates the interior of polygons, e.g. to
+//! render filled map features. Many polygons are triangulated in one batch
+//! into a single vertex and index buffer.
+//!
+//! ```rust
+//! use delaunator::{polygon, Point};
+//!
+//! let square = vec![
+//!     Point { x: 0., y: 0. },
+//!     Point { x: 1., y: 0. },
+//!     Point { x: 1., y: 1. },
+//!     Point { x: 0., y: 1. },
+//! ];
+//! let triangle = vec![
+//!     Point { x: 2., y: 0. },
+//!     Point { x: 3., y: 0. },
+//!     Point { x: 2., y: 1. },
+//! ];
+//!
+//! let batch = polygon::triangulate_polygons::<u32, _, _, _>(&[square, triangle]);
+//! assert_eq!(batch.vertices.len(), 7);
+//! assert_eq!(batch.triangles.len(), 3 * 3);
+//! assert_eq!(batch.polygons[1].triangles, 6..9);
+//! ```
+
+use std::ops::Range;
+
+use crate::{
+    traits::{HasPosition, Index, Scalar},
+    util, Point,
+};
+
+/// The combined output of [triangulate_polygons].
+#[derive(Clone, Debug)]
+pub struct PolygonBatch<T: Scalar, I> {
+    /// The vertices of all polygons, one after another.
+    pub vertices: Vec<Point<T>>,
+    /// Indices into `vertices` where each triple is a counter-clockwise triangle.
+    pub triangles: Vec<I>,
+    /// Where each input polygon ended up in `vertices` and `triangles`.
+    pub polygons: Vec<PolygonRange>,
+}
+
+/// The part of a [PolygonBatch] that belongs to one input polygon.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PolygonRange {
+    /// The range of the polygon's vertices in [PolygonBatch::vertices].
+    pub vertices: Range<usize>,
+    /// The range of the polygon's triangle indices in [PolygonBatch::triangles].
+    pub triangles: Range<usize>,
+}
+
+/// Triangulates the interior of every polygon in `polygons`.
+///
+/// Each polygon is a simple ring of vertices in either orientation, without
+/// holes. A closing vertex that repeats the first one is dropped. A polygon
+/// with `n` vertices produces `n - 2` triangles, all wound counter-clockwise.
+/// Self-intersecting polygons still produce `n - 2` triangles, but they may
+/// overlap.
+///
+/// The buffers used for clipping are shared across polygons, so batches of
+/// many small polygons don't allocate per polygon.
+///
+/// # Panics
+/// Panics if the combined vertex count doesn't fit in `I`.
+pub fn triangulate_polygons<I, T, P, V>(polygons: &[V]) -> PolygonBatch<T, I>
+where
+    I: Index,
+    T: Scalar,
+    P: HasPosition<T>,
+    V: AsRef<[P]>,
+{
+    let mut batch = PolygonBatch {
+        vertices: Vec::new(),
+        triangles: Vec::new(),
+        polygons: Vec::with_capacity(polygons.len()),
+    };
+    let mut ring = Ring::default();
+
+    for polygon in polygons {
+        let polygon = polygon.as_ref();
+        let start = batch.vertices.len();
+        let triangles_start = batch.triangles.len();
+
+        batch.vertices.extend(polygon.iter().map(|p| p.pos()));
+        if batch.vertices.len() > start + 1 && batch.vertices.last() == batch.vertices.get(start) {
+            batch.vertices.pop();
+        }
+        assert!(
+            batch.vertices.len() <= I::max_value().as_usize(),
+            "Too many vertices to index a PolygonBatch by {}",
+            std::any::type_name::<I>()
+        );
+
+        let triangles = &mut batch.triangles;
+        ring.clip(&batch.vertices[start..], |[a, b, c]| {
+            triangles.extend([a, b, c].map(|i| I::from_usize(start + i)));
+        });
+
+        batch.polygons.push(PolygonRange {
+            vertices: start..batch.vertices.len(),
+            triangles: triangles_start..batch.triangles.len(),
+        });
+    }
+    batch
+}
+
+/// A doubly linked list of the vertices that haven't been clipped yet.
+#[derive(Default)]
+struct Ring {
+    prev: Vec<usize>,
+    next: Vec<usize>,
+}
+
+impl Ring {
+    /// Clips the ears of `polygon` one by one, reporting each as a triangle.
+    fn clip<T: Scalar>(&mut self, polygon: &[Point<T>], mut emit: impl FnMut([usize; 3])) {
+        let n = polygon.len();
+        if n < 3 {
+            return;
+        }
+
+        // walk the ring counter-clockwise
+        let ccw = util::signed_area(polygon) >= T::from_f64(0.0);
+        let step = |i: usize, forward: bool| {
+            if forward == ccw {
+                (i + 1) % n
+            } else {
+                (i + n - 1) % n
+            }
+        };
+        self.prev.clear();
+        self.next.clear();
+        self.prev.extend((0..n).map(|i| step(i, false)));
+        self.next.extend((0..n).map(|i| step(i, true)));
+
+        let mut remaining = n;
+        let mut b = 0;
+        let mut misses = 0;
+        while remaining > 3 {
+            let (a, c) = (self.prev[b], self.next[b]);
+            // if a whole lap found no ear, the polygon is degenerate; clip anyway
+            if misses >= remaining || self.is_ear(polygon, a, b, c) {
+                emit([a, b, c]);
+                self.next[a] = c;
+                self.prev[c] = a;
+                remaining -= 1;
+                misses = 0;
+                b = c;
+            } else {
+                misses += 1;
+                b = c;
+            }
+        }
+        emit([self.prev[b], b, self.next[b]]);
+    }
+
+    /// Whether `a`, `b`, `c` is a convex corner with no other vertex inside.
+    fn is_ear<T: Scalar>(&self, polygon: &[Point<T>], a: usize, b: usize, c: usize) -> bool {
+        let zero = T::from_f64(0.0);
+        let [pa, pb, pc] = [a, b, c].map(|i| polygon[i]);
+        if (pb - pa).perp_dot(pc - pa) <= zero {
+            return false;
+        }
+
+        let mut i = self.next[c];
+        while i != a {
+            let p = polygon[i];
+            let inside = (pb - pa).perp_dot(p - pa) >= zero
+                && (pc - pb).perp_dot(p - pb) >= zero
+                && (pa - pc).perp_dot(p - pc) >= zero;
+            if inside && p != pa && p != pb && p != pc {
+                return false;
+            }
+            i = self.next[i];
+        }
+        true
+    }
+}
@@ -0,0 +1,119 @@
+//! Counters and timings collected while a [Triangulation] is constructed.
+//!
+//! Profiling is opt-in through [TriangulationBuilder::build_with_profile]; the
+//! regular constructors don't collect anything.
+//!
+//! ```rust
+//! use delaunator::{builder::TriangulationBuilder, Point, Triangulation};
+//!
+//! let points = vec![
+//!     Point { x: 0., y: 0. },
+//!     Point { x: 1., y: 0. },
+//!     Point { x: 1., y: 1. },
+//!     Point { x: 0., y: 1. },
+//! ];
+//!
+//! let (triangulation, profile): (Triangulation<usize>, _) = TriangulationBuilder::new()
+//!     .build_with_profile(&points)
+//!     .expect("No triangulation exists.");
+//! assert_eq!(profile.inserted, 1);
+//! ```
+//!
+//! [TriangulationBuilder::build_with_profile]: crate::builder::TriangulationBuilder::build_with_profile
+
+use std::time::{Duration, Instant};
+
+use crate::{
+    observer::{Observer, Phase},
+    traits::{Index, PointSource, Scalar},
+    Triangulation,
+};
+
+/// Counters and per-phase timings of one construction.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Profile {
+    /// The number of points inserted after the seed triangle.
+    pub inserted: usize,
+    /// The number of edge flips performed to restore the Delaunay condition.
+    pub flips: usize,
+    /// The number of hull hash buckets probed to find a starting edge.
+    pub hash_probes: usize,
+    /// The number of hull edges walked to find a visible edge.
+    pub hull_steps: usize,
+    /// Time spent removing duplicates (see [dedup](crate::builder::TriangulationBuilder::dedup)).
+    pub dedup: Duration,
+    /// Time spent choosing the seed triangle.
+    pub seed: Duration,
+    /// Time spent sorting the points by distance from the seed.
+    pub sort: Duration,
+    /// Time spent inserting points and flipping edges.
+    pub insert: Duration,
+    /// Time spent producing the final hull and buffers.
+    pub finish: Duration,
+}
+
+impl Profile {
+    /// The total time spent in all phases.
+    pub fn total(&self) -> Duration {
+        self.dedup + self.seed + self.sort + self.insert + self.finish
+    }
+}
+
+/// Collects a [Profile] during construction.
+pub(crate) struct Profiler {
+    pub(crate) profile: Profile,
+    phase: Option<(Phase, Instant)>,
+}
+
+impl Profiler {
+    pub(crate) fn new() -> Self {
+        Profiler {
+            profile: Profile::default(),
+            phase: None,
+        }
+    }
+
+    fn end_phase(&mut self) {
+        if let Some((phase, start)) = self.phase.take() {
+            let elapsed = start.elapsed();
+            let profile = &mut self.profile;
+            *match phase {
+                Phase::Dedup => &mut profile.dedup,
+                Phase::Seed => &mut profile.seed,
+                Phase::Sort => &mut profile.sort,
+                Phase::Insert => &mut profile.insert,
+                Phase::Finish => &mut profile.finish,
+            } += elapsed;
+        }
+    }
+}
+
+impl<T: Scalar, I: Index> Observer<T, I> for Profiler {
+    fn phase(&mut self, phase: Phase) {
+        self.end_phase();
+        self.phase = Some((phase, Instant::now()));
+    }
+
+    fn flip(
+        &mut self,
+        _halfedges: (usize, usize),
+        _removed: (usize, usize),
+        _added: (usize, usize),
+    ) {
+        self.profile.flips += 1;
+    }
+
+    fn hull_search(&mut self, probes: usize, steps: usize, found: bool) {
+        self.profile.inserted += found as usize;
+        self.profile.hash_probes += probes;
+        self.profile.hull_steps += steps;
+    }
+
+    fn finish<S: PointSource<T> + ?Sized>(
+        &mut self,
+        _triangulation: &Triangulation<I>,
+        _points: &S,
+    ) {
+        self.end_phase();
+    }
+}
@@ -0,0 +1,337 @@
+use std::collections::HashSet;
+
+use crate::{
+    elem::Triangle,
+    iter::{BBoxTriangleIter, SpatialOrder, SpatialTriangleIter},
+    traits::{HasPosition, Index, Scalar},
+    util::{hilbert_index, morton_index, next_halfedge},
+    Point, Triangulation,
+};
+
+/// Where a walk through the triangulation ended.
+pub(crate) enum Walk {
+    /// The point is inside (or on the boundary of) the triangle of this half-edge.
+    Inside(usize),
+    /// The point is outside the convex hull; the walk stopped at this hull half-edge.
+    Outside(usize),
+}
+
+impl<I: Index> Triangulation<I> {
+    /// Finds the [Triangle] containing `p`, or `None` if `p` is outside the convex hull.
+    ///
+    /// Points on an edge or vertex may be reported in any of the adjacent triangles.
+    pub fn locate<T: Scalar, P: HasPosition<T>>(
+        &self,
+        points: &[P],
+        p: Point<T>,
+    ) -> Option<Triangle<'_, I>> {
+        if self.is_empty() {
+            return None;
+        }
+        match self.walk(points, p, 0) {
+            Walk::Inside(e) => Some(Triangle {
+                triangulation: self.view(),
+                index: e - e % 3,
+            }),
+            Walk::Outside(_) => None,
+        }
+    }
+
+    /// Finds the point of the triangulation nearest to `p`, or `None` if the
+    /// triangulation is empty.
+    ///
+    /// Walks to `p` and then greedily along Delaunay edges to ever closer
+    /// points, which always ends at the nearest one.
+    pub fn nearest<T: Scalar, P: HasPosition<T>>(
+        &self,
+        points: &[P],
+        p: Point<T>,
+    ) -> Option<usize> {
+        self.nearest_from(points, p, 0)
+            .map(|e| self.triangles[e].as_usize())
+    }
+
+    /// Finds the triangle containing each of `queries` in parallel, like
+    /// [locate](Self::locate).
+    ///
+    /// Each thread starts its walks where its previous query ended, so queries
+    /// that are close together in the slice, such as the pixels of a raster in
+    /// row order, take only a few steps each.
+    ///
+    /// Queries only read the triangulation, which is `Send` and `Sync` whenever
+    /// its index type is, so any number of threads may query it at once.
+    #[cfg(feature = "rayon")]
+    pub fn locate_many<T, P>(
+        &self,
+        points: &[P],
+        queries: &[Point<T>],
+    ) -> Vec<Option<Triangle<'_, I>>>
+    where
+        I: Send + Sync,
+        T: Scalar + Sync,
+        P: HasPosition<T> + Sync,
+    {
+        use rayon::prelude::*;
+
+        if self.is_empty() {
+            return queries.iter().map(|_| None).collect();
+        }
+        queries
+            .par_iter()
+            .map_init(
+                || 0,
+                |hint, &q| match self.walk(points, q, *hint) {
+                    Walk::Inside(e) => {
+                        *hint = e;
+                        Some(Triangle {
+                            triangulation: self.view(),
+                            index: e - e % 3,
+                        })
+                    }
+                    Walk::Outside(e) => {
+                        *hint = e;
+                        None
+                    }
+                },
+            )
+            .collect()
+    }
+
+    /// Finds the point nearest to each of `queries` in parallel, like
+    /// [nearest](Self::nearest), with the same walk hints as
+    /// [locate_many](Self::locate_many).
+    #[cfg(feature = "rayon")]
+    pub fn nearest_many<T, P>(&self, points: &[P], queries: &[Point<T>]) -> Vec<Option<usize>>
+    where
+        I: Sync,
+        T: Scalar + Sync,
+        P: HasPosition<T> + Sync,
+    {
+        use rayon::prelude::*;
+
+        queries
+            .par_iter()
+            .map_init(
+                || 0,
+                |hint, &q| {
+                    let e = self.nearest_from(points, q, *hint)?;
+                    *hint = e;
+                    Some(self.triangles[e].as_usize())
+                },
+            )
+            .collect()
+    }
+
+    /// An iterator over the [Triangle]s that intersect the axis-aligned box
+    /// from `min` to `max`.
+    ///
+    /// Finds a first triangle by walking towards the box (or around the convex
+    /// hull if the box center is outside it) and then floods through adjacent
+    /// triangles, so no spatial index is needed.
+    pub fn triangles_in_bbox<'a, T: Scalar, P: HasPosition<T>>(
+        &'a self,
+        points: &'a [P],
+        min: Point<T>,
+        max: Point<T>,
+    ) -> BBoxTriangleIter<'a, T, I, P> {
+        let mut iter = BBoxTriangleIter {
+            triangulation: self.view(),
+            points,
+            min,
+            max,
+            stack: Vec::new(),
+            visited: HashSet::new(),
+        };
+        if self.is_empty() || min.x > max.x || min.y > max.y {
+            return iter;
+        }
+
+        let center = Point::new(
+            (min.x + max.x) / T::from_f64(2.0),
+            (min.y + max.y) / T::from_f64(2.0),
+        );
+        match self.walk(points, center, 0) {
+            Walk::Inside(e) => iter.push(e / 3),
+            Walk::Outside(start) => {
+                let mut e = start;
+                loop {
+                    if iter.intersects(e / 3) {
+                        iter.push(e / 3);
+                    }
+                    e = self.next_hull_halfedge(e);
+                    if e == start {
+                        break;
+                    }
+                }
+            }
+        }
+        iter
+    }
+
+    /// An iterator over all [Triangle]s in the order of a space-filling curve
+    /// through their centroids.
+    ///
+    /// Consecutive triangles are close together, which improves locality when
+    /// streaming or compressing large meshes. The ordering is computed once,
+    /// up front, in `O(n log n)`.
+    pub fn spatially_ordered_triangles<T: Scalar, P: HasPosition<T>>(
+        &self,
+        points: &[P],
+        order: SpatialOrder,
+    ) -> SpatialTriangleIter<'_, I> {
+        let centroids: Vec<(f64, f64)> = (0..self.len())
+            .map(|t| {
+                let [a, b, c] = [3 * t, 3 * t + 1, 3 * t + 2]
+                    .map(|e| points[self.triangles[e].as_usize()].pos());
+                let x = (a.x + b.x + c.x).to_f64();
+                let y = (a.y + b.y + c.y).to_f64();
+                (x / 3.0, y / 3.0)
+            })
+            .collect();
+
+        let (mut min_x, mut min_y) = (f64::INFINITY, f64::INFINITY);
+        let (mut max_x, mut max_y) = (f64::NEG_INFINITY, f64::NEG_INFINITY);
+        for &(x, y) in &centroids {
+            min_x = min_x.min(x);
+            min_y = min_y.min(y);
+            max_x = max_x.max(x);
+            max_y = max_y.max(y);
+        }
+        let scale = f64::from(u16::MAX) / (max_x - min_x).max(max_y - min_y).max(f64::MIN_POSITIVE);
+
+        let keys: Vec<u64> = centroids
+            .iter()
+            .map(|&(x, y)| {
+                let x = ((x - min_x) * scale) as u32;
+                let y = ((y - min_y) * scale) as u32;
+                match order {
+                    SpatialOrder::Hilbert => hilbert_index(x, y),
+                    SpatialOrder::Morton => morton_index(x, y),
+                }
+            })
+            .collect();
+
+        let mut ids: Vec<usize> = (0..self.len()).collect();
+        ids.sort_by_key(|&t| keys[t]);
+
+        SpatialTriangleIter {
+            triangulation: self.view(),
+            order: ids.into_iter(),
+        }
+    }
+
+    /// Walks from the triangle of half-edge `start` towards `p`.
+    pub(crate) fn walk<T: Scalar, P: HasPosition<T>>(
+        &self,
+        points: &[P],
+        p: Point<T>,
+        start: usize,
+    ) -> Walk {
+        let pos = |e: usize| points[self.triangles[e].as_usize()].pos();
+
+        let mut e = start;
+        // a visibility walk can cycle around degenerate configurations; fall back
+        // to a full scan if it takes longer than visiting every triangle
+        for _ in 0..=self.len() {
+            let t = e - e % 3;
+            let exit = (0..3)
+                .map(|k| t + (e + k) % 3)
+                .find(|&f| pos(f).is_clockwise(pos(next_halfedge(f)), p));
+            match exit {
+                None => return Walk::Inside(e),
+                Some(f) => match self.halfedges[f].get() {
+                    None => return Walk::Outside(f),
+                    Some(twin) => e = next_halfedge(twin.as_usize()),
+                },
+            }
+        }
+
+        (0..self.len())
+            .map(|t| 3 * t)
+            .find(|&t| (t..t + 3).all(|f| !pos(f).is_clockwise(pos(next_halfedge(f)), p)))
+            .map_or_else(
+                || {
+                    let f = (0..self.halfedges.len())
+                        .find(|&f| self.halfedges[f].is_none())
+                        .unwrap();
+                    Walk::Outside(f)
+                },
+                Walk::Inside,
+            )
+    }
+
+    /// Walks from half-edge `start` to `p`, then greedily to the nearest point.
+    /// Returns a half-edge starting at that point.
+    fn nearest_from<T: Scalar, P: HasPosition<T>>(
+        &self,
+        points: &[P],
+        p: Point<T>,
+        start: usize,
+    ) -> Option<usize> {
+        if self.is_empty() {
+            return None;
+        }
+        let dist = |e: usize| {
+            points[self.triangles[e].as_usize()]
+                .pos()
+                .distance_squared(p)
+        };
+        let mut e = match self.walk(points, p, start) {
+            Walk::Inside(e) | Walk::Outside(e) => e,
+        };
+        loop {
+            let v = self.triangles[e].as_usize();
+            let closer = self
+                .view()
+                .get_half_edge(e)
+                .unwrap()
+                .start()
+                .incident_edges()
+                // a half-edge starting at the neighbor
+                .map(|h| {
+                    if h.start().id() == v {
+                        next_halfedge(h.id())
+                    } else {
+                        h.id()
+                    }
+                })
+                .filter(|&f| dist(f) < dist(e))
+                .min_by(|&f, &g| dist(f).total_cmp(&dist(g)));
+            match closer {
+                Some(f) => e = f,
+                None => return Some(e),
+            }
+        }
+    }
+
+    /// The hull half-edge that follows hull half-edge `e` around the hull.
+    pub(crate) fn next_hull_halfedge(&self, e: usize) -> usize {
+        let mut f = next_halfedge(e);
+        while let Some(twin) = self.halfedges[f].get() {
+            f = next_halfedge(twin.as_usize());
+        }
+        f
+    }
+}
+
+/// Tests if the triangle `a`, `b`, `c` intersects the box from `min` to `max`.
+pub(crate) fn triangle_intersects_bbox<T: Scalar>(
+    [a, b, c]: [Point<T>; 3],
+    min: Point<T>,
+    max: Point<T>,
+) -> bool {
+    if a.x.max(b.x).max(c.x) < min.x
+        || a.x.min(b.x).min(c.x) > max.x
+        || a.y.max(b.y).max(c.y) < min.y
+        || a.y.min(b.y).min(c.y) > max.y
+    {
+        return false;
+    }
+
+    // the box is outside if all of its corners are outside one of the edges
+    let corners = [min, Point::new(max.x, min.y), max, Point::new(min.x, max.y)];
+    let (a, b) = if a.is_clockwise(b, c) { (b, a) } else { (a, b) };
+    [(a, b), (b, c), (c, a)]
+        .iter()
+        .all(|&(p, q)| !corners.iter().all(|&corner| p.is_clockwise(q, corner)))
+}
@@ -0,0 +1,474 @@
+//! Delaunay refinement, which inserts new points until the triangles of a
+//! domain are well shaped, e.g. as a mesh for the finite element method.
+//!
+//! [Refinement] follows Ruppert's algorithm on a [ConstrainedTriangulation].
+//! Segments and hull edges that bound the domain are split at their midpoint
+//! while a point lies inside their diametral circle. Then the circumcenter of
+//! every triangle with an angle below the minimum is inserted, unless it
+//! would lie inside the diametral circle of a segment, which is split
+//! instead. Segments stay edges, so holes stay empty.
+//!
+//! Triangles larger than a [maximum area](Refinement::max_area) are split by
+//! their circumcenter in the same way. The maximum can differ between
+//! [regions](Refinement::region), the parts of the domain that segments
+//! divide it into.
+//!
+//! Refinement is guaranteed to finish for minimum angles up to about 20.7°
+//! as long as no two segments meet at less than 60°, and in practice finishes
+//! for minimum angles up to about 30°. The angle between two segments can't be
+//! improved by refinement, so a small one can make it add points forever;
+//! [max_points](Refinement::max_points) bounds the number of new points.
+//!
+//! ```rust
+//! use delaunator::{refine::Refinement, Point, Triangulation};
+//!
+//! // a square with a square hole
+//! let mut points = vec![
+//!     Point { x: 0., y: 0. },
+//!     Point { x: 3., y: 0. },
+//!     Point { x: 3., y: 3. },
+//!     Point { x: 0., y: 3. },
+//!     Point { x: 1., y: 1. },
+//!     Point { x: 2., y: 1. },
+//!     Point { x: 2., y: 2. },
+//!     Point { x: 1., y: 2. },
+//! ];
+//! let rings = [vec![0, 1, 2, 3], vec![4, 5, 6, 7]];
+//! let mut cdt = Triangulation::<usize>::from_pslg(&points, &rings, &[]).unwrap();
+//!
+//! let inserted = Refinement::new()
+//!     .min_angle(30.)
+//!     .max_area(0.1)
+//!     .max_points(1000)
+//!     .refine(&mut cdt, &mut points);
+//!
+//! assert_eq!(points.len(), 8 + inserted.len());
+//! // the domain has an area of 8
+//! assert!(cdt.domain_triangles().len() >= 3 * 80);
+//! ```
+
+use std::collections::VecDeque;
+
+use crate::{
+    attributes::Parent,
+    constrained::{orient, shell, ConstrainedTriangulation},
+    traits::{Index, Scalar},
+    util::{key, next_halfedge, prev_halfedge},
+    Point,
+};
+
+/// Options for refining a [ConstrainedTriangulation], see the
+/// [module documentation](self).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Refinement {
+    min_angle: f64,
+    max_area: f64,
+    regions: Vec<(Point<f64>, f64)>,
+    max_points: usize,
+}
+
+impl Refinement {
+    /// Creates options that refine until every angle is at least 20°, without
+    /// a limit on the area of triangles or the number of new points.
+    pub fn new() -> Self {
+        Refinement {
+            min_angle: 20.0,
+            max_area: f64::INFINITY,
+            regions: Vec::new(),
+            max_points: usize::MAX,
+        }
+    }
+
+    /// Sets the smallest angle, in degrees, allowed in the triangles of the
+    /// domain. At 0, only the maximum area and the segments with points in
+    /// their diametral circle cause splits.
+    ///
+    /// # Panics
+    ///
+    /// Panics unless `degrees` is at least 0 and less than 60.
+    pub fn min_angle(mut self, degrees: f64) -> Self {
+        assert!(
+            (0.0..60.0).contains(&degrees),
+            "the minimum angle must be at least 0° and less than 60°"
+        );
+        self.min_angle = degrees;
+        self
+    }
+
+    /// Sets the largest area allowed for the triangles of the domain outside
+    /// of any [region](Self::region).
+    pub fn max_area(mut self, area: f64) -> Self {
+        self.max_area = area;
+        self
+    }
+
+    /// Sets the largest area allowed for the triangles of the region around
+    /// `seed`: the triangles of the domain that can be reached from the one
+    /// containing `seed` without crossing a segment. A later region that
+    /// overlaps an earlier one replaces it, and a seed outside the domain is
+    /// ignored.
+    pub fn region(mut self, seed: Point<f64>, max_area: f64) -> Self {
+        self.regions.push((seed, max_area));
+        self
+    }
+
+    /// Stops after `max_points` new points.
+    pub fn max_points(mut self, max_points: usize) -> Self {
+        self.max_points = max_points;
+        self
+    }
+
+    /// Refines `cdt`, a constrained triangulation of `points`, inside its
+    /// domain, see [ConstrainedTriangulation::inside].
+    ///
+    /// The new points are appended to `points`, and returned with the edge or
+    /// triangle each one was inserted into as its [Parent], e.g. for
+    /// [Attributes::record](crate::attributes::Attributes::record), or
+    /// [EdgeMarkers::record](crate::markers::EdgeMarkers::record) to carry
+    /// the markers of split segments over to their halves.
+    pub fn refine<T: Scalar, I: Index>(
+        &self,
+        cdt: &mut ConstrainedTriangulation<I>,
+        points: &mut Vec<Point<T>>,
+    ) -> Vec<(usize, Parent)> {
+        let sin = self.min_angle.to_radians().sin();
+        let inside = cdt.inside();
+        let mut refiner = Refiner {
+            n: points.len(),
+            ratio: 1.0 / (4.0 * sin * sin),
+            cdt,
+            points,
+            max_area: vec![self.max_area; inside.len()],
+            inside,
+            segments: Vec::new(),
+            triangles: VecDeque::new(),
+            inserted: Vec::new(),
+        };
+        for &(seed, max_area) in &self.regions {
+            refiner.mark_region(seed, max_area);
+        }
+        refiner.run(self.max_points);
+
+        #[cfg(feature = "vertices")]
+        refiner
+            .cdt
+            .triangulation
+            .update_vertices(refiner.points.len());
+
+        refiner.inserted
+    }
+}
+
+impl Default for Refinement {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The state of a refinement.
+struct Refiner<'a, T: Scalar, I: Index> {
+    cdt: &'a mut ConstrainedTriangulation<I>,
+    points: &'a mut Vec<Point<T>>,
+    /// Whether each triangle, by id, is inside the domain.
+    inside: Vec<bool>,
+    /// The largest area allowed for each triangle, by id.
+    max_area: Vec<f64>,
+    /// The number of input points.
+    n: usize,
+    /// The largest allowed ratio of the squared circumradius of a triangle to
+    /// its squared shortest edge.
+    ratio: f64,
+    /// Boundary edges to check, as pairs of points.
+    segments: Vec<(usize, usize)>,
+    /// Triangles to check, as their points.
+    triangles: VecDeque<[usize; 3]>,
+    inserted: Vec<(usize, Parent)>,
+}
+
+impl<T: Scalar, I: Index> Refiner<'_, T, I> {
+    fn run(&mut self, max_points: usize) {
+        let t = &self.cdt.triangulation;
+        for e in t.unique_edges() {
+            if self.is_boundary(e) {
+                self.segments.push(self.points_of(e));
+            }
+        }
+        let triangles: Vec<_> = (0..t.len())
+            .filter(|&k| self.inside[k])
+            .map(|k| self.corners(k))
+            .collect();
+        self.triangles.extend(triangles);
+
+        // encroached segments first, then skinny triangles
+        while self.inserted.len() < max_points {
+            if let Some((a, b)) = self.segments.pop() {
+                if let Some(e) = self.find(a, b) {
+                    if self.is_boundary(e) && self.is_encroached(e) {
+                        self.split(e);
+                    }
+                }
+                continue;
+            }
+            let corners = match self.triangles.pop_front() {
+                Some(corners) => corners,
+                None => break,
+            };
+            let [a, b, c] = corners;
+            let t = match self.cdt.find_edge(a, b) {
+                Some(e) if self.cdt.triangulation.triangles[prev_halfedge(e)].as_usize() == c => {
+                    e / 3
+                }
+                _ => continue,
+            };
+            if !self.inside[t] || !self.is_bad(t) {
+                continue;
+            }
+            let [pa, pb, pc] = corners.map(|i| self.points[i]);
+            let center = pa.circumcenter(pb, pc);
+            if !center.x.to_f64().is_finite() || !center.y.to_f64().is_finite() {
+                continue;
+            }
+            match self.locate(t, center) {
+                Some(Ok(s)) => {
+                    let encroached = self.encroached_by(s, center);
+                    if encroached.is_empty() {
+                        if self.corners(s).iter().all(|&i| self.points[i] != center) {
+                            self.insert(center, s);
+                        }
+                    } else {
+                        for (u, v) in encroached {
+                            if let Some(e) = self.find(u, v) {
+                                self.split(e);
+                            }
+                        }
+                        self.triangles.push_back(corners);
+                    }
+                }
+                Some(Err(h)) => {
+                    // the circumcenter is hidden behind a boundary edge
+                    self.split(h);
+                    self.triangles.push_back(corners);
+                }
+                None => {}
+            }
+        }
+    }
+
+    /// Whether the edge of half-edge `e` is a segment or on the hull, next to
+    /// a triangle of the domain.
+    fn is_boundary(&self, e: usize) -> bool {
+        let twin = self.cdt.triangulation.halfedges[e].get().map(I::as_usize);
+        (twin.is_none() || self.cdt.is_constrained(e))
+            && (self.inside[e / 3] || twin.map_or(false, |f| self.inside[f / 3]))
+    }
+
+    /// Whether the apex of a triangle of the domain on either side of the edge
+    /// of half-edge `e` lies inside its diametral circle.
+    fn is_encroached(&self, e: usize) -> bool {
+        let twin = self.cdt.triangulation.halfedges[e].get().map(I::as_usize);
+        std::iter::once(e)
+            .chain(twin)
+            .any(|h| self.inside[h / 3] && self.encroaches(e, self.pos(prev_halfedge(h))))
+    }
+
+    /// Whether `p` lies inside the diametral circle of the edge of half-edge
+    /// `e`.
+    fn encroaches(&self, e: usize, p: Point<T>) -> bool {
+        let (a, b) = (self.pos(e) - p, self.pos(next_halfedge(e)) - p);
+        a.x * b.x + a.y * b.y < T::from_f64(0.0)
+    }
+
+    /// Whether triangle `t` has an angle below the minimum or is too large.
+    fn is_bad(&self, t: usize) -> bool {
+        let [a, b, c] = self.corners(t).map(|i| self.points[i]);
+        let shortest = a
+            .distance_squared(b)
+            .to_f64()
+            .min(b.distance_squared(c).to_f64())
+            .min(c.distance_squared(a).to_f64());
+        let area = orient(a, b, c).to_f64() / 2.0;
+        a.circumradius_squared(b, c).to_f64() > self.ratio * shortest || area > self.max_area[t]
+    }
+
+    /// Sets the largest area of the triangles in the region around `seed`.
+    fn mark_region(&mut self, seed: Point<f64>, max_area: f64) {
+        let tr = &self.cdt.triangulation;
+        let pos = |e: usize| {
+            let p = self.pos(e);
+            Point::new(p.x.to_f64(), p.y.to_f64())
+        };
+        let start = (0..tr.len()).find(|&k| {
+            self.inside[k]
+                && (3 * k..3 * k + 3).all(|e| orient(pos(e), pos(next_halfedge(e)), seed) >= 0.0)
+        });
+        let mut stack: Vec<usize> = start.into_iter().collect();
+        let mut region = vec![false; tr.len()];
+        while let Some(k) = stack.pop() {
+            if region[k] {
+                continue;
+            }
+            region[k] = true;
+            self.max_area[k] = max_area;
+            for h in 3 * k..3 * k + 3 {
+                if let Some(f) = tr.halfedges[h].get() {
+                    if !self.cdt.is_constrained(h) {
+                        stack.push(f.as_usize() / 3);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Walks in a straight line from the centroid of triangle `t` to `p`.
+    /// Returns the triangle that contains `p`, or the boundary edge in the
+    /// way, or `None` if the walk goes astray due to rounding.
+    fn locate(&self, t: usize, p: Point<T>) -> Option<Result<usize, usize>> {
+        let tr = &self.cdt.triangulation;
+        let [a, b, c] = self.corners(t).map(|i| self.points[i]);
+        let third = T::from_f64(1.0 / 3.0);
+        let o = (a + b + c) * third;
+        let zero = T::from_f64(0.0);
+        let (mut current, mut entry) = (t, usize::MAX);
+        for _ in 0..tr.len() {
+            let outside = (3 * current..3 * current + 3).filter(|&h| {
+                h != entry && orient(self.pos(h), self.pos(next_halfedge(h)), p) < zero
+            });
+            let crossing = outside.clone().find(|&h| {
+                let (s, t) = (
+                    orient(o, p, self.pos(h)),
+                    orient(o, p, self.pos(next_halfedge(h))),
+                );
+                s >= zero && t <= zero
+            });
+            let h = match crossing.or_else(|| outside.clone().next()) {
+                Some(h) => h,
+                None => return Some(Ok(current)),
+            };
+            if tr.halfedges[h].is_none() || self.cdt.is_constrained(h) {
+                return Some(Err(h));
+            }
+            entry = tr.halfedges[h].unwrap().as_usize();
+            current = entry / 3;
+        }
+        None
+    }
+
+    /// The boundary edges, as pairs of points, whose diametral circle would
+    /// contain `p` once it is inserted into triangle `s`, i.e. those around
+    /// the triangles whose circumcircle contains `p`.
+    fn encroached_by(&self, s: usize, p: Point<T>) -> Vec<(usize, usize)> {
+        let tr = &self.cdt.triangulation;
+        let mut cavity = vec![s];
+        let mut stack = vec![s];
+        let mut encroached = Vec::new();
+        while let Some(k) = stack.pop() {
+            for h in 3 * k..3 * k + 3 {
+                match tr.halfedges[h].get() {
+                    Some(f) if !self.cdt.is_constrained(h) => {
+                        let f = f.as_usize() / 3;
+                        let [a, b, c] = self.corners(f).map(|i| self.points[i]);
+                        if !cavity.contains(&f) && p.is_in_circle(a, b, c) {
+                            cavity.push(f);
+                            stack.push(f);
+                        }
+                    }
+                    _ => {
+                        if self.is_boundary(h) && self.encroaches(h, p) {
+                            encroached.push(self.points_of(h));
+                        }
+                    }
+                }
+            }
+        }
+        encroached
+    }
+
+    /// Splits the boundary edge of half-edge `e`, at its midpoint or, for an
+    /// edge from an input point to a new one, at a power of two from the
+    /// input point.
+    fn split(&mut self, e: usize) {
+        let tr = &self.cdt.triangulation;
+        let (a, b) = self.points_of(e);
+        let length = self.points[a].distance(self.points[b]).to_f64();
+        let t = match (a < self.n, b < self.n) {
+            (true, false) => shell(length),
+            (false, true) => 1.0 - shell(length),
+            _ => 0.5,
+        };
+        let sides: Vec<_> = std::iter::once(e)
+            .chain(tr.halfedges[e].get().map(I::as_usize))
+            .collect();
+        let m = self.cdt.split_segment(self.points, e, t);
+        // the split adds a triangle on each side, in that order
+        for h in sides {
+            self.inherit(h / 3, 1);
+        }
+        self.inserted.push((m, Parent::Edge { a, b, t }));
+        self.queue_around(m);
+    }
+
+    /// Inserts `p` into triangle `s`.
+    fn insert(&mut self, p: Point<T>, s: usize) {
+        let corners = self.corners(s);
+        let len = self.cdt.triangulation.len();
+        let m = self.cdt.insert_point(self.points, p, s);
+        // the new triangles are all on the side of `s`
+        let added = self.cdt.triangulation.len() - len;
+        self.inherit(s, added);
+        let parent = Parent::triangle(self.points, corners, m);
+        self.inserted.push((m, parent));
+        self.queue_around(m);
+    }
+
+    /// Adds `count` new triangles in the same part of the domain as triangle
+    /// `k`.
+    fn inherit(&mut self, k: usize, count: usize) {
+        self.inside
+            .extend(std::iter::repeat(self.inside[k]).take(count));
+        self.max_area
+            .extend(std::iter::repeat(self.max_area[k]).take(count));
+    }
+
+    /// Queues the triangles around the new point `m` and their boundary
+    /// edges.
+    fn queue_around(&mut self, m: usize) {
+        for h in self.cdt.around(m) {
+            let k = h / 3;
+            if self.inside[k] {
+                self.triangles.push_back(self.corners(k));
+            }
+            for g in 3 * k..3 * k + 3 {
+                if self.is_boundary(g) {
+                    self.segments.push(self.points_of(g));
+                }
+            }
+        }
+    }
+
+    /// The half-edge between points `a` and `b`, in either direction.
+    fn find(&self, a: usize, b: usize) -> Option<usize> {
+        self.cdt
+            .find_edge(a, b)
+            .or_else(|| self.cdt.find_edge(b, a))
+    }
+
+    /// The points of triangle `t`.
+    fn corners(&self, t: usize) -> [usize; 3] {
+        let triangles = &self.cdt.triangulation.triangles;
+        [3 * t, 3 * t + 1, 3 * t + 2].map(|e| triangles[e].as_usize())
+    }
+
+    /// The points of the edge of half-edge `e`, as the key of a segment.
+    fn points_of(&self, e: usize) -> (usize, usize) {
+        let triangles = &self.cdt.triangulation.triangles;
+        key(
+            triangles[e].as_usize(),
+            triangles[next_halfedge(e)].as_usize(),
+        )
+    }
+
+    /// The position of the point half-edge `e` starts at.
+    fn pos(&self, e: usize) -> Point<T> {
+        self.points[self.cdt.triangulation.triangles[e].as_usize()]
+    }
+}
@@ -0,0 +1,294 @@
+//! Point sampling, for generating sites to triangulate: blue noise with
+//! [PoissonDisk], and points placed by an importance map with
+//! [rejection_sample] or [DensityGrid::error_diffusion], e.g. for low-poly
+//! image stylization.
+//!
+//! ```rust
+//! use delaunator::{sampling::PoissonDisk, Point, Triangulation};
+//!
+//! let points = PoissonDisk::new(Point { x: 0., y: 0. }, Point { x: 100., y: 100. }, 5.)
+//!     .seed(7)
+//!     .sample();
+//! let triangulation = Triangulation::<usize>::new(&points).unwrap();
+//! assert!(triangulation.len() > 100);
+//! ```
+
+use std::f64::consts::TAU;
+
+use crate::Point;
+
+/// Poisson-disk sampling of a rectangle with Bridson's algorithm.
+///
+/// Produces points that are at least `radius` apart and cover the rectangle
+/// without large gaps, in time linear in the number of points. Results are
+/// deterministic for a given seed.
+#[derive(Clone, Copy, Debug)]
+pub struct PoissonDisk {
+    min: Point<f64>,
+    max: Point<f64>,
+    radius: f64,
+    attempts: usize,
+    seed: u64,
+}
+
+impl PoissonDisk {
+    /// Samples the rectangle from `min` to `max` with points at least `radius` apart.
+    ///
+    /// # Panics
+    /// Panics if `radius` is not positive.
+    pub fn new(min: Point<f64>, max: Point<f64>, radius: f64) -> Self {
+        assert!(radius > 0.0, "radius must be positive");
+        PoissonDisk {
+            min,
+            max,
+            radius,
+            attempts: 30,
+            seed: 0,
+        }
+    }
+
+    /// Sets the number of candidates tried around each point before giving up
+    /// on it. More attempts fill the rectangle more tightly. Defaults to 30.
+    pub fn attempts(mut self, attempts: usize) -> Self {
+        self.attempts = attempts;
+        self
+    }
+
+    /// Sets the seed of the random number generator. Defaults to 0.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Samples the rectangle uniformly.
+    pub fn sample(&self) -> Vec<Point<f64>> {
+        self.sample_with_density(|_| 1.0)
+    }
+
+    /// Samples the rectangle with a varying density between `0` and `1`.
+    ///
+    /// Around a point `p` the spacing is `radius / density(p).sqrt()`, so a
+    /// density of `0.25` yields a quarter as many points per area as a density
+    /// of `1`. Densities are clamped to `1e-6..=1`.
+    pub fn sample_with_density<F: Fn(Point<f64>) -> f64>(&self, density: F) -> Vec<Point<f64>> {
+        let mut rng = SplitMix64(self.seed);
+        let (width, height) = (self.max.x - self.min.x, self.max.y - self.min.y);
+        if width < 0.0 || height < 0.0 {
+            return Vec::new();
+        }
+        let spacing = |p: Point<f64>| self.radius / density(p).clamp(1e-6, 1.0).sqrt();
+
+        // points are at least `radius` apart, so each cell holds at most one
+        let cell = self.radius / std::f64::consts::SQRT_2;
+        let cols = (width / cell) as usize + 1;
+        let rows = (height / cell) as usize + 1;
+        let mut grid = vec![usize::MAX; cols * rows];
+        let locate = |p: Point<f64>| {
+            let cx = (((p.x - self.min.x) / cell) as usize).min(cols - 1);
+            let cy = (((p.y - self.min.y) / cell) as usize).min(rows - 1);
+            (cx, cy)
+        };
+
+        let mut points = Vec::new();
+        let mut active = Vec::new();
+        let add = |p: Point<f64>, grid: &mut [usize], points: &mut Vec<_>, active: &mut Vec<_>| {
+            let (cx, cy) = locate(p);
+            grid[cy * cols + cx] = points.len();
+            active.push(points.len());
+            points.push(p);
+        };
+
+        let first = Point::new(
+            self.min.x + rng.next_f64() * width,
+            self.min.y + rng.next_f64() * height,
+        );
+        add(first, &mut grid, &mut points, &mut active);
+
+        while !active.is_empty() {
+            let k = (rng.next_u64() % active.len() as u64) as usize;
+            let origin = points[active[k]];
+            let r = spacing(origin);
+
+            let mut found = false;
+            for _ in 0..self.attempts {
+                // uniform in the annulus between r and 2r
+                let angle = rng.next_f64() * TAU;
+                let distance = r * (1.0 + 3.0 * rng.next_f64()).sqrt();
+                let p = Point::new(
+                    origin.x + distance * angle.cos(),
+                    origin.y + distance * angle.sin(),
+                );
+                if p.x < self.min.x || p.x > self.max.x || p.y < self.min.y || p.y > self.max.y {
+                    continue;
+                }
+
+                let r = spacing(p);
+                let reach = (r / cell).ceil() as usize;
+                let (cx, cy) = locate(p);
+                let free = (cy.saturating_sub(reach)..(cy + reach + 1).min(rows)).all(|y| {
+                    (cx.saturating_sub(reach)..(cx + reach + 1).min(cols)).all(|x| {
+                        let i = grid[y * cols + x];
+                        i == usize::MAX || points[i].distance_squared(p) >= r * r
+                    })
+                });
+                if free {
+                    add(p, &mut grid, &mut points, &mut active);
+                    found = true;
+                    break;
+                }
+            }
+            if !found {
+                active.swap_remove(k);
+            }
+        }
+        points
+    }
+}
+
+/// Draws `count` random points in the rectangle from `min` to `max`, with a
+/// likelihood proportional to `density`, which should lie between `0` and `1`.
+///
+/// Uniform candidates are accepted with probability `density(p)`, so sparse
+/// densities need many candidates. Gives up after a thousand candidates per
+/// point, returning fewer points, e.g. if the density is zero everywhere.
+/// Results are deterministic for a given seed.
+///
+/// ```rust
+/// use delaunator::{sampling, Point};
+///
+/// let (min, max) = (Point { x: 0., y: 0. }, Point { x: 10., y: 10. });
+/// let points = sampling::rejection_sample(min, max, 100, 1, |p| p.x / 10.);
+/// assert_eq!(points.len(), 100);
+/// ```
+pub fn rejection_sample<F: Fn(Point<f64>) -> f64>(
+    min: Point<f64>,
+    max: Point<f64>,
+    count: usize,
+    seed: u64,
+    density: F,
+) -> Vec<Point<f64>> {
+    let mut rng = SplitMix64(seed);
+    let mut points = Vec::with_capacity(count);
+    for _ in 0..count.saturating_mul(1000) {
+        if points.len() == count {
+            break;
+        }
+        let p = Point::new(
+            min.x + rng.next_f64() * (max.x - min.x),
+            min.y + rng.next_f64() * (max.y - min.y),
+        );
+        if rng.next_f64() < density(p) {
+            points.push(p);
+        }
+    }
+    points
+}
+
+/// A grayscale image used as a density map, with one value between `0` and
+/// `1` per pixel in row-major order. Pixel `(x, y)` covers the unit square from
+/// `(x, y)` to `(x + 1, y + 1)`.
+#[derive(Clone, Copy, Debug)]
+pub struct DensityGrid<'a> {
+    width: usize,
+    height: usize,
+    values: &'a [f64],
+}
+
+impl<'a> DensityGrid<'a> {
+    /// Wraps the pixel values of a `width` by `height` image.
+    ///
+    /// # Panics
+    /// Panics if `values` doesn't hold `width * height` values.
+    pub fn new(width: usize, height: usize, values: &'a [f64]) -> Self {
+        assert_eq!(
+            values.len(),
+            width * height,
+            "values must hold one value per pixel"
+        );
+        DensityGrid {
+            width,
+            height,
+            values,
+        }
+    }
+
+    /// The value of the pixel containing `p`, or `0` outside the image. Can be
+    /// passed to [PoissonDisk::sample_with_density] and [rejection_sample].
+    pub fn density(&self, p: Point<f64>) -> f64 {
+        if p.x < 0.0 || p.y < 0.0 {
+            return 0.0;
+        }
+        let (x, y) = (p.x as usize, p.y as usize);
+        if x < self.width && y < self.height {
+            self.values[y * self.width + x]
+        } else {
+            0.0
+        }
+    }
+
+    /// Places points at pixel centers by Floyd-Steinberg error diffusion, so
+    /// that every region gets about `scale` times the sum of its values in
+    /// points. A `scale` of `1` puts a point in every pixel of value `1`.
+    ///
+    /// Rows are scanned in alternating directions, which avoids the diagonal
+    /// artifacts of scanning in one direction only.
+    ///
+    /// ```rust
+    /// use delaunator::sampling::DensityGrid;
+    ///
+    /// let values = vec![0.25; 16 * 16];
+    /// let points = DensityGrid::new(16, 16, &values).error_diffusion(1.);
+    /// // about a quarter of the pixels get a point
+    /// assert!((60..=64).contains(&points.len()));
+    /// ```
+    pub fn error_diffusion(&self, scale: f64) -> Vec<Point<f64>> {
+        let (width, height) = (self.width, self.height);
+        let mut error = vec![0.0; width * height];
+        let mut points = Vec::new();
+        for y in 0..height {
+            let forward = y % 2 == 0;
+            for k in 0..width {
+                let x = if forward { k } else { width - 1 - k };
+                let i = y * width + x;
+                let value = (self.values[i] * scale).clamp(0.0, 1.0) + error[i];
+                let quantized = if value >= 0.5 { 1.0 } else { 0.0 };
+                if quantized > 0.0 {
+                    points.push(Point::new(x as f64 + 0.5, y as f64 + 0.5));
+                }
+
+                // spread the error ahead in scan order
+                let rest = value - quantized;
+                let ahead = if forward { x + 1 } else { x.wrapping_sub(1) };
+                let behind = if forward { x.wrapping_sub(1) } else { x + 1 };
+                let mut spread = |x: usize, y: usize, weight: f64| {
+                    if x < width && y < height {
+                        error[y * width + x] += rest * weight;
+                    }
+                };
+                spread(ahead, y, 7.0 / 16.0);
+                spread(behind, y + 1, 3.0 / 16.0);
+                spread(x, y + 1, 5.0 / 16.0);
+                spread(ahead, y + 1, 1.0 / 16.0);
+            }
+        }
+        points
+    }
+}
+
+/// A small, fast pseudo-random number generator (SplitMix64).
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A uniform number in `0..1`.
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
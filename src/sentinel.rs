@@ -0,0 +1,124 @@
+//! Serializing missing indices as sentinel integers instead of `null`.
+//!
+//! By default an [OptionIndex] serializes as an `Option`, which is `null` in
+//! JSON. The JavaScript Delaunator and many binary formats use a sentinel
+//! integer instead. The modules here can be used with `#[serde(with = "...")]`
+//! on `Vec<OptionIndex<I>>` fields, and [Sentinel] serializes a whole
+//! [Triangulation] in the layout of the JavaScript Delaunator.
+//!
+//! ```rust
+//! use delaunator::{sentinel::Sentinel, Point, Triangulation};
+//!
+//! let points = vec![
+//!     Point { x: 0., y: 0. },
+//!     Point { x: 1., y: 0. },
+//!     Point { x: 0., y: 1. },
+//! ];
+//! let triangulation = Triangulation::<u32>::new(&points).unwrap();
+//!
+//! let json = serde_json::to_string(&Sentinel(&triangulation)).unwrap();
+//! assert!(json.contains(r#""halfedges":[-1,-1,-1]"#));
+//! ```
+
+use serde::{de, ser::SerializeStruct, Deserialize, Serialize};
+
+use crate::{traits::Index, util::OptionIndex, Triangulation};
+
+/// Serializes `None` as `-1`, and other values as signed integers.
+pub mod minus_one {
+    use super::*;
+
+    pub fn serialize<I, S>(values: &[OptionIndex<I>], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        I: Index,
+        S: serde::Serializer,
+    {
+        serializer.collect_seq(
+            values
+                .iter()
+                .map(|v| v.map(|n| n.as_usize() as i64).unwrap_or(-1)),
+        )
+    }
+
+    pub fn deserialize<'de, I, D>(deserializer: D) -> Result<Vec<OptionIndex<I>>, D::Error>
+    where
+        I: Index,
+        D: serde::Deserializer<'de>,
+    {
+        Vec::<i64>::deserialize(deserializer)?
+            .into_iter()
+            .map(|n| match n {
+                -1 => Ok(OptionIndex::none()),
+                n if n >= 0 && (n as u64) < I::max_value().as_usize() as u64 => {
+                    Ok(OptionIndex::some(I::from_usize(n as usize)))
+                }
+                n => Err(de::Error::custom(format_args!("index {} out of range", n))),
+            })
+            .collect()
+    }
+}
+
+/// Serializes `None` as the maximum value of the index type, e.g. `u32::MAX`,
+/// which is how an [OptionIndex] is stored in memory.
+pub mod max_value {
+    use super::*;
+
+    pub fn serialize<I, S>(values: &[OptionIndex<I>], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        I: Index + Serialize,
+        S: serde::Serializer,
+    {
+        serializer.collect_seq(values.iter().map(|v| v.unwrap_or(I::max_value())))
+    }
+
+    pub fn deserialize<'de, I, D>(deserializer: D) -> Result<Vec<OptionIndex<I>>, D::Error>
+    where
+        I: Index + Deserialize<'de>,
+        D: serde::Deserializer<'de>,
+    {
+        let values = Vec::<I>::deserialize(deserializer)?;
+        Ok(values.into_iter().map(OptionIndex::from).collect())
+    }
+}
+
+/// Serializes a [Triangulation] with `-1` for missing half-edges, as the
+/// JavaScript Delaunator stores them.
+pub struct Sentinel<'a, I>(pub &'a Triangulation<I>);
+
+impl<'a, I> Serialize for Sentinel<'a, I>
+where
+    I: Serialize + Index,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        struct Halfedges<'a, I>(&'a [OptionIndex<I>]);
+
+        impl<'a, I: Index> Serialize for Halfedges<'a, I> {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+            {
+                minus_one::serialize(self.0, serializer)
+            }
+        }
+
+        #[cfg(feature = "vertices")]
+        const FIELD_COUNT: usize = 4;
+        #[cfg(not(feature = "vertices"))]
+        const FIELD_COUNT: usize = 3;
+
+        let triangulation = self.0;
+        let mut state = serializer.serialize_struct("Triangulation", FIELD_COUNT)?;
+
+        #[cfg(feature = "vertices")]
+        state.serialize_field("vertices", &triangulation.vertices)?;
+
+        state.serialize_field("triangles", &triangulation.triangles)?;
+        state.serialize_field("halfedges", &Halfedges(&triangulation.halfedges))?;
+        state.serialize_field("hull", &triangulation.hull)?;
+
+        state.end()
+    }
+}
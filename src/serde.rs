@@ -114,7 +114,7 @@ where
 
         struct TriangulationVisitor<I> {
             phantom: PhantomData<I>,
-        };
+        }
 
         impl<'de, I> de::Visitor<'de> for TriangulationVisitor<I>
         where
@@ -0,0 +1,328 @@
+//! Shape reconstruction and analysis from points sampled along curves.
+
+use crate::{
+    traits::{ApproxEq, HasPosition, Scalar},
+    util::next_halfedge,
+    Point, Triangulation,
+};
+
+/// Reconstructs the curves through unorganized samples with the crust
+/// algorithm of Amenta, Bern and Eppstein.
+///
+/// The Voronoi vertices of the samples approximate the medial axis of the
+/// curves, so in a triangulation of the samples together with those vertices,
+/// the edges between two samples hug the curves. These edges are chained into
+/// polylines of point indices. A closed curve repeats its first index at the
+/// end. Curves are recovered when they are sampled densely relative to their
+/// curvature and to the distance between them.
+///
+/// Collinear samples yield a single polyline along their line.
+///
+/// ```rust
+/// use delaunator::{shape, Point};
+///
+/// let points: Vec<_> = (0..24)
+///     .map(|i| {
+///         let angle = i as f64 * std::f64::consts::TAU / 24.;
+///         Point { x: angle.cos(), y: angle.sin() }
+///     })
+///     .collect();
+///
+/// let curves = shape::crust(&points);
+/// assert_eq!(curves.len(), 1);
+/// assert_eq!(curves[0].len(), 25);
+/// ```
+pub fn crust<T: Scalar + ApproxEq, P: HasPosition<T>>(points: &[P]) -> Vec<Vec<usize>> {
+    let n = points.len();
+    let triangulation = match Triangulation::<usize>::new(points) {
+        Some(triangulation) => triangulation,
+        None => return collinear_chain(points).into_iter().collect(),
+    };
+
+    // Voronoi vertices of thin hull triangles lie far away and only cost
+    // precision, so keep those near the samples
+    let (min, max) = bbox(points.iter().map(|p| p.pos()));
+    let center = Point::new(
+        (min.x + max.x) / T::from_f64(2.0),
+        (min.y + max.y) / T::from_f64(2.0),
+    );
+    let reach = min.distance(max) * T::from_f64(10.0);
+    let mut combined: Vec<Point<T>> = points.iter().map(|p| p.pos()).collect();
+    combined.extend(
+        triangulation
+            .circumcenters(points)
+            .into_iter()
+            .filter(|c| c.distance(center) <= reach),
+    );
+    let combined = Triangulation::<usize>::new(&combined).unwrap_or(triangulation);
+
+    let mut neighbors = vec![Vec::new(); n];
+    for e in combined.unique_edges() {
+        let (a, b) = (combined.triangles[e], combined.triangles[next_halfedge(e)]);
+        if a < n && b < n {
+            neighbors[a].push(b);
+            neighbors[b].push(a);
+        }
+    }
+    chains(&mut neighbors)
+}
+
+/// An approximate medial axis, see [medial_axis].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MedialAxis<T: Scalar> {
+    /// The vertices of the axis.
+    pub vertices: Vec<Point<T>>,
+    /// The distance from each vertex to the nearest boundary sample, i.e. the
+    /// half-width of the shape there.
+    pub radii: Vec<T>,
+    /// The edges of the axis, as pairs of indices into `vertices`.
+    pub edges: Vec<(usize, usize)>,
+}
+
+/// Approximates the medial axis (skeleton) of the shape bounded by the closed
+/// polygon `boundary`, which should be densely sampled.
+///
+/// The axis is made of the Voronoi edges of the boundary samples that lie inside
+/// the shape. Samples that are close together along the boundary create small
+/// spurious branches; an edge is kept only if the two samples it separates are
+/// more than `prune` apart (the λ-medial axis). A `prune` a few times the
+/// sample spacing removes most noise; larger values keep only the main branches.
+///
+/// ```rust
+/// use delaunator::{shape, Point};
+///
+/// // a 4 x 1 rectangle, sampled every 0.1
+/// let mut boundary = Vec::new();
+/// for i in 0..40 { boundary.push(Point { x: i as f64 * 0.1, y: 0. }); }
+/// for i in 0..10 { boundary.push(Point { x: 4., y: i as f64 * 0.1 }); }
+/// for i in 0..40 { boundary.push(Point { x: 4. - i as f64 * 0.1, y: 1. }); }
+/// for i in 0..10 { boundary.push(Point { x: 0., y: 1. - i as f64 * 0.1 }); }
+///
+/// // the axis runs along the middle, half a unit from either side
+/// let axis = shape::medial_axis(&boundary, 0.5);
+/// let (p, r) = axis.vertices.iter().zip(&axis.radii)
+///     .find(|(p, _)| (p.x - 2.).abs() < 0.1)
+///     .unwrap();
+/// assert!((p.y - 0.5).abs() < 1e-9 && (r - 0.5).abs() < 0.01);
+/// ```
+pub fn medial_axis<T: Scalar + ApproxEq>(boundary: &[Point<T>], prune: T) -> MedialAxis<T> {
+    let mut axis = MedialAxis {
+        vertices: Vec::new(),
+        radii: Vec::new(),
+        edges: Vec::new(),
+    };
+    let triangulation = match Triangulation::<usize>::new(boundary) {
+        Some(triangulation) => triangulation,
+        None => return axis,
+    };
+
+    let centers = triangulation.circumcenters(boundary);
+    let mut vertex = vec![None; centers.len()];
+    let mut add = |t: usize, axis: &mut MedialAxis<T>| {
+        *vertex[t].get_or_insert_with(|| {
+            axis.vertices.push(centers[t]);
+            axis.radii
+                .push(centers[t].distance(boundary[triangulation.triangles[3 * t]]));
+            axis.vertices.len() - 1
+        })
+    };
+    for (e, twin) in triangulation.halfedges.iter().enumerate() {
+        let f = match twin.get() {
+            Some(f) if e > f => f,
+            _ => continue,
+        };
+        let (a, b) = (triangulation.triangles[e], triangulation.triangles[f]);
+        let (t, u) = (e / 3, f / 3);
+        if boundary[a].distance(boundary[b]) > prune
+            && contains(boundary, centers[t])
+            && contains(boundary, centers[u])
+        {
+            let edge = (add(t, &mut axis), add(u, &mut axis));
+            axis.edges.push(edge);
+        }
+    }
+    axis
+}
+
+/// Finds the pole of inaccessibility of a polygon: the interior point farthest
+/// from its boundary, where a map label fits best. Returns the point and its
+/// distance to the boundary, or `None` if the polygon has no interior.
+///
+/// `rings` are the closed rings of the polygon, the outer ring and its holes,
+/// combined by the even-odd rule. Their edges are sampled every `precision`,
+/// and the pole is taken among the Voronoi vertices of the samples that lie
+/// inside the polygon, like the vertices of the [medial_axis]. Its distance is
+/// within `precision / 2` of the true maximum, so the number of samples grows
+/// with the perimeter divided by `precision`.
+///
+/// ```rust
+/// use delaunator::{shape, Point};
+///
+/// let square = [(0f64, 0.), (4., 0.), (4., 4.), (0., 4.)].map(|(x, y)| Point { x, y });
+/// let hole = [(0.5, 0.5), (1.5, 0.5), (1.5, 1.5), (0.5, 1.5)].map(|(x, y)| Point { x, y });
+///
+/// let (p, distance) = shape::pole_of_inaccessibility(&[&square[..], &hole], 0.01).unwrap();
+/// assert!(p.x > 2. && p.y > 2.);
+/// assert!(distance > 1.45 && distance <= p.x.min(p.y).min(4. - p.x).min(4. - p.y));
+/// ```
+pub fn pole_of_inaccessibility<T, V>(rings: &[V], precision: T) -> Option<(Point<T>, T)>
+where
+    T: Scalar + ApproxEq,
+    V: AsRef<[Point<T>]>,
+{
+    let zero = T::from_f64(0.0);
+    let edges = || {
+        rings.iter().flat_map(|ring| {
+            let ring = ring.as_ref();
+            (0..ring.len()).map(move |k| (ring[k], ring[(k + 1) % ring.len()]))
+        })
+    };
+
+    let mut samples = Vec::new();
+    for (a, b) in edges() {
+        let length = a.distance(b);
+        let steps = if precision > zero {
+            (length / precision).to_f64().ceil().max(1.0)
+        } else {
+            1.0
+        };
+        for i in 0..steps as usize {
+            let t = T::from_f64(i as f64 / steps);
+            samples.push(Point::new(a.x + (b.x - a.x) * t, a.y + (b.y - a.y) * t));
+        }
+    }
+    let triangulation = Triangulation::<usize>::new(&samples)?;
+
+    // every boundary point is within half a step of a sample, so a Voronoi
+    // vertex is at most its radius and at least its radius minus half a step
+    // from the boundary; check the widest circles until none can do better
+    let mut candidates: Vec<(Point<T>, T)> = triangulation
+        .circumcenters(&samples)
+        .into_iter()
+        .enumerate()
+        .map(|(t, c)| (c, c.distance(samples[triangulation.triangles[3 * t]])))
+        .collect();
+    candidates.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    let mut pole: Option<(Point<T>, T)> = None;
+    for (c, radius) in candidates {
+        if pole.map_or(false, |(_, best)| radius <= best) {
+            break;
+        }
+        let inside = rings
+            .iter()
+            .filter(|ring| contains(ring.as_ref(), c))
+            .count()
+            % 2
+            == 1;
+        if inside {
+            let distance = edges()
+                .map(|(a, b)| segment_distance(c, a, b))
+                .fold(T::infinity(), T::min);
+            if pole.map_or(true, |(_, best)| distance > best) {
+                pole = Some((c, distance));
+            }
+        }
+    }
+    pole
+}
+
+/// The distance from `p` to the segment from `a` to `b`.
+fn segment_distance<T: Scalar>(p: Point<T>, a: Point<T>, b: Point<T>) -> T {
+    let (d, v) = (b - a, p - a);
+    let length = d.length_squared();
+    if length <= T::from_f64(0.0) {
+        return p.distance(a);
+    }
+    let t = ((v.x * d.x + v.y * d.y) / length)
+        .max(T::from_f64(0.0))
+        .min(T::from_f64(1.0));
+    p.distance(Point::new(a.x + d.x * t, a.y + d.y * t))
+}
+
+/// Whether `p` lies inside the closed `polygon`, by the even-odd rule.
+fn contains<T: Scalar>(polygon: &[Point<T>], p: Point<T>) -> bool {
+    encloses(
+        (0..polygon.len()).map(|k| (polygon[k], polygon[(k + 1) % polygon.len()])),
+        p,
+    )
+}
+
+/// Whether `p` lies inside the region bounded by `edges`, by the even-odd
+/// rule. The edges may come in any order and form several rings.
+pub(crate) fn encloses<T: Scalar>(
+    edges: impl IntoIterator<Item = (Point<T>, Point<T>)>,
+    p: Point<T>,
+) -> bool {
+    let zero = T::from_f64(0.0);
+    let mut inside = false;
+    for (a, b) in edges {
+        if (a.y > p.y) != (b.y > p.y) && ((b - a).perp_dot(p - a) > zero) == (b.y > a.y) {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+/// Splits the graph given by `neighbors` into polylines, consuming its edges.
+/// Paths between points of a degree other than two come first, then cycles.
+fn chains(neighbors: &mut [Vec<usize>]) -> Vec<Vec<usize>> {
+    let mut chains = Vec::new();
+    let ends: Vec<usize> = (0..neighbors.len())
+        .filter(|&i| neighbors[i].len() != 2)
+        .collect();
+    for start in ends.into_iter().chain(0..neighbors.len()) {
+        while let Some(&next) = neighbors[start].first() {
+            let mut chain = vec![start];
+            let (mut prev, mut at) = (start, next);
+            loop {
+                unlink(neighbors, prev, at);
+                chain.push(at);
+                if at == start || neighbors[at].len() != 1 {
+                    break;
+                }
+                prev = at;
+                at = neighbors[at][0];
+            }
+            chains.push(chain);
+        }
+    }
+    chains
+}
+
+/// Removes the edge between `a` and `b`.
+fn unlink(neighbors: &mut [Vec<usize>], a: usize, b: usize) {
+    neighbors[a].retain(|&x| x != b);
+    neighbors[b].retain(|&x| x != a);
+}
+
+/// Collinear points in order along their line, without duplicates, or `None`
+/// if there are fewer than two distinct points.
+fn collinear_chain<T: Scalar, P: HasPosition<T>>(points: &[P]) -> Option<Vec<usize>> {
+    let origin = points.first()?.pos();
+    let direction = points.iter().map(|p| p.pos()).find(|&p| p != origin)? - origin;
+    let along = |i: usize| {
+        let d = points[i].pos() - origin;
+        d.x * direction.x + d.y * direction.y
+    };
+    let mut order: Vec<usize> = (0..points.len()).collect();
+    order.sort_by(|&a, &b| along(a).total_cmp(&along(b)).then(a.cmp(&b)));
+    order.dedup_by(|&mut b, &mut a| points[a].pos() == points[b].pos());
+    Some(order)
+}
+
+/// The corners of the bounding box of `points`.
+fn bbox<T: Scalar>(points: impl Iterator<Item = Point<T>>) -> (Point<T>, Point<T>) {
+    points.fold(
+        (
+            Point::new(T::infinity(), T::infinity()),
+            Point::new(-T::infinity(), -T::infinity()),
+        ),
+        |(min, max), p| {
+            (
+                Point::new(min.x.min(p.x), min.y.min(p.y)),
+                Point::new(max.x.max(p.x), max.y.max(p.y)),
+            )
+        },
+    )
+}
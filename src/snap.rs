@@ -0,0 +1,76 @@
+//! Snapping input points to a grid before triangulating.
+//!
+//! Survey and sensor data often contains points that differ only by noise.
+//! Snapping them to a grid merges such near-coincident points and removes the
+//! slivers they would otherwise produce.
+//!
+//! ```rust
+//! use delaunator::{snap, Point, Triangulation};
+//!
+//! let points = vec![
+//!     Point { x: 0.001, y: -0.002 },
+//!     Point { x: 1.0, y: 0.0 },
+//!     Point { x: 0.998, y: 1.003 },
+//!     Point { x: -0.003, y: 0.001 },
+//! ];
+//!
+//! let snapped = snap::snap_to_grid(&points, 0.1);
+//! assert_eq!(snapped.points.len(), 3);
+//! assert_eq!(snapped.mapping, [0, 1, 2, 0]);
+//!
+//! let triangulation = Triangulation::<usize>::new(&snapped.points).unwrap();
+//! assert_eq!(triangulation.len(), 1);
+//! ```
+
+use std::collections::HashMap;
+
+use crate::{
+    traits::{HasPosition, Scalar},
+    Point,
+};
+
+/// Points snapped to a grid, see [snap_to_grid].
+#[derive(Clone, Debug)]
+pub struct Snapped<T: Scalar> {
+    /// The distinct snapped points, in order of their first occurrence in the input.
+    pub points: Vec<Point<T>>,
+    /// For every input point, the index of the snapped point it was merged into.
+    pub mapping: Vec<usize>,
+}
+
+/// Rounds every coordinate to the nearest multiple of `cell_size` and merges
+/// points that land on the same grid node.
+///
+/// Attributes of the input points can be carried over to the snapped points
+/// through [Snapped::mapping].
+///
+/// # Panics
+/// Panics if `cell_size` is not positive.
+pub fn snap_to_grid<T: Scalar, P: HasPosition<T>>(points: &[P], cell_size: T) -> Snapped<T> {
+    assert!(cell_size > T::from_f64(0.0), "cell size must be positive");
+    let size = cell_size.to_f64();
+
+    let mut nodes: HashMap<(i64, i64), usize> = HashMap::with_capacity(points.len());
+    let mut snapped = Snapped {
+        points: Vec::new(),
+        mapping: Vec::with_capacity(points.len()),
+    };
+    for p in points {
+        let p = p.pos();
+        let (x, y) = (p.x.to_f64(), p.y.to_f64());
+        let key = ((x / size).round() as i64, (y / size).round() as i64);
+        let next = snapped.points.len();
+        let index = *nodes.entry(key).or_insert(next);
+        if index == next {
+            // f32 holds integers exactly only up to 2^24, so convert in two parts
+            let node = |k: i64| {
+                let (hi, lo) = (k.div_euclid(1 << 20), k.rem_euclid(1 << 20));
+                let hi = T::from_f64(hi as f64) * T::from_f64((1 << 20) as f64);
+                cell_size * (hi + T::from_f64(lo as f64))
+            };
+            snapped.points.push(Point::new(node(key.0), node(key.1)));
+        }
+        snapped.mapping.push(index);
+    }
+    snapped
+}
@@ -0,0 +1,69 @@
+//! A structure-of-arrays layout of the triangles in a [Triangulation].
+
+use crate::{traits::Index, util::OptionIndex, Triangulation};
+
+/// The triangles of a [Triangulation] stored as parallel arrays, indexed by
+/// triangle id.
+///
+/// Triangle `t` has the vertices `a[t]`, `b[t]` and `c[t]`, in the same order as
+/// in [Triangulation::triangles]. `ab[t]` is the triangle on the other side of
+/// the edge from `a[t]` to `b[t]` (or none on the convex hull), and likewise for
+/// `bc` and `ca`.
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct SoaTriangles<I> {
+    pub a: Vec<I>,
+    pub b: Vec<I>,
+    pub c: Vec<I>,
+    pub ab: Vec<OptionIndex<I>>,
+    pub bc: Vec<OptionIndex<I>>,
+    pub ca: Vec<OptionIndex<I>>,
+}
+
+impl<I: Index> SoaTriangles<I> {
+    /// The number of triangles.
+    pub fn len(&self) -> usize {
+        self.a.len()
+    }
+
+    /// Returns `true` if there are no triangles.
+    pub fn is_empty(&self) -> bool {
+        self.a.is_empty()
+    }
+}
+
+impl<I: Index> Triangulation<I> {
+    /// Copies the triangles and their adjacency into a [SoaTriangles].
+    pub fn to_soa(&self) -> SoaTriangles<I> {
+        let len = self.len();
+        let neighbor = |e: usize| {
+            self.halfedges[e]
+                .get()
+                .map(|twin| I::from_usize(twin.as_usize() / 3))
+                .into()
+        };
+
+        let mut soa = SoaTriangles {
+            a: Vec::with_capacity(len),
+            b: Vec::with_capacity(len),
+            c: Vec::with_capacity(len),
+            ab: Vec::with_capacity(len),
+            bc: Vec::with_capacity(len),
+            ca: Vec::with_capacity(len),
+        };
+        for t in 0..len {
+            soa.a.push(self.triangles[3 * t]);
+            soa.b.push(self.triangles[3 * t + 1]);
+            soa.c.push(self.triangles[3 * t + 2]);
+            soa.ab.push(neighbor(3 * t));
+            soa.bc.push(neighbor(3 * t + 1));
+            soa.ca.push(neighbor(3 * t + 2));
+        }
+        soa
+    }
+}
+
+impl<I: Index> From<&Triangulation<I>> for SoaTriangles<I> {
+    fn from(triangulation: &Triangulation<I>) -> Self {
+        triangulation.to_soa()
+    }
+}
@@ -0,0 +1,81 @@
+//! [PointSource]s that read coordinates directly from raw buffers, e.g. data
+//! coming from numpy, Arrow or a memory-mapped file.
+//!
+//! ```rust
+//! use delaunator::{source::FlatPoints, Triangulation};
+//!
+//! let coords = [0., 0., 1., 0., 1., 1., 0., 1.];
+//! let triangulation = Triangulation::<usize>::from_source(&FlatPoints::new(&coords)).unwrap();
+//! assert_eq!(triangulation.len(), 2);
+//! ```
+
+use crate::{
+    traits::{PointSource, Scalar},
+    Point,
+};
+
+/// Points stored as interleaved coordinates: `[x0, y0, x1, y1, ...]`.
+#[derive(Clone, Copy, Debug)]
+pub struct FlatPoints<'a, T> {
+    coords: &'a [T],
+}
+
+impl<'a, T: Scalar> FlatPoints<'a, T> {
+    /// Wraps a buffer of interleaved coordinates.
+    ///
+    /// # Panics
+    /// Panics if `coords` has an odd length.
+    pub fn new(coords: &'a [T]) -> Self {
+        assert!(
+            coords.len() % 2 == 0,
+            "Interleaved coordinates must have an even length"
+        );
+        FlatPoints { coords }
+    }
+}
+
+impl<'a, T: Scalar> PointSource<T> for FlatPoints<'a, T> {
+    #[inline(always)]
+    fn len(&self) -> usize {
+        self.coords.len() / 2
+    }
+
+    #[inline(always)]
+    fn point(&self, i: usize) -> Point<T> {
+        Point::new(self.coords[2 * i], self.coords[2 * i + 1])
+    }
+}
+
+/// Points stored as separate coordinate arrays (structure of arrays).
+#[derive(Clone, Copy, Debug)]
+pub struct SoaPoints<'a, T> {
+    xs: &'a [T],
+    ys: &'a [T],
+}
+
+impl<'a, T: Scalar> SoaPoints<'a, T> {
+    /// Wraps separate x and y coordinate buffers.
+    ///
+    /// # Panics
+    /// Panics if `xs` and `ys` have different lengths.
+    pub fn new(xs: &'a [T], ys: &'a [T]) -> Self {
+        assert_eq!(
+            xs.len(),
+            ys.len(),
+            "Coordinate arrays must have the same length"
+        );
+        SoaPoints { xs, ys }
+    }
+}
+
+impl<'a, T: Scalar> PointSource<T> for SoaPoints<'a, T> {
+    #[inline(always)]
+    fn len(&self) -> usize {
+        self.xs.len()
+    }
+
+    #[inline(always)]
+    fn point(&self, i: usize) -> Point<T> {
+        Point::new(self.xs[i], self.ys[i])
+    }
+}
@@ -0,0 +1,74 @@
+use spade::{DelaunayTriangulation, InsertionError, Point2, SpadeNum};
+
+use crate::{
+    traits::{HasPosition, Index, Scalar},
+    Point, Triangulation,
+};
+
+impl<T: Scalar + SpadeNum> From<Point2<T>> for Point<T> {
+    fn from(other: Point2<T>) -> Self {
+        Point {
+            x: other.x,
+            y: other.y,
+        }
+    }
+}
+
+impl<T: Scalar + SpadeNum> From<Point<T>> for Point2<T> {
+    fn from(other: Point<T>) -> Self {
+        Point2::new(other.x, other.y)
+    }
+}
+
+impl<I: Index> Triangulation<I> {
+    /// Converts a triangulation built with the `spade` crate, returning it with
+    /// its points. Point indices are spade's vertex indices, so the triangles
+    /// are taken over as they are, without triangulating again.
+    ///
+    /// Any spade triangulation works, including constrained ones, whose
+    /// triangles need not be Delaunay. Returns `None` if it has no triangles.
+    pub fn from_spade<T, S>(triangulation: &S) -> Option<(Self, Vec<Point<T>>)>
+    where
+        T: Scalar + SpadeNum,
+        S: spade::Triangulation,
+        S::Vertex: spade::HasPosition<Scalar = T>,
+    {
+        let points: Vec<Point<T>> = triangulation
+            .vertices()
+            .map(|v| v.position().into())
+            .collect();
+        let triangles: Vec<I> = triangulation
+            .inner_faces()
+            .flat_map(|face| face.vertices())
+            .map(|v| I::from_usize(v.index()))
+            .collect();
+        let triangulation = Triangulation::from_mesh(&points, triangles).ok()?;
+        Some((triangulation, points))
+    }
+
+    /// Converts the triangulation into a `spade` triangulation, for dynamic
+    /// insertion and removal or constrained edges.
+    ///
+    /// Spade can't adopt existing triangles, so this loads the points used by
+    /// the triangulation into spade again. Vertex `k` of the result is the
+    /// `k`-th of these points in index order, which is point `k` if every
+    /// point is used.
+    pub fn to_spade<T, P>(
+        &self,
+        points: &[P],
+    ) -> Result<DelaunayTriangulation<Point2<T>>, InsertionError>
+    where
+        T: Scalar + SpadeNum,
+        P: HasPosition<T>,
+    {
+        let mut used = vec![false; points.len()];
+        for &i in &self.triangles {
+            used[i.as_usize()] = true;
+        }
+        let vertices = (0..points.len())
+            .filter(|&i| used[i])
+            .map(|i| points[i].pos().into())
+            .collect();
+        DelaunayTriangulation::bulk_load_stable(vertices)
+    }
+}
@@ -0,0 +1,148 @@
+//! Summary statistics over the edges of a [Triangulation].
+
+use crate::{
+    traits::{HasPosition, Index, Scalar},
+    util::next_halfedge,
+    Triangulation,
+};
+
+/// Length statistics over the unique edges of a [Triangulation].
+///
+/// Each edge shared by two triangles is counted once.
+#[derive(Clone, Debug)]
+pub struct EdgeLengthStats<T> {
+    /// The number of unique edges.
+    pub count: usize,
+    /// The length of the shortest edge.
+    pub min: T,
+    /// The length of the longest edge.
+    pub max: T,
+    /// The mean edge length.
+    pub mean: T,
+    lengths: Vec<T>,
+}
+
+/// An equal-width histogram of edge lengths.
+#[derive(Clone, Debug)]
+pub struct Histogram<T> {
+    /// The lower bound of the first bin.
+    pub min: T,
+    /// The width of every bin.
+    pub bin_width: T,
+    /// The number of edges in each bin.
+    pub counts: Vec<usize>,
+}
+
+/// Computes length statistics over the unique edges of `triangulation`.
+///
+/// Returns `None` if the triangulation has no edges.
+pub fn edge_lengths<T: Scalar, I: Index, P: HasPosition<T>>(
+    triangulation: &Triangulation<I>,
+    points: &[P],
+) -> Option<EdgeLengthStats<T>> {
+    let mut lengths: Vec<T> = triangulation
+        .unique_edges()
+        .map(|e| {
+            let (a, b) = (
+                triangulation.triangles[e],
+                triangulation.triangles[next_halfedge(e)],
+            );
+            points[a.as_usize()]
+                .pos()
+                .distance(points[b.as_usize()].pos())
+        })
+        .collect();
+
+    if lengths.is_empty() {
+        return None;
+    }
+
+    lengths.sort_unstable_by(|a, b| a.total_cmp(b));
+
+    let count = lengths.len();
+    let sum = lengths.iter().fold(T::from_f64(0.0), |acc, &x| acc + x);
+
+    Some(EdgeLengthStats {
+        count,
+        min: lengths[0],
+        max: lengths[count - 1],
+        mean: sum / T::from_f64(count as f64),
+        lengths,
+    })
+}
+
+impl<T: Scalar> EdgeLengthStats<T> {
+    /// The edge length below which `p` percent of edges fall, linearly
+    /// interpolated between the closest ranks.
+    ///
+    /// # Panics
+    /// Panics if `p` is not within `0.0..=100.0`.
+    pub fn percentile(&self, p: f64) -> T {
+        assert!((0.0..=100.0).contains(&p), "percentile out of range");
+        let rank = p / 100.0 * (self.count - 1) as f64;
+        let lo = rank.floor() as usize;
+        let hi = rank.ceil() as usize;
+        let frac = T::from_f64(rank - lo as f64);
+        self.lengths[lo] + (self.lengths[hi] - self.lengths[lo]) * frac
+    }
+
+    /// The median edge length.
+    pub fn median(&self) -> T {
+        self.percentile(50.0)
+    }
+
+    /// All unique edge lengths, sorted in increasing order.
+    pub fn lengths(&self) -> &[T] {
+        &self.lengths
+    }
+
+    /// Bins the edge lengths into `bins` equal-width bins spanning `min..=max`.
+    ///
+    /// # Panics
+    /// Panics if `bins` is zero.
+    pub fn histogram(&self, bins: usize) -> Histogram<T> {
+        assert!(bins > 0, "histogram must have at least one bin");
+        let bin_width = (self.max - self.min) / T::from_f64(bins as f64);
+        let mut counts = vec![0; bins];
+        for &length in &self.lengths {
+            let bin = if bin_width > T::from_f64(0.0) {
+                ((length - self.min) / bin_width).to_f64() as usize
+            } else {
+                0
+            };
+            counts[bin.min(bins - 1)] += 1;
+        }
+        Histogram {
+            min: self.min,
+            bin_width,
+            counts,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Point;
+
+    #[test]
+    fn test_edge_lengths() {
+        let points = [
+            Point::new(0.0, 0.0),
+            Point::new(1.0, 0.0),
+            Point::new(1.0, 1.0),
+            Point::new(0.0, 1.0),
+        ];
+        let triangulation = Triangulation::<usize>::new(&points).unwrap();
+        let stats = edge_lengths(&triangulation, &points).unwrap();
+
+        assert_eq!(stats.count, 5);
+        assert_eq!(stats.min, 1.0);
+        assert_eq!(stats.max, 2.0f64.sqrt());
+        assert_eq!(stats.median(), 1.0);
+        assert_eq!(stats.percentile(100.0), 2.0f64.sqrt());
+
+        let histogram = stats.histogram(2);
+        assert_eq!(histogram.counts, vec![4, 1]);
+    }
+}
@@ -0,0 +1,194 @@
+//! [proptest] strategies for point sets that are hard to triangulate.
+//!
+//! Uniformly random points almost never hit the cases that break geometric
+//! code: points on a common circle, points on a common line, and points that
+//! are nearly the same. The strategies here generate those on purpose, so code
+//! built on top of a triangulation can be tested against them.
+//!
+//! ```rust
+//! use delaunator::{strategy, Triangulation};
+//! use proptest::test_runner::TestRunner;
+//!
+//! TestRunner::default()
+//!     .run(&strategy::adversarial(100), |points| {
+//!         if let Some(triangulation) = Triangulation::<usize>::new(&points) {
+//!             assert!(triangulation.len() <= 2 * points.len());
+//!         }
+//!         Ok(())
+//!     })
+//!     .unwrap();
+//! ```
+//!
+//! Some of the generated sets have no triangulation at all, e.g. a single
+//! collinear run, so the code under test has to handle `None`.
+
+use std::f64::consts::TAU;
+
+use proptest::{collection::vec, prelude::*, sample::Index};
+
+use crate::Point;
+
+/// Coordinates of origins and centers.
+const EXTENT: f64 = 1e3;
+
+fn point() -> impl Strategy<Value = Point<f64>> {
+    (-EXTENT..EXTENT, -EXTENT..EXTENT).prop_map(|(x, y)| Point::new(x, y))
+}
+
+/// Points on a regular grid of up to `max_side` by `max_side` points, in
+/// random order.
+///
+/// Every grid cell has four cocircular corners and every row and column is
+/// collinear.
+///
+/// # Panics
+///
+/// Panics if `max_side` is less than 2.
+pub fn grid(max_side: usize) -> impl Strategy<Value = Vec<Point<f64>>> {
+    assert!(max_side >= 2, "a grid needs at least two points per side");
+    (2..=max_side, 2..=max_side, point(), 1e-3..1e3f64).prop_flat_map(
+        |(width, height, origin, spacing)| {
+            let points: Vec<_> = (0..width * height)
+                .map(|i| {
+                    let (x, y) = ((i % width) as f64, (i / width) as f64);
+                    Point::new(origin.x + x * spacing, origin.y + y * spacing)
+                })
+                .collect();
+            Just(points).prop_shuffle()
+        },
+    )
+}
+
+/// Between 3 and `max_points` points evenly spaced on a circle, in random
+/// order, sometimes with the center as well.
+///
+/// The points are only cocircular up to rounding, which is exactly what makes
+/// them hard.
+///
+/// # Panics
+///
+/// Panics if `max_points` is less than 3.
+pub fn cocircular(max_points: usize) -> impl Strategy<Value = Vec<Point<f64>>> {
+    assert!(max_points >= 3, "a circle needs at least three points");
+    (
+        3..=max_points,
+        point(),
+        1e-3..1e3f64,
+        0.0..TAU,
+        any::<bool>(),
+    )
+        .prop_flat_map(|(n, center, radius, phase, with_center)| {
+            let mut points: Vec<_> = (0..n)
+                .map(|k| {
+                    let (sin, cos) = (phase + TAU * k as f64 / n as f64).sin_cos();
+                    Point::new(center.x + radius * cos, center.y + radius * sin)
+                })
+                .collect();
+            if with_center {
+                points.push(center);
+            }
+            Just(points).prop_shuffle()
+        })
+}
+
+/// Up to `max_points` random points, each followed by up to three copies that
+/// differ from it by a few units in the last place, in random order.
+///
+/// # Panics
+///
+/// Panics if `max_points` is zero.
+pub fn near_duplicates(max_points: usize) -> impl Strategy<Value = Vec<Point<f64>>> {
+    assert!(max_points > 0, "near-duplicates need at least one point");
+    let copies = vec((-4i32..=4, -4i32..=4), 0..=3);
+    vec((point(), copies), 1..=max_points).prop_flat_map(|originals| {
+        let nudge = |x: f64, ulps: i32| x + x.abs().max(1.0) * f64::EPSILON * ulps as f64;
+        let points: Vec<_> = originals
+            .into_iter()
+            .flat_map(|(p, copies)| {
+                let copies = copies
+                    .into_iter()
+                    .map(move |(dx, dy)| Point::new(nudge(p.x, dx), nudge(p.y, dy)));
+                std::iter::once(p).chain(copies)
+            })
+            .collect();
+        Just(points).prop_shuffle()
+    })
+}
+
+/// Between 3 and `max_points` points drawn from up to `max_clusters` Gaussian
+/// clusters, whose spreads range from tiny to wide.
+///
+/// # Panics
+///
+/// Panics if `max_clusters` is zero or `max_points` is less than 3.
+pub fn clusters(max_clusters: usize, max_points: usize) -> impl Strategy<Value = Vec<Point<f64>>> {
+    assert!(max_clusters > 0, "clusters need at least one cluster");
+    assert!(max_points >= 3, "clusters need at least three points");
+    let centers = vec((point(), 1e-6..10.0f64), 1..=max_clusters);
+    let samples = vec(
+        (any::<Index>(), f64::EPSILON..1.0, 0.0..TAU),
+        3..=max_points,
+    );
+    (centers, samples).prop_map(|(centers, samples)| {
+        samples
+            .into_iter()
+            .map(|(cluster, u, angle)| {
+                // Box-Muller transform
+                let (center, sigma) = centers[cluster.index(centers.len())];
+                let r = sigma * (-2.0 * u.ln()).sqrt();
+                Point::new(center.x + r * angle.cos(), center.y + r * angle.sin())
+            })
+            .collect()
+    })
+}
+
+/// Up to `max_runs` runs of between 2 and `max_points` points on a common
+/// line, in random order.
+///
+/// Runs along the axes and the diagonals are exactly collinear, runs in other
+/// directions only up to rounding. A single run has no triangulation.
+///
+/// # Panics
+///
+/// Panics if `max_runs` is zero or `max_points` is less than 2.
+pub fn collinear(max_runs: usize, max_points: usize) -> impl Strategy<Value = Vec<Point<f64>>> {
+    assert!(max_runs > 0, "collinear runs need at least one run");
+    assert!(max_points >= 2, "a collinear run needs at least two points");
+    let direction = prop_oneof![
+        Just(Point::new(1.0, 0.0)),
+        Just(Point::new(0.0, 1.0)),
+        Just(Point::new(1.0, 1.0)),
+        Just(Point::new(1.0, -1.0)),
+        (0.0..TAU).prop_map(|angle| Point::new(angle.cos(), angle.sin())),
+    ];
+    let run = (point(), direction, vec(-EXTENT..EXTENT, 2..=max_points)).prop_map(
+        |(origin, direction, offsets)| {
+            offsets
+                .into_iter()
+                .map(|t| Point::new(origin.x + t * direction.x, origin.y + t * direction.y))
+                .collect::<Vec<_>>()
+        },
+    );
+    vec(run, 1..=max_runs).prop_flat_map(|runs| Just(runs.concat()).prop_shuffle())
+}
+
+/// Any of the other strategies, with up to about `max_points` points.
+///
+/// # Panics
+///
+/// Panics if `max_points` is less than 4.
+pub fn adversarial(max_points: usize) -> BoxedStrategy<Vec<Point<f64>>> {
+    assert!(
+        max_points >= 4,
+        "adversarial sets need at least four points"
+    );
+    let side = ((max_points as f64).sqrt() as usize).max(2);
+    prop_oneof![
+        grid(side),
+        cocircular(max_points),
+        near_duplicates(max_points / 4),
+        clusters(4, max_points),
+        collinear(4, max_points / 4 + 1),
+    ]
+    .boxed()
+}
@@ -0,0 +1,209 @@
+//! Rendering [Voronoi] diagrams as SVG, for debugging and generative art.
+//!
+//! The diagram is clipped to a rectangle, which is scaled to the width of the
+//! image with the y axis pointing up. Every cell is a closed path styled by a
+//! callback, and the Delaunay edges and the points can be drawn on top.
+//!
+//! ```rust
+//! use delaunator::{svg::{CellStyle, VoronoiSvg}, voronoi::Voronoi, Point, Triangulation};
+//!
+//! let points = vec![
+//!     Point { x: 0., y: 0. },
+//!     Point { x: 2., y: 0. },
+//!     Point { x: 2., y: 2. },
+//!     Point { x: 0., y: 2. },
+//!     Point { x: 1., y: 0.5 },
+//! ];
+//! let triangulation = Triangulation::<usize>::new(&points).unwrap();
+//! let voronoi = Voronoi::new(&triangulation, &points);
+//!
+//! let svg = VoronoiSvg::new()
+//!     .width(400.)
+//!     .delaunay("red")
+//!     .style(|i| CellStyle {
+//!         fill: format!("hsl({}, 70%, 60%)", 72 * i),
+//!         ..CellStyle::default()
+//!     })
+//!     .render(&voronoi, Point { x: -1., y: -1. }, Point { x: 3., y: 3. });
+//! assert!(svg.starts_with("<svg"));
+//! assert_eq!(svg.matches("<path").count(), 6);
+//! ```
+
+use std::fmt::Write;
+
+use crate::{
+    traits::{Index, Scalar},
+    util::next_halfedge,
+    voronoi::Voronoi,
+    Point,
+};
+
+/// The look of one cell.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CellStyle {
+    /// The SVG paint the cell is filled with, e.g. `"none"` or `"#4080c0"`.
+    pub fill: String,
+    /// The SVG paint of the outline of the cell.
+    pub stroke: String,
+    /// The width of the outline in pixels.
+    pub stroke_width: f64,
+}
+
+impl Default for CellStyle {
+    /// Black outlines, no fill.
+    fn default() -> Self {
+        CellStyle {
+            fill: "none".to_string(),
+            stroke: "black".to_string(),
+            stroke_width: 1.0,
+        }
+    }
+}
+
+/// Renders a [Voronoi] diagram to an SVG string.
+pub struct VoronoiSvg<F = fn(usize) -> CellStyle> {
+    width: f64,
+    delaunay: Option<String>,
+    sites: Option<f64>,
+    style: F,
+}
+
+impl VoronoiSvg {
+    /// Creates a renderer for an image 800 pixels wide, with every cell in
+    /// the default [CellStyle] and neither Delaunay edges nor points.
+    pub fn new() -> Self {
+        VoronoiSvg {
+            width: 800.0,
+            delaunay: None,
+            sites: None,
+            style: |_| CellStyle::default(),
+        }
+    }
+}
+
+impl Default for VoronoiSvg {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Fn(usize) -> CellStyle> VoronoiSvg<F> {
+    /// Sets the width of the image in pixels. The height follows from the
+    /// aspect ratio of the rectangle.
+    pub fn width(mut self, width: f64) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Draws the Delaunay edges on top of the cells, with the SVG paint
+    /// `stroke`.
+    pub fn delaunay(mut self, stroke: &str) -> Self {
+        self.delaunay = Some(stroke.to_string());
+        self
+    }
+
+    /// Draws every point of the triangulation as a black dot of `radius`
+    /// pixels.
+    pub fn sites(mut self, radius: f64) -> Self {
+        self.sites = Some(radius);
+        self
+    }
+
+    /// Sets the style of every cell, given the index of its point.
+    pub fn style<G: Fn(usize) -> CellStyle>(self, style: G) -> VoronoiSvg<G> {
+        VoronoiSvg {
+            width: self.width,
+            delaunay: self.delaunay,
+            sites: self.sites,
+            style,
+        }
+    }
+
+    /// Renders `voronoi` clipped to the rectangle from `min` to `max`, see
+    /// [Voronoi::clip_to_rect]. Cells that are empty after clipping are left
+    /// out.
+    pub fn render<T: Scalar, I: Index>(
+        &self,
+        voronoi: &Voronoi<'_, T, I>,
+        min: Point<T>,
+        max: Point<T>,
+    ) -> String {
+        let (x0, y1) = (min.x.to_f64(), max.y.to_f64());
+        let extent = (max.x.to_f64() - x0).max(f64::MIN_POSITIVE);
+        let scale = self.width / extent;
+        let height = (y1 - min.y.to_f64()) * scale;
+        let pixel = |p: Point<T>| ((p.x.to_f64() - x0) * scale, (y1 - p.y.to_f64()) * scale);
+
+        let mut out = String::new();
+        writeln!(
+            out,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">",
+            w = self.width,
+            h = height
+        )
+        .unwrap();
+        for (i, cell) in voronoi.clip_to_rect(min, max).iter().enumerate() {
+            if cell.len() < 3 {
+                continue;
+            }
+            let style = (self.style)(i);
+            let mut d = String::new();
+            for (k, &p) in cell.iter().enumerate() {
+                let (x, y) = pixel(p);
+                write!(d, "{}{} {} ", if k == 0 { "M" } else { "L" }, x, y).unwrap();
+            }
+            d.push('Z');
+            writeln!(
+                out,
+                "  <path d=\"{}\" fill=\"{}\" stroke=\"{}\" stroke-width=\"{}\"/>",
+                d,
+                escape(&style.fill),
+                escape(&style.stroke),
+                style.stroke_width
+            )
+            .unwrap();
+        }
+
+        if let Some(stroke) = &self.delaunay {
+            let triangulation = voronoi.triangulation;
+            let mut d = String::new();
+            for e in triangulation.unique_edges() {
+                let a = voronoi.points[triangulation.triangles[e].as_usize()];
+                let b = voronoi.points[triangulation.triangles[next_halfedge(e)].as_usize()];
+                let ((ax, ay), (bx, by)) = (pixel(a), pixel(b));
+                write!(d, "M{} {} L{} {} ", ax, ay, bx, by).unwrap();
+            }
+            writeln!(
+                out,
+                "  <path d=\"{}\" fill=\"none\" stroke=\"{}\"/>",
+                d.trim_end(),
+                escape(stroke)
+            )
+            .unwrap();
+        }
+
+        if let Some(radius) = self.sites {
+            for cell in voronoi.cells() {
+                if cell.vertex_ids().next().is_some() {
+                    let (x, y) = pixel(cell.site());
+                    writeln!(
+                        out,
+                        "  <circle cx=\"{}\" cy=\"{}\" r=\"{}\"/>",
+                        x, y, radius
+                    )
+                    .unwrap();
+                }
+            }
+        }
+        out.push_str("</svg>\n");
+        out
+    }
+}
+
+/// Escapes `value` for use in a double-quoted XML attribute.
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+}
@@ -0,0 +1,434 @@
+//! Out-of-core triangulation of point sets too large for memory, one spatial
+//! tile at a time.
+//!
+//! [triangulate] makes two sequential passes over a [PointSource]: one for the
+//! bounding box, and one that buckets the points into a grid of tiles, each
+//! written to its own file in a scratch directory. The tiles are then loaded
+//! and triangulated one by one. A triangle whose circumcircle stays inside its
+//! tile can't be affected by points elsewhere, so it is final and is reported
+//! right away. The vertices of the other triangles and of the tile's hull make
+//! up the seams, which are spilled to disk as well and triangulated together
+//! once all tiles are done. The seam triangles that fill the gaps between the
+//! final ones complete the triangulation.
+//!
+//! Memory use is bounded by the largest tile plus the seams, which grow with
+//! the perimeter of the tiles rather than their area. The source is only read
+//! sequentially, so it may be backed by a memory-mapped file, e.g. through
+//! [FlatPoints](crate::source::FlatPoints).
+//!
+//! The result equals [Triangulation::new] for points in general position. Near
+//! duplicates should be removed beforehand, since tiles may disagree about
+//! which of them to keep.
+
+use crate::{
+    traits::{ApproxEq, PointSource, Scalar},
+    util::next_halfedge,
+    Point, Triangulation,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    convert::TryInto,
+    fs::{self, File, OpenOptions},
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// Triangulates `points` in tiles of roughly `tile_size` points each, calling
+/// `visit` with the point indices of each triangle.
+///
+/// The tiles and seams are kept in a scratch directory created inside `dir`,
+/// which is removed again before returning.
+///
+/// Every triangle is reported exactly once, counter-clockwise like in
+/// [Triangulation::triangles]. Returns the number of triangles, or `None` if
+/// no triangulation exists for the input (e.g. all points are collinear).
+///
+/// ```rust
+/// use delaunator::{tiled, Point};
+///
+/// let points: Vec<_> = (0..1000)
+///     .map(|i| {
+///         let i = i as f64;
+///         Point { x: (i * 0.618).fract(), y: (i * 0.414).fract() }
+///     })
+///     .collect();
+///
+/// let mut triangles = Vec::new();
+/// let len = tiled::triangulate(&points[..], 100, &std::env::temp_dir(), |t| {
+///     triangles.extend(t)
+/// })
+/// .unwrap()
+/// .unwrap();
+/// assert_eq!(triangles.len(), 3 * len);
+/// ```
+pub fn triangulate<T, S, F>(
+    points: &S,
+    tile_size: usize,
+    dir: &Path,
+    mut visit: F,
+) -> io::Result<Option<usize>>
+where
+    T: Scalar + ApproxEq,
+    S: PointSource<T> + ?Sized,
+    F: FnMut([usize; 3]),
+{
+    let grid = match Grid::new(points, tile_size) {
+        Some(grid) => grid,
+        None => return Ok(None),
+    };
+    let scratch = Scratch::create(dir)?;
+    grid.bucket(points, &scratch)?;
+
+    let mut seams = BufWriter::new(File::create(scratch.file("seams"))?);
+    let mut frontier = BufWriter::new(File::create(scratch.file("frontier"))?);
+    let mut count = 0;
+    for tile in 0..grid.len() {
+        let (ids, local) = read_points::<T>(&scratch.tile(tile))?;
+        if !ids.is_empty() {
+            count +=
+                grid.triangulate_tile(tile, &ids, &local, &mut visit, &mut seams, &mut frontier)?;
+        }
+    }
+    seams.flush()?;
+    frontier.flush()?;
+
+    count += merge_seams::<T, F>(&scratch, &mut visit)?;
+    Ok(if count > 0 { Some(count) } else { None })
+}
+
+/// A uniform grid of tiles over the bounding box of the points.
+struct Grid<T: Scalar> {
+    min: Point<T>,
+    max: Point<T>,
+    cols: usize,
+    rows: usize,
+}
+
+impl<T: Scalar + ApproxEq> Grid<T> {
+    fn new<S: PointSource<T> + ?Sized>(points: &S, tile_size: usize) -> Option<Self> {
+        if points.is_empty() {
+            return None;
+        }
+        let first = points.point(0);
+        let (mut min, mut max) = (first, first);
+        for i in 1..points.len() {
+            let p = points.point(i);
+            min = Point::new(min.x.min(p.x), min.y.min(p.y));
+            max = Point::new(max.x.max(p.x), max.y.max(p.y));
+        }
+
+        let tiles = points.len() / tile_size.max(1);
+        let cols = ((tiles as f64).sqrt().ceil() as usize).max(1);
+        Some(Grid {
+            min,
+            max,
+            cols,
+            rows: cols,
+        })
+    }
+
+    fn len(&self) -> usize {
+        self.cols * self.rows
+    }
+
+    /// Writes every point to the file of its tile, buffering about one tile's
+    /// worth of points in memory in total.
+    fn bucket<S: PointSource<T> + ?Sized>(&self, points: &S, scratch: &Scratch) -> io::Result<()> {
+        let capacity = 3 * (points.len() / (self.len() * self.len())).max(64);
+        let mut buffers = vec![Vec::new(); self.len()];
+        for i in 0..points.len() {
+            let p = points.point(i);
+            let tile = self.locate(p);
+            buffers[tile].extend_from_slice(&point_record(i, p));
+            if buffers[tile].len() >= capacity {
+                append(&scratch.tile(tile), &mut buffers[tile])?;
+            }
+        }
+        for (tile, buffer) in buffers.iter_mut().enumerate() {
+            if !buffer.is_empty() {
+                append(&scratch.tile(tile), buffer)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn locate(&self, p: Point<T>) -> usize {
+        let cell = |v: T, min: T, max: T, n: usize| {
+            let f = ((v - min) / (max - min)).to_f64();
+            let mut i = if f.is_finite() {
+                ((f * n as f64) as usize).min(n - 1)
+            } else {
+                0
+            };
+            // settle rounding errors so that the cell agrees with `edge`
+            while i > 0 && v < Self::edge(i, n, min, max) {
+                i -= 1;
+            }
+            while i + 1 < n && v >= Self::edge(i + 1, n, min, max) {
+                i += 1;
+            }
+            i
+        };
+        let cx = cell(p.x, self.min.x, self.max.x, self.cols);
+        let cy = cell(p.y, self.min.y, self.max.y, self.rows);
+        cy * self.cols + cx
+    }
+
+    /// The coordinate of the lower edge of cell `i` out of `n` along an axis.
+    fn edge(i: usize, n: usize, min: T, max: T) -> T {
+        if i == 0 {
+            min
+        } else if i == n {
+            max
+        } else {
+            let f = T::from_f64(i as f64) / T::from_f64(n as f64);
+            min + (max - min) * f
+        }
+    }
+
+    /// The parts of the bounding box outside of `tile`, as `[x0, x1, y0, y1]`.
+    fn outside(&self, tile: usize) -> Vec<[T; 4]> {
+        let (cx, cy) = (tile % self.cols, tile / self.cols);
+        let ex0 = Self::edge(cx, self.cols, self.min.x, self.max.x);
+        let ex1 = Self::edge(cx + 1, self.cols, self.min.x, self.max.x);
+        let ey0 = Self::edge(cy, self.rows, self.min.y, self.max.y);
+        let ey1 = Self::edge(cy + 1, self.rows, self.min.y, self.max.y);
+        let (bx0, bx1, by0, by1) = (self.min.x, self.max.x, self.min.y, self.max.y);
+        [
+            (cx > 0, [bx0, ex0, by0, by1]),
+            (cx + 1 < self.cols, [ex1, bx1, by0, by1]),
+            (cy > 0, [ex0, ex1, by0, ey0]),
+            (cy + 1 < self.rows, [ex0, ex1, ey1, by1]),
+        ]
+        .iter()
+        .filter(|(present, _)| *present)
+        .map(|&(_, rect)| rect)
+        .collect()
+    }
+
+    /// Reports the final triangles of a tile, and spills its seam points and
+    /// the edges between final and unfinished triangles.
+    fn triangulate_tile<F: FnMut([usize; 3]), W: Write>(
+        &self,
+        tile: usize,
+        ids: &[usize],
+        local: &[Point<T>],
+        visit: &mut F,
+        seams: &mut W,
+        frontier: &mut W,
+    ) -> io::Result<usize> {
+        let triangulation = match Triangulation::<usize>::new(local) {
+            Some(triangulation) => triangulation,
+            None => {
+                // too few or only collinear points, leave all of them to the seams
+                for (&id, &p) in ids.iter().zip(local) {
+                    write_record(seams, &point_record(id, p))?;
+                }
+                return Ok(0);
+            }
+        };
+
+        // a triangle is final if its circumcircle can't reach any other tile
+        let outside = self.outside(tile);
+        let certified: Vec<bool> = triangulation
+            .triangles
+            .chunks_exact(3)
+            .map(|tri| {
+                let [a, b, c] = [local[tri[0]], local[tri[1]], local[tri[2]]];
+                let center = a.circumcenter(b, c);
+                let r2 = a.circumradius_squared(b, c);
+                outside
+                    .iter()
+                    .all(|rect| distance_squared_to_rect(center, rect) >= r2)
+            })
+            .collect();
+
+        let mut seam = vec![false; local.len()];
+        for (e, twin) in triangulation.halfedges.iter().enumerate() {
+            let u = triangulation.triangles[e];
+            let v = triangulation.triangles[next_halfedge(e)];
+            if !certified[e / 3] || twin.is_none() {
+                seam[u] = true;
+                seam[v] = true;
+            }
+            if certified[e / 3] && !twin.get().map_or(false, |f| certified[f / 3]) {
+                write_record(frontier, &[ids[u] as u64, ids[v] as u64])?;
+            }
+        }
+        for (i, &id) in ids.iter().enumerate() {
+            if seam[i] {
+                write_record(seams, &point_record(id, local[i]))?;
+            }
+        }
+
+        let mut count = 0;
+        for (tri, _) in triangulation
+            .triangles
+            .chunks_exact(3)
+            .zip(&certified)
+            .filter(|(_, &c)| c)
+        {
+            visit([ids[tri[0]], ids[tri[1]], ids[tri[2]]]);
+            count += 1;
+        }
+        Ok(count)
+    }
+}
+
+/// Triangulates the seams and reports the triangles that fill the gaps left
+/// between the final triangles of the tiles.
+///
+/// Every gap is bordered by frontier edges, which are edges of the seam
+/// triangulation too, so the gaps are found by flooding from the unfinished
+/// side of each frontier edge without crossing any of them.
+fn merge_seams<T: Scalar + ApproxEq, F: FnMut([usize; 3])>(
+    scratch: &Scratch,
+    visit: &mut F,
+) -> io::Result<usize> {
+    let (ids, local) = read_points::<T>(&scratch.file("seams"))?;
+    let triangulation = match Triangulation::<usize>::new(&local) {
+        Some(triangulation) => triangulation,
+        None => return Ok(0),
+    };
+    let edge = |e: usize| {
+        let u = ids[triangulation.triangles[e]];
+        let v = ids[triangulation.triangles[next_halfedge(e)]];
+        (u, v)
+    };
+
+    let edges: HashMap<(usize, usize), usize> = (0..triangulation.triangles.len())
+        .map(|e| (edge(e), e))
+        .collect();
+    let mut walls = HashSet::new();
+    let mut stack = Vec::new();
+    read_records(&scratch.file("frontier"), 2, |record| {
+        let (u, v) = (record[0] as usize, record[1] as usize);
+        walls.insert((u.min(v), u.max(v)));
+        if let Some(&e) = edges.get(&(v, u)) {
+            stack.push(e / 3);
+        }
+    })?;
+
+    // without any final triangles, the seams hold every point
+    let mut gap = vec![walls.is_empty(); triangulation.len()];
+    while let Some(t) = stack.pop() {
+        if gap[t] {
+            continue;
+        }
+        gap[t] = true;
+        for e in 3 * t..3 * t + 3 {
+            let (u, v) = edge(e);
+            if !walls.contains(&(u.min(v), u.max(v))) {
+                stack.extend(triangulation.halfedges[e].map(|f| f / 3));
+            }
+        }
+    }
+
+    let mut count = 0;
+    for (tri, _) in triangulation
+        .triangles
+        .chunks_exact(3)
+        .zip(&gap)
+        .filter(|(_, &g)| g)
+    {
+        visit([ids[tri[0]], ids[tri[1]], ids[tri[2]]]);
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// A scratch directory for the tile files, removed when dropped.
+struct Scratch(PathBuf);
+
+impl Scratch {
+    fn create(dir: &Path) -> io::Result<Self> {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        let name = format!(
+            "delaunator-tiles-{}-{}",
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        );
+        let path = dir.join(name);
+        fs::create_dir(&path)?;
+        Ok(Scratch(path))
+    }
+
+    fn file(&self, name: &str) -> PathBuf {
+        self.0.join(name)
+    }
+
+    fn tile(&self, tile: usize) -> PathBuf {
+        self.file(&format!("tile-{}", tile))
+    }
+}
+
+impl Drop for Scratch {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+/// A point on disk: its index and its coordinates as `f64` bits.
+fn point_record<T: Scalar>(id: usize, p: Point<T>) -> [u64; 3] {
+    [id as u64, p.x.to_f64().to_bits(), p.y.to_f64().to_bits()]
+}
+
+fn read_points<T: Scalar>(path: &Path) -> io::Result<(Vec<usize>, Vec<Point<T>>)> {
+    let (mut ids, mut points) = (Vec::new(), Vec::new());
+    read_records(path, 3, |record| {
+        ids.push(record[0] as usize);
+        points.push(Point::new(
+            T::from_f64(f64::from_bits(record[1])),
+            T::from_f64(f64::from_bits(record[2])),
+        ));
+    })?;
+    Ok((ids, points))
+}
+
+/// Appends `words` to the file at `path` and clears them.
+fn append(path: &Path, words: &mut Vec<u64>) -> io::Result<()> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    let mut writer = BufWriter::new(file);
+    write_record(&mut writer, words)?;
+    writer.flush()?;
+    words.clear();
+    Ok(())
+}
+
+fn write_record<W: Write>(writer: &mut W, words: &[u64]) -> io::Result<()> {
+    for word in words {
+        writer.write_all(&word.to_le_bytes())?;
+    }
+    Ok(())
+}
+
+/// Calls `f` with each record of `words` little-endian words in the file at
+/// `path`. A missing file has no records.
+fn read_records<F: FnMut(&[u64])>(path: &Path, words: usize, mut f: F) -> io::Result<()> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(err),
+    };
+    let mut reader = BufReader::new(file);
+    let mut bytes = vec![0; 8 * words];
+    let mut record = vec![0; words];
+    loop {
+        match reader.read_exact(&mut bytes) {
+            Ok(()) => {}
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(()),
+            Err(err) => return Err(err),
+        }
+        for (word, chunk) in record.iter_mut().zip(bytes.chunks_exact(8)) {
+            *word = u64::from_le_bytes(chunk.try_into().unwrap());
+        }
+        f(&record);
+    }
+}
+
+fn distance_squared_to_rect<T: Scalar>(p: Point<T>, &[x0, x1, y0, y1]: &[T; 4]) -> T {
+    let dx = (x0 - p.x).max(p.x - x1).max(T::from_f64(0.0));
+    let dy = (y0 - p.y).max(p.y - y1).max(T::from_f64(0.0));
+    dx * dx + dy * dy
+}
@@ -0,0 +1,807 @@
+//! Terrain analysis on triangulated irregular networks (TINs).
+//!
+//! A TIN is a [Triangulation] together with a height `z[i]` for every point
+//! `i`, interpolated linearly across each triangle.
+//!
+//! ```rust
+//! use delaunator::{tin, Point, Triangulation};
+//!
+//! let points = vec![
+//!     Point { x: 0., y: 0. },
+//!     Point { x: 1., y: 0. },
+//!     Point { x: 1., y: 1. },
+//!     Point { x: 0., y: 1. },
+//! ];
+//! let z = vec![0_f64, 1., 1., 0.];
+//! let triangulation = Triangulation::<usize>::new(&points).unwrap();
+//!
+//! let volume = tin::cut_fill_plane(&triangulation, &points, &z, 0.5);
+//! assert!((volume.cut - 0.125).abs() < 1e-12);
+//! assert!((volume.fill - 0.125).abs() < 1e-12);
+//! ```
+
+use crate::{
+    traits::{ApproxEq, HasPosition, Index, Scalar},
+    util::next_halfedge,
+    Point, Triangulation,
+};
+
+/// Volumes between two surfaces.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct CutFill<T> {
+    /// The volume where the existing surface lies above the design surface,
+    /// i.e. material to be removed.
+    pub cut: T,
+    /// The volume where the existing surface lies below the design surface,
+    /// i.e. material to be added.
+    pub fill: T,
+}
+
+impl<T: Scalar> CutFill<T> {
+    /// The net volume, `cut - fill`.
+    pub fn net(&self) -> T {
+        self.cut - self.fill
+    }
+}
+
+/// Interpolates the height of the surface at `p`, or `None` if `p` is outside
+/// the convex hull.
+pub fn interpolate<T: Scalar, I: Index, P: HasPosition<T>>(
+    triangulation: &Triangulation<I>,
+    points: &[P],
+    z: &[T],
+    p: Point<T>,
+) -> Option<T> {
+    let t = triangulation.locate(points, p)?;
+    Some(height(points, z, [t.a().id(), t.b().id(), t.c().id()], p))
+}
+
+/// Whether a sightline clears the terrain; see [line_of_sight].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Sightline<T: Scalar> {
+    /// The terrain stays below the sightline.
+    Clear,
+    /// The terrain first rises above the sightline at this location.
+    Blocked(Point<T>),
+}
+
+impl<T: Scalar> Sightline<T> {
+    /// Whether the sightline is [Sightline::Clear].
+    pub fn is_clear(&self) -> bool {
+        matches!(self, Sightline::Clear)
+    }
+}
+
+/// Tests whether the straight line from `a` to `b`, each given as a location
+/// and an absolute height, clears the terrain.
+///
+/// Walks the triangles crossed by the sightline from `a` towards `b`, so the
+/// cost is proportional to the number of triangles crossed. Only the part of
+/// the sightline over the triangulation is checked; nothing is checked if `a`
+/// is outside the convex hull. Terrain that exactly touches the sightline does
+/// not block it.
+pub fn line_of_sight<T: Scalar, I: Index, P: HasPosition<T>>(
+    a: (Point<T>, T),
+    b: (Point<T>, T),
+    triangulation: &Triangulation<I>,
+    points: &[P],
+    z: &[T],
+) -> Sightline<T> {
+    let ((pa, za), (pb, zb)) = (a, b);
+    let zero = T::from_f64(0.0);
+    let one = T::from_f64(1.0);
+    let d = pb - pa;
+    let at = |t: T| Point::new(pa.x + d.x * t, pa.y + d.y * t);
+
+    let mut tri = match triangulation.locate(points, pa) {
+        Some(tri) => tri.id(),
+        None => return Sightline::Clear,
+    };
+    let mut t_in = zero;
+    let mut entry = None;
+    for _ in 0..triangulation.len() {
+        let e0 = 3 * tri;
+        let corners = [e0, e0 + 1, e0 + 2].map(|e| triangulation.triangles[e].as_usize());
+
+        // leave through the nearest edge that the sightline points out of
+        let mut exit = None;
+        let mut t_out = one;
+        for k in 0..3 {
+            if Some(e0 + k) == entry {
+                continue;
+            }
+            let u = points[corners[k]].pos();
+            let v = points[corners[(k + 1) % 3]].pos();
+            let denom = (v - u).perp_dot(d);
+            if denom < zero {
+                let t = (v - u).perp_dot(u - pa) / denom;
+                if t < t_out {
+                    t_out = t.max(t_in);
+                    exit = Some(e0 + k);
+                }
+            }
+        }
+
+        let clearance = |t: T| za + (zb - za) * t - height(points, z, corners, at(t));
+        let (c_in, c_out) = (clearance(t_in), clearance(t_out));
+        if c_in < zero {
+            return Sightline::Blocked(at(t_in));
+        }
+        if c_out < zero {
+            return Sightline::Blocked(at(t_in + (t_out - t_in) * c_in / (c_in - c_out)));
+        }
+
+        let next = exit.and_then(|e| triangulation.halfedges[e].get());
+        match next {
+            Some(twin) if t_out < one => {
+                entry = Some(twin.as_usize());
+                tri = twin.as_usize() / 3;
+                t_in = t_out;
+            }
+            _ => break,
+        }
+    }
+    Sightline::Clear
+}
+
+/// Traces the path of steepest descent over the terrain from `start`.
+///
+/// Within a triangle the path follows the downhill gradient of its plane.
+/// Where flow from two triangles converges on their shared edge, the path
+/// follows that edge as a channel down to its lower end. At a vertex, it
+/// continues along the steepest of the downhill edges and triangles around it.
+///
+/// The path starts at `start` and ends in a pit or on flat ground, or where it
+/// leaves the convex hull. It is empty if `start` is outside the convex hull.
+pub fn flow_path<T: Scalar, I: Index, P: HasPosition<T>>(
+    triangulation: &Triangulation<I>,
+    points: &[P],
+    z: &[T],
+    start: Point<T>,
+) -> Vec<Point<T>> {
+    enum At<T: Scalar> {
+        /// At `p` inside triangle `t`, having entered through half-edge `entry`.
+        Face(usize, Point<T>, Option<usize>),
+        /// At the vertex where half-edge `e` starts.
+        Vertex(usize),
+    }
+
+    let zero = T::from_f64(0.0);
+    let mut path = Vec::new();
+    let mut at = match triangulation.locate(points, start) {
+        Some(t) => At::Face(t.id(), start, None),
+        None => return path,
+    };
+    path.push(start);
+
+    // the path descends monotonically, so it can't visit a triangle or vertex twice
+    for _ in 0..triangulation.len() + points.len() {
+        at = match at {
+            At::Face(t, p, entry) => {
+                let dir = descent(triangulation, points, z, t);
+                let exit = match exit_edge(triangulation, points, t, p, dir, entry) {
+                    Some(exit) if dir != Point::new(zero, zero) => exit,
+                    _ => break,
+                };
+                let (e, s) = exit;
+                let q = Point::new(p.x + dir.x * s, p.y + dir.y * s);
+                path.push(q);
+
+                let twin = match triangulation.halfedges[e].get() {
+                    Some(twin) => twin.as_usize(),
+                    None => break,
+                };
+                let next_dir = descent(triangulation, points, z, twin / 3);
+                let u = points[triangulation.triangles[e].as_usize()].pos();
+                let v = points[triangulation.triangles[next_halfedge(e)].as_usize()].pos();
+                if (v - u).perp_dot(next_dir) > zero {
+                    // both triangles drain into the edge; follow it downhill
+                    let lower = if z[triangulation.triangles[e].as_usize()]
+                        <= z[triangulation.triangles[next_halfedge(e)].as_usize()]
+                    {
+                        e
+                    } else {
+                        next_halfedge(e)
+                    };
+                    At::Vertex(lower)
+                } else {
+                    At::Face(twin / 3, q, Some(twin))
+                }
+            }
+            At::Vertex(e) => {
+                let v = triangulation.triangles[e].as_usize();
+                let pv = points[v].pos();
+                if path.last() != Some(&pv) {
+                    path.push(pv);
+                }
+
+                // the steepest descent along an incident edge or into an incident triangle
+                let vertex = triangulation.get_half_edge(e).unwrap().start();
+                let mut best: Option<(T, At<T>)> = None;
+                let mut consider = |slope: T, next: At<T>| {
+                    if slope > zero && best.as_ref().map_or(true, |(s, _)| slope > *s) {
+                        best = Some((slope, next));
+                    }
+                };
+                for edge in vertex.incident_edges() {
+                    let (w, from_w) = if edge.start().id() == v {
+                        (edge.end().id(), next_halfedge(edge.id()))
+                    } else {
+                        (edge.start().id(), edge.id())
+                    };
+                    let slope = (z[v] - z[w]) / pv.distance(points[w].pos());
+                    consider(slope, At::Vertex(from_w));
+                }
+                for edge in vertex.edges() {
+                    let t = edge.id() / 3;
+                    let dir = descent(triangulation, points, z, t);
+                    let b = points[edge.end().id()].pos();
+                    let c = points[edge.next().end().id()].pos();
+                    if (b - pv).perp_dot(dir) > zero && dir.perp_dot(c - pv) > zero {
+                        consider(dir.length(), At::Face(t, pv, None));
+                    }
+                }
+                match best {
+                    Some((_, next)) => next,
+                    None => break,
+                }
+            }
+        };
+    }
+    path
+}
+
+/// Computes how much area drains through each triangle, indexed by triangle id.
+///
+/// Each triangle passes its own area, plus everything draining into it, to the
+/// neighbor its steepest descent leads into. To guarantee that flow always
+/// terminates, a triangle only drains into a neighbor whose centroid is lower;
+/// if the downhill neighbor isn't, flow goes to the lowest lower neighbor
+/// instead. Triangles without a lower neighbor are pits or outlets, and keep
+/// what they collect.
+pub fn flow_accumulation<T: Scalar, I: Index, P: HasPosition<T>>(
+    triangulation: &Triangulation<I>,
+    points: &[P],
+    z: &[T],
+) -> Vec<T> {
+    let corners =
+        |t: usize| [3 * t, 3 * t + 1, 3 * t + 2].map(|e| triangulation.triangles[e].as_usize());
+    let centroid_z: Vec<T> = (0..triangulation.len())
+        .map(|t| {
+            let [a, b, c] = corners(t);
+            (z[a] + z[b] + z[c]) / T::from_f64(3.0)
+        })
+        .collect();
+
+    let receiver = |t: usize| {
+        let [a, b, c] = corners(t);
+        let [pa, pb, pc] = [a, b, c].map(|i| points[i].pos());
+        let centroid = Point::new(
+            (pa.x + pb.x + pc.x) / T::from_f64(3.0),
+            (pa.y + pb.y + pc.y) / T::from_f64(3.0),
+        );
+        let dir = descent(triangulation, points, z, t);
+        let downhill = exit_edge(triangulation, points, t, centroid, dir, None)
+            .and_then(|(e, _)| triangulation.halfedges[e].get())
+            .map(|twin| twin.as_usize() / 3)
+            .filter(|&n| centroid_z[n] < centroid_z[t]);
+        downhill.or_else(|| {
+            (3 * t..3 * t + 3)
+                .filter_map(|e| triangulation.halfedges[e].get())
+                .map(|twin| twin.as_usize() / 3)
+                .filter(|&n| centroid_z[n] < centroid_z[t])
+                .min_by(|&m, &n| centroid_z[m].total_cmp(&centroid_z[n]))
+        })
+    };
+
+    let mut order: Vec<usize> = (0..triangulation.len()).collect();
+    order.sort_unstable_by(|&a, &b| centroid_z[b].total_cmp(&centroid_z[a]));
+
+    let mut accumulation: Vec<T> = (0..triangulation.len())
+        .map(|t| {
+            let [a, b, c] = corners(t);
+            triangle_area(points[a].pos(), points[b].pos(), points[c].pos())
+        })
+        .collect();
+    for t in order {
+        if let Some(n) = receiver(t) {
+            accumulation[n] = accumulation[n] + accumulation[t];
+        }
+    }
+    accumulation
+}
+
+/// The unit normal of every triangle, as a flat `[x, y, z, ...]` array with
+/// three components per triangle id, ready for a vertex buffer of flat-shaded
+/// triangles. Normals point up, towards positive `z`.
+pub fn triangle_normals<T: Scalar, I: Index, P: HasPosition<T>>(
+    triangulation: &Triangulation<I>,
+    points: &[P],
+    z: &[T],
+) -> Vec<f32> {
+    let mut normals = Vec::with_capacity(triangulation.triangles.len());
+    for t in 0..triangulation.len() {
+        normals.extend(normalize(face_normal(triangulation, points, z, t)));
+    }
+    normals
+}
+
+/// The unit normal of every point, as a flat `[x, y, z, ...]` array with three
+/// components per point, ready for a vertex buffer of smooth-shaded triangles.
+///
+/// Each normal is the average of the normals of the triangles around the
+/// point, weighted by their area. Points that aren't part of the triangulation
+/// get `[0, 0, 1]`.
+pub fn vertex_normals<T: Scalar, I: Index, P: HasPosition<T>>(
+    triangulation: &Triangulation<I>,
+    points: &[P],
+    z: &[T],
+) -> Vec<f32> {
+    // unnormalized face normals are twice the triangle area long
+    let mut sums = vec![[0.0; 3]; points.len()];
+    for t in 0..triangulation.len() {
+        let n = face_normal(triangulation, points, z, t);
+        for e in 3 * t..3 * t + 3 {
+            let sum = &mut sums[triangulation.triangles[e].as_usize()];
+            *sum = [sum[0] + n[0], sum[1] + n[1], sum[2] + n[2]];
+        }
+    }
+    sums.into_iter().flat_map(normalize).collect()
+}
+
+/// Estimates the gradient of `values` at every point, for higher-order
+/// interpolation or smoothing contours.
+///
+/// Each triangle has a constant gradient on the plane through its corners; a
+/// point gets the average over the triangles around it, weighted by their area
+/// (the Green-Gauss estimate). This is exact for linear fields. Points that
+/// aren't part of the triangulation get a zero gradient.
+pub fn vertex_gradients<T: Scalar, I: Index, P: HasPosition<T>>(
+    triangulation: &Triangulation<I>,
+    points: &[P],
+    values: &[T],
+) -> Vec<Point<T>> {
+    let zero = T::from_f64(0.0);
+    let mut sums = vec![(Point::new(zero, zero), zero); points.len()];
+    for t in 0..triangulation.len() {
+        let [a, b, c] =
+            [3 * t, 3 * t + 1, 3 * t + 2].map(|e| triangulation.triangles[e].as_usize());
+        let area = triangle_area(points[a].pos(), points[b].pos(), points[c].pos());
+        // the descent is the negated gradient
+        let descent = descent(triangulation, points, values, t);
+        for i in [a, b, c] {
+            let (gradient, weight) = &mut sums[i];
+            *gradient = Point::new(gradient.x - descent.x * area, gradient.y - descent.y * area);
+            *weight = *weight + area;
+        }
+    }
+    sums.into_iter()
+        .map(|(gradient, weight)| {
+            if weight > zero {
+                Point::new(gradient.x / weight, gradient.y / weight)
+            } else {
+                gradient
+            }
+        })
+        .collect()
+}
+
+/// The upward normal of triangle `t`, twice as long as its area.
+fn face_normal<T: Scalar, I: Index, P: HasPosition<T>>(
+    triangulation: &Triangulation<I>,
+    points: &[P],
+    z: &[T],
+    t: usize,
+) -> [f64; 3] {
+    let [a, b, c] = [0, 1, 2].map(|k| triangulation.triangles[3 * t + k].as_usize());
+    let corner = |i: usize| {
+        let p = points[i].pos();
+        [p.x.to_f64(), p.y.to_f64(), z[i].to_f64()]
+    };
+    let ([ax, ay, az], [bx, by, bz], [cx, cy, cz]): ([f64; 3], [f64; 3], [f64; 3]) =
+        (corner(a), corner(b), corner(c));
+    let (u, v) = ([bx - ax, by - ay, bz - az], [cx - ax, cy - ay, cz - az]);
+    [
+        u[1] * v[2] - u[2] * v[1],
+        u[2] * v[0] - u[0] * v[2],
+        u[0] * v[1] - u[1] * v[0],
+    ]
+}
+
+/// Scales `n` to unit length, or returns `[0, 0, 1]` if it is zero.
+fn normalize(n: [f64; 3]) -> [f32; 3] {
+    let length = (n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt();
+    if length > 0.0 {
+        n.map(|c| (c / length) as f32)
+    } else {
+        [0.0, 0.0, 1.0]
+    }
+}
+
+/// A multi-resolution TIN: a sequence of progressively simplified
+/// triangulations of the same points, for level of detail.
+///
+/// Built by repeatedly removing an independent set of the interior points
+/// whose removal changes the surface the least, and recording each removal as
+/// a [VertexSplit]. Level `0` is the coarsest triangulation and the last level
+/// contains every point; each level adds the points of one round of removals
+/// back in. Points on the convex hull are never removed.
+///
+/// Only the point sets of the levels are kept: [LevelOfDetail::level] and
+/// [LevelOfDetail::refine] triangulate the chosen points from scratch, which
+/// costs the same as a [Triangulation::new] of them.
+///
+/// ```rust
+/// use delaunator::{tin::LevelOfDetail, Point, Triangulation};
+///
+/// let points: Vec<_> = (0..100)
+///     .map(|i| Point { x: (i % 10) as f64, y: (i / 10) as f64 })
+///     .collect();
+/// let z: Vec<f64> = points.iter().map(|p| (p.x * 0.7).sin() * (p.y * 0.3).cos()).collect();
+///
+/// let lod = LevelOfDetail::new(&points, &z).unwrap();
+/// let coarse: Triangulation<usize> = lod.level(&points, 0);
+/// let full: Triangulation<usize> = lod.level(&points, lod.levels() - 1);
+/// assert!(coarse.len() < full.len());
+///
+/// // keep detail only where the surface error exceeds a tolerance that grows
+/// // with the distance from a viewer at the origin
+/// let view: Triangulation<usize> =
+///     lod.refine(&points, |p, error| error > 0.01 * (p.x * p.x + p.y * p.y).sqrt());
+/// assert!(view.len() <= full.len());
+/// ```
+#[derive(Clone, Debug)]
+pub struct LevelOfDetail<T: Scalar> {
+    base: Vec<usize>,
+    splits: Vec<VertexSplit<T>>,
+    levels: Vec<usize>,
+}
+
+/// The record of one point removed while simplifying a [LevelOfDetail].
+#[derive(Clone, Debug, PartialEq)]
+pub struct VertexSplit<T> {
+    /// The index of the point.
+    pub vertex: usize,
+    /// The first level that contains the point.
+    pub level: usize,
+    /// The vertical distance between the point and the surface without it.
+    pub error: T,
+}
+
+impl<T: Scalar + ApproxEq> LevelOfDetail<T> {
+    /// Simplifies the TIN of `points` with heights `z` level by level.
+    /// Returns `None` if no triangulation exists for the input.
+    pub fn new<P: HasPosition<T>>(points: &[P], z: &[T]) -> Option<Self> {
+        let mut triangulation = Triangulation::<usize>::new(points)?;
+        let mut alive = vec![false; points.len()];
+        for &i in &triangulation.triangles {
+            alive[i] = true;
+        }
+
+        let mut rounds: Vec<Vec<VertexSplit<T>>> = Vec::new();
+        loop {
+            let mut neighbors = vec![Vec::new(); points.len()];
+            let mut on_hull = vec![false; points.len()];
+            for (e, twin) in triangulation.halfedges.iter().enumerate() {
+                let u = triangulation.triangles[e];
+                let v = triangulation.triangles[next_halfedge(e)];
+                neighbors[u].push(v);
+                if twin.is_none() {
+                    neighbors[v].push(u);
+                    on_hull[u] = true;
+                    on_hull[v] = true;
+                }
+            }
+
+            // the error of each interior point against its retriangulated neighborhood
+            let mut candidates: Vec<(usize, T)> = (0..points.len())
+                .filter(|&i| alive[i] && !on_hull[i])
+                .filter_map(|i| {
+                    let ring: Vec<Point<T>> =
+                        neighbors[i].iter().map(|&j| points[j].pos()).collect();
+                    let ring_z: Vec<T> = neighbors[i].iter().map(|&j| z[j]).collect();
+                    let local = Triangulation::<usize>::new(&ring)?;
+                    let surface = interpolate(&local, &ring, &ring_z, points[i].pos())?;
+                    Some((i, (z[i] - surface).abs()))
+                })
+                .collect();
+            candidates.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+            // remove the better half of a set of non-adjacent points
+            let mut blocked = vec![false; points.len()];
+            let mut round = Vec::new();
+            for &(i, error) in &candidates[..(candidates.len() + 1) / 2] {
+                if blocked[i] {
+                    continue;
+                }
+                for &j in &neighbors[i] {
+                    blocked[j] = true;
+                }
+                round.push(VertexSplit {
+                    vertex: i,
+                    level: 0,
+                    error,
+                });
+            }
+            if round.is_empty() {
+                break;
+            }
+
+            for split in &round {
+                alive[split.vertex] = false;
+            }
+            let subset: Vec<usize> = (0..points.len()).filter(|&i| alive[i]).collect();
+            triangulation = match Triangulation::from_subset(points, &subset) {
+                Some(triangulation) => triangulation,
+                None => {
+                    // the remaining points are collinear; keep the last round
+                    for split in &round {
+                        alive[split.vertex] = true;
+                    }
+                    break;
+                }
+            };
+            rounds.push(round);
+        }
+
+        let base = (0..points.len()).filter(|&i| alive[i]).collect();
+        let mut splits = Vec::new();
+        let mut levels = vec![0];
+        for (level, round) in rounds.into_iter().rev().enumerate() {
+            splits.extend(round.into_iter().map(|split| VertexSplit {
+                level: level + 1,
+                ..split
+            }));
+            levels.push(splits.len());
+        }
+        Some(LevelOfDetail {
+            base,
+            splits,
+            levels,
+        })
+    }
+
+    /// The number of levels, including the full-resolution one.
+    pub fn levels(&self) -> usize {
+        self.levels.len()
+    }
+
+    /// The points that are part of every level.
+    pub fn base(&self) -> &[usize] {
+        &self.base
+    }
+
+    /// The removed points, in the order in which they are added back.
+    pub fn splits(&self) -> &[VertexSplit<T>] {
+        &self.splits
+    }
+
+    /// Triangulates the points of level `lod`, from `0` (coarsest) to
+    /// `levels() - 1` (all points).
+    ///
+    /// # Panics
+    /// Panics if `lod` is not less than [LevelOfDetail::levels].
+    pub fn level<I: Index, P: HasPosition<T>>(&self, points: &[P], lod: usize) -> Triangulation<I> {
+        let splits = &self.splits[..self.levels[lod]];
+        self.triangulate(points, splits.iter().map(|split| split.vertex))
+    }
+
+    /// Triangulates the base points plus every removed point for which
+    /// `refine` returns `true`, given its position and [VertexSplit::error].
+    /// This allows view-dependent refinement, e.g. with a tolerance that grows
+    /// with the distance from the viewer.
+    pub fn refine<I, P, F>(&self, points: &[P], mut refine: F) -> Triangulation<I>
+    where
+        I: Index,
+        P: HasPosition<T>,
+        F: FnMut(Point<T>, T) -> bool,
+    {
+        let splits = self
+            .splits
+            .iter()
+            .filter(|split| refine(points[split.vertex].pos(), split.error));
+        self.triangulate(points, splits.map(|split| split.vertex))
+    }
+
+    fn triangulate<I: Index, P: HasPosition<T>>(
+        &self,
+        points: &[P],
+        extra: impl Iterator<Item = usize>,
+    ) -> Triangulation<I> {
+        let mut subset = self.base.clone();
+        subset.extend(extra);
+        Triangulation::from_subset(points, &subset).expect("base points are not collinear")
+    }
+}
+
+/// The direction of steepest descent on the plane through triangle `t`, i.e.
+/// its negated gradient.
+fn descent<T: Scalar, I: Index, P: HasPosition<T>>(
+    triangulation: &Triangulation<I>,
+    points: &[P],
+    z: &[T],
+    t: usize,
+) -> Point<T> {
+    let [a, b, c] = [3 * t, 3 * t + 1, 3 * t + 2].map(|e| triangulation.triangles[e].as_usize());
+    let [pa, pb, pc] = [a, b, c].map(|i| points[i].pos());
+    let (e1, e2) = (pb - pa, pc - pa);
+    let (dz1, dz2) = (z[b] - z[a], z[c] - z[a]);
+    let det = e1.perp_dot(e2);
+    if det == T::from_f64(0.0) {
+        return Point::new(T::from_f64(0.0), T::from_f64(0.0));
+    }
+    Point::new(
+        (dz2 * e1.y - dz1 * e2.y) / det,
+        (dz1 * e2.x - dz2 * e1.x) / det,
+    )
+}
+
+/// The half-edge of triangle `t` through which the ray from `p` along `dir`
+/// leaves it, and the ray parameter at the crossing.
+fn exit_edge<T: Scalar, I: Index, P: HasPosition<T>>(
+    triangulation: &Triangulation<I>,
+    points: &[P],
+    t: usize,
+    p: Point<T>,
+    dir: Point<T>,
+    entry: Option<usize>,
+) -> Option<(usize, T)> {
+    let zero = T::from_f64(0.0);
+    let mut exit: Option<(usize, T)> = None;
+    for e in 3 * t..3 * t + 3 {
+        if Some(e) == entry {
+            continue;
+        }
+        let u = points[triangulation.triangles[e].as_usize()].pos();
+        let v = points[triangulation.triangles[next_halfedge(e)].as_usize()].pos();
+        let denom = (v - u).perp_dot(dir);
+        if denom < zero {
+            let s = ((v - u).perp_dot(u - p) / denom).max(zero);
+            if exit.map_or(true, |(_, best)| s < best) {
+                exit = Some((e, s));
+            }
+        }
+    }
+    exit
+}
+
+/// The height at `p` of the plane through the triangle `corners`.
+fn height<T: Scalar, P: HasPosition<T>>(
+    points: &[P],
+    z: &[T],
+    corners: [usize; 3],
+    p: Point<T>,
+) -> T {
+    let [a, b, c] = corners;
+    let [pa, pb, pc] = corners.map(|i| points[i].pos());
+    let area = (pb - pa).perp_dot(pc - pa);
+    if area == T::from_f64(0.0) {
+        return z[a];
+    }
+    let wb = (p - pa).perp_dot(pc - pa) / area;
+    let wc = (pb - pa).perp_dot(p - pa) / area;
+    z[a] + (z[b] - z[a]) * wb + (z[c] - z[a]) * wc
+}
+
+/// Computes the volumes between a surface and the horizontal plane at height
+/// `plane`, over the area of the triangulation.
+pub fn cut_fill_plane<T: Scalar, I: Index, P: HasPosition<T>>(
+    triangulation: &Triangulation<I>,
+    points: &[P],
+    z: &[T],
+    plane: T,
+) -> CutFill<T> {
+    let mut volume = CutFill {
+        cut: T::from_f64(0.0),
+        fill: T::from_f64(0.0),
+    };
+    for tri in triangulation.triangles.chunks_exact(3) {
+        let [a, b, c] = [tri[0], tri[1], tri[2]].map(|i| i.as_usize());
+        let area = triangle_area(points[a].pos(), points[b].pos(), points[c].pos());
+        let (cut, fill) = split_volume(area, [z[a] - plane, z[b] - plane, z[c] - plane]);
+        volume.cut = volume.cut + cut;
+        volume.fill = volume.fill + fill;
+    }
+    volume
+}
+
+/// Computes the volumes between an existing and a design surface over the
+/// area covered by both.
+///
+/// The height difference is evaluated at the points of both surfaces and
+/// interpolated linearly over their combined triangulation, as is usual for
+/// composite surfaces. The result is exact wherever the two surfaces are
+/// linear over the combined triangles, e.g. if they share their points.
+pub fn cut_fill<T, I, J, P, Q>(
+    existing: &Triangulation<I>,
+    existing_points: &[P],
+    existing_z: &[T],
+    design: &Triangulation<J>,
+    design_points: &[Q],
+    design_z: &[T],
+) -> CutFill<T>
+where
+    T: Scalar + ApproxEq,
+    I: Index,
+    J: Index,
+    P: HasPosition<T>,
+    Q: HasPosition<T>,
+{
+    let mut points: Vec<Point<T>> = existing_points.iter().map(|p| p.pos()).collect();
+    points.extend(design_points.iter().map(|p| p.pos()));
+
+    // the height difference at every point, where both surfaces are defined
+    let n = existing_points.len();
+    let diff: Vec<Option<T>> = points
+        .iter()
+        .enumerate()
+        .map(|(i, &p)| {
+            let (existing_z, design_z) = if i < n {
+                (
+                    Some(existing_z[i]),
+                    interpolate(design, design_points, design_z, p),
+                )
+            } else {
+                (
+                    interpolate(existing, existing_points, existing_z, p),
+                    Some(design_z[i - n]),
+                )
+            };
+            Some(existing_z? - design_z?)
+        })
+        .collect();
+
+    let mut volume = CutFill {
+        cut: T::from_f64(0.0),
+        fill: T::from_f64(0.0),
+    };
+    let combined = match Triangulation::<usize>::new(&points) {
+        Some(combined) => combined,
+        None => return volume,
+    };
+    for tri in combined.triangles.chunks_exact(3) {
+        let d = match (diff[tri[0]], diff[tri[1]], diff[tri[2]]) {
+            (Some(a), Some(b), Some(c)) => [a, b, c],
+            _ => continue,
+        };
+        let area = triangle_area(points[tri[0]], points[tri[1]], points[tri[2]]);
+        let (cut, fill) = split_volume(area, d);
+        volume.cut = volume.cut + cut;
+        volume.fill = volume.fill + fill;
+    }
+    volume
+}
+
+fn triangle_area<T: Scalar>(a: Point<T>, b: Point<T>, c: Point<T>) -> T {
+    (b - a).perp_dot(c - a).abs() / T::from_f64(2.0)
+}
+
+/// Splits the volume of a linear function with values `d` at the corners of a
+/// triangle with the given `area` into its positive and negative parts, as
+/// `(positive, -negative)`.
+fn split_volume<T: Scalar>(area: T, d: [T; 3]) -> (T, T) {
+    let zero = T::from_f64(0.0);
+    let three = T::from_f64(3.0);
+    let total = area * (d[0] + d[1] + d[2]) / three;
+    let positive = d.iter().filter(|&&v| v > zero).count();
+    let negative = d.iter().filter(|&&v| v < zero).count();
+
+    // the part on the side of a lone vertex `v` is a smaller triangle
+    // with height `v` at one corner and zero at the other two
+    let lone = |i: usize| {
+        let (v, q, r) = (d[i], d[(i + 1) % 3], d[(i + 2) % 3]);
+        area * v * v * v / (three * (v - q) * (v - r))
+    };
+
+    if negative == 0 {
+        (total, zero)
+    } else if positive == 0 {
+        (zero, -total)
+    } else if positive == 1 {
+        let pos = lone(d.iter().position(|&v| v > zero).unwrap());
+        (pos, pos - total)
+    } else {
+        let neg = lone(d.iter().position(|&v| v < zero).unwrap());
+        (total - neg, -neg)
+    }
+}
@@ -0,0 +1,469 @@
+//! What happened to every point during construction, for finding out why a
+//! point is missing from a [Triangulation] or reproducing how it was built.
+//!
+//! Tracing is opt-in through [TriangulationBuilder::build_with_trace], and a
+//! summary is available from [TriangulationBuilder::build_with_report]; the
+//! regular constructors don't record anything. For stepping through a
+//! construction, [TriangulationBuilder::build_with_steps] records the triangles
+//! added, the edges flipped and the hull changes of every point, and a [Replay]
+//! rebuilds the triangulation after any of them; `examples/debugger.rs` shows
+//! the steps in a window with the `debugger` feature.
+//!
+//! ```rust
+//! use delaunator::{builder::TriangulationBuilder, trace::PointStatus, Point, Triangulation};
+//!
+//! let points = vec![
+//!     Point { x: 0., y: 0. },
+//!     Point { x: 1., y: 0. },
+//!     Point { x: 1., y: 1. },
+//!     Point { x: 0., y: 1. },
+//!     Point { x: 1., y: 1. },
+//! ];
+//!
+//! let (triangulation, trace): (Triangulation<usize>, _) = TriangulationBuilder::new()
+//!     .build_with_trace(&points)
+//!     .expect("No triangulation exists.");
+//! assert_eq!(trace.order.len(), 5);
+//! assert_eq!(trace.status.iter().filter(|&&s| s == PointStatus::Seed).count(), 3);
+//!
+//! // the seed triangle reproduces the triangulation
+//! let again: Triangulation<usize> = TriangulationBuilder::new()
+//!     .build_with_seed_triangle(&points, trace.seed);
+//! assert_eq!(again.triangles, triangulation.triangles);
+//!
+//! // one of the two copies of (1, 1) is left out
+//! let skipped = trace
+//!     .status
+//!     .iter()
+//!     .filter(|&&s| s == PointStatus::Duplicate || s == PointStatus::NotVisible);
+//! assert_eq!(skipped.count(), 1);
+//! ```
+//!
+//! [TriangulationBuilder::build_with_trace]: crate::builder::TriangulationBuilder::build_with_trace
+//! [TriangulationBuilder::build_with_report]: crate::builder::TriangulationBuilder::build_with_report
+//! [TriangulationBuilder::build_with_steps]: crate::builder::TriangulationBuilder::build_with_steps
+
+use crate::{
+    observer::Observer,
+    traits::{Index, PointSource, Scalar},
+    Point, Triangulation,
+};
+
+/// What construction did with a point.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PointStatus {
+    /// The point was inserted into the triangulation.
+    Inserted,
+    /// The point is a corner of the seed triangle.
+    Seed,
+    /// The point was skipped as a duplicate of another point, either by
+    /// [dedup](crate::builder::TriangulationBuilder::dedup) or because it
+    /// nearly equals the point inserted before it.
+    Duplicate,
+    /// The point was skipped because no edge of the hull was visible from it,
+    /// which happens for near-duplicates of points inserted earlier.
+    NotVisible,
+}
+
+/// The seed triangle, the insertion order and the status of every point of
+/// one construction.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Trace<T: Scalar> {
+    /// The counter-clockwise seed triangle. Passing it to
+    /// [build_with_seed_triangle](crate::builder::TriangulationBuilder::build_with_seed_triangle)
+    /// with the same options and points repeats the construction.
+    pub seed: (usize, usize, usize),
+    /// The circumcenter of the seed triangle, from which points are sorted.
+    pub center: Point<T>,
+    /// The points in the order they were inserted, by distance from the seed
+    /// triangle. Points removed by
+    /// [dedup](crate::builder::TriangulationBuilder::dedup) are left out.
+    pub order: Vec<usize>,
+    /// The status of every point, indexed by point.
+    pub status: Vec<PointStatus>,
+}
+
+/// Collects a [Trace] during construction.
+pub(crate) struct Tracer<T: Scalar> {
+    pub(crate) trace: Trace<T>,
+}
+
+impl<T: Scalar> Tracer<T> {
+    pub(crate) fn new(n: usize) -> Self {
+        Tracer {
+            trace: Trace {
+                seed: (0, 0, 0),
+                center: Point::new(T::from_f64(0.0), T::from_f64(0.0)),
+                order: Vec::with_capacity(n),
+                status: vec![PointStatus::Inserted; n],
+            },
+        }
+    }
+}
+
+impl<T: Scalar, I: Index> Observer<T, I> for Tracer<T> {
+    fn start(&mut self, seed: (usize, usize, usize), center: Point<T>) {
+        self.trace.seed = seed;
+        self.trace.center = center;
+    }
+
+    fn insert<S: PointSource<T> + ?Sized>(
+        &mut self,
+        _triangulation: &Triangulation<I>,
+        _points: &S,
+        i: usize,
+        _dist: T,
+    ) {
+        self.trace.order.push(i);
+    }
+
+    fn status(&mut self, i: usize, status: PointStatus) {
+        self.trace.status[i] = status;
+    }
+}
+
+/// A degeneracy of the input that construction handled but that may surprise.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Warning {
+    /// Edges were checked whose four surrounding points are exactly cocircular,
+    /// with the number of such checks. Which diagonal is chosen depends on the
+    /// insertion order unless a [TieBreak](crate::builder::TieBreak) is set.
+    Cocircular(usize),
+    /// The points are nearly collinear: the smallest seed triangle found has a
+    /// circumradius over a million times the extent of the points, and the
+    /// triangulation is made of slivers.
+    NearlyCollinear,
+}
+
+/// A summary of one construction.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConstructionReport {
+    /// The number of points inserted, including the seed triangle.
+    pub inserted: usize,
+    /// The number of points skipped as duplicates of another point.
+    pub duplicates: usize,
+    /// The number of points skipped because no hull edge was visible from them.
+    pub not_visible: usize,
+    /// The number of edge flips performed to restore the Delaunay condition.
+    pub flips: usize,
+    /// Degeneracies found in the input.
+    pub warnings: Vec<Warning>,
+}
+
+/// Collects a [ConstructionReport] during construction.
+pub(crate) struct Reporter {
+    pub(crate) report: ConstructionReport,
+    cocircular: usize,
+    seed: (usize, usize, usize),
+    center: Option<Point<f64>>,
+}
+
+impl Reporter {
+    pub(crate) fn new() -> Self {
+        Reporter {
+            report: ConstructionReport::default(),
+            cocircular: 0,
+            seed: (0, 0, 0),
+            center: None,
+        }
+    }
+}
+
+impl<T: Scalar, I: Index> Observer<T, I> for Reporter {
+    fn start(&mut self, seed: (usize, usize, usize), center: Point<T>) {
+        self.seed = seed;
+        self.center = Some(Point::new(center.x.to_f64(), center.y.to_f64()));
+    }
+
+    fn status(&mut self, _i: usize, status: PointStatus) {
+        let report = &mut self.report;
+        *match status {
+            PointStatus::Inserted | PointStatus::Seed => &mut report.inserted,
+            PointStatus::Duplicate => &mut report.duplicates,
+            PointStatus::NotVisible => &mut report.not_visible,
+        } += 1;
+    }
+
+    fn flip(
+        &mut self,
+        _halfedges: (usize, usize),
+        _removed: (usize, usize),
+        _added: (usize, usize),
+    ) {
+        self.report.flips += 1;
+    }
+
+    fn cocircular(&mut self) {
+        self.cocircular += 1;
+    }
+
+    fn finish<S: PointSource<T> + ?Sized>(
+        &mut self,
+        _triangulation: &Triangulation<I>,
+        points: &S,
+    ) {
+        if self.cocircular > 0 {
+            self.report
+                .warnings
+                .push(Warning::Cocircular(self.cocircular));
+        }
+
+        let point = |i: usize| {
+            let p = points.point(i);
+            Point::<f64>::new(p.x.to_f64(), p.y.to_f64())
+        };
+        let (mut min, mut max) = (point(0), point(0));
+        for i in 1..points.len() {
+            let p = point(i);
+            min = Point::new(min.x.min(p.x), min.y.min(p.y));
+            max = Point::new(max.x.max(p.x), max.y.max(p.y));
+        }
+        if let Some(center) = self.center {
+            if center.distance(point(self.seed.0)) > 1e6 * min.distance(max) {
+                self.report.warnings.push(Warning::NearlyCollinear);
+            }
+        }
+    }
+}
+
+/// What happened in one [Step] of a construction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Event {
+    /// Construction started from this counter-clockwise seed triangle, which
+    /// is also the first hull.
+    Seed(usize, usize, usize),
+    /// A point was taken in insertion order and handled as its status says.
+    Point(usize, PointStatus),
+    /// A triangle was added with these counter-clockwise corners.
+    Triangle(usize, usize, usize),
+    /// An edge was flipped to restore the Delaunay condition.
+    Flip {
+        /// The two half-edges of the flipped edge.
+        halfedges: (usize, usize),
+        /// The points of the diagonal before the flip.
+        removed: (usize, usize),
+        /// The points of the diagonal after the flip; the first one is now
+        /// the start of the second half-edge and the second one the start of
+        /// the first.
+        added: (usize, usize),
+    },
+    /// The hull now runs from point `from` through `point` to `to`, leaving out
+    /// the points that were between `from` and `to`.
+    Hull {
+        /// The point before the new one on the hull.
+        from: usize,
+        /// The point added to the hull.
+        point: usize,
+        /// The point after the new one on the hull.
+        to: usize,
+    },
+}
+
+/// The changes one point made to a construction, see
+/// [TriangulationBuilder::build_with_steps](crate::builder::TriangulationBuilder::build_with_steps).
+/// [Replay] applies the changes to get the triangulation after any step.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Step {
+    /// The events of this step in order, starting with the [Event::Seed] or
+    /// [Event::Point] that began it.
+    pub events: Vec<Event>,
+}
+
+impl Step {
+    /// The [Event::Seed] or [Event::Point] that began this step.
+    pub fn event(&self) -> Event {
+        self.events[0]
+    }
+
+    /// The number of edges flipped to restore the Delaunay condition in this
+    /// step.
+    pub fn flips(&self) -> usize {
+        self.events
+            .iter()
+            .filter(|e| matches!(e, Event::Flip { .. }))
+            .count()
+    }
+}
+
+/// Rebuilds the triangulation after any step by applying the events of the
+/// steps before it.
+///
+/// Seeking forward only applies the steps in between; seeking back starts over
+/// from the first step.
+#[derive(Clone, Debug)]
+pub struct Replay<'a> {
+    steps: &'a [Step],
+    done: usize,
+    triangles: Vec<usize>,
+    next: Vec<usize>,
+    start: Option<usize>,
+}
+
+impl<'a> Replay<'a> {
+    /// Starts a replay of `steps` before the first step.
+    pub fn new(steps: &'a [Step]) -> Self {
+        Replay {
+            steps,
+            done: 0,
+            triangles: Vec::new(),
+            next: Vec::new(),
+            start: None,
+        }
+    }
+
+    /// Moves to the state after step `k`, or after the last step if `k` is
+    /// past it.
+    pub fn seek(&mut self, k: usize) {
+        let k = (k + 1).min(self.steps.len());
+        if k < self.done {
+            *self = Replay::new(self.steps);
+        }
+        for step in &self.steps[self.done..k] {
+            for &event in &step.events {
+                self.apply(event);
+            }
+        }
+        self.done = k;
+    }
+
+    /// The triangles after the current step, as triples of point indices.
+    pub fn triangles(&self) -> &[usize] {
+        &self.triangles
+    }
+
+    /// The edges of the hull after the current step, as pairs of point indices,
+    /// counter-clockwise around the triangulation.
+    pub fn hull(&self) -> Vec<(usize, usize)> {
+        let mut hull = Vec::new();
+        if let Some(start) = self.start {
+            let mut e = start;
+            loop {
+                let n = self.next[e];
+                hull.push((e, n));
+                e = n;
+                if e == start {
+                    break;
+                }
+            }
+        }
+        hull
+    }
+
+    fn link(&mut self, a: usize, b: usize) {
+        if self.next.len() <= a {
+            self.next.resize(a + 1, usize::MAX);
+        }
+        self.next[a] = b;
+    }
+
+    fn apply(&mut self, event: Event) {
+        match event {
+            Event::Seed(i0, i1, i2) => {
+                self.link(i0, i1);
+                self.link(i1, i2);
+                self.link(i2, i0);
+                self.start = Some(i0);
+            }
+            Event::Point(..) => {}
+            Event::Triangle(a, b, c) => self.triangles.extend_from_slice(&[a, b, c]),
+            Event::Flip {
+                halfedges: (a, b),
+                added: (p0, p1),
+                ..
+            } => {
+                self.triangles[a] = p1;
+                self.triangles[b] = p0;
+            }
+            Event::Hull { from, point, to } => {
+                self.link(from, point);
+                self.link(point, to);
+                self.start = Some(from);
+            }
+        }
+    }
+}
+
+/// Records a [Step] for every point during construction.
+pub(crate) struct Stepper {
+    pub(crate) steps: Vec<Step>,
+    pending: Option<Step>,
+}
+
+impl Stepper {
+    pub(crate) fn new() -> Self {
+        Stepper {
+            steps: Vec::new(),
+            pending: None,
+        }
+    }
+
+    fn complete(&mut self) {
+        if let Some(step) = self.pending.take() {
+            self.steps.push(step);
+        }
+    }
+
+    fn begin(&mut self, event: Event) {
+        self.complete();
+        self.pending = Some(Step {
+            events: vec![event],
+        });
+    }
+
+    fn record(&mut self, event: Event) {
+        if let Some(step) = &mut self.pending {
+            step.events.push(event);
+        }
+    }
+}
+
+impl<T: Scalar, I: Index> Observer<T, I> for Stepper {
+    fn start(&mut self, seed: (usize, usize, usize), _center: Point<T>) {
+        self.begin(Event::Seed(seed.0, seed.1, seed.2));
+    }
+
+    fn insert<S: PointSource<T> + ?Sized>(
+        &mut self,
+        _triangulation: &Triangulation<I>,
+        _points: &S,
+        i: usize,
+        _dist: T,
+    ) {
+        self.begin(Event::Point(i, PointStatus::Inserted));
+    }
+
+    fn status(&mut self, i: usize, status: PointStatus) {
+        // duplicates removed before the seed triangle have no step
+        if let Some(step) = &mut self.pending {
+            if let Event::Point(j, _) = step.events[0] {
+                if i == j {
+                    step.events[0] = Event::Point(i, status);
+                }
+            }
+        }
+    }
+
+    fn added(&mut self, _t: usize, [a, b, c]: [usize; 3]) {
+        self.record(Event::Triangle(a, b, c));
+    }
+
+    fn flip(&mut self, halfedges: (usize, usize), removed: (usize, usize), added: (usize, usize)) {
+        self.record(Event::Flip {
+            halfedges,
+            removed,
+            added,
+        });
+    }
+
+    fn hull(&mut self, from: usize, point: usize, to: usize) {
+        self.record(Event::Hull { from, point, to });
+    }
+
+    fn finish<S: PointSource<T> + ?Sized>(
+        &mut self,
+        _triangulation: &Triangulation<I>,
+        _points: &S,
+    ) {
+        self.complete();
+    }
+}
@@ -1,4 +1,5 @@
 use std::{
+    cmp::Ordering,
     convert::TryInto,
     ops::{Add, Div, Mul, Neg, Sub},
 };
@@ -12,33 +13,49 @@ pub trait ApproxEq: Copy {
 
 impl ApproxEq for f32 {
     fn approx_eq(self, other: Self) -> bool {
-        const EPSILON: f32 = std::f32::EPSILON;
+        const EPSILON: f32 = f32::EPSILON;
         (self - other).abs() <= EPSILON
     }
 }
 
 impl ApproxEq for f64 {
     fn approx_eq(self, other: Self) -> bool {
-        const EPSILON: f64 = 2.0 * std::f64::EPSILON;
+        const EPSILON: f64 = 2.0 * f64::EPSILON;
         (self - other).abs() <= EPSILON
     }
 }
 
+/// An integer type used to index points, triangles and half-edges.
+///
+/// Index newtypes can implement it on top of their `TryFrom<usize>` and
+/// `Into<usize>` conversions; `max_value()` is reserved for [OptionIndex](crate::util::OptionIndex).
 pub trait Index: Copy + PartialEq<Self> {
     fn max_value() -> Self;
-    fn from_usize(n: usize) -> Self;
+
+    /// Converts `n`, or returns `None` if it doesn't fit.
+    fn try_from_usize(n: usize) -> Option<Self>;
+
+    /// Converts `n`.
+    ///
+    /// # Panics
+    /// Panics if `n` doesn't fit.
+    #[inline]
+    fn from_usize(n: usize) -> Self {
+        Self::try_from_usize(n).expect("index out of range")
+    }
+
     fn as_usize(self) -> usize;
 }
 
 impl Index for u16 {
     #[inline]
     fn max_value() -> Self {
-        u16::max_value()
+        u16::MAX
     }
 
     #[inline]
-    fn from_usize(n: usize) -> Self {
-        n.try_into().unwrap()
+    fn try_from_usize(n: usize) -> Option<Self> {
+        n.try_into().ok()
     }
 
     #[inline]
@@ -50,12 +67,12 @@ impl Index for u16 {
 impl Index for u32 {
     #[inline]
     fn max_value() -> Self {
-        u32::max_value()
+        u32::MAX
     }
 
     #[inline]
-    fn from_usize(n: usize) -> Self {
-        n.try_into().unwrap()
+    fn try_from_usize(n: usize) -> Option<Self> {
+        n.try_into().ok()
     }
 
     #[inline]
@@ -68,7 +85,12 @@ impl Index for u32 {
 impl Index for usize {
     #[inline]
     fn max_value() -> Self {
-        usize::max_value()
+        usize::MAX
+    }
+
+    #[inline]
+    fn try_from_usize(n: usize) -> Option<Self> {
+        Some(n)
     }
 
     #[inline]
@@ -82,6 +104,12 @@ impl Index for usize {
     }
 }
 
+/// A floating point coordinate type.
+///
+/// Implemented for `f32` and `f64`. With the `num-traits` feature it is
+/// implemented for every `num_traits::Float` type instead, so other floating
+/// point types such as half or extended precision floats can be triangulated
+/// once they also implement [ApproxEq].
 pub trait Scalar:
     Copy
     + Add<Self, Output = Self>
@@ -90,15 +118,27 @@ pub trait Scalar:
     + Div<Self, Output = Self>
     + Neg<Output = Self>
     + PartialOrd<Self>
-    + From<f32>
-    + Into<f64>
 {
     fn abs(self) -> Self;
     fn min(self, other: Self) -> Self;
     fn max(self, other: Self) -> Self;
+    fn sqrt(self) -> Self;
     fn infinity() -> Self;
+
+    /// Converts a constant or count to the nearest representable value.
+    fn from_f64(x: f64) -> Self;
+
+    /// Converts to `f64`, rounding if `f64` can't represent the value exactly.
+    fn to_f64(self) -> f64;
+
+    /// Compares by the IEEE 754 total order of the `f64` values, so sorting
+    /// values that include NaN doesn't panic.
+    fn total_cmp(&self, other: &Self) -> Ordering {
+        self.to_f64().total_cmp(&other.to_f64())
+    }
 }
 
+#[cfg(not(feature = "num-traits"))]
 impl Scalar for f32 {
     #[inline(always)]
     fn abs(self) -> Self {
@@ -115,12 +155,28 @@ impl Scalar for f32 {
         self.max(other)
     }
 
+    #[inline(always)]
+    fn sqrt(self) -> Self {
+        self.sqrt()
+    }
+
     #[inline(always)]
     fn infinity() -> Self {
         f32::INFINITY
     }
+
+    #[inline(always)]
+    fn from_f64(x: f64) -> Self {
+        x as f32
+    }
+
+    #[inline(always)]
+    fn to_f64(self) -> f64 {
+        f64::from(self)
+    }
 }
 
+#[cfg(not(feature = "num-traits"))]
 impl Scalar for f64 {
     #[inline(always)]
     fn abs(self) -> Self {
@@ -137,10 +193,63 @@ impl Scalar for f64 {
         self.max(other)
     }
 
+    #[inline(always)]
+    fn sqrt(self) -> Self {
+        self.sqrt()
+    }
+
     #[inline(always)]
     fn infinity() -> Self {
         f64::INFINITY
     }
+
+    #[inline(always)]
+    fn from_f64(x: f64) -> Self {
+        x
+    }
+
+    #[inline(always)]
+    fn to_f64(self) -> f64 {
+        self
+    }
+}
+
+#[cfg(feature = "num-traits")]
+impl<T: num_traits::Float> Scalar for T {
+    #[inline(always)]
+    fn abs(self) -> Self {
+        num_traits::Float::abs(self)
+    }
+
+    #[inline(always)]
+    fn min(self, other: Self) -> Self {
+        num_traits::Float::min(self, other)
+    }
+
+    #[inline(always)]
+    fn max(self, other: Self) -> Self {
+        num_traits::Float::max(self, other)
+    }
+
+    #[inline(always)]
+    fn sqrt(self) -> Self {
+        num_traits::Float::sqrt(self)
+    }
+
+    #[inline(always)]
+    fn infinity() -> Self {
+        num_traits::Float::infinity()
+    }
+
+    #[inline(always)]
+    fn from_f64(x: f64) -> Self {
+        <T as num_traits::NumCast>::from(x).unwrap_or_else(T::nan)
+    }
+
+    #[inline(always)]
+    fn to_f64(self) -> f64 {
+        num_traits::ToPrimitive::to_f64(&self).unwrap_or(f64::NAN)
+    }
 }
 
 pub trait HasPosition<T: Scalar> {
@@ -153,3 +262,33 @@ impl<T: Scalar> HasPosition<T> for Point<T> {
         *self
     }
 }
+
+/// A source of point coordinates, indexed from `0` to `len() - 1`.
+///
+/// Implemented for slices of [HasPosition] values and, in the [source](crate::source)
+/// module, for raw coordinate buffers that can be triangulated without copying
+/// them into [Point]s first.
+pub trait PointSource<T: Scalar> {
+    /// The number of points.
+    fn len(&self) -> usize;
+
+    /// The `i`-th point.
+    fn point(&self, i: usize) -> Point<T>;
+
+    /// Returns `true` if there are no points.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T: Scalar, P: HasPosition<T>> PointSource<T> for [P] {
+    #[inline(always)]
+    fn len(&self) -> usize {
+        <[P]>::len(self)
+    }
+
+    #[inline(always)]
+    fn point(&self, i: usize) -> Point<T> {
+        self[i].pos()
+    }
+}
@@ -0,0 +1,536 @@
+//! A compact binary file format for triangulations, `.tri`, that can be
+//! memory-mapped and used without parsing or copying.
+//!
+//! Triangulating a national-scale point cloud takes a while, so a precomputed
+//! triangulation can be saved next to its points and opened instantly later.
+//! With the `mmap` feature, [MappedTriangulation] maps a file into memory and
+//! hands out a [TriangulationRef](crate::view::TriangulationRef) that borrows
+//! the mapped arrays directly.
+//!
+//! ```rust
+//! use delaunator::{tri, Point, Triangulation};
+//!
+//! let points = vec![
+//!     Point { x: 0., y: 0. },
+//!     Point { x: 1., y: 0. },
+//!     Point { x: 1., y: 1. },
+//!     Point { x: 0., y: 1. },
+//! ];
+//! let triangulation = Triangulation::<u32>::new(&points).unwrap();
+//!
+//! let mut file = Vec::new();
+//! tri::write(&mut file, &triangulation, Some(&points), None).unwrap();
+//!
+//! let loaded = tri::read::<_, u32>(&file[..]).unwrap();
+//! assert_eq!(loaded.triangulation.triangles, triangulation.triangles);
+//! assert_eq!(loaded.points.as_deref(), Some(&points[..]));
+//! ```
+//!
+//! # Layout
+//!
+//! All numbers are little-endian. A file starts with a 32 byte header:
+//!
+//! | Offset | Size | Contents |
+//! |--------|------|----------|
+//! | 0      | 4    | the magic bytes `DTRI` |
+//! | 4      | 2    | the format version, currently 1 |
+//! | 6      | 1    | the width of an index in bytes: 2, 4 or 8 |
+//! | 7      | 1    | flags: 1 if there are points, 2 if there are values |
+//! | 8      | 8    | the number of half-edges |
+//! | 16     | 8    | the number of hull points |
+//! | 24     | 8    | the number of points and values |
+//!
+//! The sections follow in this order, each padded with zeros to a multiple of
+//! eight bytes: `triangles`, `halfedges` with all bits set for `None`, `hull`,
+//! the points as interleaved `f64` coordinates `[x0, y0, x1, y1, ...]` and one
+//! `f64` value per point, e.g. an elevation. The last two are only present if
+//! their flag is set.
+
+use std::{
+    convert::TryFrom,
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+use crate::{
+    traits::Index,
+    util::{next_halfedge, OptionIndex},
+    Point, Triangulation,
+};
+
+const MAGIC: &[u8; 4] = b"DTRI";
+const VERSION: u16 = 1;
+const HEADER_LEN: usize = 32;
+
+const HAS_POINTS: u8 = 1;
+const HAS_VALUES: u8 = 2;
+
+/// The reasons a `.tri` file can't be read.
+#[derive(Debug)]
+pub enum TriError {
+    /// Reading failed.
+    Io(io::Error),
+    /// The file doesn't start with the `.tri` magic bytes.
+    Magic,
+    /// The file has a format version this library doesn't know.
+    Version(u16),
+    /// The indices in the file have this width in bytes, which the index type
+    /// can't hold or, for a mapped file, doesn't match.
+    IndexWidth(u8),
+    /// The file is shorter than its header announces.
+    Truncated,
+    /// The header or the arrays are inconsistent, e.g. the half-edges don't
+    /// form triangles, a point is out of bounds or a twin doesn't point back.
+    Malformed,
+}
+
+impl std::fmt::Display for TriError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TriError::Io(err) => write!(f, "{}", err),
+            TriError::Magic => write!(f, "not a .tri file"),
+            TriError::Version(version) => write!(f, "unsupported .tri version {}", version),
+            TriError::IndexWidth(width) => write!(f, "unsupported index width {}", width),
+            TriError::Truncated => write!(f, "unexpected end of file"),
+            TriError::Malformed => write!(f, "malformed .tri file"),
+        }
+    }
+}
+
+impl std::error::Error for TriError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TriError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for TriError {
+    fn from(err: io::Error) -> Self {
+        TriError::Io(err)
+    }
+}
+
+/// The contents of a `.tri` file.
+pub struct TriFile<I> {
+    /// The triangulation.
+    pub triangulation: Triangulation<I>,
+    /// The points, if the file has them.
+    pub points: Option<Vec<Point<f64>>>,
+    /// A value per point, if the file has them.
+    pub values: Option<Vec<f64>>,
+}
+
+/// Writes a triangulation, and optionally its points and a value per point,
+/// in the `.tri` format.
+///
+/// # Panics
+/// Panics if `points` and `values` are both given but differ in length, or if
+/// `I` isn't 2, 4 or 8 bytes wide.
+pub fn write<W: Write, I: Index>(
+    mut writer: W,
+    triangulation: &Triangulation<I>,
+    points: Option<&[Point<f64>]>,
+    values: Option<&[f64]>,
+) -> io::Result<()> {
+    let count = match (points, values) {
+        (Some(points), Some(values)) => {
+            assert_eq!(
+                points.len(),
+                values.len(),
+                "points and values differ in length"
+            );
+            points.len()
+        }
+        (Some(points), None) => points.len(),
+        (None, Some(values)) => values.len(),
+        (None, None) => 0,
+    };
+    let width = std::mem::size_of::<I>();
+    assert!(
+        matches!(width, 2 | 4 | 8),
+        "unsupported index width {}",
+        width
+    );
+    let flags = if points.is_some() { HAS_POINTS } else { 0 }
+        | if values.is_some() { HAS_VALUES } else { 0 };
+
+    let mut header = Vec::with_capacity(HEADER_LEN);
+    header.extend_from_slice(MAGIC);
+    header.extend_from_slice(&VERSION.to_le_bytes());
+    header.extend_from_slice(&[width as u8, flags]);
+    for len in [
+        triangulation.halfedges.len(),
+        triangulation.hull.len(),
+        count,
+    ] {
+        header.extend_from_slice(&(len as u64).to_le_bytes());
+    }
+    writer.write_all(&header)?;
+
+    let mut section = Vec::new();
+    let indices = triangulation.triangles.iter().map(|&i| Some(i.as_usize()));
+    write_section(&mut writer, &mut section, indices, width)?;
+    let twins = triangulation.halfedges.iter().map(|e| e.map(I::as_usize));
+    write_section(&mut writer, &mut section, twins, width)?;
+    let hull = triangulation.hull.iter().map(|&i| Some(i.as_usize()));
+    write_section(&mut writer, &mut section, hull, width)?;
+
+    if let Some(points) = points {
+        section.clear();
+        for p in points {
+            section.extend_from_slice(&p.x.to_le_bytes());
+            section.extend_from_slice(&p.y.to_le_bytes());
+        }
+        writer.write_all(&section)?;
+    }
+    if let Some(values) = values {
+        section.clear();
+        for v in values {
+            section.extend_from_slice(&v.to_le_bytes());
+        }
+        writer.write_all(&section)?;
+    }
+    Ok(())
+}
+
+/// Writes a section of indices, `None` as all bits set, padded to eight bytes.
+fn write_section<W: Write, It: Iterator<Item = Option<usize>>>(
+    writer: &mut W,
+    buffer: &mut Vec<u8>,
+    indices: It,
+    width: usize,
+) -> io::Result<()> {
+    buffer.clear();
+    for i in indices {
+        let bytes = i.map_or(u64::MAX, |i| i as u64).to_le_bytes();
+        buffer.extend_from_slice(&bytes[..width]);
+    }
+    buffer.resize(padded(buffer.len()), 0);
+    writer.write_all(buffer)
+}
+
+/// Reads a `.tri` file, converting its indices to `I`.
+///
+/// Returns [TriError::Malformed] if a triangle or the hull refers to a point
+/// beyond the points of the file, or a half-edge and its twin don't point at
+/// each other. Without points or values in the file, the hull must stay
+/// within the points of the triangles.
+pub fn read<R: Read, I: Index>(mut reader: R) -> Result<TriFile<I>, TriError> {
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+    let layout = Layout::parse(&bytes)?;
+
+    let index = |offset: usize, k: usize| -> Option<usize> {
+        let start = offset + k * layout.width;
+        let raw = &bytes[start..start + layout.width];
+        if raw.iter().all(|&b| b == 0xff) {
+            return None;
+        }
+        let mut word = [0; 8];
+        word[..layout.width].copy_from_slice(raw);
+        Some(u64::from_le_bytes(word) as usize)
+    };
+    let indices = |offset: usize, len: usize| -> Result<Vec<I>, TriError> {
+        (0..len)
+            .map(|k| {
+                index(offset, k)
+                    .and_then(I::try_from_usize)
+                    .filter(|&i| i != I::max_value())
+                    .ok_or(TriError::IndexWidth(layout.width as u8))
+            })
+            .collect()
+    };
+    let float = |offset: usize, k: usize| {
+        let start = offset + 8 * k;
+        let mut word = [0; 8];
+        word.copy_from_slice(&bytes[start..start + 8]);
+        f64::from_le_bytes(word)
+    };
+
+    let triangles = indices(layout.triangles, layout.halfedge_count)?;
+    let halfedges = (0..layout.halfedge_count)
+        .map(|k| match index(layout.halfedges, k) {
+            None => Ok(OptionIndex::none()),
+            Some(e) => I::try_from_usize(e)
+                .filter(|&e| e != I::max_value())
+                .map(OptionIndex::some)
+                .ok_or(TriError::IndexWidth(layout.width as u8)),
+        })
+        .collect::<Result<_, _>>()?;
+    let hull = indices(layout.hull, layout.hull_count)?;
+    let points = layout.points.map(|offset| {
+        (0..layout.point_count)
+            .map(|k| Point::new(float(offset, 2 * k), float(offset, 2 * k + 1)))
+            .collect()
+    });
+    let values = layout
+        .values
+        .map(|offset| (0..layout.point_count).map(|k| float(offset, k)).collect());
+
+    #[allow(unused_mut)]
+    let mut triangulation = Triangulation {
+        #[cfg(feature = "vertices")]
+        vertices: Vec::new(),
+        triangles,
+        halfedges,
+        hull,
+    };
+    let known = layout.points.is_some() || layout.values.is_some();
+    check(&triangulation, known.then_some(layout.point_count))?;
+    #[cfg(feature = "vertices")]
+    {
+        let n = triangulation
+            .triangles
+            .iter()
+            .map(|&i| i.as_usize() + 1)
+            .max();
+        triangulation.update_vertices(n.unwrap_or(0).max(layout.point_count));
+    }
+    Ok(TriFile {
+        triangulation,
+        points,
+        values,
+    })
+}
+
+/// Checks that the indices of `triangulation` are below `points`, or that the
+/// hull is within the points of the triangles if that isn't known, and that
+/// every twin points back at its half-edge.
+fn check<I: Index>(
+    triangulation: &Triangulation<I>,
+    points: Option<usize>,
+) -> Result<(), TriError> {
+    let Triangulation {
+        triangles,
+        halfedges,
+        hull,
+        ..
+    } = triangulation;
+    let points = points.unwrap_or_else(|| match triangles.iter().map(|i| i.as_usize()).max() {
+        Some(max) => max + 1,
+        None => usize::MAX,
+    });
+    let in_bounds = |i: &I| i.as_usize() < points;
+    if !triangles.iter().all(in_bounds) || !hull.iter().all(in_bounds) {
+        return Err(TriError::Malformed);
+    }
+
+    let point = |e: usize| triangles[e].as_usize();
+    let twins = halfedges.iter().enumerate().all(|(e, twin)| {
+        twin.get().map(I::as_usize).map_or(true, |f| {
+            f < halfedges.len()
+                && halfedges[f].get().map(I::as_usize) == Some(e)
+                && point(f) == point(next_halfedge(e))
+                && point(next_halfedge(f)) == point(e)
+        })
+    });
+    if !twins {
+        return Err(TriError::Malformed);
+    }
+    Ok(())
+}
+
+/// Saves a triangulation, and optionally its points and a value per point, to
+/// a `.tri` file at `path`. See [write].
+pub fn save<I: Index, P: AsRef<Path>>(
+    path: P,
+    triangulation: &Triangulation<I>,
+    points: Option<&[Point<f64>]>,
+    values: Option<&[f64]>,
+) -> io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    write(&mut writer, triangulation, points, values)?;
+    writer.flush()
+}
+
+/// Loads the `.tri` file at `path`. See [read].
+pub fn load<I: Index, P: AsRef<Path>>(path: P) -> Result<TriFile<I>, TriError> {
+    read(BufReader::new(File::open(path)?))
+}
+
+/// Where the sections of a `.tri` file start, in bytes.
+struct Layout {
+    width: usize,
+    halfedge_count: usize,
+    hull_count: usize,
+    point_count: usize,
+    triangles: usize,
+    halfedges: usize,
+    hull: usize,
+    points: Option<usize>,
+    values: Option<usize>,
+}
+
+impl Layout {
+    fn parse(bytes: &[u8]) -> Result<Self, TriError> {
+        if bytes.len() < HEADER_LEN {
+            return Err(if bytes.starts_with(MAGIC) || bytes.len() < MAGIC.len() {
+                TriError::Truncated
+            } else {
+                TriError::Magic
+            });
+        }
+        if &bytes[..4] != MAGIC {
+            return Err(TriError::Magic);
+        }
+        let version = u16::from_le_bytes([bytes[4], bytes[5]]);
+        if version != VERSION {
+            return Err(TriError::Version(version));
+        }
+        let (width, flags) = (bytes[6] as usize, bytes[7]);
+        if !matches!(width, 2 | 4 | 8) {
+            return Err(TriError::IndexWidth(bytes[6]));
+        }
+        if flags & !(HAS_POINTS | HAS_VALUES) != 0 {
+            return Err(TriError::Malformed);
+        }
+        let count = |offset: usize| {
+            let mut word = [0; 8];
+            word.copy_from_slice(&bytes[offset..offset + 8]);
+            usize::try_from(u64::from_le_bytes(word)).map_err(|_| TriError::Truncated)
+        };
+        let (halfedge_count, hull_count, point_count) = (count(8)?, count(16)?, count(24)?);
+        if halfedge_count % 3 != 0 {
+            return Err(TriError::Malformed);
+        }
+
+        // the end of every section, with overflow treated as a short file
+        let section = |start: usize, len: usize, size: usize| {
+            len.checked_mul(size)
+                .and_then(|bytes| start.checked_add(padded(bytes)))
+                .ok_or(TriError::Truncated)
+        };
+        let triangles = HEADER_LEN;
+        let halfedges = section(triangles, halfedge_count, width)?;
+        let hull = section(halfedges, halfedge_count, width)?;
+        let mut end = section(hull, hull_count, width)?;
+        let mut points = None;
+        if flags & HAS_POINTS != 0 {
+            points = Some(end);
+            end = section(end, point_count, 16)?;
+        }
+        let mut values = None;
+        if flags & HAS_VALUES != 0 {
+            values = Some(end);
+            end = section(end, point_count, 8)?;
+        }
+        if bytes.len() < end {
+            return Err(TriError::Truncated);
+        }
+        Ok(Layout {
+            width,
+            halfedge_count,
+            hull_count,
+            point_count,
+            triangles,
+            halfedges,
+            hull,
+            points,
+            values,
+        })
+    }
+}
+
+fn padded(len: usize) -> usize {
+    (len + 7) / 8 * 8
+}
+
+/// An index type whose values can be borrowed straight from the bytes of a
+/// `.tri` file: `u16`, `u32` and `usize`.
+#[cfg(all(feature = "mmap", target_endian = "little"))]
+pub trait RawIndex: Index + private::Sealed {}
+
+#[cfg(all(feature = "mmap", target_endian = "little"))]
+impl RawIndex for u16 {}
+#[cfg(all(feature = "mmap", target_endian = "little"))]
+impl RawIndex for u32 {}
+#[cfg(all(feature = "mmap", target_endian = "little"))]
+impl RawIndex for usize {}
+
+#[cfg(all(feature = "mmap", target_endian = "little"))]
+mod private {
+    pub trait Sealed {}
+
+    impl Sealed for u16 {}
+    impl Sealed for u32 {}
+    impl Sealed for usize {}
+}
+
+/// A `.tri` file mapped into memory, whose arrays are borrowed without
+/// copying. Only available on little-endian targets with the `mmap` feature.
+///
+/// Opening a file only reads its header, so the operating system pages the
+/// arrays in as they are used.
+#[cfg(all(feature = "mmap", target_endian = "little"))]
+pub struct MappedTriangulation<I> {
+    map: memmap2::Mmap,
+    layout: Layout,
+    index: std::marker::PhantomData<I>,
+}
+
+#[cfg(all(feature = "mmap", target_endian = "little"))]
+impl<I: RawIndex> MappedTriangulation<I> {
+    /// Maps the `.tri` file at `path`, which must have been written with
+    /// indices of type `I`, or at least of the same width.
+    ///
+    /// # Safety
+    /// The file must not be modified or truncated while it is mapped, which
+    /// would change or invalidate the borrowed arrays under the caller's feet.
+    pub unsafe fn open<P: AsRef<Path>>(path: P) -> Result<Self, TriError> {
+        let map = memmap2::Mmap::map(&File::open(path)?)?;
+        let layout = Layout::parse(&map)?;
+        // the map is page-aligned and every section starts at a multiple of 8
+        if layout.width != std::mem::size_of::<I>() {
+            return Err(TriError::IndexWidth(layout.width as u8));
+        }
+        Ok(Self {
+            map,
+            layout,
+            index: std::marker::PhantomData,
+        })
+    }
+
+    fn slice<U>(&self, offset: usize, len: usize) -> &[U] {
+        let bytes = &self.map[offset..offset + len * std::mem::size_of::<U>()];
+        assert_eq!(bytes.as_ptr().align_offset(std::mem::align_of::<U>()), 0);
+        // SAFETY: the bytes are in bounds and aligned, and `U` is either the
+        // index type, a `u16`, `u32` or `usize` by `RawIndex`, or `f64`, for
+        // which every bit pattern is valid. The half-edges are sliced as
+        // indices too; `TriangulationRef::new` views them as `OptionIndex`,
+        // which is a transparent wrapper that takes every index as well
+        unsafe { std::slice::from_raw_parts(bytes.as_ptr().cast(), len) }
+    }
+
+    /// The triangulation, borrowing the mapped arrays.
+    ///
+    /// The arrays aren't validated, see
+    /// [TriangulationRef::new](crate::view::TriangulationRef::new).
+    pub fn view(&self) -> crate::view::TriangulationRef<'_, I> {
+        let Layout {
+            halfedge_count,
+            hull_count,
+            ..
+        } = self.layout;
+        crate::view::TriangulationRef::new(
+            self.slice(self.layout.triangles, halfedge_count),
+            self.slice(self.layout.halfedges, halfedge_count),
+            self.slice(self.layout.hull, hull_count),
+        )
+    }
+
+    /// The points as interleaved coordinates `[x0, y0, x1, y1, ...]`, ready for
+    /// [FlatPoints](crate::source::FlatPoints), if the file has them.
+    pub fn coords(&self) -> Option<&[f64]> {
+        let offset = self.layout.points?;
+        Some(self.slice(offset, 2 * self.layout.point_count))
+    }
+
+    /// A value per point, if the file has them.
+    pub fn values(&self) -> Option<&[f64]> {
+        let offset = self.layout.values?;
+        Some(self.slice(offset, self.layout.point_count))
+    }
+}
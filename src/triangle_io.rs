@@ -0,0 +1,378 @@
+//! Reading and writing the file formats of Jonathan Shewchuk's
+//! [Triangle](https://www.cs.cmu.edu/~quake/triangle.html), which many FEM
+//! tools consume: `.node` files with points, `.ele` files with triangles and
+//! `.poly` files with segments and holes.
+//!
+//! Vertices in these files may be numbered from zero or one; readers detect
+//! the numbering from the first vertex and return zero-based indices. Writers
+//! number from zero.
+//!
+//! ```rust
+//! use delaunator::{triangle_io, Point, Triangulation};
+//!
+//! let points = vec![
+//!     Point { x: 0., y: 0. },
+//!     Point { x: 1., y: 0. },
+//!     Point { x: 1., y: 1. },
+//!     Point { x: 0., y: 1. },
+//! ];
+//! let triangulation = Triangulation::<usize>::new(&points).unwrap();
+//!
+//! let (mut node, mut ele) = (Vec::new(), Vec::new());
+//! triangle_io::write_node(&mut node, &points, None).unwrap();
+//! triangle_io::write_ele(&mut ele, &triangulation.triangles).unwrap();
+//!
+//! let nodes = triangle_io::read_node(&node[..]).unwrap();
+//! let triangles = triangle_io::read_ele(&ele[..], &nodes).unwrap();
+//! let imported = Triangulation::<usize>::from_mesh(&nodes.points, triangles).unwrap();
+//! assert_eq!(imported.len(), 2);
+//! ```
+
+use std::io::{self, BufRead, Write};
+
+use crate::{
+    traits::{HasPosition, Index, Scalar},
+    Point, Triangulation,
+};
+
+/// The reasons a file can't be read.
+#[derive(Debug)]
+pub enum FormatError {
+    /// Reading failed.
+    Io(io::Error),
+    /// A value on this line (counting from one) is missing or malformed.
+    Syntax { line: usize },
+    /// A vertex on this line references a vertex that does not exist, or one
+    /// that the index type can't hold.
+    IndexOutOfBounds { line: usize },
+    /// The file ended before all announced entries were read.
+    UnexpectedEnd,
+}
+
+impl std::fmt::Display for FormatError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FormatError::Io(err) => write!(f, "{}", err),
+            FormatError::Syntax { line } => write!(f, "malformed value on line {}", line),
+            FormatError::IndexOutOfBounds { line } => {
+                write!(f, "line {} references a missing vertex", line)
+            }
+            FormatError::UnexpectedEnd => write!(f, "unexpected end of file"),
+        }
+    }
+}
+
+impl std::error::Error for FormatError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FormatError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for FormatError {
+    fn from(err: io::Error) -> Self {
+        FormatError::Io(err)
+    }
+}
+
+/// The contents of a `.node` file, or of the vertex section of a `.poly` file.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Nodes {
+    /// The vertices.
+    pub points: Vec<Point<f64>>,
+    /// The number of attributes per vertex.
+    pub attribute_count: usize,
+    /// The attributes of all vertices, `attribute_count` per vertex.
+    pub attributes: Vec<f64>,
+    /// The boundary marker of every vertex, if the file has them.
+    pub markers: Option<Vec<i32>>,
+    /// The number of the first vertex in the file, `0` or `1`. Pass it on to
+    /// [read_poly] for files that refer to these vertices.
+    pub first: usize,
+}
+
+/// A segment of a `.poly` file, between two vertices.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Segment {
+    /// The zero-based index of the first vertex.
+    pub start: usize,
+    /// The zero-based index of the second vertex.
+    pub end: usize,
+    /// The boundary marker, `0` if the file has none.
+    pub marker: i32,
+}
+
+/// The contents of a `.poly` file.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Poly {
+    /// The vertices, or `None` if they are in a separate `.node` file.
+    pub nodes: Option<Nodes>,
+    /// The segments, with zero-based vertex indices.
+    pub segments: Vec<Segment>,
+    /// A point inside each hole.
+    pub holes: Vec<Point<f64>>,
+}
+
+/// Reads a `.node` file.
+pub fn read_node<R: BufRead>(reader: R) -> Result<Nodes, FormatError> {
+    let mut lines = Lines::new(reader);
+    read_nodes(&mut lines)?.ok_or(FormatError::UnexpectedEnd)
+}
+
+/// Reads an `.ele` file, returning the vertex indices of the triangles in
+/// groups of three. `nodes` are the vertices of the `.node` file the
+/// triangles refer to.
+///
+/// Only the corners of higher-order triangles are kept, and triangle
+/// attributes are skipped. The result can be passed to
+/// [Triangulation::from_mesh] or [Triangulation::from_triangles].
+pub fn read_ele<R: BufRead, I: Index>(reader: R, nodes: &Nodes) -> Result<Vec<I>, FormatError> {
+    let mut lines = Lines::new(reader);
+    let (line, header) = lines.next_values()?.ok_or(FormatError::UnexpectedEnd)?;
+    let count = header.count(0, line)?;
+    if header.count(1, line)? < 3 {
+        return Err(FormatError::Syntax { line });
+    }
+
+    let mut triangles = Vec::with_capacity(3 * count);
+    for _ in 0..count {
+        let (line, values) = lines.next_values()?.ok_or(FormatError::UnexpectedEnd)?;
+        for k in 1..4 {
+            let i = values.index(k, nodes.first, line)?;
+            let i = Some(i)
+                .filter(|&i| i < nodes.points.len())
+                .and_then(I::try_from_usize)
+                .ok_or(FormatError::IndexOutOfBounds { line })?;
+            triangles.push(i);
+        }
+    }
+    Ok(triangles)
+}
+
+/// Reads a `.poly` file. `first` is the number of the first vertex in the
+/// separate `.node` file, used if the `.poly` file contains no vertices.
+///
+/// Regional attributes at the end of the file are skipped.
+pub fn read_poly<R: BufRead>(reader: R, first: usize) -> Result<Poly, FormatError> {
+    let mut lines = Lines::new(reader);
+    let nodes = read_nodes(&mut lines)?;
+    let first = nodes.as_ref().map_or(first, |nodes| nodes.first);
+
+    let (line, header) = lines.next_values()?.ok_or(FormatError::UnexpectedEnd)?;
+    let count = header.count(0, line)?;
+    let has_markers = header.count(1, line).unwrap_or(0) > 0;
+    let mut segments = Vec::with_capacity(count);
+    for _ in 0..count {
+        let (line, values) = lines.next_values()?.ok_or(FormatError::UnexpectedEnd)?;
+        segments.push(Segment {
+            start: values.index(1, first, line)?,
+            end: values.index(2, first, line)?,
+            marker: if has_markers {
+                values.parse(3, line)?
+            } else {
+                0
+            },
+        });
+    }
+
+    let mut holes = Vec::new();
+    if let Some((line, header)) = lines.next_values()? {
+        let count = header.count(0, line)?;
+        for _ in 0..count {
+            let (line, values) = lines.next_values()?.ok_or(FormatError::UnexpectedEnd)?;
+            holes.push(Point::new(values.parse(1, line)?, values.parse(2, line)?));
+        }
+    }
+
+    Ok(Poly {
+        nodes,
+        segments,
+        holes,
+    })
+}
+
+/// Writes `points` as a `.node` file, with boundary markers if given.
+pub fn write_node<W: Write, T: Scalar, P: HasPosition<T>>(
+    mut writer: W,
+    points: &[P],
+    markers: Option<&[i32]>,
+) -> io::Result<()> {
+    write_nodes(&mut writer, points, markers)
+}
+
+/// Writes triangles, given as groups of three vertex indices such as
+/// [Triangulation::triangles], as an `.ele` file.
+pub fn write_ele<W: Write, I: Index>(mut writer: W, triangles: &[I]) -> io::Result<()> {
+    writeln!(writer, "{} 3 0", triangles.len() / 3)?;
+    for (t, v) in triangles.chunks_exact(3).enumerate() {
+        let [a, b, c] = [v[0], v[1], v[2]].map(I::as_usize);
+        writeln!(writer, "{} {} {} {}", t, a, b, c)?;
+    }
+    Ok(())
+}
+
+/// Writes a `.poly` file with `points`, `segments` and `holes`. Pass no points
+/// to keep the vertices in a separate `.node` file.
+pub fn write_poly<W: Write, T: Scalar, P: HasPosition<T>>(
+    mut writer: W,
+    points: &[P],
+    segments: &[Segment],
+    holes: &[Point<T>],
+) -> io::Result<()> {
+    if points.is_empty() {
+        writeln!(writer, "0 2 0 0")?;
+    } else {
+        write_nodes(&mut writer, points, None)?;
+    }
+    writeln!(writer, "{} 1", segments.len())?;
+    for (s, segment) in segments.iter().enumerate() {
+        writeln!(
+            writer,
+            "{} {} {} {}",
+            s, segment.start, segment.end, segment.marker
+        )?;
+    }
+    writeln!(writer, "{}", holes.len())?;
+    for (h, p) in holes.iter().enumerate() {
+        let (x, y) = (p.x.to_f64(), p.y.to_f64());
+        writeln!(writer, "{} {} {}", h, x, y)?;
+    }
+    Ok(())
+}
+
+/// The edges of the convex hull of `triangulation` as segments, counter-clockwise,
+/// each with the given boundary `marker`.
+pub fn hull_segments<I: Index>(triangulation: &Triangulation<I>, marker: i32) -> Vec<Segment> {
+    let hull = &triangulation.hull;
+    (0..hull.len())
+        .map(|k| Segment {
+            start: hull[k].as_usize(),
+            end: hull[(k + 1) % hull.len()].as_usize(),
+            marker,
+        })
+        .collect()
+}
+
+/// Reads the vertex section of a `.node` or `.poly` file, or `None` if it
+/// announces no vertices.
+fn read_nodes<R: BufRead>(lines: &mut Lines<R>) -> Result<Option<Nodes>, FormatError> {
+    let (line, header) = lines.next_values()?.ok_or(FormatError::UnexpectedEnd)?;
+    let count = header.count(0, line)?;
+    if count == 0 {
+        return Ok(None);
+    }
+    if header.count(1, line)? != 2 {
+        return Err(FormatError::Syntax { line });
+    }
+    let attribute_count = header.count(2, line).unwrap_or(0);
+    let has_markers = header.count(3, line).unwrap_or(0) > 0;
+
+    let mut nodes = Nodes {
+        points: Vec::with_capacity(count),
+        attribute_count,
+        attributes: Vec::with_capacity(count * attribute_count),
+        markers: if has_markers {
+            Some(Vec::with_capacity(count))
+        } else {
+            None
+        },
+        first: 0,
+    };
+    for i in 0..count {
+        let (line, values) = lines.next_values()?.ok_or(FormatError::UnexpectedEnd)?;
+        if i == 0 {
+            nodes.first = values.count(0, line)?;
+        }
+        nodes
+            .points
+            .push(Point::new(values.parse(1, line)?, values.parse(2, line)?));
+        for k in 0..attribute_count {
+            nodes.attributes.push(values.parse(3 + k, line)?);
+        }
+        if let Some(markers) = &mut nodes.markers {
+            markers.push(values.parse(3 + attribute_count, line)?);
+        }
+    }
+    Ok(Some(nodes))
+}
+
+/// Writes the vertex section of a `.node` or `.poly` file.
+fn write_nodes<W: Write, T: Scalar, P: HasPosition<T>>(
+    writer: &mut W,
+    points: &[P],
+    markers: Option<&[i32]>,
+) -> io::Result<()> {
+    writeln!(
+        writer,
+        "{} 2 0 {}",
+        points.len(),
+        markers.is_some() as usize
+    )?;
+    for (i, p) in points.iter().enumerate() {
+        let p = p.pos();
+        let (x, y) = (p.x.to_f64(), p.y.to_f64());
+        match markers {
+            Some(markers) => writeln!(writer, "{} {} {} {}", i, x, y, markers[i])?,
+            None => writeln!(writer, "{} {} {}", i, x, y)?,
+        }
+    }
+    Ok(())
+}
+
+/// The non-empty lines of a file, split into values, without comments.
+struct Lines<R> {
+    reader: R,
+    line: usize,
+    buffer: String,
+}
+
+impl<R: BufRead> Lines<R> {
+    fn new(reader: R) -> Self {
+        Lines {
+            reader,
+            line: 0,
+            buffer: String::new(),
+        }
+    }
+
+    /// The number and values of the next line with any values on it.
+    fn next_values(&mut self) -> Result<Option<(usize, Values)>, FormatError> {
+        loop {
+            self.buffer.clear();
+            if self.reader.read_line(&mut self.buffer)? == 0 {
+                return Ok(None);
+            }
+            self.line += 1;
+            let content = self.buffer.split('#').next().unwrap_or("");
+            let values: Vec<String> = content.split_whitespace().map(str::to_owned).collect();
+            if !values.is_empty() {
+                return Ok(Some((self.line, Values(values))));
+            }
+        }
+    }
+}
+
+/// The values on one line.
+struct Values(Vec<String>);
+
+impl Values {
+    fn parse<V: std::str::FromStr>(&self, k: usize, line: usize) -> Result<V, FormatError> {
+        self.0
+            .get(k)
+            .and_then(|v| v.parse().ok())
+            .ok_or(FormatError::Syntax { line })
+    }
+
+    fn count(&self, k: usize, line: usize) -> Result<usize, FormatError> {
+        self.parse(k, line)
+    }
+
+    /// A zero-based vertex index from a vertex number.
+    fn index(&self, k: usize, first: usize, line: usize) -> Result<usize, FormatError> {
+        self.count(k, line)?
+            .checked_sub(first)
+            .ok_or(FormatError::IndexOutOfBounds { line })
+    }
+}
@@ -1,11 +1,104 @@
+use std::cmp::Ordering;
+
 use crate::{
+    builder::{TieBreak, TriangulationBuilder},
     elem::*,
-    hull::Hull,
+    hull::{Hull, Scratch},
     iter::*,
-    traits::{ApproxEq, HasPosition, Index, Scalar},
+    kernel::Kernel,
+    observer::{Observer, Phase},
+    source::FlatPoints,
+    trace::PointStatus,
+    traits::{ApproxEq, HasPosition, Index, PointSource, Scalar},
     util::{self, OptionIndex},
+    view::TriangulationRef,
+    Point,
 };
 
+/// How [Triangulation::construct] starts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Seed {
+    /// Search for a small triangle near the middle of the points.
+    Find,
+    /// Start from the given counter-clockwise triangle.
+    Triangle(usize, usize, usize),
+    /// Start from the first three points and insert the rest in index order,
+    /// which the caller guarantees to be by distance from the seed circumcenter.
+    Sorted,
+}
+
+/// Which edges [Triangulation::wireframe_indices_of] exports.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum WireframeEdges {
+    /// Every edge.
+    All,
+    /// Only the edges on the convex hull.
+    Hull,
+    /// Only the edges between two triangles.
+    Interior,
+}
+
+/// The nonzero pattern of a square sparse matrix in compressed sparse row
+/// form, see [Triangulation::sparsity_pattern].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SparsityPattern {
+    /// Where each row starts in `columns`, plus the total number of nonzeros
+    /// at the end.
+    pub offsets: Vec<usize>,
+    /// The column indices of the nonzeros, sorted within each row.
+    pub columns: Vec<usize>,
+}
+
+impl SparsityPattern {
+    /// The number of rows.
+    pub fn rows(&self) -> usize {
+        self.offsets.len().saturating_sub(1)
+    }
+
+    /// The column indices of the nonzeros in row `i`.
+    pub fn row(&self, i: usize) -> &[usize] {
+        &self.columns[self.offsets[i]..self.offsets[i + 1]]
+    }
+}
+
+/// Links between the points of the convex hull for constant-time navigation,
+/// see [Triangulation::hull_links]. Each array is indexed by point and holds
+/// nothing for points that aren't on the hull.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HullLinks<I: Index> {
+    /// The next point of the hull, counter-clockwise.
+    pub next: Vec<OptionIndex<I>>,
+    /// The previous point of the hull, counter-clockwise.
+    pub prev: Vec<OptionIndex<I>>,
+    /// The half-edge on the boundary of the triangulation that starts at the point.
+    pub tri: Vec<OptionIndex<I>>,
+}
+
+/// Upper bounds on the size of a triangulation, see
+/// [Triangulation::estimate_sizes].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SizeEstimate {
+    /// The most triangles the triangulation can have.
+    pub triangles: usize,
+    /// The most half-edges, i.e. the length of `triangles` and `halfedges`.
+    pub halfedges: usize,
+    /// The most points on the hull.
+    pub hull: usize,
+    /// The size of `triangles` in bytes.
+    pub triangles_bytes: usize,
+    /// The size of `halfedges` in bytes.
+    pub halfedges_bytes: usize,
+    /// The size of `hull` in bytes.
+    pub hull_bytes: usize,
+}
+
+impl SizeEstimate {
+    /// The size of all three arrays in bytes.
+    pub fn total_bytes(&self) -> usize {
+        self.triangles_bytes + self.halfedges_bytes + self.hull_bytes
+    }
+}
+
 /// Result of the Delaunay triangulation.
 pub struct Triangulation<I> {
     #[cfg(feature = "vertices")]
@@ -31,73 +124,313 @@ pub struct Triangulation<I> {
 }
 
 impl<I: Index> Triangulation<I> {
-    fn alloc(n: usize) -> Self {
-        assert!(
-            n <= I::max_value().as_usize() / 2,
-            "Too many points to index a Triangulation by {}",
-            std::any::type_name::<I>()
-        );
-        let max_triangles = 2 * n - 5;
+    /// Upper bounds on the triangle, half-edge and hull counts of a
+    /// triangulation of `n` points, and the bytes they take with indices of
+    /// type `I`, for callers that allocate their own buffers.
+    ///
+    /// A triangulation of `n` points with `h` of them on the hull has
+    /// `2n - 2 - h` triangles, so there are at most `2n - 5` of them. Points
+    /// skipped as duplicates only make the triangulation smaller.
+    ///
+    /// ```rust
+    /// use delaunator::Triangulation;
+    ///
+    /// let sizes = Triangulation::<u32>::estimate_sizes(1000);
+    /// assert_eq!(sizes.triangles, 1995);
+    /// assert_eq!(sizes.triangles_bytes, 1995 * 3 * 4);
+    /// ```
+    pub fn estimate_sizes(n: usize) -> SizeEstimate {
+        let triangles = if n < 3 { 0 } else { 2 * n - 5 };
+        let halfedges = 3 * triangles;
+        SizeEstimate {
+            triangles,
+            halfedges,
+            hull: n,
+            triangles_bytes: halfedges * std::mem::size_of::<I>(),
+            halfedges_bytes: halfedges * std::mem::size_of::<OptionIndex<I>>(),
+            hull_bytes: n * std::mem::size_of::<I>(),
+        }
+    }
+
+    /// Allocates room for the triangulation of `n` points, or for none of its
+    /// triangles if only the hull is computed. The half-edges go into the
+    /// given buffer, which may be reused from an earlier construction.
+    fn alloc(n: usize, hull_only: bool, mut halfedges: Vec<OptionIndex<I>>) -> Self {
+        let capacity = if hull_only {
+            0
+        } else {
+            Self::estimate_sizes(n).halfedges
+        };
+        halfedges.clear();
+        halfedges.reserve(capacity);
         Self {
             #[cfg(feature = "vertices")]
             vertices: Vec::new(),
-            triangles: Vec::with_capacity(max_triangles * 3),
-            halfedges: Vec::with_capacity(max_triangles * 3),
+            triangles: Vec::with_capacity(capacity),
+            halfedges,
             hull: Vec::new(),
         }
     }
 
     /// Triangulate a set of 2D points.
-    /// Returns `None` if no triangulation exists for the input (e.g. all points are collinear).
+    /// Returns `None` if no triangulation exists for the input (e.g. all points are collinear),
+    /// or if `I` is too small to index its half-edges.
+    ///
+    /// Use a [TriangulationBuilder] to customize construction.
     pub fn new<T: Scalar + ApproxEq, P: HasPosition<T>>(points: &[P]) -> Option<Self> {
-        Some(Triangulation::with_seed_triangle(
-            points,
-            util::find_seed_triangle(points)?,
-        ))
+        TriangulationBuilder::new().build(points)
+    }
+
+    /// Triangulate points read from a [PointSource], such as a raw coordinate
+    /// buffer from the [source](crate::source) module, without copying them.
+    /// Returns `None` if no triangulation exists for the input.
+    pub fn from_source<T: Scalar + ApproxEq, S: PointSource<T> + ?Sized>(
+        points: &S,
+    ) -> Option<Self> {
+        TriangulationBuilder::new().build_from_source(points)
+    }
+
+    /// Triangulate points given as interleaved coordinates `[x0, y0, x1, y1, ...]`,
+    /// the layout used by the JavaScript Delaunator and many binary formats,
+    /// without copying them into [Point]s.
+    /// Returns `None` if no triangulation exists for the input.
+    ///
+    /// # Panics
+    /// Panics if `coords` has an odd length.
+    pub fn from_flat_coords<T: Scalar + ApproxEq>(coords: &[T]) -> Option<Self> {
+        Triangulation::from_source(&FlatPoints::new(coords))
+    }
+
+    /// Triangulate the points produced by an iterator, e.g. a parser or a
+    /// generator, returning the triangulation together with the collected points
+    /// its indices refer to.
+    /// Returns `None` if no triangulation exists for the input.
+    ///
+    /// Construction sorts all points before inserting any, so they are
+    /// collected up front; an iterator with an exact size hint is collected
+    /// without reallocating.
+    pub fn from_iter<T, P, It>(points: It) -> Option<(Self, Vec<Point<T>>)>
+    where
+        T: Scalar + ApproxEq,
+        P: HasPosition<T>,
+        It: IntoIterator<Item = P>,
+    {
+        let points: Vec<Point<T>> = points.into_iter().map(|p| p.pos()).collect();
+        let triangulation = Triangulation::new(&points)?;
+        Some((triangulation, points))
+    }
+
+    /// Triangulate points that are already sorted for insertion, skipping the
+    /// sort that dominates construction time for large inputs.
+    /// See [TriangulationBuilder::build_with_sorted_points].
+    pub fn with_sorted_points<T: Scalar + ApproxEq, P: HasPosition<T>>(
+        points: &[P],
+    ) -> Option<Self> {
+        TriangulationBuilder::new().build_with_sorted_points(points)
     }
 
     pub fn with_seed_triangle<T: Scalar + ApproxEq, P: HasPosition<T>>(
         points: &[P],
         seed_triangle: (usize, usize, usize),
     ) -> Self {
+        TriangulationBuilder::new().build_with_seed_triangle(points, seed_triangle)
+    }
+
+    /// Triangulate a set of 2D points, calling `visit` with the vertices of
+    /// each triangle as soon as it is final.
+    ///
+    /// Triangles are reported while construction is still running, once no
+    /// point that is yet to be inserted can affect them, so they can be
+    /// processed or written out in a pipelined fashion. Every triangle is
+    /// reported exactly once, with its vertices in the same order as in
+    /// `triangles`. Returns `None` (and reports nothing) if no triangulation
+    /// exists for the input.
+    pub fn with_visitor<T: Scalar + ApproxEq, P: HasPosition<T>, F: FnMut([usize; 3])>(
+        points: &[P],
+        visit: F,
+    ) -> Option<Self> {
+        TriangulationBuilder::new().build_with_visitor(points, visit)
+    }
+
+    pub(crate) fn construct<T, K, S, O>(
+        options: &TriangulationBuilder<K>,
+        points: &S,
+        seed: Seed,
+        observer: &mut O,
+        scratch: &mut Scratch<T, I>,
+    ) -> Option<Self>
+    where
+        T: Scalar + ApproxEq,
+        K: Kernel<T>,
+        S: PointSource<T> + ?Sized,
+        O: Observer<T, I>,
+    {
+        Self::construct_with(options, points, seed, observer, scratch, false)
+    }
+
+    /// Triangulate a set of 2D points, returning only the triangles. The
+    /// half-edges that construction needs are kept in `scratch` for reuse, and
+    /// the hull is not collected.
+    pub(crate) fn construct_triangles<T, K, S>(
+        options: &TriangulationBuilder<K>,
+        points: &S,
+        scratch: &mut Scratch<T, I>,
+    ) -> Option<Vec<I>>
+    where
+        T: Scalar + ApproxEq,
+        K: Kernel<T>,
+        S: PointSource<T> + ?Sized,
+    {
+        let mut triangulation =
+            Self::construct_with(options, points, Seed::Find, &mut (), scratch, true)?;
+        scratch.halfedges = std::mem::take(&mut triangulation.halfedges);
+        Some(triangulation.triangles)
+    }
+
+    fn construct_with<T, K, S, O>(
+        options: &TriangulationBuilder<K>,
+        points: &S,
+        seed: Seed,
+        observer: &mut O,
+        scratch: &mut Scratch<T, I>,
+        triangles_only: bool,
+    ) -> Option<Self>
+    where
+        T: Scalar + ApproxEq,
+        K: Kernel<T>,
+        S: PointSource<T> + ?Sized,
+        O: Observer<T, I>,
+    {
         let n = points.len();
-        let (i0, i1, i2) = seed_triangle;
-        let center = points[i0]
-            .pos()
-            .circumcenter(points[i1].pos(), points[i2].pos());
+        // every point, triangle and half-edge id must fit in `I` below the
+        // `None` sentinel, which makes the `I::from_usize` calls below infallible
+        I::try_from_usize(Self::estimate_sizes(n).halfedges)?;
+        observer.phase(Phase::Dedup);
+        let duplicates = options
+            .dedup
+            .map(|epsilon| util::find_duplicates(points, epsilon));
+        let keep = |i: usize| duplicates.as_ref().map_or(true, |d| !d[i]);
+        for i in (0..n).filter(|&i| !keep(i)) {
+            observer.status(i, PointStatus::Duplicate);
+        }
 
-        let mut triangulation = Triangulation::<I>::alloc(n);
-        triangulation.add_triangle(i0, i1, i2, None.into(), None.into(), None.into());
+        observer.phase(Phase::Seed);
+        let (i0, i1, i2) = match seed {
+            Seed::Find => util::find_seed_triangle::<T, K, S>(points, keep)?,
+            Seed::Triangle(i0, i1, i2) => (i0, i1, i2),
+            Seed::Sorted => {
+                if n < 3 {
+                    return None;
+                }
+                let [p0, p1, p2] = [0, 1, 2].map(|i| points.point(i));
+                if K::is_clockwise(p0, p1, p2) {
+                    (0, 2, 1)
+                } else if K::is_clockwise(p0, p2, p1) {
+                    (0, 1, 2)
+                } else {
+                    return None;
+                }
+            }
+        };
+        let center = K::circumcenter(points.point(i0), points.point(i1), points.point(i2));
+
+        let halfedges = if triangles_only {
+            std::mem::take(&mut scratch.halfedges)
+        } else {
+            Vec::new()
+        };
+        let mut triangulation = Triangulation::<I>::alloc(n, options.hull_only, halfedges);
+        observer.start((i0, i1, i2), center);
+        if !options.hull_only {
+            triangulation.add_triangle(i0, i1, i2, None.into(), None.into(), None.into());
+            observer.added(0, [i0, i1, i2]);
+            observer.changed(0);
+        }
+
+        let Scratch {
+            hull, dists, stack, ..
+        } = scratch;
 
         // sort the points by distance from the seed triangle circumcenter
-        let mut dists: Vec<_> = points
-            .iter()
-            .enumerate()
-            .map(|(i, point)| (i, center.distance_squared(point.pos())))
-            .collect();
+        observer.phase(Phase::Sort);
+        dists.clear();
+        dists.extend(
+            (0..n)
+                .filter(|&i| keep(i))
+                .map(|i| (i, center.distance_squared(points.point(i)))),
+        );
 
-        dists.sort_unstable_by(|&(_, da), &(_, db)| da.partial_cmp(&db).unwrap());
+        match (seed, options.tie_break) {
+            (Seed::Sorted, _) => {} // already in order
+            (_, TieBreak::None) => {
+                dists.sort_unstable_by(|&(_, da), &(_, db)| da.partial_cmp(&db).unwrap())
+            }
+            (_, TieBreak::LowestIndex) => dists.sort_unstable_by(|&(ia, da), &(ib, db)| {
+                da.partial_cmp(&db).unwrap().then(ia.cmp(&ib))
+            }),
+        }
 
-        let mut hull = Hull::new(n, center, i0, i1, i2, points);
+        hull.init(n, center, i0, i1, i2, points);
+        observer.phase(Phase::Insert);
 
-        for (k, &(i, _)) in dists.iter().enumerate() {
-            let p = points[i].pos();
+        for (k, &(i, dist)) in dists.iter().enumerate() {
+            let p = points.point(i);
+            observer.insert(&triangulation, points, i, dist);
 
-            // skip near-duplicates
-            if k > 0 && p.nearly_equals(points[dists[k - 1].0].pos()) {
-                continue;
-            }
             // skip seed triangle points
             if i == i0 || i == i1 || i == i2 {
+                observer.status(i, PointStatus::Seed);
+                continue;
+            }
+            // skip near-duplicates
+            if k > 0 && p.nearly_equals(points.point(dists[k - 1].0)) {
+                observer.status(i, PointStatus::Duplicate);
                 continue;
             }
 
             // find a visible edge on the convex hull using edge hash
-            let (e, walk_back) = hull.find_visible_edge(p, points);
+            let (e, walk_back) = hull.find_visible_edge::<K, S, O>(p, points, observer);
             let mut e = match e {
-                None => continue, // likely a near-duplicate point; skip it
+                None => {
+                    // likely a near-duplicate point; skip it
+                    observer.status(i, PointStatus::NotVisible);
+                    continue;
+                }
                 Some(e) => e,
             };
+            observer.status(i, PointStatus::Inserted);
+
+            if options.hull_only {
+                // only remove the hull points that the new point hides
+                let mut n = hull.next[e].unwrap();
+                loop {
+                    let q = hull.next[n].unwrap();
+                    if !K::is_clockwise(p, points.point(n), points.point(q)) {
+                        break;
+                    }
+                    hull.next[n] = OptionIndex::none(); // mark as removed
+                    n = q;
+                }
+                if walk_back {
+                    loop {
+                        let q = hull.prev[e].unwrap();
+                        if !K::is_clockwise(p, points.point(q), points.point(e)) {
+                            break;
+                        }
+                        hull.next[e] = OptionIndex::none(); // mark as removed
+                        e = q;
+                    }
+                }
+                hull.prev[i] = e.into();
+                hull.next[i] = n.into();
+                hull.prev[n] = i.into();
+                hull.next[e] = i.into();
+                hull.start = e;
+                hull.hash_edge(p, i);
+                hull.hash_edge(points.point(e), e);
+                observer.hull(e, i, n);
+                continue;
+            }
 
             // add the first triangle from the point
             let t = triangulation.add_triangle(
@@ -108,21 +441,40 @@ impl<I: Index> Triangulation<I> {
                 None.into(),
                 hull.tri[e],
             );
+            observer.added(t / 3, [e, i, hull.next[e].unwrap()]);
+            observer.changed(t / 3);
 
             // recursively flip triangles from the point until they satisfy the Delaunay condition
-            hull.tri[i] = I::from_usize(triangulation.legalize(t + 2, points, &mut hull)).into();
+            hull.tri[i] = I::from_usize(triangulation.legalize::<T, K, S, O>(
+                t + 2,
+                points,
+                hull,
+                stack,
+                options.tie_break,
+                observer,
+            ))
+            .into();
             hull.tri[e] = I::from_usize(t).into(); // keep track of boundary triangles on the hull
 
             // walk forward through the hull, adding more triangles and flipping recursively
             let mut n = hull.next[e].unwrap();
             loop {
                 let q = hull.next[n].unwrap();
-                if !p.is_clockwise(points[n].pos(), points[q].pos()) {
+                if !K::is_clockwise(p, points.point(n), points.point(q)) {
                     break;
                 }
                 let t = triangulation.add_triangle(n, i, q, hull.tri[i], None.into(), hull.tri[n]);
-                hull.tri[i] =
-                    I::from_usize(triangulation.legalize(t + 2, points, &mut hull)).into();
+                observer.added(t / 3, [n, i, q]);
+                observer.changed(t / 3);
+                hull.tri[i] = I::from_usize(triangulation.legalize::<T, K, S, O>(
+                    t + 2,
+                    points,
+                    hull,
+                    stack,
+                    options.tie_break,
+                    observer,
+                ))
+                .into();
                 hull.next[n] = OptionIndex::none(); // mark as removed
                 n = q;
             }
@@ -131,12 +483,21 @@ impl<I: Index> Triangulation<I> {
             if walk_back {
                 loop {
                     let q = hull.prev[e].unwrap();
-                    if !p.is_clockwise(points[q].pos(), points[e].pos()) {
+                    if !K::is_clockwise(p, points.point(q), points.point(e)) {
                         break;
                     }
                     let t =
                         triangulation.add_triangle(q, i, e, None.into(), hull.tri[e], hull.tri[q]);
-                    triangulation.legalize(t + 2, points, &mut hull);
+                    observer.added(t / 3, [q, i, e]);
+                    observer.changed(t / 3);
+                    triangulation.legalize::<T, K, S, O>(
+                        t + 2,
+                        points,
+                        hull,
+                        stack,
+                        options.tie_break,
+                        observer,
+                    );
                     hull.tri[q] = I::from_usize(t).into();
                     hull.next[e] = OptionIndex::none(); // mark as removed
                     e = q;
@@ -152,7 +513,14 @@ impl<I: Index> Triangulation<I> {
 
             // save the two new edges in the hash table
             hull.hash_edge(p, i);
-            hull.hash_edge(points[e].pos(), e);
+            hull.hash_edge(points.point(e), e);
+            observer.hull(e, i, n);
+        }
+
+        observer.phase(Phase::Finish);
+        if triangles_only {
+            triangulation.triangles.shrink_to_fit();
+            return Some(triangulation);
         }
 
         // expose hull as a vector of point indices
@@ -165,21 +533,27 @@ impl<I: Index> Triangulation<I> {
             }
         }
 
+        if !options.collinear_hull_points {
+            let h = &triangulation.hull;
+            let len = h.len();
+            let corner = |k: usize| {
+                let [p, q, r] = [h[(k + len - 1) % len], h[k], h[(k + 1) % len]]
+                    .map(|i| points.point(i.as_usize()));
+                K::is_clockwise(p, q, r) || K::is_clockwise(p, r, q)
+            };
+            let corners: Vec<I> = (0..len).filter(|&k| corner(k)).map(|k| h[k]).collect();
+            triangulation.hull = corners;
+        }
+
         triangulation.triangles.shrink_to_fit();
         triangulation.halfedges.shrink_to_fit();
 
         #[cfg(feature = "vertices")]
-        {
-            triangulation.vertices.resize(n, I::max_value());
-            for (i, &j) in triangulation.triangles.iter().enumerate() {
-                let j = j.as_usize();
-                if triangulation.vertices[j] == I::max_value() {
-                    triangulation.vertices[j] = I::from_usize(i);
-                }
-            }
-        }
+        triangulation.update_vertices(n);
+
+        observer.finish(&triangulation, points);
 
-        triangulation
+        Some(triangulation)
     }
 
     /// The number of triangles in the triangulation.
@@ -191,67 +565,522 @@ impl<I: Index> Triangulation<I> {
         self.len() == 0
     }
 
-    pub fn triangles(&self) -> TriangleIter<'_, I> {
-        TriangleIter {
-            triangulation: self,
-            index: 0,
-            end: self.triangles.len(),
+    /// The circumcenter of every triangle, indexed by triangle id.
+    pub fn circumcenters<T: Scalar, P: HasPosition<T>>(&self, points: &[P]) -> Vec<Point<T>> {
+        self.triangles
+            .chunks_exact(3)
+            .map(|t| triangle_circumcenter(points, t))
+            .collect()
+    }
+
+    /// The circumcenter of every triangle, indexed by triangle id, computed in parallel.
+    #[cfg(feature = "rayon")]
+    pub fn par_circumcenters<T, P>(&self, points: &[P]) -> Vec<Point<T>>
+    where
+        I: Sync,
+        T: Scalar + Send,
+        P: HasPosition<T> + Sync,
+    {
+        use rayon::prelude::*;
+
+        self.triangles
+            .par_chunks_exact(3)
+            .map(|t| triangle_circumcenter(points, t))
+            .collect()
+    }
+
+    /// Every edge of the triangulation once, as a half-edge id: the half-edge
+    /// with the higher id of an interior edge, and the half-edge of a hull edge.
+    pub fn unique_edges(&self) -> impl Iterator<Item = usize> + '_ {
+        self.halfedges
+            .iter()
+            .enumerate()
+            .filter(|&(e, twin)| twin.get().map_or(true, |twin| e > twin.as_usize()))
+            .map(|(e, _)| e)
+    }
+
+    /// A line-list index buffer with every edge of the triangulation once, as
+    /// pairs of point indices, for rendering a wireframe.
+    ///
+    /// # Panics
+    /// Panics if a point index doesn't fit in `J`.
+    pub fn wireframe_indices<J: Index>(&self) -> Vec<J> {
+        self.wireframe_indices_of(WireframeEdges::All)
+    }
+
+    /// Like [wireframe_indices](Self::wireframe_indices), but only with the
+    /// selected `edges`.
+    pub fn wireframe_indices_of<J: Index>(&self, edges: WireframeEdges) -> Vec<J> {
+        let keep = |e: usize| match edges {
+            WireframeEdges::All => true,
+            WireframeEdges::Interior => self.halfedges[e].is_some(),
+            WireframeEdges::Hull => self.halfedges[e].is_none(),
+        };
+        let mut indices = Vec::new();
+        for e in self.unique_edges().filter(|&e| keep(e)) {
+            let (a, b) = (self.triangles[e], self.triangles[util::next_halfedge(e)]);
+            indices.extend([J::from_usize(a.as_usize()), J::from_usize(b.as_usize())]);
         }
+        indices
     }
 
-    pub fn half_edges(&self) -> HalfEdgeIter<'_, I> {
-        HalfEdgeIter {
-            triangulation: self,
-            index: 0,
-            end: self.halfedges.len(),
+    /// The nonzero pattern of a matrix coupling every point with itself and its
+    /// neighbors, such as an FEM stiffness matrix, in compressed sparse row form.
+    ///
+    /// There is a row for every point up to the highest index in the
+    /// triangulation; points that aren't part of it only couple with themselves.
+    pub fn sparsity_pattern(&self) -> SparsityPattern {
+        let n = self
+            .triangles
+            .iter()
+            .map(|&i| i.as_usize() + 1)
+            .max()
+            .unwrap_or(0);
+        let neighbors = self.adjacency(n);
+
+        let mut pattern = SparsityPattern {
+            offsets: Vec::with_capacity(n + 1),
+            columns: Vec::with_capacity(n + self.halfedges.len() + self.hull.len()),
+        };
+        pattern.offsets.push(0);
+        for (i, mut row) in neighbors.into_iter().enumerate() {
+            row.push(i);
+            row.sort_unstable();
+            pattern.columns.extend(row);
+            pattern.offsets.push(pattern.columns.len());
         }
+        pattern
     }
 
-    #[cfg(feature = "vertices")]
-    pub fn vertices(&self) -> VertexIter<'_, I> {
-        VertexIter {
-            triangulation: self,
-            index: 0,
-            end: self.vertices.len(),
+    /// The neighbors of each of the first `n` points, which must include every
+    /// point of the triangulation, in no particular order.
+    pub(crate) fn adjacency(&self, n: usize) -> Vec<Vec<usize>> {
+        let mut neighbors = vec![Vec::new(); n];
+        for (e, twin) in self.halfedges.iter().enumerate() {
+            let a = self.triangles[e].as_usize();
+            let b = self.triangles[util::next_halfedge(e)].as_usize();
+            neighbors[a].push(b);
+            // hull edges have no twin to add the reverse direction
+            if twin.is_none() {
+                neighbors[b].push(a);
+            }
         }
+        neighbors
     }
 
-    #[cfg(feature = "vertices")]
-    pub fn get_vertex(&self, id: usize) -> Option<Vertex<'_, I>> {
-        if id < self.vertices.len() {
-            Some(Vertex {
-                triangulation: self,
-                index: self.vertices[id].as_usize(),
+    /// The links between consecutive points of `hull` and the boundary
+    /// half-edge at each of them, like `hullNext`, `hullPrev` and `hullTri` in
+    /// the JavaScript Delaunator. Following them from any hull point takes
+    /// constant time, where `hull` needs a search for the point first.
+    ///
+    /// The arrays have a slot for every point up to the highest index in the
+    /// triangulation.
+    pub fn hull_links(&self) -> HullLinks<I> {
+        let n = self
+            .triangles
+            .iter()
+            .chain(&self.hull)
+            .map(|&i| i.as_usize() + 1)
+            .max()
+            .unwrap_or(0);
+        let mut links = HullLinks {
+            next: vec![OptionIndex::none(); n],
+            prev: vec![OptionIndex::none(); n],
+            tri: vec![OptionIndex::none(); n],
+        };
+        let len = self.hull.len();
+        for k in 0..len {
+            let (a, b) = (self.hull[k], self.hull[(k + 1) % len]);
+            links.next[a.as_usize()] = OptionIndex::some(b);
+            links.prev[b.as_usize()] = OptionIndex::some(a);
+        }
+        for (e, twin) in self.halfedges.iter().enumerate() {
+            if twin.is_none() {
+                links.tri[self.triangles[e].as_usize()] = OptionIndex::some(I::from_usize(e));
+            }
+        }
+        links
+    }
+
+    /// A [GraphViz](https://graphviz.org) DOT graph of the half-edge structure,
+    /// for debugging topology.
+    ///
+    /// Every triangle is a record node with a field per half-edge, labeled with
+    /// the half-edge id and the points it connects. Twins are linked by an edge
+    /// without arrows, and a twin link that doesn't point back or points past
+    /// the end of `halfedges` is drawn as a red arrow. Half-edges on the hull
+    /// are marked with `hull`.
+    pub fn to_dot(&self) -> String {
+        self.dot((0..self.len()).collect())
+    }
+
+    /// Like [to_dot](Self::to_dot), but only with the triangles at most `depth`
+    /// steps across edges away from `triangle`. Triangles just outside the
+    /// neighborhood are drawn as dashed nodes.
+    ///
+    /// # Panics
+    /// Panics if `triangle` is out of bounds.
+    pub fn to_dot_around(&self, triangle: usize, depth: usize) -> String {
+        assert!(triangle < self.len(), "triangle {} out of bounds", triangle);
+        let mut seen = vec![false; self.len()];
+        seen[triangle] = true;
+        let mut ring = vec![triangle];
+        let mut triangles = ring.clone();
+        for _ in 0..depth {
+            let mut next = Vec::new();
+            for t in ring {
+                for e in 3 * t..3 * t + 3 {
+                    if let Some(twin) = self.halfedges[e].get() {
+                        let u = twin.as_usize() / 3;
+                        if u < self.len() && !seen[u] {
+                            seen[u] = true;
+                            next.push(u);
+                        }
+                    }
+                }
+            }
+            triangles.extend_from_slice(&next);
+            ring = next;
+        }
+        triangles.sort_unstable();
+        self.dot(triangles)
+    }
+
+    fn dot(&self, triangles: Vec<usize>) -> String {
+        use std::fmt::Write;
+
+        let mut included = vec![false; self.len()];
+        for &t in &triangles {
+            included[t] = true;
+        }
+        let mut out = String::from("digraph triangulation {\n    node [shape=record];\n");
+        let mut outside = Vec::new();
+        for &t in &triangles {
+            let fields: Vec<String> = (3 * t..3 * t + 3)
+                .map(|e| {
+                    let (a, b) = (self.triangles[e], self.triangles[util::next_halfedge(e)]);
+                    let hull = if self.halfedges[e].is_none() {
+                        " hull"
+                    } else {
+                        ""
+                    };
+                    format!(
+                        "<e{}> {}: {} -\\> {}{}",
+                        e,
+                        e,
+                        a.as_usize(),
+                        b.as_usize(),
+                        hull
+                    )
+                })
+                .collect();
+            writeln!(out, "    t{} [label=\"t{}|{}\"];", t, t, fields.join("|")).unwrap();
+        }
+        for &t in &triangles {
+            for e in 3 * t..3 * t + 3 {
+                let twin = match self.halfedges[e].get() {
+                    Some(twin) => twin.as_usize(),
+                    None => continue,
+                };
+                if twin >= self.halfedges.len() {
+                    writeln!(out, "    t{}:e{} -> \"{}?\" [color=red];", t, e, twin).unwrap();
+                    continue;
+                }
+                let back = self.halfedges[twin] == OptionIndex::some(I::from_usize(e));
+                let (u, style) = (twin / 3, if back { "dir=none" } else { "color=red" });
+                if !included[u] {
+                    outside.push(u);
+                    writeln!(out, "    t{}:e{} -> t{} [{}];", t, e, u, style).unwrap();
+                } else if !back || e < twin {
+                    writeln!(out, "    t{}:e{} -> t{}:e{} [{}];", t, e, u, twin, style).unwrap();
+                }
+            }
+        }
+        outside.sort_unstable();
+        outside.dedup();
+        for u in outside {
+            writeln!(out, "    t{} [shape=ellipse, style=dashed];", u).unwrap();
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// Rewrites the triangulation in a canonical form that doesn't depend on the
+    /// order in which triangles were created.
+    ///
+    /// Each triangle is rotated so that its smallest point index comes first,
+    /// triangles are sorted lexicographically and `halfedges` is renumbered to
+    /// match. `hull` is rotated to start at its smallest point index.
+    pub fn normalize(&mut self) {
+        let len = self.len();
+        let rotation: Vec<usize> = self
+            .triangles
+            .chunks_exact(3)
+            .map(|t| {
+                let (a, b, c) = (t[0].as_usize(), t[1].as_usize(), t[2].as_usize());
+                if a < b && a < c {
+                    0
+                } else if b < c {
+                    1
+                } else {
+                    2
+                }
             })
-        } else {
-            None
+            .collect();
+
+        let key = |t: usize| {
+            let i = |k: usize| self.triangles[3 * t + (rotation[t] + k) % 3].as_usize();
+            (i(0), i(1), i(2))
+        };
+        let mut order: Vec<usize> = (0..len).collect();
+        order.sort_unstable_by_key(|&t| key(t));
+
+        let mut position = vec![0; len];
+        for (new, &old) in order.iter().enumerate() {
+            position[old] = new;
         }
+        let new_halfedge = |e: usize| {
+            let t = e / 3;
+            3 * position[t] + (e % 3 + 3 - rotation[t]) % 3
+        };
+
+        let mut triangles = vec![I::from_usize(0); self.triangles.len()];
+        let mut halfedges = vec![OptionIndex::none(); self.halfedges.len()];
+        for e in 0..self.triangles.len() {
+            let f = new_halfedge(e);
+            triangles[f] = self.triangles[e];
+            halfedges[f] = self.halfedges[e]
+                .get()
+                .map(|h| I::from_usize(new_halfedge(h.as_usize())))
+                .into();
+        }
+        self.triangles = triangles;
+        self.halfedges = halfedges;
+
+        if let Some(start) = (0..self.hull.len()).min_by_key(|&i| self.hull[i].as_usize()) {
+            self.hull.rotate_left(start);
+        }
+
+        #[cfg(feature = "vertices")]
+        self.update_vertices(self.vertices.len());
     }
 
-    pub fn get_triangle(&self, id: usize) -> Option<Triangle<'_, I>> {
-        let index = 3 * id;
-        if index < self.triangles.len() {
-            Some(Triangle {
-                triangulation: self,
+    /// Renumbers the points densely, dropping points that aren't part of any
+    /// triangle (e.g. duplicates).
+    ///
+    /// Returns the compacted triangulation and the mapping from old to new point
+    /// indices, where dropped points map to `I::max_value()`. Kept points retain
+    /// their relative order, so the compacted points can be gathered with:
+    ///
+    /// ```rust
+    /// # use delaunator::{Point, Triangulation};
+    /// let points = vec![
+    ///     Point { x: 0., y: 0. },
+    ///     Point { x: 1., y: 0. },
+    ///     Point { x: 0., y: 0. },
+    ///     Point { x: 0., y: 1. },
+    /// ];
+    /// let triangulation = Triangulation::<u32>::new(&points).unwrap();
+    /// let (compacted, mapping) = triangulation.compact(&points);
+    /// let points: Vec<_> = points
+    ///     .iter()
+    ///     .zip(&mapping)
+    ///     .filter(|(_, &i)| i != u32::MAX)
+    ///     .map(|(p, _)| *p)
+    ///     .collect();
+    /// assert_eq!(points.len(), 3);
+    /// ```
+    pub fn compact<T: Scalar, P: HasPosition<T>>(&self, points: &[P]) -> (Self, Vec<I>) {
+        let mut mapping = vec![I::max_value(); points.len()];
+        for &i in &self.triangles {
+            mapping[i.as_usize()] = I::from_usize(0);
+        }
+        let mut n = 0;
+        for i in mapping.iter_mut() {
+            if *i != I::max_value() {
+                *i = I::from_usize(n);
+                n += 1;
+            }
+        }
+
+        let remap = |i: &I| mapping[i.as_usize()];
+        #[allow(unused_mut)]
+        let mut triangulation = Triangulation {
+            #[cfg(feature = "vertices")]
+            vertices: Vec::new(),
+            triangles: self.triangles.iter().map(remap).collect(),
+            halfedges: self.halfedges.clone(),
+            hull: self.hull.iter().map(remap).collect(),
+        };
+
+        #[cfg(feature = "vertices")]
+        triangulation.update_vertices(n);
+
+        (triangulation, mapping)
+    }
+
+    /// Triangulates the points `points[i]` for every `i` in `subset`, with point
+    /// indices referring to `points`.
+    pub(crate) fn from_subset<T: Scalar + ApproxEq, P: HasPosition<T>>(
+        points: &[P],
+        subset: &[usize],
+    ) -> Option<Self> {
+        let local: Vec<Point<T>> = subset.iter().map(|&i| points[i].pos()).collect();
+        let mut triangulation = Triangulation::<I>::new(&local)?;
+        let remap = |i: &mut I| *i = I::from_usize(subset[i.as_usize()]);
+        triangulation.triangles.iter_mut().for_each(remap);
+        triangulation.hull.iter_mut().for_each(remap);
+
+        #[cfg(feature = "vertices")]
+        triangulation.update_vertices(points.len());
+
+        Some(triangulation)
+    }
+
+    /// Tests if every interior edge satisfies the local Delaunay condition, i.e.
+    /// no triangle's circumcircle contains the far point of its neighbor.
+    pub fn is_delaunay<T: Scalar, P: HasPosition<T>>(&self, points: &[P]) -> bool {
+        self.find_illegal_edge(points).is_none()
+    }
+
+    /// Finds the first half-edge that violates the local Delaunay condition.
+    /// Returns `None` if the triangulation is Delaunay.
+    pub fn find_illegal_edge<T: Scalar, P: HasPosition<T>>(
+        &self,
+        points: &[P],
+    ) -> Option<HalfEdge<'_, I>> {
+        (0..self.halfedges.len())
+            .find(|&a| {
+                self.halfedges[a]
+                    .get()
+                    .map_or(false, |b| a < b.as_usize() && self.is_illegal(a, points))
+            })
+            .map(|index| HalfEdge {
+                triangulation: self.view(),
                 index,
             })
-        } else {
-            None
+    }
+
+    /// Tests if the point opposite to half-edge `a` in the adjacent triangle lies
+    /// inside the circumcircle of the triangle to the left of `a`.
+    ///
+    /// `a` must not be on the convex hull.
+    pub(crate) fn is_illegal<T: Scalar, P: HasPosition<T>>(&self, a: usize, points: &[P]) -> bool {
+        let b = self.halfedges[a].unwrap().as_usize();
+
+        let p0 = self.triangles[util::prev_halfedge(a)].as_usize();
+        let pr = self.triangles[a].as_usize();
+        let pl = self.triangles[util::next_halfedge(a)].as_usize();
+        let p1 = self.triangles[util::prev_halfedge(b)].as_usize();
+
+        points[p1]
+            .pos()
+            .is_in_circle(points[p0].pos(), points[pr].pos(), points[pl].pos())
+    }
+
+    /// Flips edges to reduce the total edge length, approximating the
+    /// minimum-weight triangulation. Returns the number of flips performed.
+    ///
+    /// See [minimize_edge_cost](Self::minimize_edge_cost).
+    pub fn minimize_edge_length<T: Scalar, P: HasPosition<T>>(&mut self, points: &[P]) -> usize {
+        self.minimize_edge_cost(points, |a, b| a.distance(b))
+    }
+
+    /// Flips edges to reduce the total `cost` of all edges, given the positions
+    /// of their endpoints. Returns the number of flips performed.
+    ///
+    /// Any interior edge whose flip yields a cheaper diagonal of its convex
+    /// quadrilateral is flipped, until no such edge remains. The result is a
+    /// local optimum: usually close to, but not always, the cheapest
+    /// triangulation of the points. It is generally no longer Delaunay.
+    pub fn minimize_edge_cost<T: Scalar, P: HasPosition<T>, F: FnMut(Point<T>, Point<T>) -> T>(
+        &mut self,
+        points: &[P],
+        mut cost: F,
+    ) -> usize {
+        let mut stack: Vec<usize> = (0..self.halfedges.len())
+            .filter(|&a| self.halfedges[a].get().map_or(false, |b| a < b.as_usize()))
+            .collect();
+        let mut flips = 0;
+
+        while let Some(a) = stack.pop() {
+            let b = match self.halfedges[a].get() {
+                None => continue,
+                Some(b) => b.as_usize(),
+            };
+
+            let pos = |i: usize| points[self.triangles[i].as_usize()].pos();
+            let p0 = pos(util::prev_halfedge(a));
+            let pr = pos(a);
+            let pl = pos(b);
+            let p1 = pos(util::prev_halfedge(b));
+            // only flip if both new triangles are counter-clockwise
+            if !p0.is_clockwise(pl, p1) || !p1.is_clockwise(pr, p0) {
+                continue;
+            }
+            if cost(p0, p1) >= cost(pr, pl) {
+                continue;
+            }
+
+            self.flip(a);
+            flips += 1;
+
+            // the outer edges of the quadrilateral may now be improvable
+            stack.push(a);
+            stack.push(util::next_halfedge(a));
+            stack.push(b);
+            stack.push(util::next_halfedge(b));
+        }
+
+        #[cfg(feature = "vertices")]
+        self.update_vertices(points.len());
+
+        flips
+    }
+
+    /// Borrows the triangulation as a [TriangulationRef].
+    pub fn view(&self) -> TriangulationRef<'_, I> {
+        TriangulationRef::from(self)
+    }
+
+    pub fn triangles(&self) -> TriangleIter<'_, I> {
+        self.view().triangles()
+    }
+
+    pub fn half_edges(&self) -> HalfEdgeIter<'_, I> {
+        self.view().half_edges()
+    }
+
+    /// The number of half-edges that start at each of `n` points, which is also
+    /// the number of triangles around it, for [Vertex::exact_edges](crate::Vertex::exact_edges)
+    /// and [Vertex::exact_triangles](crate::Vertex::exact_triangles).
+    pub fn degrees(&self, n: usize) -> Vec<usize> {
+        let mut degrees = vec![0; n];
+        for &i in &self.triangles {
+            degrees[i.as_usize()] += 1;
         }
+        degrees
+    }
+
+    #[cfg(feature = "vertices")]
+    pub fn vertices(&self) -> VertexIter<'_, I> {
+        self.view().vertices()
+    }
+
+    #[cfg(feature = "vertices")]
+    pub fn get_vertex(&self, id: usize) -> Option<Vertex<'_, I>> {
+        self.view().get_vertex(id)
+    }
+
+    pub fn get_triangle(&self, id: usize) -> Option<Triangle<'_, I>> {
+        self.view().get_triangle(id)
     }
 
     pub fn get_half_edge(&self, id: usize) -> Option<HalfEdge<'_, I>> {
-        if id < self.halfedges.len() {
-            Some(HalfEdge {
-                triangulation: self,
-                index: id,
-            })
-        } else {
-            None
-        }
+        self.view().get_half_edge(id)
     }
 
-    fn add_triangle(
+    pub(crate) fn add_triangle(
         &mut self,
         i0: usize,
         i1: usize,
@@ -283,77 +1112,184 @@ impl<I: Index> Triangulation<I> {
         t
     }
 
-    fn legalize<T: Scalar, P: HasPosition<T>>(
+    fn legalize<T: Scalar, K: Kernel<T>, S: PointSource<T> + ?Sized, O: Observer<T, I>>(
         &mut self,
         a: usize,
-        points: &[P],
+        points: &S,
         hull: &mut Hull<T, I>,
+        stack: &mut Vec<usize>,
+        tie_break: TieBreak,
+        observer: &mut O,
     ) -> usize {
-        let b = self.halfedges[a];
-
-        // if the pair of triangles doesn't satisfy the Delaunay condition
-        // (p1 is inside the circumcircle of [p0, pl, pr]), flip them,
-        // then do the same check/flip recursively for the new pair of triangles
-        //
-        //           pl                    pl
-        //          /||\                  /  \
-        //       al/ || \bl            al/    \a
-        //        /  ||  \              /      \
-        //       /  a||b  \    flip    /___ar___\
-        //     p0\   ||   /p1   =>   p0\---bl---/p1
-        //        \  ||  /              \      /
-        //       ar\ || /br             b\    /br
-        //          \||/                  \  /
-        //           pr                    pr
-        //
-        let ar = util::prev_halfedge(a);
+        stack.clear();
+        let mut a = a;
+        loop {
+            let b = self.halfedges[a];
 
-        let b = match b.get() {
-            None => return ar,
-            Some(b) => b.as_usize(),
-        };
+            // if the pair of triangles doesn't satisfy the Delaunay condition
+            // (p1 is inside the circumcircle of [p0, pl, pr]), flip them,
+            // then do the same check/flip for the new pair of triangles
+            //
+            //           pl                    pl
+            //          /||\                  /  \
+            //       al/ || \bl            al/    \a
+            //        /  ||  \              /      \
+            //       /  a||b  \    flip    /___ar___\
+            //     p0\   ||   /p1   =>   p0\---bl---/p1
+            //        \  ||  /              \      /
+            //       ar\ || /br             b\    /br
+            //          \||/                  \  /
+            //           pr                    pr
+            //
+            let ar = util::prev_halfedge(a);
 
-        let al = util::next_halfedge(a);
-        let bl = util::prev_halfedge(b);
+            let b = match b.get() {
+                Some(b) => b.as_usize(),
+                None => match stack.pop() {
+                    None => return ar,
+                    Some(next) => {
+                        a = next;
+                        continue;
+                    }
+                },
+            };
 
-        let p0 = self.triangles[ar].as_usize();
-        let pr = self.triangles[a].as_usize();
-        let pl = self.triangles[al].as_usize();
-        let p1 = self.triangles[bl].as_usize();
-
-        let illegal =
-            points[p1]
-                .pos()
-                .is_in_circle(points[p0].pos(), points[pr].pos(), points[pl].pos());
-        if illegal {
-            self.triangles[a] = I::from_usize(p1);
-            self.triangles[b] = I::from_usize(p0);
-
-            let hbl = self.halfedges[bl];
-            let har = self.halfedges[ar];
-
-            // edge swapped on the other side of the hull (rare); fix the halfedge reference
-            if hbl.is_none() {
-                hull.swap_halfedge(I::from_usize(bl), I::from_usize(a));
+            let al = util::next_halfedge(a);
+            let bl = util::prev_halfedge(b);
+
+            let p0 = self.triangles[ar].as_usize();
+            let pr = self.triangles[a].as_usize();
+            let pl = self.triangles[al].as_usize();
+            let p1 = self.triangles[bl].as_usize();
+
+            let side = K::in_circle(
+                points.point(p1),
+                points.point(p0),
+                points.point(pr),
+                points.point(pl),
+            );
+            if side == Ordering::Equal {
+                observer.cocircular();
             }
+            let illegal = side == Ordering::Greater
+                || (side == Ordering::Equal
+                    && tie_break == TieBreak::LowestIndex
+                    && p0.min(p1) < pr.min(pl));
+            if illegal {
+                // edge swapped on the other side of the hull (rare); fix the halfedge reference
+                if self.halfedges[bl].is_none() {
+                    hull.swap_halfedge(I::from_usize(bl), I::from_usize(a));
+                }
 
-            self.halfedges[a] = hbl;
-            self.halfedges[b] = har;
-            self.halfedges[ar] = I::from_usize(bl).into();
+                self.flip(a);
+                observer.flip((a, b), (pl, pr), (p0, p1));
+                observer.changed(a / 3);
+                observer.changed(b / 3);
 
-            if let Some(hbl) = hbl.get() {
-                self.halfedges[hbl.as_usize()] = I::from_usize(a).into();
+                // check `a` again, then the other new outer edge
+                stack.push(util::next_halfedge(b));
+                continue;
             }
-            if let Some(har) = har.get() {
-                self.halfedges[har.as_usize()] = I::from_usize(b).into();
+
+            match stack.pop() {
+                None => return ar,
+                Some(next) => a = next,
             }
-            self.halfedges[bl] = I::from_usize(ar).into();
+        }
+    }
+
+    /// Flips the shared edge of the two triangles adjacent to half-edge `a`
+    /// (see the diagram in `legalize`). Afterwards, `prev(a)` and `prev(b)`
+    /// hold the new diagonal.
+    ///
+    /// `a` must not be on the convex hull.
+    pub(crate) fn flip(&mut self, a: usize) {
+        let b = self.halfedges[a].unwrap().as_usize();
+
+        let ar = util::prev_halfedge(a);
+        let bl = util::prev_halfedge(b);
+
+        let p0 = self.triangles[ar];
+        let p1 = self.triangles[bl];
+
+        self.triangles[a] = p1;
+        self.triangles[b] = p0;
 
-            let br = util::next_halfedge(b);
+        let hbl = self.halfedges[bl];
+        let har = self.halfedges[ar];
 
-            self.legalize(a, points, hull);
-            return self.legalize(br, points, hull);
+        self.halfedges[a] = hbl;
+        self.halfedges[b] = har;
+        self.halfedges[ar] = I::from_usize(bl).into();
+        self.halfedges[bl] = I::from_usize(ar).into();
+
+        if let Some(hbl) = hbl.get() {
+            self.halfedges[hbl.as_usize()] = I::from_usize(a).into();
+        }
+        if let Some(har) = har.get() {
+            self.halfedges[har.as_usize()] = I::from_usize(b).into();
         }
-        ar
     }
+
+    /// Flips edges until every interior edge satisfies the local Delaunay
+    /// condition. Returns the number of flips performed.
+    pub(crate) fn legalize_all<T: Scalar, P: HasPosition<T>>(&mut self, points: &[P]) -> usize {
+        let mut stack: Vec<usize> = (0..self.halfedges.len())
+            .filter(|&a| self.halfedges[a].is_some())
+            .collect();
+        let mut flips = 0;
+
+        while let Some(a) = stack.pop() {
+            let b = match self.halfedges[a].get() {
+                None => continue,
+                Some(b) => b.as_usize(),
+            };
+            if !self.is_illegal(a, points) {
+                continue;
+            }
+
+            // only flip if both new triangles are counter-clockwise
+            let pos = |i: usize| points[self.triangles[i].as_usize()].pos();
+            let p0 = pos(util::prev_halfedge(a));
+            let pr = pos(a);
+            let pl = pos(b);
+            let p1 = pos(util::prev_halfedge(b));
+            if !p0.is_clockwise(pl, p1) || !p1.is_clockwise(pr, p0) {
+                continue;
+            }
+
+            self.flip(a);
+            flips += 1;
+
+            stack.push(a);
+            stack.push(util::next_halfedge(a));
+            stack.push(b);
+            stack.push(util::next_halfedge(b));
+        }
+
+        flips
+    }
+
+    /// Rebuilds the `vertices` index for a triangulation of `n` points.
+    #[cfg(feature = "vertices")]
+    pub(crate) fn update_vertices(&mut self, n: usize) {
+        self.vertices.clear();
+        self.vertices.resize(n, I::max_value());
+        for (i, &j) in self.triangles.iter().enumerate() {
+            let j = j.as_usize();
+            if self.vertices[j] == I::max_value() {
+                self.vertices[j] = I::from_usize(i);
+            }
+        }
+    }
+}
+
+fn triangle_circumcenter<T: Scalar, P: HasPosition<T>, I: Index>(
+    points: &[P],
+    t: &[I],
+) -> Point<T> {
+    let a = points[t[0].as_usize()].pos();
+    let b = points[t[1].as_usize()].pos();
+    let c = points[t[2].as_usize()].pos();
+    a.circumcenter(b, c)
 }
@@ -0,0 +1,273 @@
+//! Updating a triangulation after some of its points changed.
+//!
+//! Interactive editors often move, add or remove a handful of points in a
+//! triangulation of millions. [Triangulation::update] re-triangulates only the
+//! cavity around the changes and leaves the rest of the triangulation as is.
+//!
+//! ```rust
+//! use delaunator::{update::Update, Point, Triangulation};
+//!
+//! let mut points = vec![
+//!     Point { x: 0., y: 0. },
+//!     Point { x: 4., y: 0. },
+//!     Point { x: 4., y: 4. },
+//!     Point { x: 0., y: 4. },
+//!     Point { x: 1., y: 1. },
+//! ];
+//! let mut triangulation = Triangulation::<usize>::new(&points).unwrap();
+//!
+//! // move the inner point and add another one
+//! points[4] = Point { x: 3., y: 1. };
+//! points.push(Point { x: 1., y: 3. });
+//!
+//! let update = triangulation.update(&points, &[4, 5], &[]).unwrap();
+//! assert!(matches!(update, Update::Local(_)));
+//! assert_eq!(triangulation.len(), 6);
+//! assert!(triangulation.is_delaunay(&points));
+//! ```
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    query::Walk,
+    shape,
+    traits::{ApproxEq, HasPosition, Index, Scalar},
+    util::{next_halfedge, OptionIndex},
+    Point, Triangulation,
+};
+
+/// How [Triangulation::update] applied the changes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Update {
+    /// Only the cavity around the changes was re-triangulated. Holds the number
+    /// of triangles that were replaced.
+    Local(usize),
+    /// The changes reached the convex hull, so the whole triangulation was rebuilt.
+    Rebuilt,
+}
+
+impl<I: Index> Triangulation<I> {
+    /// Updates the triangulation after the points in `changed` were moved or
+    /// added and the points in `removed` were removed.
+    ///
+    /// `points` holds the current positions; indices of removed points stay
+    /// reserved and their positions are ignored. Points that were skipped as
+    /// duplicates before stay skipped unless they are listed in `changed`.
+    ///
+    /// The cavity is the union of the triangles around the changed and removed
+    /// points and the triangles whose circumcircles contain a changed point. It
+    /// is re-triangulated in place: triangle ids outside the cavity are kept,
+    /// except that triangles from the end are moved into slots freed when the
+    /// cavity shrinks. Changes on or beyond the convex hull, and cavities that
+    /// can't be patched consistently, fall back to re-triangulating every point.
+    ///
+    /// Returns `None` and leaves the triangulation unchanged if no triangulation
+    /// exists for the updated points.
+    pub fn update<T: Scalar + ApproxEq, P: HasPosition<T>>(
+        &mut self,
+        points: &[P],
+        changed: &[usize],
+        removed: &[usize],
+    ) -> Option<Update> {
+        let dirty: HashSet<usize> = changed.iter().chain(removed).copied().collect();
+        if !dirty.is_empty() {
+            if let Some(replaced) = self.update_cavity(points, changed, &dirty) {
+                return Some(Update::Local(replaced));
+            }
+        }
+
+        let mut used = vec![false; points.len()];
+        for &i in &self.triangles {
+            used[i.as_usize()] = true;
+        }
+        for &i in changed {
+            used[i] = true;
+        }
+        for &i in removed {
+            used[i] = false;
+        }
+        let subset: Vec<usize> = (0..points.len()).filter(|&i| used[i]).collect();
+        *self = Triangulation::from_subset(points, &subset)?;
+        Some(Update::Rebuilt)
+    }
+
+    /// Re-triangulates the cavity around the `dirty` points, returning the number
+    /// of triangles replaced, or `None` if the changes can't be applied locally.
+    fn update_cavity<T: Scalar + ApproxEq, P: HasPosition<T>>(
+        &mut self,
+        points: &[P],
+        changed: &[usize],
+        dirty: &HashSet<usize>,
+    ) -> Option<usize> {
+        // the hull must stay as it is
+        if self.is_empty() || self.hull.iter().any(|i| dirty.contains(&i.as_usize())) {
+            return None;
+        }
+        let zero = T::from_f64(0.0);
+        let hull: Vec<Point<T>> = self
+            .hull
+            .iter()
+            .map(|&i| points[i.as_usize()].pos())
+            .collect();
+        let inside_hull = |p: Point<T>| {
+            (0..hull.len()).all(|k| {
+                let (a, b) = (hull[k], hull[(k + 1) % hull.len()]);
+                (b - a).perp_dot(p - a) > zero
+            })
+        };
+        if !changed.iter().all(|&i| inside_hull(points[i].pos())) {
+            return None;
+        }
+
+        // the stars of the dirty points
+        let mut cavity: HashSet<usize> = (0..self.len())
+            .filter(|&t| (3 * t..3 * t + 3).any(|e| dirty.contains(&self.triangles[e].as_usize())))
+            .collect();
+
+        // grow it by the triangles in conflict with the new positions
+        let corners =
+            |t: usize| [0, 1, 2].map(|k| points[self.triangles[3 * t + k].as_usize()].pos());
+        let conflicts = |t: usize| {
+            let [a, b, c] = corners(t);
+            changed
+                .iter()
+                .any(|&i| points[i].pos().is_in_circle(a, b, c))
+        };
+        let mut stack: Vec<usize> = cavity.iter().copied().collect();
+        for &i in changed {
+            let start = stack.first().map_or(0, |&t| 3 * t);
+            let t = match self.walk(points, points[i].pos(), start) {
+                Walk::Inside(e) => e / 3,
+                Walk::Outside(_) => return None,
+            };
+            if !cavity.contains(&t) && conflicts(t) {
+                cavity.insert(t);
+                stack.push(t);
+            }
+        }
+        while let Some(t) = stack.pop() {
+            for e in 3 * t..3 * t + 3 {
+                if let Some(n) = self.halfedges[e].get().map(|n| n.as_usize() / 3) {
+                    if !cavity.contains(&n) && conflicts(n) {
+                        cavity.insert(n);
+                        stack.push(n);
+                    }
+                }
+            }
+        }
+
+        // the boundary of the cavity, with the cavity on the left of each edge
+        let mut boundary: HashMap<(usize, usize), OptionIndex<I>> = HashMap::new();
+        let mut subset: HashSet<usize> = changed.iter().copied().collect();
+        for &t in &cavity {
+            for e in 3 * t..3 * t + 3 {
+                let a = self.triangles[e].as_usize();
+                if !dirty.contains(&a) {
+                    subset.insert(a);
+                }
+                let twin = self.halfedges[e];
+                if twin
+                    .get()
+                    .map_or(true, |twin| !cavity.contains(&(twin.as_usize() / 3)))
+                {
+                    boundary.insert((a, self.triangles[next_halfedge(e)].as_usize()), twin);
+                }
+            }
+        }
+
+        // triangulate the points of the cavity and keep the triangles inside it
+        let mut subset: Vec<usize> = subset.into_iter().collect();
+        subset.sort_unstable();
+        let local = Triangulation::<usize>::from_subset(points, &subset)?;
+        let inside = |p: Point<T>| {
+            let edges = boundary
+                .keys()
+                .map(|&(a, b)| (points[a].pos(), points[b].pos()));
+            shape::encloses(edges, p)
+        };
+        let third = T::from_f64(1.0 / 3.0);
+        let patch: Vec<[usize; 3]> = local
+            .triangles
+            .chunks_exact(3)
+            .map(|t| [t[0], t[1], t[2]])
+            .filter(|&[a, b, c]| {
+                let [a, b, c] = [a, b, c].map(|i| points[i].pos());
+                inside(Point::new(
+                    (a.x + b.x + c.x) * third,
+                    (a.y + b.y + c.y) * third,
+                ))
+            })
+            .collect();
+
+        // the patch must fill the cavity exactly and use every changed point
+        let mut edges: HashMap<(usize, usize), usize> = HashMap::with_capacity(3 * patch.len());
+        for (t, tri) in patch.iter().enumerate() {
+            for k in 0..3 {
+                edges.insert((tri[k], tri[(k + 1) % 3]), 3 * t + k);
+            }
+        }
+        let closed = edges
+            .keys()
+            .all(|&(a, b)| edges.contains_key(&(b, a)) != boundary.contains_key(&(a, b)));
+        let used: HashSet<usize> = patch.iter().flatten().copied().collect();
+        if !closed
+            || !boundary.keys().all(|edge| edges.contains_key(edge))
+            || !changed.iter().all(|i| used.contains(i))
+        {
+            return None;
+        }
+
+        // write the patch into the slots of the cavity, appending any extra triangles
+        let mut slots: Vec<usize> = cavity.into_iter().collect();
+        slots.sort_unstable();
+        let replaced = slots.len();
+        let len = self.len();
+        let id = |t: usize| slots.get(t).copied().unwrap_or(len + t - replaced);
+        for (t, tri) in patch.iter().enumerate() {
+            let s = id(t);
+            let vertices = tri.map(I::from_usize);
+            if s < self.len() {
+                self.triangles[3 * s..3 * s + 3].copy_from_slice(&vertices);
+            } else {
+                self.triangles.extend(vertices);
+                self.halfedges.extend([OptionIndex::none(); 3]);
+            }
+        }
+        for (t, tri) in patch.iter().enumerate() {
+            for k in 0..3 {
+                let (a, b) = (tri[k], tri[(k + 1) % 3]);
+                let e = 3 * id(t) + k;
+                let twin = match edges.get(&(b, a)) {
+                    Some(&f) => OptionIndex::some(I::from_usize(3 * id(f / 3) + f % 3)),
+                    None => boundary[&(a, b)],
+                };
+                self.halfedges[e] = twin;
+                if let Some(twin) = twin.get() {
+                    self.halfedges[twin.as_usize()] = OptionIndex::some(I::from_usize(e));
+                }
+            }
+        }
+
+        // fill slots that are no longer needed with triangles from the end
+        for &s in slots[patch.len().min(replaced)..].iter().rev() {
+            let last = self.len() - 1;
+            if last != s {
+                for k in 0..3 {
+                    let e = 3 * s + k;
+                    self.triangles[e] = self.triangles[3 * last + k];
+                    self.halfedges[e] = self.halfedges[3 * last + k];
+                    if let Some(twin) = self.halfedges[e].get() {
+                        self.halfedges[twin.as_usize()] = OptionIndex::some(I::from_usize(e));
+                    }
+                }
+            }
+            self.triangles.truncate(3 * last);
+            self.halfedges.truncate(3 * last);
+        }
+
+        #[cfg(feature = "vertices")]
+        self.update_vertices(points.len());
+
+        Some(replaced)
+    }
+}
@@ -1,5 +1,8 @@
+use std::collections::{HashMap, HashSet};
+
 use crate::{
-    traits::{HasPosition, Index, Scalar},
+    kernel::Kernel,
+    traits::{Index, PointSource, Scalar},
     Point,
 };
 
@@ -7,6 +10,7 @@ use crate::{
 ///
 /// Supports values from `0` to `I::max_usize() - 1`.
 #[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[repr(transparent)]
 pub struct OptionIndex<I>(I);
 
 impl<I: Index> OptionIndex<I> {
@@ -22,6 +26,15 @@ impl<I: Index> OptionIndex<I> {
         }
     }
 
+    /// Views a slice of raw indices, with `I::max_value()` for `None`, as
+    /// `OptionIndex` values without copying it.
+    #[inline(always)]
+    pub fn from_slice(n: &[I]) -> &[Self] {
+        // SAFETY: `OptionIndex<I>` is a transparent wrapper and every `I` is a
+        // valid `OptionIndex<I>`
+        unsafe { std::slice::from_raw_parts(n.as_ptr().cast(), n.len()) }
+    }
+
     /// Creates the `None` value.
     #[inline(always)]
     pub fn none() -> Self {
@@ -86,6 +99,50 @@ impl<I: Index> OptionIndex<I> {
     pub fn expect(self, msg: &str) -> I {
         self.get().expect(msg)
     }
+
+    /// Returns the contained value or `default`.
+    #[inline(always)]
+    pub fn unwrap_or(self, default: I) -> I {
+        self.get().unwrap_or(default)
+    }
+
+    /// Maps the contained value with `f`.
+    #[inline(always)]
+    pub fn map<U, F: FnOnce(I) -> U>(self, f: F) -> Option<U> {
+        self.get().map(f)
+    }
+
+    /// Returns `None` if the value is `None`, otherwise calls `f` with it.
+    #[inline(always)]
+    pub fn and_then<U, F: FnOnce(I) -> Option<U>>(self, f: F) -> Option<U> {
+        self.get().and_then(f)
+    }
+
+    /// Returns the value if it is `Some` and `predicate` returns `true` for it,
+    /// otherwise `None`.
+    #[inline(always)]
+    pub fn filter<P: FnOnce(&I) -> bool>(self, predicate: P) -> Self {
+        match self.get() {
+            Some(n) if predicate(&n) => self,
+            _ => OptionIndex::none(),
+        }
+    }
+
+    /// Returns an iterator over the contained value, if any.
+    #[inline(always)]
+    pub fn iter(self) -> std::option::IntoIter<I> {
+        self.get().into_iter()
+    }
+}
+
+impl<I: Index> IntoIterator for OptionIndex<I> {
+    type Item = I;
+    type IntoIter = std::option::IntoIter<I>;
+
+    #[inline(always)]
+    fn into_iter(self) -> Self::IntoIter {
+        self.get().into_iter()
+    }
 }
 
 impl<I: Index> Default for OptionIndex<I> {
@@ -143,33 +200,63 @@ pub(crate) fn prev_halfedge(i: usize) -> usize {
     }
 }
 
-pub(crate) fn calc_bbox_center<T: Scalar, P: HasPosition<T>>(points: &[P]) -> Point<T> {
+/// The key of an undirected segment between two points.
+pub(crate) fn key(a: usize, b: usize) -> (usize, usize) {
+    (a.min(b), a.max(b))
+}
+
+/// Kahan and Babuska summation, Neumaier variant; accumulates less FP error.
+pub(crate) fn sum<T: Scalar, It: IntoIterator<Item = T>>(x: It) -> T {
+    let mut sum = T::from_f64(0.0);
+    let mut err = T::from_f64(0.0);
+    for k in x {
+        let m = sum + k;
+        err = err
+            + if sum.abs() >= k.abs() {
+                sum - m + k
+            } else {
+                k - m + sum
+            };
+        sum = m;
+    }
+    sum + err
+}
+
+/// Twice the signed area of `polygon`, positive if it is counter-clockwise.
+pub(crate) fn signed_area<T: Scalar>(polygon: &[Point<T>]) -> T {
+    (0..polygon.len()).fold(T::from_f64(0.0), |area, k| {
+        area + polygon[k].perp_dot(polygon[(k + 1) % polygon.len()])
+    })
+}
+
+pub(crate) fn calc_bbox_center<T: Scalar, S: PointSource<T> + ?Sized>(points: &S) -> Point<T> {
     let mut min_x = T::infinity();
     let mut min_y = T::infinity();
     let mut max_x = -T::infinity();
     let mut max_y = -T::infinity();
-    for p in points.iter() {
-        let p = p.pos();
+    for i in 0..points.len() {
+        let p = points.point(i);
         min_x = min_x.min(p.x);
         min_y = min_y.min(p.y);
         max_x = max_x.max(p.x);
         max_y = max_y.max(p.y);
     }
     Point {
-        x: (min_x + max_x) / 2.0.into(),
-        y: (min_y + max_y) / 2.0.into(),
+        x: (min_x + max_x) / T::from_f64(2.0),
+        y: (min_y + max_y) / T::from_f64(2.0),
     }
 }
 
-pub(crate) fn find_closest_point<T: Scalar, P: HasPosition<T>>(
-    points: &[P],
+pub(crate) fn find_closest_point<T: Scalar, S: PointSource<T> + ?Sized>(
+    points: &S,
     p0: Point<T>,
+    include: impl Fn(usize) -> bool,
 ) -> Option<usize> {
     let mut min_dist = T::infinity();
     let mut k: usize = 0;
-    for (i, p) in points.iter().enumerate() {
-        let d = p0.distance_squared(p.pos());
-        if d > 0.0.into() && d < min_dist {
+    for i in (0..points.len()).filter(|&i| include(i)) {
+        let d = p0.distance_squared(points.point(i));
+        if d > T::from_f64(0.0) && d < min_dist {
             k = i;
             min_dist = d;
         }
@@ -181,27 +268,29 @@ pub(crate) fn find_closest_point<T: Scalar, P: HasPosition<T>>(
     }
 }
 
-pub(crate) fn find_seed_triangle<T: Scalar, P: HasPosition<T>>(
-    points: &[P],
+/// Picks a seed triangle among the points for which `include` returns `true`.
+pub(crate) fn find_seed_triangle<T: Scalar, K: Kernel<T>, S: PointSource<T> + ?Sized>(
+    points: &S,
+    include: impl Fn(usize) -> bool,
 ) -> Option<(usize, usize, usize)> {
     // pick a seed point close to the center
     let bbox_center = calc_bbox_center(points);
-    let i0 = find_closest_point(points, bbox_center)?;
-    let p0 = points[i0].pos();
+    let i0 = find_closest_point(points, bbox_center, &include)?;
+    let p0 = points.point(i0);
 
     // find the point closest to the seed
-    let i1 = find_closest_point(points, p0)?;
-    let p1 = points[i1].pos();
+    let i1 = find_closest_point(points, p0, &include)?;
+    let p1 = points.point(i1);
 
     // find the third point which forms the smallest circumcircle with the first two
     let mut min_radius = T::infinity();
     let mut i2: usize = 0;
-    for (i, p) in points.iter().enumerate() {
-        if i == i0 || i == i1 {
+    for i in 0..points.len() {
+        if i == i0 || i == i1 || !include(i) {
             continue;
         }
-        let p = p.pos();
-        let r = p0.circumradius_squared(p1, p);
+        let p = points.point(i);
+        let r = K::circumradius_squared(p0, p1, p);
         if r < min_radius {
             i2 = i;
             min_radius = r;
@@ -212,10 +301,135 @@ pub(crate) fn find_seed_triangle<T: Scalar, P: HasPosition<T>>(
         None
     } else {
         // swap the order of the seed points for counter-clockwise orientation
-        Some(if p0.is_clockwise(p1, points[i2].pos()) {
+        Some(if K::is_clockwise(p0, p1, points.point(i2)) {
             (i0, i2, i1)
         } else {
             (i0, i1, i2)
         })
     }
 }
+
+/// Marks every point that lies within `epsilon` of a lower-indexed point that
+/// is kept, using a hash grid with cells of size `epsilon`.
+///
+/// With an `epsilon` of zero, only exact duplicates are marked.
+pub(crate) fn find_duplicates<T: Scalar, S: PointSource<T> + ?Sized>(
+    points: &S,
+    epsilon: f64,
+) -> Vec<bool> {
+    let n = points.len();
+    let mut duplicates = vec![false; n];
+
+    if epsilon <= 0.0 {
+        let mut seen = HashSet::with_capacity(n);
+        for (i, duplicate) in duplicates.iter_mut().enumerate() {
+            let p = points.point(i);
+            // adding zero turns -0.0 into 0.0
+            let (x, y) = (p.x.to_f64(), p.y.to_f64());
+            *duplicate = !seen.insert(((x + 0.0).to_bits(), (y + 0.0).to_bits()));
+        }
+        return duplicates;
+    }
+
+    // kept points are chained per cell; they are at least `epsilon` apart, so
+    // each cell holds only a few of them
+    let mut cells: HashMap<(i64, i64), usize> = HashMap::with_capacity(n);
+    let mut chain = vec![usize::MAX; n];
+    let epsilon_squared = epsilon * epsilon;
+    for i in 0..n {
+        let p = points.point(i);
+        let (x, y) = (p.x.to_f64(), p.y.to_f64());
+        let (cx, cy) = ((x / epsilon).floor() as i64, (y / epsilon).floor() as i64);
+
+        let duplicate = (cx - 1..=cx + 1)
+            .flat_map(|kx| (cy - 1..=cy + 1).map(move |ky| (kx, ky)))
+            .any(|key| {
+                let mut j = cells.get(&key).copied().unwrap_or(usize::MAX);
+                while j != usize::MAX {
+                    let q = points.point(j);
+                    let (qx, qy) = (q.x.to_f64(), q.y.to_f64());
+                    if (qx - x) * (qx - x) + (qy - y) * (qy - y) <= epsilon_squared {
+                        return true;
+                    }
+                    j = chain[j];
+                }
+                false
+            });
+
+        if duplicate {
+            duplicates[i] = true;
+        } else {
+            let head = cells.entry((cx, cy)).or_insert(usize::MAX);
+            chain[i] = *head;
+            *head = i;
+        }
+    }
+    duplicates
+}
+
+/// Distance of the cell `(x, y)` along a Hilbert curve over a `2^16 x 2^16` grid.
+pub(crate) fn hilbert_index(mut x: u32, mut y: u32) -> u64 {
+    const N: u32 = 1 << 16;
+    let mut d = 0;
+    let mut s = N / 2;
+    while s > 0 {
+        let rx = (x & s != 0) as u32;
+        let ry = (y & s != 0) as u32;
+        d += u64::from(s) * u64::from(s) * u64::from((3 * rx) ^ ry);
+        if ry == 0 {
+            if rx == 1 {
+                x = N - 1 - x;
+                y = N - 1 - y;
+            }
+            std::mem::swap(&mut x, &mut y);
+        }
+        s /= 2;
+    }
+    d
+}
+
+/// Distance of the cell `(x, y)` along a Morton (Z-order) curve.
+pub(crate) fn morton_index(x: u32, y: u32) -> u64 {
+    fn spread(v: u32) -> u64 {
+        let mut v = u64::from(v);
+        v = (v | (v << 16)) & 0x0000_ffff_0000_ffff;
+        v = (v | (v << 8)) & 0x00ff_00ff_00ff_00ff;
+        v = (v | (v << 4)) & 0x0f0f_0f0f_0f0f_0f0f;
+        v = (v | (v << 2)) & 0x3333_3333_3333_3333;
+        v = (v | (v << 1)) & 0x5555_5555_5555_5555;
+        v
+    }
+    spread(x) | (spread(y) << 1)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_space_filling_curves() {
+        let cells = [(0, 0), (1, 0), (1, 1), (0, 1)];
+        let hilbert: Vec<u64> = cells.iter().map(|&(x, y)| hilbert_index(x, y)).collect();
+        assert_eq!(hilbert, [0, 1, 2, 3]);
+
+        let cells = [(0, 0), (1, 0), (0, 1), (1, 1)];
+        let morton: Vec<u64> = cells.iter().map(|&(x, y)| morton_index(x, y)).collect();
+        assert_eq!(morton, [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_option_index_combinators() {
+        let some = OptionIndex::some(3u32);
+        let none = OptionIndex::<u32>::none();
+
+        assert_eq!(some.map(|n| n * 2), Some(6));
+        assert_eq!(none.map(|n| n * 2), None);
+        assert_eq!(some.and_then(|n| n.checked_sub(4)), None);
+        assert_eq!(some.unwrap_or(7), 3);
+        assert_eq!(none.unwrap_or(7), 7);
+        assert_eq!(some.filter(|&n| n > 2), some);
+        assert_eq!(some.filter(|&n| n > 3), none);
+        assert_eq!(some.into_iter().collect::<Vec<_>>(), [3]);
+        assert_eq!(none.iter().count(), 0);
+    }
+}
@@ -0,0 +1,177 @@
+//! Borrowed triangulations.
+//!
+//! A [TriangulationRef] gives the element and iterator API of a [Triangulation]
+//! over index arrays owned elsewhere, e.g. a memory-mapped file, a zero-copy
+//! archive or another library's mesh, without copying them into `Vec`s.
+//!
+//! ```rust
+//! use delaunator::view::TriangulationRef;
+//!
+//! // two triangles sharing the edge between points 1 and 2
+//! let triangles = [0u32, 1, 2, 2, 1, 3];
+//! let halfedges = [u32::MAX, 4, u32::MAX, u32::MAX, 1, u32::MAX];
+//! let hull = [0u32, 1, 3, 2];
+//!
+//! let view = TriangulationRef::new(&triangles, &halfedges, &hull);
+//! assert_eq!(view.len(), 2);
+//!
+//! let edge = view.get_half_edge(1).unwrap();
+//! assert_eq!(edge.twin().unwrap().id(), 4);
+//! assert_eq!(edge.right().unwrap().id(), 1);
+//! ```
+
+use crate::{elem::*, iter::*, traits::Index, util::OptionIndex, Triangulation};
+
+/// A triangulation whose arrays are borrowed, with the same layout as the
+/// fields of a [Triangulation].
+pub struct TriangulationRef<'a, I> {
+    #[cfg(feature = "vertices")]
+    /// For every point, a half-edge starting at it, as in [Triangulation::vertices].
+    /// Empty unless set with [TriangulationRef::with_vertices].
+    pub vertices: &'a [I],
+
+    /// Point indices where each triple represents a triangle.
+    pub triangles: &'a [I],
+
+    /// The twin of every half-edge, `None` on the convex hull.
+    pub halfedges: &'a [OptionIndex<I>],
+
+    /// Point indices of the convex hull, counter-clockwise.
+    pub hull: &'a [I],
+}
+
+impl<'a, I: Index> TriangulationRef<'a, I> {
+    /// Wraps the arrays of a triangulation, with `I::max_value()` marking the
+    /// half-edges on the convex hull in `halfedges`.
+    ///
+    /// The arrays aren't validated, so elements of an inconsistent triangulation
+    /// may point anywhere or panic when they index out of bounds.
+    ///
+    /// # Panics
+    /// Panics if `triangles` and `halfedges` differ in length or the length isn't
+    /// a multiple of three.
+    pub fn new(triangles: &'a [I], halfedges: &'a [I], hull: &'a [I]) -> Self {
+        assert_eq!(
+            triangles.len(),
+            halfedges.len(),
+            "triangles and halfedges differ in length"
+        );
+        assert!(
+            triangles.len() % 3 == 0,
+            "triangles must hold triples of points"
+        );
+        Self {
+            #[cfg(feature = "vertices")]
+            vertices: &[],
+            triangles,
+            halfedges: OptionIndex::from_slice(halfedges),
+            hull,
+        }
+    }
+
+    /// Sets the half-edge starting at every point, see [Triangulation::vertices].
+    #[cfg(feature = "vertices")]
+    pub fn with_vertices(self, vertices: &'a [I]) -> Self {
+        Self { vertices, ..self }
+    }
+
+    /// The number of triangles in the triangulation.
+    pub fn len(self) -> usize {
+        self.triangles.len() / 3
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn triangles(self) -> TriangleIter<'a, I> {
+        TriangleIter {
+            triangulation: self,
+            index: 0,
+            end: self.triangles.len(),
+        }
+    }
+
+    pub fn half_edges(self) -> HalfEdgeIter<'a, I> {
+        HalfEdgeIter {
+            triangulation: self,
+            index: 0,
+            end: self.halfedges.len(),
+        }
+    }
+
+    #[cfg(feature = "vertices")]
+    pub fn vertices(self) -> VertexIter<'a, I> {
+        VertexIter {
+            triangulation: self,
+            index: 0,
+            end: self.vertices.len(),
+        }
+    }
+
+    #[cfg(feature = "vertices")]
+    pub fn get_vertex(self, id: usize) -> Option<Vertex<'a, I>> {
+        if id < self.vertices.len() {
+            Some(Vertex {
+                triangulation: self,
+                index: self.vertices[id].as_usize(),
+            })
+        } else {
+            None
+        }
+    }
+
+    pub fn get_triangle(self, id: usize) -> Option<Triangle<'a, I>> {
+        let index = 3 * id;
+        if index < self.triangles.len() {
+            Some(Triangle {
+                triangulation: self,
+                index,
+            })
+        } else {
+            None
+        }
+    }
+
+    pub fn get_half_edge(self, id: usize) -> Option<HalfEdge<'a, I>> {
+        if id < self.halfedges.len() {
+            Some(HalfEdge {
+                triangulation: self,
+                index: id,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Copies the arrays into an owned [Triangulation].
+    pub fn to_triangulation(self) -> Triangulation<I> {
+        Triangulation {
+            #[cfg(feature = "vertices")]
+            vertices: self.vertices.to_vec(),
+            triangles: self.triangles.to_vec(),
+            halfedges: self.halfedges.to_vec(),
+            hull: self.hull.to_vec(),
+        }
+    }
+}
+
+impl<I> Clone for TriangulationRef<'_, I> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<I> Copy for TriangulationRef<'_, I> {}
+
+impl<'a, I> From<&'a Triangulation<I>> for TriangulationRef<'a, I> {
+    fn from(triangulation: &'a Triangulation<I>) -> Self {
+        Self {
+            #[cfg(feature = "vertices")]
+            vertices: &triangulation.vertices,
+            triangles: &triangulation.triangles,
+            halfedges: &triangulation.halfedges,
+            hull: &triangulation.hull,
+        }
+    }
+}
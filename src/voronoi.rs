@@ -0,0 +1,683 @@
+//! The Voronoi diagram, the dual of a Delaunay [Triangulation].
+//!
+//! The Voronoi cell of a point is the region of the plane closer to it than to
+//! any other point. Cells on the convex hull are unbounded, so they are
+//! clipped to a boundary to get closed polygons.
+//!
+//! ```rust
+//! use delaunator::{voronoi, Point, Triangulation};
+//!
+//! let points = vec![
+//!     Point { x: 0., y: 0. },
+//!     Point { x: 1., y: 0. },
+//!     Point { x: 1., y: 1. },
+//!     Point { x: 0., y: 1. },
+//! ];
+//! let triangulation = Triangulation::<usize>::new(&points).unwrap();
+//!
+//! // Thiessen polygons restricted to a triangular study area
+//! let area = [
+//!     Point { x: -1., y: -1. },
+//!     Point { x: 3., y: -1. },
+//!     Point { x: -1., y: 3. },
+//! ];
+//! let cells = voronoi::clip_to_polygon(&triangulation, &points, &area);
+//! assert_eq!(cells.len(), points.len());
+//! ```
+//!
+//! The diagram itself is available as a [Voronoi], which borrows the
+//! triangulation and the points and lists the circumcenters around every cell:
+//!
+//! ```rust
+//! use delaunator::{voronoi::Voronoi, Point, Triangulation};
+//!
+//! let points = vec![
+//!     Point { x: 0., y: 0. },
+//!     Point { x: 2., y: 0. },
+//!     Point { x: 2., y: 2. },
+//!     Point { x: 0., y: 2. },
+//!     Point { x: 1., y: 0.5 },
+//! ];
+//! let triangulation = Triangulation::<usize>::new(&points).unwrap();
+//! let voronoi = Voronoi::new(&triangulation, &points);
+//!
+//! assert!(voronoi.is_bounded(4));
+//! assert_eq!(voronoi.cell(4).vertices().count(), 4);
+//! assert!(!voronoi.is_bounded(0));
+//! ```
+
+use std::iter;
+
+use crate::{
+    traits::{ApproxEq, HasPosition, Index, Scalar},
+    util::{self, next_halfedge, prev_halfedge},
+    Point, Triangulation,
+};
+
+/// The Voronoi diagram of a triangulation, borrowing the triangulation and its
+/// points.
+///
+/// Every triangle contributes the vertex at its circumcenter, identified by the
+/// triangle id, and every point owns the cell around it. Cells of points on
+/// the convex hull are unbounded: their vertices run counter-clockwise from
+/// one infinite edge to the other.
+pub struct Voronoi<'a, T: Scalar, I> {
+    /// The triangulation the diagram is the dual of.
+    pub triangulation: &'a Triangulation<I>,
+    /// The points of the triangulation, the sites of the cells.
+    pub points: &'a [Point<T>],
+    /// The circumcenter of every triangle, the vertices of the diagram.
+    pub circumcenters: Vec<Point<T>>,
+    /// For every point, the first outgoing half-edge counter-clockwise, or
+    /// `usize::MAX` for points that aren't part of the triangulation.
+    spokes: Vec<usize>,
+}
+
+impl<'a, T: Scalar, I: Index> Voronoi<'a, T, I> {
+    /// Builds the Voronoi diagram of `triangulation`, a triangulation of
+    /// `points`.
+    pub fn new(triangulation: &'a Triangulation<I>, points: &'a [Point<T>]) -> Self {
+        let mut spokes = vec![usize::MAX; points.len()];
+        for (e, &i) in triangulation.triangles.iter().enumerate() {
+            let i = i.as_usize();
+            // on the hull, start at the outgoing hull edge so walks don't wrap
+            if spokes[i] == usize::MAX || triangulation.halfedges[e].is_none() {
+                spokes[i] = e;
+            }
+        }
+        Voronoi {
+            circumcenters: triangulation.circumcenters(points),
+            triangulation,
+            points,
+            spokes,
+        }
+    }
+
+    /// The number of cells, one per point, including points that aren't part
+    /// of the triangulation.
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Whether the diagram has no cells.
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Whether the cell of point `i` is bounded, i.e. the point is part of the
+    /// triangulation and not on its convex hull.
+    pub fn is_bounded(&self, i: usize) -> bool {
+        self.spokes[i] != usize::MAX && self.triangulation.halfedges[self.spokes[i]].is_some()
+    }
+
+    /// The cell of point `i`.
+    ///
+    /// # Panics
+    /// Panics if `i` is out of bounds.
+    pub fn cell(&self, i: usize) -> VoronoiCell<'_, T, I> {
+        assert!(i < self.len(), "cell {} out of bounds", i);
+        VoronoiCell {
+            voronoi: self,
+            index: i,
+        }
+    }
+
+    /// An iterator over all cells, one per point.
+    pub fn cells(&self) -> impl ExactSizeIterator<Item = VoronoiCell<'_, T, I>> + '_ {
+        (0..self.len()).map(move |i| self.cell(i))
+    }
+
+    /// Clips every cell to the rectangle from `min` to `max`, closing the
+    /// unbounded cells on the convex hull.
+    ///
+    /// Returns one counter-clockwise polygon per point, indexed like `points`.
+    /// Points that aren't part of the triangulation and cells that lie
+    /// entirely outside the rectangle get an empty polygon.
+    pub fn clip_to_rect(&self, min: Point<T>, max: Point<T>) -> Vec<Vec<Point<T>>> {
+        let rect = [min, Point::new(max.x, min.y), max, Point::new(min.x, max.y)];
+        (0..self.len()).map(|i| self.clip_cell(i, &rect)).collect()
+    }
+
+    /// Clips every cell to the convex polygon `boundary`, e.g. a hexagon or a
+    /// polygonal approximation of a circle, like
+    /// [clip_to_rect](Self::clip_to_rect).
+    ///
+    /// The boundary may be in either orientation; the cells are always
+    /// counter-clockwise. A boundary that isn't convex gives wrong cells.
+    pub fn clip_to_convex(&self, boundary: &[Point<T>]) -> Vec<Vec<Point<T>>> {
+        let boundary = counter_clockwise(boundary);
+        (0..self.len())
+            .map(|i| self.clip_cell(i, &boundary))
+            .collect()
+    }
+
+    /// The centroid of every cell, clipped to the rectangle from `min` to `max`
+    /// as in [clip_to_rect](Self::clip_to_rect), weighted by `density`.
+    ///
+    /// This is [weighted_centroids] for a diagram that is already built; see
+    /// there for `resolution`. Moving every point to its centroid and
+    /// rebuilding the diagram is one step of weighted Lloyd relaxation, which
+    /// [relax] runs to convergence.
+    pub fn weighted_centroids<F: Fn(Point<T>) -> T>(
+        &self,
+        min: Point<T>,
+        max: Point<T>,
+        resolution: usize,
+        density: F,
+    ) -> Vec<Option<Point<T>>> {
+        self.clip_to_rect(min, max)
+            .iter()
+            .map(|cell| weighted_centroid(cell, resolution.max(1), &density))
+            .collect()
+    }
+
+    /// Clips the cell of point `i` to the counter-clockwise convex polygon
+    /// `boundary`.
+    fn clip_cell(&self, i: usize, boundary: &[Point<T>]) -> Vec<Point<T>> {
+        if self.spokes[i] == usize::MAX {
+            return Vec::new();
+        }
+        if self.is_bounded(i) {
+            // clip the cell by the boundary
+            let cell = self.cell(i).vertices().collect::<Vec<_>>();
+            (0..boundary.len()).fold(cell, |polygon, k| {
+                let (a, b) = (boundary[k], boundary[(k + 1) % boundary.len()]);
+                let d = b - a;
+                clip_half_plane(&polygon, a, Point::new(d.y, T::from_f64(0.0) - d.x))
+            })
+        } else {
+            // the cell has infinite edges, so clip the boundary by the cell
+            let p = self.points[i];
+            let half = T::from_f64(0.5);
+            self.cell(i)
+                .neighbors()
+                .fold(boundary.to_vec(), |polygon, j| {
+                    let q = self.points[j];
+                    let mid = Point::new((p.x + q.x) * half, (p.y + q.y) * half);
+                    clip_half_plane(&polygon, mid, q - p)
+                })
+        }
+    }
+}
+
+/// One cell of a [Voronoi] diagram, around one of the points.
+pub struct VoronoiCell<'a, T: Scalar, I> {
+    voronoi: &'a Voronoi<'a, T, I>,
+    index: usize,
+}
+
+impl<'a, T: Scalar, I: Index> VoronoiCell<'a, T, I> {
+    /// The index of the point the cell surrounds.
+    pub fn id(&self) -> usize {
+        self.index
+    }
+
+    /// The position of the point the cell surrounds.
+    pub fn site(&self) -> Point<T> {
+        self.voronoi.points[self.index]
+    }
+
+    /// Whether the cell is bounded, see [Voronoi::is_bounded].
+    pub fn is_bounded(&self) -> bool {
+        self.voronoi.is_bounded(self.index)
+    }
+
+    /// The vertices of the cell counter-clockwise, as indices into
+    /// [Voronoi::circumcenters], which are also the ids of the triangles
+    /// around the point. Empty for points that aren't part of the
+    /// triangulation.
+    ///
+    /// The vertices of an unbounded cell run from the one on the infinite
+    /// edge dual to the outgoing hull edge to the one on the infinite edge
+    /// dual to the incoming hull edge.
+    pub fn vertex_ids(&self) -> impl Iterator<Item = usize> + 'a {
+        self.spokes().map(|e| e / 3)
+    }
+
+    /// The positions of the vertices of the cell, see
+    /// [vertex_ids](Self::vertex_ids).
+    pub fn vertices(&self) -> impl Iterator<Item = Point<T>> + 'a {
+        let circumcenters = &self.voronoi.circumcenters;
+        self.vertex_ids().map(move |t| circumcenters[t])
+    }
+
+    /// The points whose cells share an edge with this one, counter-clockwise,
+    /// like `neighbors` in d3-delaunay. These are the Delaunay neighbors of
+    /// the point, including both of its neighbors along the convex hull.
+    ///
+    /// The edge shared with a neighbor has zero length if the two points lie
+    /// on a common circle with the corners of the triangles on either side.
+    pub fn neighbors(&self) -> impl Iterator<Item = usize> + 'a {
+        let spokes = self.spokes();
+        // on the hull, the far end of the incoming hull edge comes last
+        let last = if self.is_bounded() {
+            None
+        } else {
+            spokes.clone().last().map(prev_halfedge)
+        };
+        let triangles = &self.voronoi.triangulation.triangles;
+        spokes
+            .map(move |e| triangles[next_halfedge(e)].as_usize())
+            .chain(last.map(move |e| triangles[e].as_usize()))
+    }
+
+    /// The half-edges starting at the point counter-clockwise, one per
+    /// triangle around it.
+    fn spokes(&self) -> impl Iterator<Item = usize> + Clone + 'a {
+        let Voronoi {
+            triangulation,
+            spokes,
+            ..
+        } = self.voronoi;
+        let start = Some(spokes[self.index]).filter(|&e| e != usize::MAX);
+        iter::successors(start, move |&e| {
+            triangulation.halfedges[prev_halfedge(e)]
+                .get()
+                .map(I::as_usize)
+                .filter(|&twin| Some(twin) != start)
+        })
+    }
+}
+
+/// The order-2 Voronoi diagram, whose regions are the parts of the plane
+/// where a given pair of points are the two nearest ones.
+///
+/// Only pairs that share a Delaunay edge have a region, so there is one
+/// region per edge of the triangulation. Within the cell of a point, the
+/// second nearest point is always one of its Delaunay neighbors, so each
+/// region is cut out by the bisectors to the neighbors of its two points.
+/// Regions of pairs on the convex hull are unbounded.
+///
+/// ```rust
+/// use delaunator::{voronoi::Order2Voronoi, Point, Triangulation};
+///
+/// let points = vec![
+///     Point { x: 0., y: 0. },
+///     Point { x: 2., y: 0. },
+///     Point { x: 2., y: 2. },
+///     Point { x: 0., y: 2. },
+///     Point { x: 1., y: 0.5 },
+/// ];
+/// let triangulation = Triangulation::<usize>::new(&points).unwrap();
+/// let diagram = Order2Voronoi::new(&triangulation, &points);
+///
+/// // one region per Delaunay edge
+/// assert_eq!(diagram.len(), 8);
+/// let regions = diagram.clip_to_rect(Point { x: -1., y: -1. }, Point { x: 3., y: 3. });
+/// assert_eq!(regions.len(), diagram.pairs.len());
+/// ```
+pub struct Order2Voronoi<'a, T: Scalar, I> {
+    /// The order-1 diagram the regions are derived from.
+    pub voronoi: Voronoi<'a, T, I>,
+    /// The pair of points of every region, the smaller index first, in the
+    /// order of the half-edges of the triangulation.
+    pub pairs: Vec<(usize, usize)>,
+}
+
+impl<'a, T: Scalar, I: Index> Order2Voronoi<'a, T, I> {
+    /// Builds the order-2 Voronoi diagram of `triangulation`, a triangulation
+    /// of `points`.
+    pub fn new(triangulation: &'a Triangulation<I>, points: &'a [Point<T>]) -> Self {
+        let pairs = triangulation
+            .unique_edges()
+            .map(|e| {
+                let a = triangulation.triangles[e].as_usize();
+                let b = triangulation.triangles[next_halfedge(e)].as_usize();
+                (a.min(b), a.max(b))
+            })
+            .collect();
+        Order2Voronoi {
+            voronoi: Voronoi::new(triangulation, points),
+            pairs,
+        }
+    }
+
+    /// The number of regions, one per Delaunay edge.
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    /// Whether the diagram has no regions.
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    /// Clips every region to the rectangle from `min` to `max`.
+    ///
+    /// Returns one counter-clockwise polygon per region, indexed like
+    /// [pairs](Self::pairs). Regions that lie entirely outside the rectangle
+    /// get an empty polygon.
+    pub fn clip_to_rect(&self, min: Point<T>, max: Point<T>) -> Vec<Vec<Point<T>>> {
+        let rect = [min, Point::new(max.x, min.y), max, Point::new(min.x, max.y)];
+        (0..self.len())
+            .map(|k| self.clip_region(k, &rect))
+            .collect()
+    }
+
+    /// Clips every region to the convex polygon `boundary`, which may be
+    /// given in either orientation, like [clip_to_rect](Self::clip_to_rect).
+    pub fn clip_to_convex(&self, boundary: &[Point<T>]) -> Vec<Vec<Point<T>>> {
+        let boundary = counter_clockwise(boundary);
+        (0..self.len())
+            .map(|k| self.clip_region(k, &boundary))
+            .collect()
+    }
+
+    /// Clips region `k` to the counter-clockwise convex polygon `boundary`.
+    fn clip_region(&self, k: usize, boundary: &[Point<T>]) -> Vec<Point<T>> {
+        let (a, b) = self.pairs[k];
+        let points = self.voronoi.points;
+        let half = T::from_f64(0.5);
+        let bisector = |p: Point<T>, q: Point<T>, polygon: Vec<Point<T>>| {
+            let mid = Point::new((p.x + q.x) * half, (p.y + q.y) * half);
+            clip_half_plane(&polygon, mid, q - p)
+        };
+        // closer to both a and b than to any neighbor of either
+        let neighbors = self.voronoi.cell(a).neighbors();
+        neighbors
+            .chain(self.voronoi.cell(b).neighbors())
+            .filter(|&j| j != a && j != b)
+            .fold(boundary.to_vec(), |polygon, j| {
+                let polygon = bisector(points[a], points[j], polygon);
+                bisector(points[b], points[j], polygon)
+            })
+    }
+}
+
+/// Computes the Voronoi cell of every point, clipped to the simple polygon
+/// `boundary`, which may be given in either orientation.
+///
+/// Returns one counter-clockwise polygon per point, indexed like `points`.
+/// Points that aren't part of the triangulation (e.g. skipped duplicates) and
+/// cells that lie entirely outside the boundary get an empty polygon.
+///
+/// Each cell is the intersection of the half-planes bounded by the
+/// perpendicular bisectors to its Delaunay neighbors, so the boundary is clipped
+/// by every one of them in turn. If the boundary is not convex, a cell may
+/// intersect it in several pieces; these are returned as one polygon joined by
+/// zero-width bridges along the cell's edges, which doesn't affect its area.
+pub fn clip_to_polygon<T: Scalar, I: Index, P: HasPosition<T>>(
+    triangulation: &Triangulation<I>,
+    points: &[P],
+    boundary: &[Point<T>],
+) -> Vec<Vec<Point<T>>> {
+    let neighbors = triangulation.adjacency(points.len());
+    clip_cells(points, &neighbors, boundary)
+}
+
+/// The pieces of a polygon broken up along Voronoi cells, see [fracture].
+#[derive(Clone, Debug)]
+pub struct Fracture<T: Scalar> {
+    /// The piece around each seed, as a counter-clockwise polygon, indexed like
+    /// the seeds. Empty for duplicate seeds and seeds whose cell lies outside
+    /// the polygon.
+    pub pieces: Vec<Vec<Point<T>>>,
+    /// For each piece, the pieces that share an edge of positive length with it.
+    pub adjacency: Vec<Vec<usize>>,
+}
+
+/// Breaks `polygon` into the Voronoi cells of `seeds`, e.g. for destruction
+/// effects, along with which pieces touch each other.
+///
+/// The polygon may be given in either orientation; see [clip_to_polygon] for
+/// how non-convex polygons are handled. Unlike [clip_to_polygon], collinear
+/// seeds are allowed and cut the polygon into parallel strips.
+pub fn fracture<T: Scalar + ApproxEq, P: HasPosition<T>>(
+    polygon: &[Point<T>],
+    seeds: &[P],
+) -> Fracture<T> {
+    let neighbors = match Triangulation::<usize>::new(seeds) {
+        Some(triangulation) => triangulation.adjacency(seeds.len()),
+        None => collinear_neighbors(seeds),
+    };
+    let mut pieces = clip_cells(seeds, &neighbors, polygon);
+    if !seeds.is_empty() && neighbors.iter().all(Vec::is_empty) {
+        // all seeds coincide, so the first one gets everything
+        pieces[0] = counter_clockwise(polygon);
+    }
+
+    // a tolerance for points lying on a bisector, relative to the polygon size
+    let (min, max) = polygon.iter().fold(
+        (
+            Point::new(T::infinity(), T::infinity()),
+            Point::new(-T::infinity(), -T::infinity()),
+        ),
+        |(min, max), p| {
+            (
+                Point::new(min.x.min(p.x), min.y.min(p.y)),
+                Point::new(max.x.max(p.x), max.y.max(p.y)),
+            )
+        },
+    );
+    let epsilon = min.distance(max) * T::from_f64(1e-9);
+
+    let mut adjacency = vec![Vec::new(); seeds.len()];
+    for (i, neighbors) in neighbors.iter().enumerate() {
+        let p = seeds[i].pos();
+        for &j in neighbors.iter().filter(|&&j| i < j) {
+            let q = seeds[j].pos();
+            let normal = q - p;
+            let half = T::from_f64(0.5);
+            let mid = Point::new((p.x + q.x) * half, (p.y + q.y) * half);
+            let on_bisector = |x: Point<T>| {
+                let d = x - mid;
+                (d.x * normal.x + d.y * normal.y).abs() <= epsilon * normal.length()
+            };
+            let piece = &pieces[i];
+            let shared = (0..piece.len()).any(|k| {
+                let (a, b) = (piece[k], piece[(k + 1) % piece.len()]);
+                on_bisector(a) && on_bisector(b) && a.distance(b) > epsilon
+            });
+            if shared {
+                adjacency[i].push(j);
+                adjacency[j].push(i);
+            }
+        }
+    }
+    Fracture { pieces, adjacency }
+}
+
+/// Computes the centroid of every Voronoi cell, clipped to `boundary` as in
+/// [clip_to_polygon], weighted by `density`.
+///
+/// `density` is integrated over each cell by splitting it into triangles and
+/// each triangle into `resolution * resolution` smaller ones, sampled at their
+/// centroids, so `resolution` should be high enough for the triangles to
+/// resolve the detail of the density. Returns `None` for empty cells and cells
+/// without positive weight.
+pub fn weighted_centroids<T, I, P, F>(
+    triangulation: &Triangulation<I>,
+    points: &[P],
+    boundary: &[Point<T>],
+    resolution: usize,
+    density: F,
+) -> Vec<Option<Point<T>>>
+where
+    T: Scalar,
+    I: Index,
+    P: HasPosition<T>,
+    F: Fn(Point<T>) -> T,
+{
+    clip_to_polygon(triangulation, points, boundary)
+        .iter()
+        .map(|cell| weighted_centroid(cell, resolution.max(1), &density))
+        .collect()
+}
+
+/// Moves every point to the weighted centroid of its cell `iterations` times
+/// (Lloyd's algorithm), approaching a centroidal Voronoi tessellation whose
+/// cells are smaller where `density` is higher.
+///
+/// Starting from points placed by the density, e.g. with
+/// [rejection_sample](crate::sampling::rejection_sample), this produces weighted
+/// Voronoi stippling. See [weighted_centroids] for `resolution`. Points whose
+/// cell has no weight stay where they are, and relaxation stops early if the
+/// points become collinear.
+///
+/// ```rust
+/// use delaunator::{voronoi, Point};
+///
+/// let boundary = [(0., 0.), (1., 0.), (1., 1.), (0., 1.)].map(|(x, y)| Point { x, y });
+/// let mut points: Vec<_> = (0..25)
+///     .map(|i| Point { x: (i % 5) as f64 / 5. + 0.1, y: (i / 5) as f64 / 5. + 0.1 })
+///     .collect();
+///
+/// // darker towards the right, so stipples gather there
+/// voronoi::relax(&mut points, &boundary, 20, 4, |p| p.x * p.x);
+/// let mean = points.iter().map(|p| p.x).sum::<f64>() / 25.;
+/// assert!(mean > 0.6);
+/// ```
+pub fn relax<T, F>(
+    points: &mut [Point<T>],
+    boundary: &[Point<T>],
+    iterations: usize,
+    resolution: usize,
+    density: F,
+) where
+    T: Scalar + ApproxEq,
+    F: Fn(Point<T>) -> T,
+{
+    for _ in 0..iterations {
+        let triangulation = match Triangulation::<usize>::new(points) {
+            Some(triangulation) => triangulation,
+            None => return,
+        };
+        let centroids = weighted_centroids(&triangulation, points, boundary, resolution, &density);
+        for (p, centroid) in points.iter_mut().zip(centroids) {
+            if let Some(centroid) = centroid {
+                *p = centroid;
+            }
+        }
+    }
+}
+
+/// The centroid of `polygon` weighted by `density`, see [weighted_centroids].
+fn weighted_centroid<T: Scalar>(
+    polygon: &[Point<T>],
+    n: usize,
+    density: impl Fn(Point<T>) -> T,
+) -> Option<Point<T>> {
+    let zero = T::from_f64(0.0);
+    let (mut mass, mut x, mut y) = (zero, zero, zero);
+    let steps = T::from_f64(n as f64);
+    for k in 1..polygon.len().saturating_sub(1) {
+        // a fan of triangles with signed areas, so non-convex cells work too
+        let (a, b, c) = (polygon[0], polygon[k], polygon[k + 1]);
+        let area = (b - a).perp_dot(c - a) / (T::from_f64(2.0) * steps * steps);
+        let (u, v) = (b - a, c - a);
+        let at = |i: f64, j: f64| {
+            let (i, j) = (T::from_f64(i) / steps, T::from_f64(j) / steps);
+            Point::new(a.x + u.x * i + v.x * j, a.y + u.y * i + v.y * j)
+        };
+        let mut sample = |p: Point<T>| {
+            let w = density(p) * area;
+            mass = mass + w;
+            x = x + p.x * w;
+            y = y + p.y * w;
+        };
+        // the small triangles pointing like the big one, then those pointing
+        // the other way
+        for i in 0..n {
+            for j in 0..n - i {
+                let (i, j) = (i as f64, j as f64);
+                sample(at(i + 1.0 / 3.0, j + 1.0 / 3.0));
+                if i + j + 2.0 <= n as f64 {
+                    sample(at(i + 2.0 / 3.0, j + 2.0 / 3.0));
+                }
+            }
+        }
+    }
+    if mass > zero {
+        Some(Point::new(x / mass, y / mass))
+    } else {
+        None
+    }
+}
+
+/// Clips `boundary` to the Voronoi cell of each point, given its neighbors.
+/// Points without neighbors get an empty cell.
+fn clip_cells<T: Scalar, P: HasPosition<T>>(
+    points: &[P],
+    neighbors: &[Vec<usize>],
+    boundary: &[Point<T>],
+) -> Vec<Vec<Point<T>>> {
+    let boundary = counter_clockwise(boundary);
+    (0..points.len())
+        .map(|i| {
+            if neighbors[i].is_empty() {
+                return Vec::new();
+            }
+            let p = points[i].pos();
+            neighbors[i].iter().fold(boundary.clone(), |polygon, &j| {
+                let q = points[j].pos();
+                let half = T::from_f64(0.5);
+                let mid = Point::new((p.x + q.x) * half, (p.y + q.y) * half);
+                clip_half_plane(&polygon, mid, q - p)
+            })
+        })
+        .collect()
+}
+
+/// The neighbors of each point along the line through collinear `points`.
+/// Exact duplicates are left without neighbors.
+fn collinear_neighbors<T: Scalar, P: HasPosition<T>>(points: &[P]) -> Vec<Vec<usize>> {
+    let mut neighbors = vec![Vec::new(); points.len()];
+    let origin = match points.first() {
+        Some(p) => p.pos(),
+        None => return neighbors,
+    };
+    let direction = match points.iter().map(|p| p.pos()).find(|&p| p != origin) {
+        Some(p) => p - origin,
+        None => return neighbors,
+    };
+
+    let along = |i: usize| {
+        let d = points[i].pos() - origin;
+        d.x * direction.x + d.y * direction.y
+    };
+    let mut order: Vec<usize> = (0..points.len()).collect();
+    order.sort_by(|&a, &b| along(a).total_cmp(&along(b)).then(a.cmp(&b)));
+    order.dedup_by(|&mut b, &mut a| points[a].pos() == points[b].pos());
+    for w in order.windows(2) {
+        neighbors[w[0]].push(w[1]);
+        neighbors[w[1]].push(w[0]);
+    }
+    neighbors
+}
+
+/// `boundary` in counter-clockwise order.
+fn counter_clockwise<T: Scalar>(boundary: &[Point<T>]) -> Vec<Point<T>> {
+    let mut boundary = boundary.to_vec();
+    if util::signed_area(&boundary) < T::from_f64(0.0) {
+        boundary.reverse();
+    }
+    boundary
+}
+
+/// Clips `polygon` to the half-plane of points `x` with `(x - origin) · normal <= 0`
+/// (one step of Sutherland–Hodgman).
+pub(crate) fn clip_half_plane<T: Scalar>(
+    polygon: &[Point<T>],
+    origin: Point<T>,
+    normal: Point<T>,
+) -> Vec<Point<T>> {
+    let zero = T::from_f64(0.0);
+    let side = |p: Point<T>| {
+        let d = p - origin;
+        d.x * normal.x + d.y * normal.y
+    };
+
+    let mut result = Vec::with_capacity(polygon.len() + 1);
+    for (k, &p) in polygon.iter().enumerate() {
+        let q = polygon[(k + 1) % polygon.len()];
+        let (sp, sq) = (side(p), side(q));
+        if sp <= zero {
+            result.push(p);
+        }
+        if (sp < zero && sq > zero) || (sp > zero && sq < zero) {
+            let t = sp / (sp - sq);
+            result.push(Point::new(p.x + (q.x - p.x) * t, p.y + (q.y - p.y) * t));
+        }
+    }
+    result
+}
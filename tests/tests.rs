@@ -1,59 +1,3762 @@
-use delaunator::{Point, Triangulation};
+// `Index` may be changed to any index type, so casts to `usize` are intentional.
+#![allow(clippy::unnecessary_cast)]
+
+use delaunator::{
+    builder::{TieBreak, TriangulationBuilder, Triangulator},
+    iter::SpatialOrder,
+    kernel::FloatKernel,
+    source::{FlatPoints, SoaPoints},
+    Point, Triangle, Triangulation,
+};
 
 type Index = usize;
 
-#[test]
-fn basic() {
-    validate(&load_fixture(include_str!("fixtures/ukraine.json")));
+#[test]
+fn basic() {
+    validate(&load_fixture(include_str!("fixtures/ukraine.json")));
+}
+
+#[test]
+fn js_issues() {
+    validate(&load_fixture(include_str!("fixtures/issue5.json")));
+    validate(&load_fixture(include_str!("fixtures/issue11.json")));
+    validate(&load_fixture(include_str!("fixtures/issue13.json")));
+    validate(&load_fixture(include_str!("fixtures/issue24.json")));
+    validate(&load_fixture(include_str!("fixtures/issue43.json")));
+    validate(&load_fixture(include_str!("fixtures/issue44.json")));
+}
+
+#[test]
+fn robustness() {
+    let robustness1 = load_fixture(include_str!("fixtures/robustness1.json"));
+
+    validate(&robustness1);
+    validate(&(scale_points(&robustness1, 1e-9)));
+    validate(&(scale_points(&robustness1, 1e-2)));
+    validate(&(scale_points(&robustness1, 100.0)));
+    validate(&(scale_points(&robustness1, 1e9)));
+
+    let robustness2 = load_fixture(include_str!("fixtures/robustness2.json"));
+    validate(&robustness2[0..100]);
+    validate(&robustness2);
+}
+
+#[test]
+fn bad_input() {
+    let mut points = vec![Point { x: 0., y: 0. }];
+    assert!(
+        Triangulation::<Index>::new(&points).is_none(),
+        "Expected empty triangulation (1 point)"
+    );
+
+    points.push(Point { x: 1., y: 0. });
+    assert!(
+        Triangulation::<Index>::new(&points).is_none(),
+        "Expected empty triangulation (2 point)"
+    );
+
+    points.push(Point { x: 2., y: 0. });
+    assert!(
+        Triangulation::<Index>::new(&points).is_none(),
+        "Expected empty triangulation (collinear points)"
+    );
+
+    points.push(Point { x: 1., y: 1. });
+    validate(&points);
+}
+
+#[test]
+fn circumcenters() {
+    let points = load_fixture(include_str!("fixtures/ukraine.json"));
+    let triangulation = Triangulation::<Index>::new(&points).unwrap();
+    let centers = triangulation.circumcenters(&points);
+    assert_eq!(centers.len(), triangulation.len());
+
+    for (t, c) in triangulation.triangles.chunks(3).zip(centers) {
+        let ra = c.distance_squared(points[t[0] as usize]);
+        let rb = c.distance_squared(points[t[1] as usize]);
+        let rc = c.distance_squared(points[t[2] as usize]);
+        assert!((ra - rb).abs() <= 1e-9 * ra && (ra - rc).abs() <= 1e-9 * ra);
+    }
+}
+
+#[test]
+fn is_delaunay() {
+    let points = load_fixture(include_str!("fixtures/ukraine.json"));
+    let triangulation = Triangulation::<Index>::new(&points).unwrap();
+    assert!(triangulation.is_delaunay(&points));
+
+    let points = vec![
+        Point { x: 0., y: 0. },
+        Point { x: 2., y: 0. },
+        Point { x: 2., y: 1. },
+        Point { x: 0., y: 1.5 },
+    ];
+    let triangulation = Triangulation::<Index>::new(&points).unwrap();
+    assert!(triangulation.is_delaunay(&points));
+    assert!(triangulation.find_illegal_edge(&points).is_none());
+
+    // pull a vertex towards the shared diagonal so that it falls inside the
+    // circumcircle of the neighboring triangle
+    let diagonal = triangulation
+        .half_edges()
+        .find(|e| e.twin().is_some())
+        .unwrap();
+    let u = points[diagonal.start().id()];
+    let v = points[diagonal.end().id()];
+    let w = diagonal.prev().start().id();
+    let mid = Point {
+        x: (u.x + v.x) / 2.,
+        y: (u.y + v.y) / 2.,
+    };
+    let mut moved = points.clone();
+    moved[w] = Point {
+        x: mid.x + (points[w].x - mid.x) * 0.1,
+        y: mid.y + (points[w].y - mid.y) * 0.1,
+    };
+    assert!(!triangulation.is_delaunay(&moved));
+    let illegal = triangulation.find_illegal_edge(&moved).unwrap();
+    assert_eq!(
+        illegal.id().min(illegal.twin().unwrap().id()),
+        diagonal.id().min(diagonal.twin().unwrap().id())
+    );
+}
+
+#[test]
+fn from_triangles() {
+    // a fan over the points of an ellipse is valid but far from Delaunay
+    let points: Vec<_> = (0..24)
+        .map(|i| {
+            let a = i as f64 / 24. * std::f64::consts::TAU;
+            Point {
+                x: 3. * a.cos(),
+                y: a.sin(),
+            }
+        })
+        .collect();
+    let fan: Vec<Index> = (1..23)
+        .flat_map(|i| vec![0, i as Index, i as Index + 1])
+        .collect();
+
+    let triangulation = Triangulation::<Index>::from_triangles(&points, fan).unwrap();
+    assert_eq!(triangulation.len(), 22);
+    assert_eq!(triangulation.hull.len(), 24);
+    assert!(triangulation.is_delaunay(&points));
+
+    // clockwise input is accepted too
+    let clockwise = vec![0, 2, 1, 0, 3, 2];
+    let triangulation = Triangulation::<Index>::from_triangles(&points, clockwise).unwrap();
+    assert_eq!(triangulation.hull, vec![0, 1, 2, 3]);
+
+    let non_manifold = vec![0, 1, 2, 0, 1, 2];
+    assert!(Triangulation::<Index>::from_triangles(&points, non_manifold).is_err());
+}
+
+#[test]
+fn from_mesh() {
+    let points = load_fixture(include_str!("fixtures/ukraine.json"));
+    let expected = Triangulation::<Index>::new(&points).unwrap();
+
+    let triangulation =
+        Triangulation::<Index>::from_mesh(&points, expected.triangles.clone()).unwrap();
+    assert_eq!(triangulation.triangles, expected.triangles);
+    assert_eq!(triangulation.halfedges, expected.halfedges);
+    assert_eq!(triangulation.hull.len(), expected.hull.len());
+
+    // two triangles touching at a single point
+    let points = [
+        Point { x: 0., y: 0. },
+        Point { x: 1., y: 0. },
+        Point { x: 0., y: 1. },
+        Point { x: -1., y: 0. },
+        Point { x: 0., y: -1. },
+    ];
+    let bowtie = vec![0, 1, 2, 0, 3, 4];
+    assert_eq!(
+        Triangulation::<Index>::from_mesh(&points, bowtie).err(),
+        Some(delaunator::import::TopologyError::NonManifoldVertex { vertex: 0 })
+    );
+}
+
+#[test]
+fn normalize() {
+    let points = load_fixture(include_str!("fixtures/ukraine.json"));
+    let mut expected = Triangulation::<Index>::new(&points).unwrap();
+
+    // the same mesh with triangles reordered and rotated
+    let mut triangles: Vec<[Index; 3]> = expected
+        .triangles
+        .chunks(3)
+        .enumerate()
+        .map(|(i, t)| match i % 3 {
+            0 => [t[0], t[1], t[2]],
+            1 => [t[1], t[2], t[0]],
+            _ => [t[2], t[0], t[1]],
+        })
+        .collect();
+    triangles.reverse();
+    let triangles = triangles.into_iter().flatten().collect();
+    let mut triangulation = Triangulation::<Index>::from_mesh(&points, triangles).unwrap();
+    assert_ne!(triangulation.triangles, expected.triangles);
+
+    expected.normalize();
+    triangulation.normalize();
+    assert_eq!(triangulation.triangles, expected.triangles);
+    assert_eq!(triangulation.halfedges, expected.halfedges);
+    assert_eq!(triangulation.hull, expected.hull);
+    assert!(expected
+        .triangles
+        .chunks(3)
+        .all(|t| t[0] < t[1] && t[0] < t[2]));
+}
+
+#[test]
+fn tie_break() {
+    const W: usize = 8;
+    let points: Vec<_> = (0..W * W)
+        .map(|i| Point {
+            x: (i % W) as f64,
+            y: (i / W) as f64,
+        })
+        .collect();
+
+    let builder = TriangulationBuilder::new().tie_break(TieBreak::LowestIndex);
+    let mut a: Triangulation<usize> = builder.build(&points).unwrap();
+    let mut b: Triangulation<u16> = builder.build(&points).unwrap();
+    a.normalize();
+    b.normalize();
+    assert!(a
+        .triangles
+        .iter()
+        .copied()
+        .eq(b.triangles.iter().map(|&i| i as usize)));
+
+    assert_eq!(a.len(), 2 * (W - 1) * (W - 1));
+
+    // every grid cell is split along the diagonal from its lowest corner
+    let has_edge = |u: usize, v: usize| {
+        a.half_edges().any(|e| {
+            e.start().id() == u && e.end().id() == v || e.start().id() == v && e.end().id() == u
+        })
+    };
+    for y in 0..W - 1 {
+        for x in 0..W - 1 {
+            let k = y * W + x;
+            assert!(has_edge(k, k + W + 1));
+        }
+    }
+}
+
+#[test]
+fn hull_geometry() {
+    let points = [
+        Point { x: 0., y: 0. },
+        Point { x: 2., y: 0. },
+        Point { x: 2., y: 2. },
+        Point { x: 0., y: 2. },
+        Point { x: 1., y: 0.5 },
+    ];
+    let triangulation = Triangulation::<Index>::new(&points).unwrap();
+
+    assert_eq!(triangulation.hull_area(&points), 4.);
+    assert_eq!(triangulation.hull_perimeter(&points), 8.);
+    assert_eq!(triangulation.hull_centroid(&points), Point { x: 1., y: 1. });
+    assert_eq!(triangulation.hull_polygon(&points).len(), 4);
+    assert_eq!(triangulation.area(&points), 4.);
+    assert_eq!(triangulation.centroid(&points), Point { x: 1., y: 1. });
+}
+
+#[test]
+fn locate() {
+    let points = load_fixture(include_str!("fixtures/ukraine.json"));
+    let triangulation = Triangulation::<Index>::new(&points).unwrap();
+
+    for t in triangulation.triangles() {
+        let [a, b, c] = [t.a(), t.b(), t.c()].map(|v| points[v.id()]);
+        let centroid = Point {
+            x: (a.x + b.x + c.x) / 3.,
+            y: (a.y + b.y + c.y) / 3.,
+        };
+        assert_eq!(
+            triangulation.locate(&points, centroid).unwrap().id(),
+            t.id()
+        );
+    }
+
+    assert!(triangulation
+        .locate(&points, Point { x: 1e9, y: 1e9 })
+        .is_none());
+}
+
+#[test]
+fn triangles_in_bbox() {
+    let points = load_fixture(include_str!("fixtures/ukraine.json"));
+    let triangulation = Triangulation::<Index>::new(&points).unwrap();
+
+    let (min, max) = points.iter().fold(
+        (
+            Point {
+                x: f64::INFINITY,
+                y: f64::INFINITY,
+            },
+            Point {
+                x: -f64::INFINITY,
+                y: -f64::INFINITY,
+            },
+        ),
+        |(min, max), p| {
+            (
+                Point {
+                    x: min.x.min(p.x),
+                    y: min.y.min(p.y),
+                },
+                Point {
+                    x: max.x.max(p.x),
+                    y: max.y.max(p.y),
+                },
+            )
+        },
+    );
+    let lerp = |u: f64, v: f64| Point {
+        x: min.x + (max.x - min.x) * u,
+        y: min.y + (max.y - min.y) * v,
+    };
+
+    // the whole extent, a box in the middle, a box straddling a corner of the
+    // bounding box (center outside the hull) and a box outside the hull
+    let boxes = [
+        (lerp(0., 0.), lerp(1., 1.)),
+        (lerp(0.4, 0.4), lerp(0.6, 0.5)),
+        (lerp(-0.1, 0.3), lerp(0.1, 0.6)),
+        (lerp(1.1, 1.1), lerp(1.2, 1.2)),
+    ];
+    for &(bmin, bmax) in &boxes {
+        let found: Vec<usize> = triangulation
+            .triangles_in_bbox(&points, bmin, bmax)
+            .map(|t| t.id())
+            .collect();
+        let mut unique = found.clone();
+        unique.sort_unstable();
+        unique.dedup();
+        assert_eq!(unique.len(), found.len());
+
+        let inside =
+            |p: &Point<f64>| bmin.x <= p.x && p.x <= bmax.x && bmin.y <= p.y && p.y <= bmax.y;
+        for t in triangulation.triangles() {
+            let vertices: Vec<_> = t.vertices().map(|v| points[v.id()]).collect();
+            let overlaps = vertices.iter().any(|p| p.x >= bmin.x)
+                && vertices.iter().any(|p| p.x <= bmax.x)
+                && vertices.iter().any(|p| p.y >= bmin.y)
+                && vertices.iter().any(|p| p.y <= bmax.y);
+            if vertices.iter().any(inside) {
+                assert!(unique.binary_search(&t.id()).is_ok());
+            }
+            if !overlaps {
+                assert!(unique.binary_search(&t.id()).is_err());
+            }
+        }
+    }
+
+    assert_eq!(
+        triangulation
+            .triangles_in_bbox(&points, lerp(0., 0.), lerp(1., 1.))
+            .count(),
+        triangulation.len()
+    );
+    assert_eq!(
+        triangulation
+            .triangles_in_bbox(&points, lerp(1.1, 1.1), lerp(1.2, 1.2))
+            .count(),
+        0
+    );
+}
+
+#[test]
+fn spatially_ordered_triangles() {
+    let points = load_fixture(include_str!("fixtures/ukraine.json"));
+    let triangulation = Triangulation::<Index>::new(&points).unwrap();
+
+    let centroid = |t: &Triangle<'_, Index>| {
+        let (x, y) = t.vertices().fold((0., 0.), |(x, y), v| {
+            (x + points[v.id()].x, y + points[v.id()].y)
+        });
+        Point {
+            x: x / 3.,
+            y: y / 3.,
+        }
+    };
+    let path_length = |triangles: Vec<Triangle<'_, Index>>| {
+        triangles
+            .windows(2)
+            .map(|w| {
+                let (a, b) = (centroid(&w[0]), centroid(&w[1]));
+                ((a.x - b.x).powi(2) + (a.y - b.y).powi(2)).sqrt()
+            })
+            .sum::<f64>()
+    };
+
+    let unordered = path_length(triangulation.triangles().collect());
+    for &order in &[SpatialOrder::Hilbert, SpatialOrder::Morton] {
+        let triangles: Vec<_> = triangulation
+            .spatially_ordered_triangles(&points, order)
+            .collect();
+        let mut ids: Vec<usize> = triangles.iter().map(|t| t.id()).collect();
+        ids.sort_unstable();
+        assert!(ids.iter().copied().eq(0..triangulation.len()));
+        assert!(path_length(triangles) < unordered);
+    }
+}
+
+#[test]
+fn with_visitor() {
+    let fixtures = [
+        load_fixture(include_str!("fixtures/ukraine.json")),
+        load_fixture(include_str!("fixtures/robustness1.json")),
+        load_fixture(include_str!("fixtures/issue44.json")),
+    ];
+    for points in &fixtures {
+        let mut visited = Vec::new();
+        let triangulation =
+            Triangulation::<Index>::with_visitor(points, |t| visited.push(t)).unwrap();
+
+        let mut expected: Vec<[usize; 3]> = triangulation
+            .triangles
+            .chunks_exact(3)
+            .map(|t| [t[0] as usize, t[1] as usize, t[2] as usize])
+            .collect();
+        expected.sort_unstable();
+        visited.sort_unstable();
+        assert_eq!(visited, expected);
+    }
+
+    let collinear = [
+        Point { x: 0., y: 0. },
+        Point { x: 1., y: 0. },
+        Point { x: 2., y: 0. },
+    ];
+    let mut visited = 0;
+    assert!(Triangulation::<Index>::with_visitor(&collinear, |_| visited += 1).is_none());
+    assert_eq!(visited, 0);
+}
+
+#[test]
+fn compact() {
+    let mut points = load_fixture(include_str!("fixtures/ukraine.json"));
+    points.insert(0, points[10]);
+    points.push(points[20]);
+    let triangulation = Triangulation::<Index>::new(&points).unwrap();
+
+    let (compacted, mapping) = triangulation.compact(&points);
+    assert_eq!(mapping.len(), points.len());
+    for (i, &j) in mapping.iter().enumerate() {
+        let used = triangulation.triangles.contains(&(i as Index));
+        assert_eq!(used, j != Index::MAX);
+    }
+    assert!(mapping[0] == Index::MAX || mapping[11] == Index::MAX);
+
+    let kept: Vec<Point<f64>> = points
+        .iter()
+        .zip(&mapping)
+        .filter(|(_, &i)| i != Index::MAX)
+        .map(|(p, _)| *p)
+        .collect();
+    assert_eq!(compacted.len(), triangulation.len());
+    for (a, b) in compacted.triangles.iter().zip(&triangulation.triangles) {
+        assert_eq!(kept[*a as usize], points[*b as usize]);
+    }
+    assert_eq!(compacted.halfedges, triangulation.halfedges);
+    assert_eq!(compacted.hull.len(), triangulation.hull.len());
+    assert!(compacted.is_delaunay(&kept));
+}
+
+#[test]
+fn tiled() {
+    // a deterministic pseudo-random point set without cocircular points
+    let mut seed = 1u64;
+    let mut random = || {
+        seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (seed >> 11) as f64 / (1u64 << 53) as f64
+    };
+    let mut points: Vec<Point<f64>> = (0..3000)
+        .map(|_| Point {
+            x: random() * 100.,
+            y: random() * 50.,
+        })
+        .collect();
+    // include a sparse cluster whose hull triangles reach across many tiles
+    points.extend((0..20).map(|i| Point {
+        x: 150. + i as f64,
+        y: 5. * random(),
+    }));
+
+    let mut expected = Triangulation::<Index>::new(&points).unwrap();
+    expected.normalize();
+
+    let dir = std::env::temp_dir().join(format!("delaunator-tiled-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    for &tile_size in &[10, 100, 1000, 100_000] {
+        let mut triangles = Vec::new();
+        let len = delaunator::tiled::triangulate(&points[..], tile_size, &dir, |t| {
+            triangles.extend(t.iter().map(|&i| i as Index))
+        })
+        .unwrap()
+        .unwrap();
+        assert_eq!(len, expected.len());
+
+        let mut tiled = Triangulation::<Index>::from_mesh(&points, triangles).unwrap();
+        tiled.normalize();
+        assert_eq!(tiled.triangles, expected.triangles);
+    }
+
+    // the tiles can be streamed from any point source
+    let coords: Vec<f64> = points.iter().flat_map(|p| vec![p.x, p.y]).collect();
+    let source = delaunator::source::FlatPoints::new(&coords);
+    let len = delaunator::tiled::triangulate(&source, 300, &dir, |_| {})
+        .unwrap()
+        .unwrap();
+    assert_eq!(len, expected.len());
+
+    let collinear = [
+        Point { x: 0., y: 0. },
+        Point { x: 1., y: 0. },
+        Point { x: 2., y: 0. },
+    ];
+    assert!(
+        delaunator::tiled::triangulate(&collinear[..], 1, &dir, |_| {})
+            .unwrap()
+            .is_none()
+    );
+
+    // the scratch files are cleaned up
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+    std::fs::remove_dir(&dir).unwrap();
+}
+
+#[test]
+fn from_source() {
+    let points = load_fixture(include_str!("fixtures/ukraine.json"));
+    let expected = Triangulation::<Index>::new(&points).unwrap();
+
+    let flat: Vec<f64> = points.iter().flat_map(|p| [p.x, p.y]).collect();
+    let triangulation = Triangulation::<Index>::from_source(&FlatPoints::new(&flat)).unwrap();
+    assert_eq!(triangulation.triangles, expected.triangles);
+    assert_eq!(triangulation.halfedges, expected.halfedges);
+    assert_eq!(triangulation.hull, expected.hull);
+
+    let triangulation = Triangulation::<Index>::from_flat_coords(&flat).unwrap();
+    assert_eq!(triangulation.triangles, expected.triangles);
+    assert_eq!(triangulation.halfedges, expected.halfedges);
+
+    let (triangulation, collected) =
+        Triangulation::<Index>::from_iter(flat.chunks_exact(2).map(|c| Point { x: c[0], y: c[1] }))
+            .unwrap();
+    assert!(collected == points);
+    assert_eq!(triangulation.triangles, expected.triangles);
+
+    let xs: Vec<f64> = points.iter().map(|p| p.x).collect();
+    let ys: Vec<f64> = points.iter().map(|p| p.y).collect();
+    let triangulation = Triangulation::<Index>::from_source(&SoaPoints::new(&xs, &ys)).unwrap();
+    assert_eq!(triangulation.triangles, expected.triangles);
+    assert_eq!(triangulation.halfedges, expected.halfedges);
+    assert_eq!(triangulation.hull, expected.hull);
+
+    assert!(Triangulation::<Index>::from_source(&FlatPoints::new(&[0., 0., 1., 1.])).is_none());
+}
+
+#[test]
+fn dedup() {
+    let points = load_fixture(include_str!("fixtures/ukraine.json"));
+    let expected = TriangulationBuilder::new()
+        .dedup(0.)
+        .build::<Index, _, _>(&points)
+        .unwrap();
+
+    // append exact and near duplicates, scattered throughout the point set
+    let mut noisy = points.clone();
+    for (i, p) in points.iter().enumerate().step_by(3) {
+        let offset = if i % 2 == 0 { 1e-9 } else { -1e-9 };
+        noisy.push(*p);
+        noisy.push(Point {
+            x: p.x + offset,
+            y: p.y - offset,
+        });
+    }
+    let triangulation = TriangulationBuilder::new()
+        .dedup(1e-6)
+        .build::<Index, _, _>(&noisy)
+        .unwrap();
+    assert!(triangulation
+        .triangles
+        .iter()
+        .all(|&i| (i as usize) < points.len()));
+    assert_eq!(triangulation.len(), expected.len());
+    validate_triangulation(&noisy, &triangulation);
+
+    let collinear = [
+        Point { x: 0., y: 0. },
+        Point { x: 1., y: 0. },
+        Point { x: 0., y: 1e-9 },
+    ];
+    assert!(TriangulationBuilder::new()
+        .dedup(1e-6)
+        .build::<Index, _, _>(&collinear)
+        .is_none());
+
+    // only kept points remove later ones, so the end of a chain survives
+    let chain = [
+        Point { x: 0., y: 0. },
+        Point { x: 0.6e-6, y: 0. },
+        Point { x: 1.2e-6, y: 0. },
+        Point { x: 1., y: 1. },
+        Point { x: -1., y: 1. },
+    ];
+    let triangulation = TriangulationBuilder::new()
+        .dedup(1e-6)
+        .build::<Index, _, _>(&chain)
+        .unwrap();
+    assert!(triangulation.triangles.contains(&2));
+    assert!(!triangulation.triangles.contains(&1));
+}
+
+#[test]
+fn triangulator() {
+    let fixtures = [
+        load_fixture(include_str!("fixtures/ukraine.json")),
+        load_fixture(include_str!("fixtures/issue13.json")),
+        load_fixture(include_str!("fixtures/robustness1.json")),
+        load_fixture(include_str!("fixtures/issue43.json")),
+    ];
+
+    let mut triangulator = TriangulationBuilder::new()
+        .tie_break(TieBreak::LowestIndex)
+        .triangulator::<Index, f64>();
+    for _ in 0..2 {
+        for points in &fixtures {
+            let expected = TriangulationBuilder::new()
+                .tie_break(TieBreak::LowestIndex)
+                .build::<Index, _, _>(points)
+                .unwrap();
+            let triangulation = triangulator.triangulate(points).unwrap();
+            assert_eq!(triangulation.triangles, expected.triangles);
+            assert_eq!(triangulation.halfedges, expected.halfedges);
+            assert_eq!(triangulation.hull, expected.hull);
+        }
+    }
+
+    let collinear = [
+        Point { x: 0., y: 0. },
+        Point { x: 1., y: 0. },
+        Point { x: 2., y: 0. },
+    ];
+    assert!(triangulator.triangulate(&collinear).is_none());
+}
+
+#[test]
+fn kernel() {
+    let points = load_fixture(include_str!("fixtures/ukraine.json"));
+    let expected = Triangulation::<Index>::new(&points).unwrap();
+    let triangulation = TriangulationBuilder::new()
+        .kernel::<FloatKernel>()
+        .build::<Index, _, _>(&points)
+        .unwrap();
+    assert_eq!(triangulation.triangles, expected.triangles);
+    assert_eq!(triangulation.halfedges, expected.halfedges);
+}
+
+#[test]
+fn profile() {
+    let points = load_fixture(include_str!("fixtures/ukraine.json"));
+    let expected = Triangulation::<Index>::new(&points).unwrap();
+    let (triangulation, profile) = TriangulationBuilder::new()
+        .build_with_profile::<Index, _, _>(&points)
+        .unwrap();
+    assert_eq!(triangulation.triangles, expected.triangles);
+
+    // every point in the triangulation except the seed was inserted, and
+    // probed the hash at least once
+    let used: std::collections::HashSet<_> = triangulation.triangles.iter().collect();
+    assert_eq!(profile.inserted, used.len() - 3);
+    assert!(profile.hash_probes >= profile.inserted);
+    assert!(profile.flips > 0);
+    assert!(profile.total() >= profile.insert);
+}
+
+#[test]
+fn geographic() {
+    use delaunator::geographic::{Projection, Projector, EARTH_RADIUS};
+
+    // one degree along the equator, crossing the antimeridian
+    let lonlat = [
+        Point { x: 179.5, y: 0. },
+        Point { x: -179.5, y: 0. },
+        Point { x: 179.5, y: 1. },
+    ];
+    let degree = EARTH_RADIUS.to_radians();
+    let wrapped = Projector::new(Projection::WebMercator).project(&lonlat);
+    assert!(wrapped[0].distance(wrapped[1]) > 300. * degree);
+
+    for &projection in &[Projection::AzimuthalEquidistant, Projection::WebMercator] {
+        let (triangulation, points) = Projector::new(projection)
+            .unwrap_antimeridian(true)
+            .triangulate::<Index, _>(&lonlat)
+            .unwrap();
+        assert_eq!(triangulation.len(), 1);
+        assert!((points[0].distance(points[1]) - degree).abs() < 1e-3 * degree);
+    }
+}
+
+#[test]
+fn cut_fill() {
+    use delaunator::tin;
+
+    // z = x over the unit square, against the plane z = 0.5 given as a grid
+    let existing_points = [
+        Point { x: 0., y: 0. },
+        Point { x: 1., y: 0. },
+        Point { x: 1., y: 1. },
+        Point { x: 0., y: 1. },
+    ];
+    let existing_z: Vec<f64> = existing_points.iter().map(|p| p.x).collect();
+    let existing = Triangulation::<Index>::new(&existing_points).unwrap();
+
+    let design_points: Vec<_> = (0..9)
+        .map(|i| Point {
+            x: (i % 3) as f64 / 2.,
+            y: (i / 3) as f64 / 2.,
+        })
+        .collect();
+    let design_z = vec![0.5; design_points.len()];
+    let design = Triangulation::<Index>::new(&design_points).unwrap();
+
+    let plane = tin::cut_fill_plane(&existing, &existing_points, &existing_z, 0.5);
+    let surfaces = tin::cut_fill(
+        &existing,
+        &existing_points,
+        &existing_z,
+        &design,
+        &design_points,
+        &design_z,
+    );
+    for volume in &[plane, surfaces] {
+        assert!((volume.cut - 0.125).abs() < 1e-12);
+        assert!((volume.fill - 0.125).abs() < 1e-12);
+        assert!(volume.net().abs() < 1e-12);
+    }
+
+    let below = tin::cut_fill_plane(&existing, &existing_points, &existing_z, -1.);
+    assert!((below.cut - 1.5).abs() < 1e-12);
+    assert_eq!(below.fill, 0.);
+}
+
+#[test]
+fn line_of_sight() {
+    use delaunator::tin::{self, Sightline};
+
+    // flat terrain with a ridge of height 10 along x = 2
+    let points: Vec<_> = (0..25)
+        .map(|i| Point {
+            x: (i % 5) as f64,
+            y: (i / 5) as f64,
+        })
+        .collect();
+    let z: Vec<f64> = points
+        .iter()
+        .map(|p| if p.x == 2. { 10. } else { 0. })
+        .collect();
+    let triangulation = Triangulation::<Index>::new(&points).unwrap();
+
+    let low = |x: f64, h: f64| (Point { x, y: 2.3 }, h);
+    match tin::line_of_sight(low(0., 1.), low(4., 1.), &triangulation, &points, &z) {
+        Sightline::Blocked(p) => {
+            assert!((p.x - 1.1).abs() < 1e-9, "{:?}", p);
+            assert!((p.y - 2.3).abs() < 1e-9);
+        }
+        Sightline::Clear => panic!("sightline should be blocked"),
+    }
+    assert!(tin::line_of_sight(low(0., 11.), low(4., 11.), &triangulation, &points, &z).is_clear());
+    assert!(tin::line_of_sight(low(0., 1.), low(0.9, 1.), &triangulation, &points, &z).is_clear());
+}
+
+#[test]
+fn flow() {
+    use delaunator::tin;
+
+    // a valley along y = 2 that falls towards x = 0
+    let points: Vec<_> = (0..25)
+        .map(|i| Point {
+            x: (i % 5) as f64,
+            y: (i / 5) as f64,
+        })
+        .collect();
+    let z: Vec<f64> = points
+        .iter()
+        .map(|p| (p.y - 2.).abs() + 0.5 * p.x)
+        .collect();
+    let triangulation = Triangulation::<Index>::new(&points).unwrap();
+    let height = |p: Point<f64>| tin::interpolate(&triangulation, &points, &z, p).unwrap();
+
+    let path = tin::flow_path(&triangulation, &points, &z, Point { x: 3.5, y: 3.7 });
+    assert_eq!(path.first(), Some(&Point { x: 3.5, y: 3.7 }));
+    assert_eq!(path.last(), Some(&Point { x: 0., y: 2. }));
+    for w in path.windows(2) {
+        assert!(height(w[1]) < height(w[0]) + 1e-12);
+    }
+    assert!(tin::flow_path(&triangulation, &points, &z, Point { x: 5., y: 5. }).is_empty());
+
+    let accumulation = tin::flow_accumulation(&triangulation, &points, &z);
+    let (outlet, &most) = accumulation
+        .iter()
+        .enumerate()
+        .max_by(|a, b| a.1.partial_cmp(b.1).unwrap())
+        .unwrap();
+    assert!(triangulation.triangles[3 * outlet..3 * outlet + 3]
+        .iter()
+        .any(|&i| points[i as usize] == Point { x: 0., y: 2. }));
+    assert!(most > 0.5 && most <= 16. + 1e-12);
+
+    // a missing elevation doesn't panic
+    let mut holes = z.clone();
+    holes[12] = f64::NAN;
+    let accumulation = tin::flow_accumulation(&triangulation, &points, &holes);
+    assert_eq!(accumulation.len(), triangulation.len());
+}
+
+#[test]
+fn voronoi_clip_to_polygon() {
+    use delaunator::voronoi;
+
+    fn area(polygon: &[Point<f64>]) -> f64 {
+        (0..polygon.len())
+            .map(|k| {
+                let (p, q) = (polygon[k], polygon[(k + 1) % polygon.len()]);
+                p.x * q.y - p.y * q.x
+            })
+            .sum::<f64>()
+            / 2.
+    }
+
+    let points = load_fixture(include_str!("fixtures/ukraine.json"));
+    let triangulation = Triangulation::<Index>::new(&points).unwrap();
+    let hull = triangulation.hull_polygon(&points);
+    let (cx, cy) = (
+        hull.iter().map(|p| p.x).sum::<f64>() / hull.len() as f64,
+        hull.iter().map(|p| p.y).sum::<f64>() / hull.len() as f64,
+    );
+    let r = hull
+        .iter()
+        .map(|p| (p.x - cx).hypot(p.y - cy))
+        .fold(0., f64::max);
+
+    // a clockwise star, which isn't convex, around the center of the points
+    let star: Vec<Point<f64>> = (0..10)
+        .map(|k| {
+            let angle = -(k as f64) * std::f64::consts::PI / 5.;
+            let radius = if k % 2 == 0 { r } else { r / 3. };
+            Point {
+                x: cx + radius * angle.cos(),
+                y: cy + radius * angle.sin(),
+            }
+        })
+        .collect();
+
+    let cells = voronoi::clip_to_polygon(&triangulation, &points, &star);
+    assert_eq!(cells.len(), points.len());
+
+    // the cells partition the star
+    let total: f64 = cells.iter().map(|cell| area(cell)).sum();
+    let min = cells.iter().map(|cell| area(cell)).fold(0., f64::min);
+    assert!(min > -1e-12 * area(&star).abs());
+    assert!((total + area(&star)).abs() < 1e-9 * area(&star).abs());
+}
+
+#[test]
+fn triangulate_polygons() {
+    use delaunator::polygon;
+
+    // a clockwise L shape with a closing vertex, and a counter-clockwise comb
+    let l_shape: Vec<Point<f64>> = [(0, 0), (0, 2), (1, 2), (1, 1), (2, 1), (2, 0), (0, 0)]
+        .iter()
+        .map(|&(x, y)| Point {
+            x: x as f64,
+            y: y as f64,
+        })
+        .collect();
+    let mut comb = vec![Point { x: 0., y: 0. }, Point { x: 9., y: 0. }];
+    for k in (0..5).rev() {
+        let x = 2. * k as f64;
+        comb.extend([Point { x: x + 1., y: 3. }, Point { x, y: 3. }]);
+        if k > 0 {
+            comb.extend([Point { x, y: 1. }, Point { x: x - 1., y: 1. }]);
+        }
+    }
+    let polygons = vec![l_shape, Vec::new(), comb.clone()];
+
+    let batch = polygon::triangulate_polygons::<Index, _, _, _>(&polygons);
+    assert_eq!(batch.polygons.len(), 3);
+    assert_eq!(batch.polygons[0].vertices, 0..6);
+    assert_eq!(batch.polygons[1].vertices, 6..6);
+    assert!(batch.polygons[1].triangles.is_empty());
+    assert_eq!(batch.polygons[2].vertices, 6..6 + comb.len());
+
+    let expected_area = [3., 0., 9. + 5. * 2.];
+    for (range, &expected) in batch.polygons.iter().zip(&expected_area) {
+        let triangles = &batch.triangles[range.triangles.clone()];
+        assert_eq!(triangles.len(), 3 * range.vertices.len().saturating_sub(2));
+        let mut area = 0.;
+        for t in triangles.chunks_exact(3) {
+            let [a, b, c] = [t[0], t[1], t[2]].map(|i| batch.vertices[i as usize]);
+            assert!(range.vertices.contains(&(t[0] as usize)));
+            let twice = (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x);
+            assert!(twice > 0.);
+            area += twice / 2.;
+        }
+        assert!((area - expected).abs() < 1e-12);
+    }
+}
+
+#[test]
+fn cluster_by_edge_length() {
+    use delaunator::graphs::{self, EdgeCutoff};
+
+    // a dense and a sparse grid far apart, plus an outlier and a duplicate
+    let mut points: Vec<_> = (0..25)
+        .map(|i| Point {
+            x: (i % 5) as f64,
+            y: (i / 5) as f64,
+        })
+        .collect();
+    points.extend((0..25).map(|i| Point {
+        x: 30. + 3. * (i % 5) as f64,
+        y: 3. * (i / 5) as f64,
+    }));
+    points.push(Point { x: 15., y: 40. });
+    points.push(points[0]);
+    let triangulation = Triangulation::<Index>::new(&points).unwrap();
+
+    for &cutoff in &[EdgeCutoff::Length(5.), EdgeCutoff::LocalDeviations(1.)] {
+        let labels = graphs::cluster_by_edge_length(&triangulation, &points, cutoff);
+        assert!(labels[..25].iter().all(|&l| l == Some(0)), "{:?}", cutoff);
+        assert!(labels[25..50].iter().all(|&l| l == Some(1)), "{:?}", cutoff);
+        assert_eq!(labels[50], Some(2));
+        assert_eq!(labels[51], None);
+    }
+}
+
+#[test]
+fn snap_to_grid() {
+    use delaunator::snap;
+
+    // a jittered grid where every point is duplicated with a little noise
+    let points: Vec<_> = (0..200)
+        .map(|i| {
+            let k = i / 2;
+            let jitter = if i % 2 == 0 { 0.01 } else { -0.01 };
+            Point {
+                x: 1e7 + (k % 10) as f64 + jitter,
+                y: (k / 10) as f64 - jitter,
+            }
+        })
+        .collect();
+
+    let snapped = snap::snap_to_grid(&points, 0.5);
+    assert_eq!(snapped.points.len(), 100);
+    for (i, &j) in snapped.mapping.iter().enumerate() {
+        assert_eq!(j, i / 2);
+        let (p, q) = (points[i], snapped.points[j]);
+        assert!((p.x - q.x).abs() <= 0.25 && (p.y - q.y).abs() <= 0.25);
+    }
+    assert_eq!(snapped.points[11], Point { x: 1e7 + 1., y: 1. });
+    validate(&snapped.points);
+}
+
+#[test]
+fn level_of_detail() {
+    use delaunator::tin::LevelOfDetail;
+
+    let points = load_fixture(include_str!("fixtures/ukraine.json"));
+    let z: Vec<f64> = points
+        .iter()
+        .map(|p| (p.x * 0.1).sin() + (p.y * 0.07).cos())
+        .collect();
+    let full = Triangulation::<Index>::new(&points).unwrap();
+
+    let lod = LevelOfDetail::new(&points, &z).unwrap();
+    assert!(lod.levels() > 2);
+    let sizes: Vec<usize> = (0..lod.levels())
+        .map(|level| {
+            let triangulation = lod.level::<Index, _>(&points, level);
+            validate_triangulation(&points, &triangulation);
+            triangulation.len()
+        })
+        .collect();
+    assert!(sizes.windows(2).all(|w| w[0] < w[1]), "{:?}", sizes);
+    assert_eq!(sizes[sizes.len() - 1], full.len());
+    assert!(lod
+        .splits()
+        .windows(2)
+        .all(|w| 1 <= w[0].level && w[0].level <= w[1].level));
+
+    let none = lod.refine::<Index, _, _>(&points, |_, _| false);
+    let all = lod.refine::<Index, _, _>(&points, |_, _| true);
+    assert_eq!(none.len(), sizes[0]);
+    assert_eq!(all.len(), full.len());
+
+    // a plane simplifies down to its convex hull
+    let z: Vec<f64> = points.iter().map(|p| 2. * p.x - p.y).collect();
+    let lod = LevelOfDetail::new(&points, &z).unwrap();
+    let coarse = lod.level::<Index, _>(&points, 0);
+    assert_eq!(lod.base().len(), coarse.hull.len());
+}
+
+#[test]
+fn voronoi_diagram() {
+    use delaunator::voronoi::Voronoi;
+
+    let points = load_fixture(include_str!("fixtures/ukraine.json"));
+    let triangulation = Triangulation::<Index>::new(&points).unwrap();
+    let voronoi = Voronoi::new(&triangulation, &points);
+    assert_eq!(voronoi.len(), points.len());
+    assert_eq!(voronoi.circumcenters.len(), triangulation.len());
+
+    let on_hull = |i: usize| triangulation.hull.iter().any(|&h| h as usize == i);
+    let mut vertices = 0;
+    assert_eq!(voronoi.cells().len(), points.len());
+    for cell in voronoi.cells() {
+        let (i, ids): (usize, Vec<usize>) = (cell.id(), cell.vertex_ids().collect());
+        assert_eq!(cell.site(), points[i]);
+        // skipped duplicates have no cell
+        assert_eq!(cell.is_bounded(), !on_hull(i) && !ids.is_empty());
+        vertices += ids.len();
+        // every vertex of the cell is equidistant from its site and the other
+        // corners of its triangle
+        for (&t, p) in ids.iter().zip(cell.vertices()) {
+            assert_eq!(p, voronoi.circumcenters[t]);
+            let r = p.distance(points[i]);
+            for k in 0..3 {
+                let q = points[triangulation.triangles[3 * t + k] as usize];
+                assert!((p.distance(q) - r).abs() <= 1e-6 * r.max(1.));
+            }
+        }
+    }
+    // every triangle is a vertex of the cells of its three corners
+    assert_eq!(vertices, 3 * triangulation.len());
+}
+
+#[test]
+fn voronoi_clip_to_convex() {
+    use delaunator::voronoi::Voronoi;
+
+    fn area(polygon: &[Point<f64>]) -> f64 {
+        (0..polygon.len())
+            .map(|k| {
+                let (p, q) = (polygon[k], polygon[(k + 1) % polygon.len()]);
+                p.x * q.y - p.y * q.x
+            })
+            .sum::<f64>()
+            / 2.
+    }
+
+    let points = load_fixture(include_str!("fixtures/ukraine.json"));
+    let triangulation = Triangulation::<Index>::new(&points).unwrap();
+    let voronoi = Voronoi::new(&triangulation, &points);
+    let (mut min, mut max) = (points[0], points[0]);
+    for p in &points {
+        min = Point::new(min.x.min(p.x), min.y.min(p.y));
+        max = Point::new(max.x.max(p.x), max.y.max(p.y));
+    }
+    let center = Point::new((min.x + max.x) / 2., (min.y + max.y) / 2.);
+    let radius = (max.x - min.x).min(max.y - min.y) / 2.;
+
+    // a clockwise hexagon inside the points
+    let hexagon: Vec<_> = (0..6)
+        .map(|k| {
+            let angle = -(k as f64) * std::f64::consts::TAU / 6.;
+            Point::new(
+                center.x + radius * angle.cos(),
+                center.y + radius * angle.sin(),
+            )
+        })
+        .collect();
+    let cells = voronoi.clip_to_convex(&hexagon);
+    assert_eq!(cells.len(), points.len());
+    let total: f64 = cells.iter().map(|cell| area(cell)).sum();
+    let expected = -area(&hexagon);
+    assert!((total - expected).abs() < 1e-9 * expected);
+    for cell in cells.iter().filter(|cell| cell.len() >= 3) {
+        assert!(area(cell) >= 0.);
+        for q in cell {
+            assert!(q.distance(center) <= radius + 1e-9);
+        }
+    }
+
+    // a rectangle gives the same cells as clip_to_rect
+    let rect = [min, Point::new(max.x, min.y), max, Point::new(min.x, max.y)];
+    assert_eq!(
+        voronoi.clip_to_convex(&rect),
+        voronoi.clip_to_rect(min, max)
+    );
+}
+
+#[test]
+fn order2_voronoi() {
+    use delaunator::voronoi::Order2Voronoi;
+
+    fn area(polygon: &[Point<f64>]) -> f64 {
+        (0..polygon.len())
+            .map(|k| {
+                let (p, q) = (polygon[k], polygon[(k + 1) % polygon.len()]);
+                p.x * q.y - p.y * q.x
+            })
+            .sum::<f64>()
+            / 2.
+    }
+
+    let points = load_fixture(include_str!("fixtures/ukraine.json"));
+    let triangulation = Triangulation::<Index>::new(&points).unwrap();
+    let diagram = Order2Voronoi::new(&triangulation, &points);
+    let hull = triangulation.hull.len();
+    assert_eq!(2 * diagram.len() - hull, triangulation.triangles.len());
+
+    let (mut min, mut max) = (points[0], points[0]);
+    for p in &points {
+        min = Point::new(min.x.min(p.x), min.y.min(p.y));
+        max = Point::new(max.x.max(p.x), max.y.max(p.y));
+    }
+    let regions = diagram.clip_to_rect(min, max);
+    assert_eq!(regions.len(), diagram.len());
+    // the regions cover the rectangle without overlapping
+    let total: f64 = regions.iter().map(|region| area(region)).sum();
+    let expected = (max.x - min.x) * (max.y - min.y);
+    assert!((total - expected).abs() < 1e-9 * expected);
+
+    // sample points lie in the region of their two nearest points
+    let sites: Vec<usize> = (0..points.len())
+        .filter(|&i| diagram.voronoi.cell(i).vertex_ids().next().is_some())
+        .collect();
+    for k in 0..400 {
+        let (u, v) = ((k % 20) as f64 + 0.5, (k / 20) as f64 + 0.5);
+        let x = Point::new(
+            min.x + (max.x - min.x) * u / 20.,
+            min.y + (max.y - min.y) * v / 20.,
+        );
+        let mut nearest = sites.clone();
+        nearest.sort_by(|&i, &j| {
+            x.distance(points[i])
+                .partial_cmp(&x.distance(points[j]))
+                .unwrap()
+        });
+        let pair = (nearest[0].min(nearest[1]), nearest[0].max(nearest[1]));
+        let region = diagram.pairs.iter().position(|&p| p == pair).unwrap();
+        let polygon = &regions[region];
+        for j in 0..polygon.len() {
+            let (a, b) = (polygon[j], polygon[(j + 1) % polygon.len()]);
+            assert!((b - a).perp_dot(x - a) >= -1e-6);
+        }
+    }
+}
+
+#[test]
+fn voronoi_neighbors() {
+    use delaunator::voronoi::Voronoi;
+    use std::collections::BTreeSet;
+
+    let points = load_fixture(include_str!("fixtures/ukraine.json"));
+    let triangulation = Triangulation::<Index>::new(&points).unwrap();
+    let voronoi = Voronoi::new(&triangulation, &points);
+
+    // cells are neighbors exactly when their sites share a Delaunay edge
+    let mut adjacent = vec![BTreeSet::new(); points.len()];
+    for t in triangulation.triangles.chunks_exact(3) {
+        for k in 0..3 {
+            adjacent[t[k] as usize].insert(t[(k + 1) % 3] as usize);
+            adjacent[t[(k + 1) % 3] as usize].insert(t[k] as usize);
+        }
+    }
+    for cell in voronoi.cells() {
+        let neighbors: Vec<usize> = cell.neighbors().collect();
+        let unique: BTreeSet<usize> = neighbors.iter().copied().collect();
+        assert_eq!(unique.len(), neighbors.len());
+        assert_eq!(unique, adjacent[cell.id()]);
+        // an unbounded cell has one more neighbor than vertices
+        let extra = usize::from(!cell.is_bounded() && !neighbors.is_empty());
+        assert_eq!(neighbors.len(), cell.vertex_ids().count() + extra);
+    }
+}
+
+#[test]
+fn voronoi_clip_to_rect() {
+    use delaunator::voronoi::Voronoi;
+
+    fn area(polygon: &[Point<f64>]) -> f64 {
+        (0..polygon.len())
+            .map(|k| {
+                let (p, q) = (polygon[k], polygon[(k + 1) % polygon.len()]);
+                p.x * q.y - p.y * q.x
+            })
+            .sum::<f64>()
+            / 2.
+    }
+
+    let points = load_fixture(include_str!("fixtures/ukraine.json"));
+    let triangulation = Triangulation::<Index>::new(&points).unwrap();
+    let voronoi = Voronoi::new(&triangulation, &points);
+    let (mut min, mut max) = (points[0], points[0]);
+    for p in &points {
+        min = Point::new(min.x.min(p.x), min.y.min(p.y));
+        max = Point::new(max.x.max(p.x), max.y.max(p.y));
+    }
+    // a margin, and a rectangle that cuts through the points
+    let margin = Point::new(1., 1.);
+    for (min, max) in [
+        (min - margin, max + margin),
+        (min, Point::new((min.x + max.x) / 2., max.y)),
+    ] {
+        let cells = voronoi.clip_to_rect(min, max);
+        assert_eq!(cells.len(), points.len());
+        let total: f64 = cells.iter().map(|cell| area(cell)).sum();
+        let expected = (max.x - min.x) * (max.y - min.y);
+        assert!((total - expected).abs() < 1e-9 * expected);
+        for (p, cell) in points.iter().zip(&cells) {
+            for q in cell {
+                assert!(q.x >= min.x - 1e-9 && q.x <= max.x + 1e-9);
+                assert!(q.y >= min.y - 1e-9 && q.y <= max.y + 1e-9);
+            }
+            if cell.len() >= 3 && p.x < max.x - 1e-9 {
+                assert!(area(cell) > 0.);
+            }
+        }
+    }
+}
+
+#[test]
+fn voronoi_fracture() {
+    use delaunator::voronoi;
+
+    let square = [
+        Point { x: 0., y: 0. },
+        Point { x: 4., y: 0. },
+        Point { x: 4., y: 4. },
+        Point { x: 0., y: 4. },
+    ];
+    let area = |polygon: &[Point<f64>]| {
+        (0..polygon.len())
+            .map(|k| {
+                let (p, q) = (polygon[k], polygon[(k + 1) % polygon.len()]);
+                p.x * q.y - p.y * q.x
+            })
+            .sum::<f64>()
+            / 2.
+    };
+
+    // a 2x2 grid of seeds splits the square into quarters, with diagonal
+    // neighbors touching only at the center
+    let seeds = [
+        Point { x: 1., y: 1. },
+        Point { x: 3., y: 1. },
+        Point { x: 1., y: 3. },
+        Point { x: 3., y: 3. },
+    ];
+    let fracture = voronoi::fracture(&square, &seeds);
+    for piece in &fracture.pieces {
+        assert!((area(piece) - 4.).abs() < 1e-12);
+    }
+    let mut adjacency = fracture.adjacency.clone();
+    adjacency.iter_mut().for_each(|a| a.sort_unstable());
+    assert_eq!(adjacency, [vec![1, 2], vec![0, 3], vec![0, 3], vec![1, 2]]);
+
+    // collinear seeds cut strips, duplicates get nothing
+    let seeds = [
+        Point { x: 3., y: 2. },
+        Point { x: 1., y: 2. },
+        Point { x: 1., y: 2. },
+    ];
+    let fracture = voronoi::fracture(&square, &seeds);
+    assert!((area(&fracture.pieces[0]) - 8.).abs() < 1e-12);
+    assert!((area(&fracture.pieces[1]) - 8.).abs() < 1e-12);
+    assert!(fracture.pieces[2].is_empty());
+    assert_eq!(fracture.adjacency, [vec![1], vec![0], vec![]]);
+
+    let fracture = voronoi::fracture(&square, &[Point { x: 1., y: 1. }]);
+    assert_eq!(area(&fracture.pieces[0]), 16.);
+}
+
+#[test]
+fn poisson_disk_sampling() {
+    use delaunator::sampling::PoissonDisk;
+
+    let (min, max) = (Point { x: 0., y: 0. }, Point { x: 100., y: 50. });
+    let sampler = PoissonDisk::new(min, max, 2.).seed(42);
+    let points = sampler.sample();
+    assert_eq!(points, sampler.sample());
+    assert!(points
+        .iter()
+        .all(|p| p.x >= min.x && p.x <= max.x && p.y >= min.y && p.y <= max.y));
+
+    // the closest pair of points is joined by a Delaunay edge
+    let triangulation = Triangulation::<Index>::new(&points).unwrap();
+    for e in 0..triangulation.triangles.len() {
+        let a = points[triangulation.triangles[e] as usize];
+        let b = points[triangulation.triangles[if e % 3 == 2 { e - 2 } else { e + 1 }] as usize];
+        assert!(a.distance(b) >= 2.);
+    }
+    // and the rectangle is covered densely
+    assert!(points.len() > 300, "{}", points.len());
+
+    // a quarter of the density on the right half
+    let points = sampler.sample_with_density(|p| if p.x < 50. { 1. } else { 0.25 });
+    let left = points.iter().filter(|p| p.x < 50.).count();
+    let right = points.len() - left;
+    assert!(left > 3 * right && left < 5 * right, "{} {}", left, right);
+}
+
+#[test]
+fn importance_sampling() {
+    use delaunator::sampling::{self, DensityGrid};
+
+    // a horizontal gradient from black to white
+    let (width, height) = (64, 32);
+    let values: Vec<f64> = (0..width * height)
+        .map(|i| (i % width) as f64 / (width - 1) as f64)
+        .collect();
+    let grid = DensityGrid::new(width, height, &values);
+
+    let points = grid.error_diffusion(1.);
+    let total: f64 = values.iter().sum();
+    assert!((points.len() as f64 - total).abs() < 2., "{}", points.len());
+    let left = points.iter().filter(|p| p.x < 32.).count();
+    assert!(3 * left < points.len());
+    assert!(Triangulation::<Index>::new(&points).is_some());
+
+    let (min, max) = (Point { x: 0., y: 0. }, Point { x: 64., y: 32. });
+    let points = sampling::rejection_sample(min, max, 500, 3, |p| grid.density(p));
+    assert_eq!(points.len(), 500);
+    assert_eq!(
+        points,
+        sampling::rejection_sample(min, max, 500, 3, |p| grid.density(p))
+    );
+    let left = points.iter().filter(|p| p.x < 32.).count();
+    assert!(3 * left < points.len());
+
+    assert!(sampling::rejection_sample(min, max, 10, 3, |_| 0.).is_empty());
+}
+
+#[test]
+fn dual_mesh() {
+    use delaunator::dual::DualMesh;
+
+    let points = load_fixture(include_str!("fixtures/ukraine.json"));
+    let mesh = DualMesh::<f64, Index>::new(&points).unwrap();
+    assert_eq!(mesh.corner_count(), mesh.triangulation.len());
+
+    let mut edges = 0;
+    for s in 0..mesh.site_count() {
+        let cell: Vec<usize> = mesh.site_edges(s).collect();
+        edges += cell.len();
+        for &e in &cell {
+            assert_eq!(mesh.edge_start_site(e), s);
+            assert!(mesh.corner_sites(mesh.edge_inner_corner(e)).contains(&s));
+        }
+        // consecutive corners of a cell are linked across the shared edge
+        for w in cell.windows(2) {
+            let across = mesh.edge_outer_corner(mesh.corner_edges(w[0] / 3)[(w[0] + 2) % 3]);
+            assert_eq!(across, Some(mesh.edge_inner_corner(w[1])));
+        }
+
+        let neighbors: Vec<usize> = mesh.site_neighbors(s).collect();
+        let boundary = mesh.is_boundary_site(s);
+        assert_eq!(neighbors.len(), cell.len() + boundary as usize);
+        for &n in &neighbors {
+            assert!(mesh.site_neighbors(n).any(|m| m == s));
+        }
+    }
+    assert_eq!(edges, mesh.edge_count());
+
+    let boundary = (0..mesh.site_count())
+        .filter(|&s| mesh.is_boundary_site(s))
+        .count();
+    assert_eq!(boundary, mesh.triangulation.hull.len());
+
+    for e in 0..mesh.edge_count() {
+        match mesh.edge_twin(e) {
+            Some(twin) => {
+                assert_eq!(mesh.edge_start_site(twin), mesh.edge_end_site(e));
+                assert_eq!(
+                    mesh.edge_outer_corner(twin),
+                    Some(mesh.edge_inner_corner(e))
+                );
+            }
+            None => assert_eq!(mesh.edge_outer_corner(e), None),
+        }
+    }
+}
+
+#[test]
+fn update() {
+    use delaunator::update::Update;
+
+    let mut points: Vec<Point<f64>> = (0..900)
+        .map(|i| {
+            let (x, y) = ((i % 30) as f64, (i / 30) as f64);
+            Point::new(
+                x + 0.3 * (i as f64 * 1.7).sin(),
+                y + 0.3 * (i as f64 * 2.3).cos(),
+            )
+        })
+        .collect();
+    let mut triangulation = Triangulation::<Index>::new(&points).unwrap();
+    let mut removed = Vec::new();
+
+    let check = |triangulation: &Triangulation<Index>, points: &[Point<f64>], removed: &[usize]| {
+        validate_triangulation(points, triangulation);
+        assert!(triangulation.is_delaunay(points));
+        let live: Vec<Point<f64>> = (0..points.len())
+            .filter(|i| !removed.contains(i))
+            .map(|i| points[i])
+            .collect();
+        assert_eq!(
+            triangulation.len(),
+            Triangulation::<Index>::new(&live).unwrap().len()
+        );
+        assert!(triangulation
+            .triangles
+            .iter()
+            .all(|&i| !removed.contains(&(i as usize))));
+    };
+
+    // move a few points
+    let before = triangulation.triangles.clone();
+    points[15 * 30 + 15].x += 0.6;
+    points[10 * 30 + 20].y -= 0.4;
+    let update = triangulation.update(&points, &[15 * 30 + 15, 10 * 30 + 20], &[]);
+    assert!(matches!(update, Some(Update::Local(_))));
+    check(&triangulation, &points, &removed);
+    let kept = before
+        .chunks_exact(3)
+        .zip(triangulation.triangles.chunks_exact(3))
+        .filter(|(a, b)| a == b)
+        .count();
+    assert!(kept > triangulation.len() * 9 / 10);
+
+    // add some
+    points.push(Point::new(5.5, 5.5));
+    points.push(Point::new(20.25, 7.75));
+    let update = triangulation.update(&points, &[900, 901], &[]);
+    assert!(matches!(update, Some(Update::Local(_))));
+    check(&triangulation, &points, &removed);
+
+    // remove some
+    removed.extend([3 * 30 + 3, 25 * 30 + 12, 900]);
+    let update = triangulation.update(&points, &[], &removed);
+    assert!(matches!(update, Some(Update::Local(_))));
+    check(&triangulation, &points, &removed);
+
+    // moving a hull point rebuilds
+    points[0] = Point::new(-2., -2.);
+    let update = triangulation.update(&points, &[0], &[]);
+    assert_eq!(update, Some(Update::Rebuilt));
+    check(&triangulation, &points, &removed);
+}
+
+#[test]
+fn tin_normals() {
+    use delaunator::tin;
+
+    let points: Vec<Point<f64>> = (0..100)
+        .map(|i| Point::new((i % 10) as f64, (i / 10) as f64))
+        .collect();
+
+    // a tilted plane has the same normal everywhere
+    let z: Vec<f64> = points.iter().map(|p| 0.5 * p.x).collect();
+    let triangulation = Triangulation::<Index>::new(&points).unwrap();
+    let expected = [-0.5 / 1.25_f64.sqrt(), 0.0, 1.0 / 1.25_f64.sqrt()];
+    let faces = tin::triangle_normals(&triangulation, &points, &z);
+    let vertices = tin::vertex_normals(&triangulation, &points, &z);
+    assert_eq!(faces.len(), 3 * triangulation.len());
+    assert_eq!(vertices.len(), 3 * points.len());
+    for n in faces.chunks_exact(3).chain(vertices.chunks_exact(3)) {
+        for k in 0..3 {
+            assert!((n[k] as f64 - expected[k]).abs() < 1e-6);
+        }
+    }
+
+    // on a paraboloid, vertex normals point away from the axis
+    let z: Vec<f64> = points
+        .iter()
+        .map(|p| (p.x - 4.5).powi(2) + (p.y - 4.5).powi(2))
+        .collect();
+    let vertices = tin::vertex_normals(&triangulation, &points, &z);
+    for (p, n) in points.iter().zip(vertices.chunks_exact(3)) {
+        let length = n.iter().map(|c| c * c).sum::<f32>().sqrt();
+        assert!((length - 1.0).abs() < 1e-6);
+        assert!(n[2] > 0.0);
+        assert!(n[0] as f64 * (p.x - 4.5) <= 1e-6);
+        assert!(n[1] as f64 * (p.y - 4.5) <= 1e-6);
+    }
+}
+
+#[test]
+fn wireframe_indices() {
+    use delaunator::triangulation::WireframeEdges;
+    use std::collections::HashSet;
+
+    let points = load_fixture(include_str!("fixtures/ukraine.json"));
+    let triangulation = Triangulation::<Index>::new(&points).unwrap();
+
+    let all = triangulation.wireframe_indices::<u32>();
+    let hull = triangulation.wireframe_indices_of::<u32>(WireframeEdges::Hull);
+    let interior = triangulation.wireframe_indices_of::<u32>(WireframeEdges::Interior);
+    assert_eq!(hull.len(), 2 * triangulation.hull.len());
+    assert_eq!(all.len(), hull.len() + interior.len());
+
+    // every edge appears once, in either direction
+    let edges: HashSet<(u32, u32)> = all
+        .chunks_exact(2)
+        .map(|e| (e[0].min(e[1]), e[0].max(e[1])))
+        .collect();
+    assert_eq!(edges.len(), all.len() / 2);
+    assert_eq!(3 * triangulation.len(), all.len() - hull.len() / 2);
+    assert_eq!(triangulation.unique_edges().count(), all.len() / 2);
+}
+
+#[test]
+fn minimize_edge_length() {
+    let points = load_fixture(include_str!("fixtures/ukraine.json"));
+    let mut triangulation = Triangulation::<Index>::new(&points).unwrap();
+    let total = |triangulation: &Triangulation<Index>| {
+        let lengths: Vec<f64> = triangulation
+            .wireframe_indices::<usize>()
+            .chunks_exact(2)
+            .map(|e| points[e[0]].distance(points[e[1]]))
+            .collect();
+        sum(&lengths)
+    };
+
+    let before = total(&triangulation);
+    let len = triangulation.len();
+    let flips = triangulation.minimize_edge_length(&points);
+    assert!(flips > 0);
+    assert!(total(&triangulation) < before);
+    assert_eq!(triangulation.len(), len);
+    validate_triangulation(&points, &triangulation);
+
+    // a local optimum: no further flip helps
+    assert_eq!(triangulation.minimize_edge_length(&points), 0);
+}
+
+#[test]
+fn triangle_io() {
+    use delaunator::triangle_io::{self, Segment};
+
+    let points = load_fixture(include_str!("fixtures/ukraine.json"));
+    let triangulation = Triangulation::<Index>::new(&points).unwrap();
+    let markers: Vec<i32> = (0..points.len() as i32).map(|i| i % 3).collect();
+
+    let (mut node, mut ele, mut poly) = (Vec::new(), Vec::new(), Vec::new());
+    triangle_io::write_node(&mut node, &points, Some(&markers)).unwrap();
+    triangle_io::write_ele(&mut ele, &triangulation.triangles).unwrap();
+    let segments = triangle_io::hull_segments(&triangulation, 7);
+    triangle_io::write_poly(&mut poly, &[] as &[Point<f64>], &segments, &[points[0]]).unwrap();
+
+    let nodes = triangle_io::read_node(&node[..]).unwrap();
+    assert_eq!(nodes.points, points);
+    assert_eq!(nodes.markers.as_deref(), Some(&markers[..]));
+    let triangles: Vec<Index> = triangle_io::read_ele(&ele[..], &nodes).unwrap();
+    assert_eq!(triangles, triangulation.triangles);
+    let read = triangle_io::read_poly(&poly[..], nodes.first).unwrap();
+    assert_eq!(read.nodes, None);
+    assert_eq!(read.segments, segments);
+    assert_eq!(read.holes, [points[0]]);
+
+    // one-based numbering, comments, attributes and regional attributes
+    let poly = "\
+        # a square with a hole
+        4 2 1 0
+        1 0 0 0.5
+        2 4 0 0.5 # attribute
+        3 4 4 0.5
+
+        4 0 4 0.5
+        4 1
+        1 1 2 5
+        2 2 3 5
+        3 3 4 6
+        4 4 1 6
+        1
+        1 2 2
+        0
+    ";
+    let read = triangle_io::read_poly(poly.as_bytes(), 0).unwrap();
+    let nodes = read.nodes.unwrap();
+    assert_eq!(nodes.first, 1);
+    assert_eq!(nodes.points[2], Point::new(4., 4.));
+    assert_eq!(nodes.attributes, [0.5; 4]);
+    assert_eq!(
+        read.segments[3],
+        Segment {
+            start: 3,
+            end: 0,
+            marker: 6
+        }
+    );
+    assert_eq!(read.holes, [Point::new(2., 2.)]);
+
+    assert!(matches!(
+        triangle_io::read_node("2 2 0 0\n0 1 1\n".as_bytes()),
+        Err(triangle_io::FormatError::UnexpectedEnd)
+    ));
+    assert!(matches!(
+        triangle_io::read_node("1 2 0 0\n0 1 x\n".as_bytes()),
+        Err(triangle_io::FormatError::Syntax { line: 2 })
+    ));
+    assert!(matches!(
+        triangle_io::read_node("1 3 0 0\n0 1 1 1\n".as_bytes()),
+        Err(triangle_io::FormatError::Syntax { line: 1 })
+    ));
+    // a vertex beyond the nodes
+    let nodes = triangle_io::read_node(&node[..]).unwrap();
+    let ele = format!("1 3 0\n0 0 1 {}\n", points.len());
+    assert!(matches!(
+        triangle_io::read_ele::<_, Index>(ele.as_bytes(), &nodes),
+        Err(triangle_io::FormatError::IndexOutOfBounds { line: 2 })
+    ));
+}
+
+#[test]
+fn edge_markers() {
+    use delaunator::{markers::EdgeMarkers, triangle_io};
+
+    let mut points = vec![
+        Point::new(0., 0.),
+        Point::new(4., 0.),
+        Point::new(4., 4.),
+        Point::new(0., 4.),
+        Point::new(2., 2.),
+    ];
+    let triangulation = Triangulation::<Index>::new(&points).unwrap();
+    let mut markers = EdgeMarkers::new();
+    markers.mark_hull(&triangulation, 1);
+    markers.insert(4, 0, 9);
+    assert_eq!(markers.len(), 5);
+
+    // refine the bottom edge: both halves keep its marker
+    points.push(Point::new(2., 0.));
+    assert_eq!(markers.split(0, 1, 5), Some(1));
+    assert_eq!(markers.split(0, 2, 5), None);
+    let triangulation = Triangulation::<Index>::new(&points).unwrap();
+    let per_halfedge = markers.halfedge_markers(&triangulation);
+    for (e, marker) in per_halfedge.iter().enumerate() {
+        let edge = triangulation.get_half_edge(e).unwrap();
+        let (a, b) = (edge.start().id(), edge.end().id());
+        if edge.twin().is_none() {
+            assert_eq!(*marker, Some(1));
+        } else if (a, b) == (0, 4) || (a, b) == (4, 0) {
+            assert_eq!(*marker, Some(9));
+        } else {
+            assert_eq!(*marker, None);
+        }
+    }
+
+    // markers round-trip through .poly segments
+    let mut poly = Vec::new();
+    triangle_io::write_poly(&mut poly, &points, &markers.segments(), &[]).unwrap();
+    let read = triangle_io::read_poly(&poly[..], 0).unwrap();
+    let read: EdgeMarkers = read.segments.into_iter().collect();
+    assert_eq!(read, markers);
+}
+
+#[test]
+fn refined_edge_markers() {
+    use delaunator::{markers::EdgeMarkers, refine::Refinement};
+
+    let mut points = vec![
+        Point::new(0., 0.),
+        Point::new(4., 0.),
+        Point::new(4., 4.),
+        Point::new(0., 4.),
+    ];
+    let mut cdt = Triangulation::<Index>::from_pslg(&points, &[vec![0, 1, 2, 3]], &[]).unwrap();
+    let mut markers = EdgeMarkers::new();
+    for k in 0..4 {
+        markers.insert(k, (k + 1) % 4, k as i32 + 1);
+    }
+
+    let inserted = Refinement::new()
+        .min_angle(30.)
+        .max_area(0.5)
+        .refine(&mut cdt, &mut points);
+    for &(m, parent) in &inserted {
+        markers.record(m, parent);
+    }
+
+    // every sub-segment keeps the marker of the side it lies on
+    let side = |p: Point<f64>| {
+        if p.y == 0. {
+            1
+        } else if p.x == 4. {
+            2
+        } else if p.y == 4. {
+            3
+        } else {
+            4
+        }
+    };
+    let segments = cdt.segments();
+    assert!(segments.len() > 4);
+    assert_eq!(markers.len(), segments.len());
+    for (a, b) in segments {
+        let mid = Point::new(
+            (points[a].x + points[b].x) / 2.,
+            (points[a].y + points[b].y) / 2.,
+        );
+        assert_eq!(markers.get(a, b), Some(side(mid)));
+    }
+}
+
+#[test]
+fn sparsity_pattern() {
+    let points = load_fixture(include_str!("fixtures/ukraine.json"));
+    let triangulation = Triangulation::<Index>::new(&points).unwrap();
+    let pattern = triangulation.sparsity_pattern();
+
+    // the diagonal plus both directions of every edge
+    let edges = triangulation.wireframe_indices::<usize>();
+    assert_eq!(pattern.columns.len(), pattern.rows() + edges.len());
+    assert_eq!(*pattern.offsets.last().unwrap(), pattern.columns.len());
+    for i in 0..pattern.rows() {
+        let row = pattern.row(i);
+        assert!(row.windows(2).all(|w| w[0] < w[1]));
+        assert!(row.binary_search(&i).is_ok());
+    }
+    for e in edges.chunks_exact(2) {
+        assert!(pattern.row(e[0]).binary_search(&e[1]).is_ok());
+        assert!(pattern.row(e[1]).binary_search(&e[0]).is_ok());
+    }
+}
+
+#[test]
+fn dtfe() {
+    use delaunator::{density, tin};
+
+    // twice as many points per area on the right half
+    let mut points = Vec::new();
+    for i in 0..40 {
+        for j in 0..20 {
+            let x = i as f64 * 0.5 + 0.05 * (j as f64).sin();
+            points.push(Point::new(x, j as f64 + 0.05 * (i as f64).cos()));
+            if x > 10.0 {
+                points.push(Point::new(x + 0.25, j as f64 + 0.5));
+            }
+        }
+    }
+    let triangulation = Triangulation::<Index>::new(&points).unwrap();
+    let densities = density::dtfe(&triangulation, &points);
+    let at =
+        |x, y| tin::interpolate(&triangulation, &points, &densities, Point::new(x, y)).unwrap();
+    assert!((at(5., 10.) - 2.).abs() < 0.2);
+    assert!((at(15., 10.) - 4.).abs() < 0.4);
+
+    // the field conserves mass: its integral is the number of points
+    let masses = vec![2.0; points.len()];
+    let weighted = density::dtfe_weighted(&triangulation, &points, &masses);
+    let integral: Vec<f64> = triangulation
+        .triangles
+        .chunks_exact(3)
+        .map(|t| {
+            let (a, b, c) = (
+                points[t[0] as usize],
+                points[t[1] as usize],
+                points[t[2] as usize],
+            );
+            let area = ((b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)).abs() / 2.;
+            area * t.iter().map(|&i| weighted[i as usize]).sum::<f64>() / 3.
+        })
+        .collect();
+    assert!((sum(&integral) - 2.0 * points.len() as f64).abs() < 1e-6);
+}
+
+#[test]
+fn vertex_gradients() {
+    use delaunator::tin;
+
+    let points = load_fixture(include_str!("fixtures/ukraine.json"));
+    let triangulation = Triangulation::<Index>::new(&points).unwrap();
+
+    // exact for a linear field
+    let values: Vec<f64> = points.iter().map(|p| 2.0 * p.x - 3.0 * p.y + 1.0).collect();
+    let gradients = tin::vertex_gradients(&triangulation, &points, &values);
+    for (i, g) in gradients.iter().enumerate() {
+        if triangulation.triangles.contains(&(i as Index)) {
+            assert!((g.x - 2.0).abs() < 1e-6 && (g.y + 3.0).abs() < 1e-6);
+        }
+    }
+
+    // close for a smooth field on a fine grid
+    let points: Vec<Point<f64>> = (0..400)
+        .map(|i| Point::new((i % 20) as f64 * 0.1, (i / 20) as f64 * 0.1))
+        .collect();
+    let triangulation = Triangulation::<Index>::new(&points).unwrap();
+    let values: Vec<f64> = points.iter().map(|p| p.x * p.x + p.y).collect();
+    let gradients = tin::vertex_gradients(&triangulation, &points, &values);
+    let (p, g) = (points[210], gradients[210]);
+    assert!((g.x - 2.0 * p.x).abs() < 0.1 && (g.y - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn crust() {
+    use delaunator::shape;
+    use std::f64::consts::TAU;
+
+    // an ellipse and, well apart from it, an open arc, sampled out of order
+    let mut points = Vec::new();
+    for i in 0..60 {
+        let angle = (i * 7 % 60) as f64 * TAU / 60.;
+        points.push(Point::new(3. * angle.cos(), 2. * angle.sin()));
+    }
+    for i in 0..20 {
+        let angle = (i * 3 % 20) as f64 * TAU / 60.;
+        points.push(Point::new(10. + 2. * angle.cos(), 2. * angle.sin()));
+    }
+
+    let mut curves = shape::crust(&points);
+    curves.sort_by_key(|c| c.len());
+    assert_eq!(curves.len(), 2);
+
+    let (arc, ellipse) = (&curves[0], &curves[1]);
+    assert_eq!(arc.len(), 20);
+    assert!(arc.iter().all(|&i| i >= 60));
+    assert_eq!(ellipse.len(), 61);
+    assert_eq!(ellipse.first(), ellipse.last());
+
+    // consecutive points of each polyline are neighbors on their curve
+    for curve in [arc, ellipse] {
+        for w in curve.windows(2) {
+            assert!(points[w[0]].distance(points[w[1]]) < 0.5);
+        }
+    }
+
+    let line: Vec<Point<f64>> = [3., 1., 2., 0.].iter().map(|&x| Point::new(x, x)).collect();
+    assert_eq!(shape::crust(&line), [[0, 2, 1, 3]]);
+}
+
+#[test]
+fn medial_axis() {
+    use delaunator::shape;
+
+    // an L-shaped region, two units wide, sampled every 0.1
+    let corners = [(0., 0.), (6., 0.), (6., 2.), (2., 2.), (2., 6.), (0., 6.)];
+    let mut boundary = Vec::new();
+    for k in 0..corners.len() {
+        let (a, b) = (corners[k], corners[(k + 1) % corners.len()]);
+        let steps = (((b.0 - a.0) as f64).abs() + ((b.1 - a.1) as f64).abs()) as usize * 10;
+        for i in 0..steps {
+            let t = i as f64 / steps as f64;
+            boundary.push(Point::new(a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t));
+        }
+    }
+
+    let full = shape::medial_axis(&boundary, 0.);
+    let axis = shape::medial_axis(&boundary, 0.5);
+    assert!(axis.edges.len() < full.edges.len());
+    assert_eq!(axis.vertices.len(), axis.radii.len());
+
+    // the pruned axis runs along the middle of both arms
+    for (p, &r) in axis.vertices.iter().zip(&axis.radii) {
+        assert!(p.x > 0. && p.y > 0. && (p.x < 2. || p.y < 2.));
+        let nearest = boundary
+            .iter()
+            .map(|b| b.distance(*p))
+            .fold(f64::INFINITY, f64::min);
+        assert!((nearest - r).abs() < 1e-9);
+    }
+    assert!(axis
+        .vertices
+        .iter()
+        .any(|p| (p.x - 5.).abs() < 0.2 && (p.y - 1.).abs() < 0.05));
+    assert!(axis
+        .vertices
+        .iter()
+        .any(|p| (p.x - 1.).abs() < 0.05 && (p.y - 5.).abs() < 0.2));
+
+    // and is connected
+    let mut parent: Vec<usize> = (0..axis.vertices.len()).collect();
+    fn find(parent: &mut [usize], i: usize) -> usize {
+        if parent[i] != i {
+            parent[i] = find(parent, parent[i]);
+        }
+        parent[i]
+    }
+    for &(a, b) in &axis.edges {
+        let (ra, rb) = (find(&mut parent, a), find(&mut parent, b));
+        parent[ra] = rb;
+    }
+    let root = find(&mut parent, 0);
+    assert!((0..axis.vertices.len()).all(|i| find(&mut parent, i) == root));
+}
+
+#[test]
+fn pole_of_inaccessibility() {
+    use delaunator::shape;
+
+    // in an L-shaped region two units wide, the widest circle sits in the elbow,
+    // touching both outer sides and the inner corner
+    let corners = [(0., 0.), (6., 0.), (6., 2.), (2., 2.), (2., 6.), (0., 6.)];
+    let ring: Vec<Point<f64>> = corners.iter().map(|&(x, y)| Point::new(x, y)).collect();
+    let (p, distance) = shape::pole_of_inaccessibility(&[&ring], 0.05).unwrap();
+    let radius = 2. * 2f64.sqrt() / (1. + 2f64.sqrt());
+    assert!(p.x.min(p.y) >= distance - 1e-9);
+    assert!(p.distance(Point::new(2., 2.)) >= distance - 1e-9);
+    assert!(distance <= radius + 1e-9 && distance > radius - 0.025);
+
+    // no point on a fine grid is farther from the boundary
+    let clearance = |x: f64, y: f64| {
+        if x >= 2. && y >= 2. {
+            return -1.;
+        }
+        let (dx, dy) = ((2. - x).hypot(2. - y), x.min(y).min(6. - x).min(6. - y));
+        if x > 2. || y > 2. {
+            dy.min(if x > 2. { 2. - y } else { 2. - x })
+        } else {
+            dy.min(dx)
+        }
+    };
+    for i in 1..60 {
+        for j in 1..60 {
+            assert!(clearance(i as f64 * 0.1, j as f64 * 0.1) <= distance + 0.025);
+        }
+    }
+
+    // a self-cancelling ring has no interior
+    let line = [Point::new(0., 0.), Point::new(1., 0.), Point::new(2., 0.)];
+    assert!(shape::pole_of_inaccessibility(&[&line[..]], 0.1).is_none());
+}
+
+#[test]
+fn weighted_centroids() {
+    use delaunator::{sampling, voronoi};
+
+    // four unit cells in a 2 x 2 square
+    let points = vec![
+        Point::new(0.5, 0.5),
+        Point::new(1.5, 0.5),
+        Point::new(0.5, 1.5),
+        Point::new(1.5, 1.5),
+    ];
+    let boundary = [(0f64, 0.), (2., 0.), (2., 2.), (0., 2.)].map(|(x, y)| Point::new(x, y));
+    let triangulation = Triangulation::<Index>::new(&points).unwrap();
+
+    let uniform = voronoi::weighted_centroids(&triangulation, &points, &boundary, 1, |_| 1.);
+    for (c, p) in uniform.iter().zip(&points) {
+        assert!(c.unwrap().distance(*p) < 1e-12);
+    }
+
+    // a density growing to the right pulls every centroid a sixth of a cell
+    // along, to x = 2/3 in the first cell
+    let linear = voronoi::weighted_centroids(&triangulation, &points, &boundary, 16, |p| p.x);
+    let c = linear[0].unwrap();
+    assert!((c.x - 2. / 3.).abs() < 1e-3 && (c.y - 0.5).abs() < 1e-3);
+    assert!(linear.iter().all(|c| c.unwrap().x > 0.));
+
+    // cells without weight have no centroid
+    let left = voronoi::weighted_centroids(&triangulation, &points, &boundary, 4, |p| {
+        if p.x < 1. {
+            1.
+        } else {
+            0.
+        }
+    });
+    assert!(left[0].is_some() && left[1].is_none());
+
+    // the same centroids from a diagram that is already built
+    let diagram = voronoi::Voronoi::new(&triangulation, &points);
+    let (min, max) = (boundary[0], boundary[2]);
+    let cached = diagram.weighted_centroids(min, max, 16, |p| p.x);
+    for (a, b) in cached.iter().zip(&linear) {
+        assert!(a.unwrap().distance(b.unwrap()) < 1e-9);
+    }
+
+    // relaxing keeps the points inside and evens out the weight of the cells
+    let mut stipples =
+        sampling::rejection_sample(Point::new(0., 0.), Point::new(2., 2.), 200, 3, |p| p.y / 2.);
+    let spread = |points: &[Point<f64>]| {
+        let triangulation = Triangulation::<Index>::new(points).unwrap();
+        let cells = voronoi::clip_to_polygon(&triangulation, points, &boundary);
+        let masses: Vec<f64> = cells
+            .iter()
+            .map(|cell| {
+                // the weight p.y integrated over the cell, exact for polygons
+                (0..cell.len())
+                    .map(|k| {
+                        let (a, b) = (cell[k], cell[(k + 1) % cell.len()]);
+                        a.perp_dot(b) * (a.y + b.y) / 6.
+                    })
+                    .sum::<f64>()
+            })
+            .collect();
+        let max = masses.iter().cloned().fold(0., f64::max);
+        let min = masses.iter().cloned().fold(f64::INFINITY, f64::min);
+        max / min
+    };
+    let before = spread(&stipples);
+    voronoi::relax(&mut stipples, &boundary, 20, 4, |p| p.y);
+    assert!(stipples
+        .iter()
+        .all(|p| (0.0..=2.).contains(&p.x) && (0.0..=2.).contains(&p.y)));
+    assert!(spread(&stipples) < before / 2.);
+}
+
+#[test]
+fn greedy_spanner() {
+    use delaunator::graphs;
+
+    let points: Vec<Point<f64>> = load_fixture(include_str!("fixtures/ukraine.json"))
+        .into_iter()
+        .take(300)
+        .collect();
+    let triangulation = Triangulation::<Index>::new(&points).unwrap();
+    let delaunay = graphs::greedy_spanner(&triangulation, &points, 1.);
+    let mst = graphs::minimum_spanning_tree(&triangulation, &points);
+
+    // shortest path lengths from `from` over `edges`
+    let paths = |edges: &[(usize, usize, f64)], from: usize| {
+        let mut distance = vec![f64::INFINITY; points.len()];
+        let mut done = vec![false; points.len()];
+        distance[from] = 0.;
+        while let Some(i) = (0..points.len())
+            .filter(|&i| !done[i] && distance[i] < f64::INFINITY)
+            .min_by(|&a, &b| distance[a].partial_cmp(&distance[b]).unwrap())
+        {
+            done[i] = true;
+            for &(u, v, length) in edges {
+                let j = if u == i {
+                    v
+                } else if v == i {
+                    u
+                } else {
+                    continue;
+                };
+                distance[j] = distance[j].min(distance[i] + length);
+            }
+        }
+        distance
+    };
+
+    let mut previous = delaunay.len();
+    for &stretch in &[1.2, 1.5, 2., 3.] {
+        let spanner = graphs::greedy_spanner(&triangulation, &points, stretch);
+        assert!(spanner.len() <= previous);
+        assert!(spanner.len() >= mst.len());
+        assert!(spanner.windows(2).all(|w| w[0].2 <= w[1].2));
+        assert!(mst.iter().all(|&(u, v, _)| spanner
+            .iter()
+            .any(|&(a, b, _)| (a, b) == (u, v) || (a, b) == (v, u))));
+        previous = spanner.len();
+
+        // every Delaunay edge is spanned within the stretch factor
+        for from in (0..points.len()).step_by(25) {
+            let distance = paths(&spanner, from);
+            for &(u, v, length) in &delaunay {
+                if u == from || v == from {
+                    let other = if u == from { v } else { u };
+                    assert!(distance[other] <= stretch * length * (1. + 1e-12));
+                }
+            }
+        }
+    }
+    assert!(previous < delaunay.len() / 2);
+}
+
+#[test]
+fn single_linkage() {
+    use delaunator::graphs::{self, EdgeCutoff};
+
+    let points = load_fixture(include_str!("fixtures/ukraine.json"));
+    let triangulation = Triangulation::<Index>::new(&points).unwrap();
+    let used = {
+        let mut used = triangulation.triangles.clone();
+        used.sort_unstable();
+        used.dedup();
+        used.len()
+    };
+
+    let tree = graphs::minimum_spanning_tree(&triangulation, &points);
+    assert_eq!(tree.len(), used - 1);
+    assert!(tree.windows(2).all(|w| w[0].2 <= w[1].2));
+
+    let dendrogram = graphs::single_linkage(&triangulation, &points);
+    assert_eq!(dendrogram.merges.len(), used - 1);
+    assert_eq!(dendrogram.merges.last().unwrap().size, used);
+    for (k, merge) in dendrogram.merges.iter().enumerate() {
+        assert!(merge.left < points.len() + k && merge.right < points.len() + k);
+    }
+
+    for &distance in &[0.0, 1.0, 10.0, 100.0, 1e9] {
+        assert_eq!(
+            dendrogram.cut(distance),
+            graphs::cluster_by_edge_length(&triangulation, &points, EdgeCutoff::Length(distance))
+        );
+    }
+}
+
+#[test]
+fn alpha_filtration() {
+    use delaunator::alpha::{self, Simplex};
+    use std::collections::HashSet;
+
+    let points = load_fixture(include_str!("fixtures/ukraine.json"));
+    let triangulation = Triangulation::<Index>::new(&points).unwrap();
+    let filtration = alpha::alpha_filtration(&triangulation, &points);
+
+    // faces never enter after the triangles they bound
+    for (e, &value) in filtration.halfedges.iter().enumerate() {
+        assert!(value <= filtration.triangles[e / 3]);
+        if let Some(twin) = triangulation.halfedges[e].get() {
+            assert_eq!(value, filtration.halfedges[twin as usize]);
+        }
+    }
+
+    let simplices = filtration.simplices(&triangulation);
+    let mut seen = HashSet::new();
+    for (simplex, _) in &simplices {
+        match *simplex {
+            Simplex::Vertex(_) => {}
+            Simplex::Edge(a, b) => {
+                assert!(a < b);
+                assert!(seen.contains(&Simplex::Vertex(a)) && seen.contains(&Simplex::Vertex(b)));
+            }
+            Simplex::Triangle(a, b, c) => {
+                for (u, v) in [(a, b), (b, c), (c, a)] {
+                    assert!(seen.contains(&Simplex::Edge(u.min(v), u.max(v))));
+                }
+            }
+        }
+        seen.insert(*simplex);
+    }
+    assert!(simplices.windows(2).all(|w| w[0].1 <= w[1].1));
+    let edges = triangulation.wireframe_indices::<usize>().len() / 2;
+    let vertices = filtration.vertices.iter().filter(|&&v| v == 0.0).count();
+    assert_eq!(simplices.len(), vertices + edges + triangulation.len());
+}
+
+#[test]
+fn hull_only() {
+    let points = load_fixture(include_str!("fixtures/ukraine.json"));
+    let expected = Triangulation::<Index>::new(&points).unwrap();
+    let builder = TriangulationBuilder::new().hull_only(true);
+    let hull = builder.build::<Index, _, _>(&points).unwrap();
+    assert!(hull.triangles.is_empty() && hull.halfedges.is_empty());
+    assert_eq!(hull.hull, expected.hull);
+
+    // a grid, with many collinear points on the hull
+    let points: Vec<_> = (0..100)
+        .map(|i| Point {
+            x: (i % 10) as f64,
+            y: (i / 10) as f64,
+        })
+        .collect();
+    let expected = Triangulation::<Index>::new(&points).unwrap();
+    let hull = builder.build::<Index, _, _>(&points).unwrap();
+    assert_eq!(hull.hull, expected.hull);
+}
+
+#[test]
+fn triangulate_indices() {
+    let points = load_fixture(include_str!("fixtures/ukraine.json"));
+    let expected = Triangulation::<Index>::new(&points).unwrap();
+    let mut triangulator = Triangulator::<f64, Index>::new();
+    // the second run reuses the half-edge buffer of the first
+    for _ in 0..2 {
+        let triangles = triangulator.triangulate_indices(&points).unwrap();
+        assert_eq!(triangles, expected.triangles);
+    }
+
+    let collinear = [
+        Point { x: 0., y: 0. },
+        Point { x: 1., y: 0. },
+        Point { x: 2., y: 0. },
+    ];
+    assert!(triangulator.triangulate_indices(&collinear).is_none());
+}
+
+#[test]
+fn trace() {
+    use delaunator::trace::PointStatus;
+
+    let mut points = load_fixture(include_str!("fixtures/ukraine.json"));
+    points.push(points[10]);
+    points.push(Point {
+        x: points[20].x + 1e-9,
+        y: points[20].y,
+    });
+    let (triangulation, trace) = TriangulationBuilder::new()
+        .build_with_trace::<Index, _, _>(&points)
+        .unwrap();
+
+    let mut order = trace.order.clone();
+    order.sort_unstable();
+    assert_eq!(order, (0..points.len()).collect::<Vec<_>>());
+
+    let mut used = vec![false; points.len()];
+    for &i in &triangulation.triangles {
+        used[i as usize] = true;
+    }
+    for (i, &status) in trace.status.iter().enumerate() {
+        let inserted = status == PointStatus::Inserted || status == PointStatus::Seed;
+        assert_eq!(inserted, used[i], "point {} is {:?}", i, status);
+    }
+    assert_eq!(
+        trace
+            .status
+            .iter()
+            .filter(|&&s| s == PointStatus::Seed)
+            .count(),
+        3
+    );
+
+    // the seed triangle reproduces the construction
+    let (i0, i1, i2) = trace.seed;
+    assert!(trace.center == points[i0].circumcenter(points[i1], points[i2]));
+    let again: Triangulation<Index> =
+        TriangulationBuilder::new().build_with_seed_triangle(&points, trace.seed);
+    assert_eq!(again.triangles, triangulation.triangles);
+    assert_eq!(again.halfedges, triangulation.halfedges);
+
+    // points removed up front are left out of the order
+    let (_, trace) = TriangulationBuilder::new()
+        .dedup(0.0)
+        .build_with_trace::<Index, _, _>(&points)
+        .unwrap();
+    assert!(!trace.order.contains(&(points.len() - 2)));
+    assert_eq!(trace.status[points.len() - 2], PointStatus::Duplicate);
+}
+
+#[test]
+fn construction_report() {
+    use delaunator::trace::Warning;
+
+    let points = load_fixture(include_str!("fixtures/ukraine.json"));
+    let (triangulation, report) = TriangulationBuilder::new()
+        .build_with_report::<Index, _, _>(&points)
+        .unwrap();
+    let (_, profile) = TriangulationBuilder::new()
+        .build_with_profile::<Index, _, _>(&points)
+        .unwrap();
+    assert_eq!(
+        report.inserted + report.duplicates + report.not_visible,
+        points.len()
+    );
+    let mut used: Vec<_> = triangulation.triangles.clone();
+    used.sort_unstable();
+    used.dedup();
+    assert_eq!(report.inserted, used.len());
+    assert_eq!(report.flips, profile.flips);
+    assert!(!report.warnings.contains(&Warning::NearlyCollinear));
+
+    let grid: Vec<_> = (0..100)
+        .map(|i| Point {
+            x: (i % 10) as f64,
+            y: (i / 10) as f64,
+        })
+        .collect();
+    let (_, report) = TriangulationBuilder::new()
+        .build_with_report::<Index, _, _>(&grid)
+        .unwrap();
+    assert!(matches!(report.warnings[..], [Warning::Cocircular(n)] if n > 0));
+
+    let line: Vec<_> = (0..10)
+        .map(|i| Point {
+            x: i as f64,
+            y: if i == 5 { 1e-9 } else { 0. },
+        })
+        .collect();
+    let (_, report) = TriangulationBuilder::new()
+        .build_with_report::<Index, _, _>(&line)
+        .unwrap();
+    assert!(report.warnings.contains(&Warning::NearlyCollinear));
+}
+
+#[test]
+fn construction_steps() {
+    use delaunator::trace::{Event, PointStatus, Replay};
+
+    let points = load_fixture(include_str!("fixtures/ukraine.json"));
+    let (triangulation, steps) = TriangulationBuilder::new()
+        .build_with_steps::<Index, _, _>(&points)
+        .unwrap();
+    let (_, trace) = TriangulationBuilder::new()
+        .build_with_trace::<Index, _, _>(&points)
+        .unwrap();
+    let (_, report) = TriangulationBuilder::new()
+        .build_with_report::<Index, _, _>(&points)
+        .unwrap();
+
+    // the seed triangle, then every point in insertion order
+    let (i0, i1, i2) = trace.seed;
+    assert_eq!(steps[0].event(), Event::Seed(i0, i1, i2));
+    let mut replay = Replay::new(&steps);
+    replay.seek(0);
+    assert_eq!(replay.triangles().len(), 3);
+    assert_eq!(replay.hull().len(), 3);
+    assert_eq!(steps.len(), 1 + trace.order.len());
+    for (step, &i) in steps[1..].iter().zip(&trace.order) {
+        assert_eq!(step.event(), Event::Point(i, trace.status[i]));
+    }
+    assert_eq!(steps.iter().map(|s| s.flips()).sum::<usize>(), report.flips);
+
+    // skipped points leave the triangulation as it was
+    for step in &steps[1..] {
+        match step.event() {
+            Event::Point(_, PointStatus::Inserted) => assert!(step.events.len() > 1),
+            _ => assert_eq!(step.events.len(), 1),
+        }
+    }
+
+    replay.seek(steps.len() - 1);
+    assert_eq!(replay.triangles(), &triangulation.triangles[..]);
+    assert_eq!(replay.hull().len(), triangulation.hull.len());
+
+    // seeking back starts over
+    replay.seek(0);
+    assert_eq!(replay.triangles().len(), 3);
+    replay.seek(steps.len());
+    assert_eq!(replay.triangles(), &triangulation.triangles[..]);
+}
+
+#[test]
+fn sorted_points() {
+    let points = load_fixture(include_str!("fixtures/ukraine.json"));
+    let (expected, trace) = TriangulationBuilder::new()
+        .build_with_trace::<Index, _, _>(&points)
+        .unwrap();
+
+    // the seed triangle first, then the rest in insertion order
+    let (i0, i1, i2) = trace.seed;
+    let mut order = vec![i0, i1, i2];
+    order.extend(
+        trace
+            .order
+            .iter()
+            .filter(|&&i| i != i0 && i != i1 && i != i2),
+    );
+    let sorted: Vec<_> = order.iter().map(|&i| points[i]).collect();
+
+    let triangulation = Triangulation::<Index>::with_sorted_points(&sorted).unwrap();
+    validate_triangulation(&sorted, &triangulation);
+    // the same triangles, by original point index
+    let triangles = |t: &Triangulation<Index>, id: &dyn Fn(Index) -> usize| {
+        let mut triangles: Vec<[usize; 3]> = t
+            .triangles
+            .chunks_exact(3)
+            .map(|t| {
+                let mut t = [id(t[0]), id(t[1]), id(t[2])];
+                t.sort_unstable();
+                t
+            })
+            .collect();
+        triangles.sort_unstable();
+        triangles
+    };
+    assert_eq!(
+        triangles(&triangulation, &|i| order[i as usize]),
+        triangles(&expected, &|i| i as usize)
+    );
+
+    assert!(Triangulation::<Index>::with_sorted_points(&sorted[..2]).is_none());
+}
+
+#[test]
+fn collinear_hull_points() {
+    let points: Vec<_> = (0..16)
+        .map(|i| Point {
+            x: (i % 4) as f64,
+            y: (i / 4) as f64,
+        })
+        .collect();
+    let triangulation = Triangulation::<Index>::new(&points).unwrap();
+    assert_eq!(triangulation.hull.len(), 12);
+
+    for builder in [
+        TriangulationBuilder::new(),
+        TriangulationBuilder::new().hull_only(true),
+    ] {
+        let corners = builder
+            .collinear_hull_points(false)
+            .build::<Index, _, _>(&points)
+            .unwrap();
+        let mut hull = corners.hull.clone();
+        hull.sort_unstable();
+        assert_eq!(hull, [0, 3, 12, 15]);
+    }
+}
+
+#[test]
+fn hull_links() {
+    let points = load_fixture(include_str!("fixtures/ukraine.json"));
+    let triangulation = Triangulation::<Index>::new(&points).unwrap();
+    let links = triangulation.hull_links();
+
+    let hull = &triangulation.hull;
+    for k in 0..hull.len() {
+        let (a, b) = (hull[k], hull[(k + 1) % hull.len()]);
+        assert_eq!(links.next[a as usize].get(), Some(b));
+        assert_eq!(links.prev[b as usize].get(), Some(a));
+
+        let e = links.tri[a as usize].unwrap() as usize;
+        assert!(triangulation.halfedges[e].is_none());
+        assert_eq!(triangulation.triangles[e], a);
+        let next = if e % 3 == 2 { e - 2 } else { e + 1 };
+        assert_eq!(triangulation.triangles[next], b);
+    }
+    let on_hull = links.next.iter().filter(|i| i.is_some()).count();
+    assert_eq!(on_hull, hull.len());
+}
+
+#[test]
+fn index_newtype() {
+    use delaunator::traits::Index as _;
+    use std::convert::TryFrom;
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct Id(u16);
+
+    impl delaunator::traits::Index for Id {
+        fn max_value() -> Self {
+            Id(u16::MAX)
+        }
+
+        fn try_from_usize(n: usize) -> Option<Self> {
+            u16::try_from(n).ok().map(Id)
+        }
+
+        fn as_usize(self) -> usize {
+            self.0.into()
+        }
+    }
+
+    assert_eq!(Id::try_from_usize(70_000), None);
+    assert_eq!(Id::from_usize(7), Id(7));
+
+    let points = load_fixture(include_str!("fixtures/ukraine.json"));
+    let triangulation = Triangulation::<Id>::new(&points).unwrap();
+    let expected = Triangulation::<Index>::new(&points).unwrap();
+    assert!(triangulation
+        .triangles
+        .iter()
+        .map(|i| i.as_usize())
+        .eq(expected.triangles.iter().map(|&i| i as usize)));
+}
+
+#[test]
+fn scalar_newtype() {
+    use delaunator::traits::{ApproxEq, Scalar};
+    use std::ops::{Add, Div, Mul, Neg, Sub};
+
+    // no `From<f32>` or `Into<f64>`, like fixed point or checked float types
+    #[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+    struct Meters(f64);
+
+    impl Add for Meters {
+        type Output = Self;
+        fn add(self, other: Self) -> Self {
+            Meters(self.0 + other.0)
+        }
+    }
+
+    impl Sub for Meters {
+        type Output = Self;
+        fn sub(self, other: Self) -> Self {
+            Meters(self.0 - other.0)
+        }
+    }
+
+    impl Mul for Meters {
+        type Output = Self;
+        fn mul(self, other: Self) -> Self {
+            Meters(self.0 * other.0)
+        }
+    }
+
+    impl Div for Meters {
+        type Output = Self;
+        fn div(self, other: Self) -> Self {
+            Meters(self.0 / other.0)
+        }
+    }
+
+    impl Neg for Meters {
+        type Output = Self;
+        fn neg(self) -> Self {
+            Meters(-self.0)
+        }
+    }
+
+    impl Scalar for Meters {
+        fn abs(self) -> Self {
+            Meters(self.0.abs())
+        }
+
+        fn min(self, other: Self) -> Self {
+            Meters(self.0.min(other.0))
+        }
+
+        fn max(self, other: Self) -> Self {
+            Meters(self.0.max(other.0))
+        }
+
+        fn sqrt(self) -> Self {
+            Meters(self.0.sqrt())
+        }
+
+        fn infinity() -> Self {
+            Meters(f64::INFINITY)
+        }
+
+        fn from_f64(x: f64) -> Self {
+            Meters(x)
+        }
+
+        fn to_f64(self) -> f64 {
+            self.0
+        }
+    }
+
+    impl ApproxEq for Meters {
+        fn approx_eq(self, other: Self) -> bool {
+            self.0.approx_eq(other.0)
+        }
+    }
+
+    let points = load_fixture(include_str!("fixtures/ukraine.json"));
+    let meters: Vec<Point<Meters>> = points
+        .iter()
+        .map(|p| Point::new(Meters(p.x), Meters(p.y)))
+        .collect();
+    let triangulation = Triangulation::<Index>::new(&meters).unwrap();
+    let expected = Triangulation::<Index>::new(&points).unwrap();
+    assert_eq!(triangulation.triangles, expected.triangles);
+    assert_eq!(triangulation.hull, expected.hull);
+}
+
+#[test]
+fn triangulation_ref() {
+    use delaunator::TriangulationRef;
+
+    let points = load_fixture(include_str!("fixtures/ukraine.json"));
+    let triangulation = Triangulation::<u32>::new(&points).unwrap();
+
+    // as another library would store them, without `OptionIndex`
+    let halfedges: Vec<u32> = triangulation
+        .halfedges
+        .iter()
+        .map(|e| e.unwrap_or(u32::MAX))
+        .collect();
+    let view = TriangulationRef::new(&triangulation.triangles, &halfedges, &triangulation.hull);
+    assert_eq!(view.len(), triangulation.len());
+    assert_eq!(view.halfedges, &triangulation.halfedges[..]);
+
+    let twins = |edges: delaunator::iter::HalfEdgeIter<'_, u32>| -> Vec<_> {
+        edges
+            .map(|e| (e.start().id(), e.end().id(), e.twin().map(|t| t.id())))
+            .collect()
+    };
+    assert_eq!(twins(view.half_edges()), twins(triangulation.half_edges()));
+    let fans: Vec<Vec<usize>> = view
+        .triangles()
+        .map(|t| t.a().triangles().map(|t| t.id()).collect())
+        .collect();
+    let expected: Vec<Vec<usize>> = triangulation
+        .triangles()
+        .map(|t| t.a().triangles().map(|t| t.id()).collect())
+        .collect();
+    assert_eq!(fans, expected);
+
+    let copy = view.to_triangulation();
+    assert_eq!(copy.triangles, triangulation.triangles);
+    assert_eq!(copy.halfedges, triangulation.halfedges);
+    assert_eq!(copy.hull, triangulation.hull);
+}
+
+#[test]
+fn triangulation_mut() {
+    use delaunator::edit::EditError;
+
+    let mut points: Vec<Point<f64>> = (0..16)
+        .map(|i| Point::new((i % 4) as f64, (i / 4) as f64))
+        .collect();
+    let mut triangulation = Triangulation::<Index>::new(&points).unwrap();
+    let hull_edge = |t: &Triangulation<Index>| {
+        (0..t.halfedges.len())
+            .find(|&e| t.halfedges[e].is_none())
+            .unwrap()
+    };
+    let interior_edge = |t: &Triangulation<Index>| {
+        (0..t.halfedges.len())
+            .find(|&e| t.halfedges[e].is_some())
+            .unwrap()
+    };
+
+    let e = hull_edge(&triangulation);
+    let mut edit = triangulation.edit();
+    assert_eq!(edit.flip_edge(e), Err(EditError::HullEdge));
+    assert_eq!(edit.split_triangle(100, 16), Err(EditError::OutOfBounds));
+
+    // split a triangle at its centroid
+    let t = edit.view().get_triangle(0).unwrap();
+    let [a, b, c] = [t.a().id(), t.b().id(), t.c().id()].map(|i| points[i]);
+    points.push(Point::new((a.x + b.x + c.x) / 3., (a.y + b.y + c.y) / 3.));
+    let spoke = edit.split_triangle(0, 16).unwrap();
+    // the other diagonal around a spoke of a degree-three point is a triangle edge
+    assert_eq!(edit.flip_edge(spoke), Err(EditError::DuplicateEdge));
+    validate_triangulation(&points, &triangulation);
+    assert_eq!(triangulation.len(), 20);
+
+    // split an interior and a hull edge at their midpoints
+    for m in [17, 18] {
+        let e = if m == 17 {
+            interior_edge(&triangulation)
+        } else {
+            hull_edge(&triangulation)
+        };
+        let edge = triangulation.get_half_edge(e).unwrap();
+        let (a, b) = (points[edge.start().id()], points[edge.end().id()]);
+        points.push(Point::new((a.x + b.x) / 2., (a.y + b.y) / 2.));
+        let h = triangulation.edit().split_edge(e, m).unwrap();
+        assert_eq!(triangulation.triangles[h], m);
+        validate_triangulation(&points, &triangulation);
+    }
+    assert_eq!(triangulation.len(), 23);
+    assert_eq!(triangulation.hull.len(), 13);
+
+    // flipping the diagonal of a grid square twice restores it
+    let square = |e: &delaunator::HalfEdge<'_, Index>| {
+        let corners = e.left().vertices().chain(e.right().unwrap().vertices());
+        corners
+            .map(|v| points[v.id()])
+            .all(|p| p.x.fract() == 0. && p.y.fract() == 0.)
+    };
+    let e = triangulation
+        .half_edges()
+        .find(|e| {
+            e.twin().is_some()
+                && square(e)
+                && points[e.start().id()].x != points[e.end().id()].x
+                && points[e.start().id()].y != points[e.end().id()].y
+        })
+        .unwrap();
+    let (a, b) = (e.start().id(), e.end().id());
+    let e = e.id();
+    let diagonal = triangulation.edit().flip_edge(e).unwrap();
+    validate_triangulation(&points, &triangulation);
+    assert!(triangulation.get_half_edge(diagonal).unwrap().start().id() != a);
+    let restored = triangulation.edit().flip_edge(diagonal).unwrap();
+    let restored = triangulation.get_half_edge(restored).unwrap();
+    let mut ends = [restored.start().id(), restored.end().id()];
+    ends.sort_unstable();
+    assert_eq!(ends, [a.min(b), a.max(b)]);
+    validate_triangulation(&points, &triangulation);
+
+    // collapsing the split edges removes their midpoints
+    let len = triangulation.len();
+    for m in [18, 17] {
+        let e = triangulation
+            .half_edges()
+            .find(|e| e.end().id() == m && triangulation.halfedges[e.id()].is_none() == (m == 18))
+            .unwrap()
+            .id();
+        assert_eq!(triangulation.edit().collapse_edge(e), Ok(m));
+        assert!(!triangulation.triangles.contains(&m));
+        validate_triangulation(&points, &triangulation);
+    }
+    assert_eq!(triangulation.len(), len - 3);
+    assert_eq!(triangulation.hull.len(), 12);
+
+    #[cfg(feature = "vertices")]
+    for (i, &e) in triangulation.vertices.iter().enumerate() {
+        if i < 17 {
+            assert_eq!(triangulation.triangles[e], i);
+        } else {
+            assert_eq!(e, Index::MAX);
+        }
+    }
+}
+
+#[test]
+fn journal_rollback() {
+    use delaunator::edit::Journal;
+
+    let points: Vec<Point<f64>> = (0..16)
+        .map(|i| Point::new((i % 4) as f64, (i / 4) as f64))
+        .collect();
+    let mut triangulation = Triangulation::<Index>::new(&points).unwrap();
+    let state =
+        |t: &Triangulation<Index>| (t.triangles.clone(), t.halfedges.clone(), t.hull.clone());
+    let original = state(&triangulation);
+    #[cfg(feature = "vertices")]
+    let vertices = triangulation.vertices.clone();
+
+    let mut journal = Journal::new();
+    let start = journal.snapshot();
+    let hull_edge =
+        |t: &Triangulation<Index>| t.halfedges.iter().position(|e| e.is_none()).unwrap();
+    let interior_edge =
+        |t: &Triangulation<Index>| t.halfedges.iter().position(|e| e.is_some()).unwrap();
+
+    let mut edit = triangulation.edit_with_journal(&mut journal);
+    edit.split_triangle(3, 16).unwrap();
+    let e = hull_edge(&triangulation);
+    triangulation
+        .edit_with_journal(&mut journal)
+        .split_edge(e, 17)
+        .unwrap();
+    let split = state(&triangulation);
+    let middle = journal.snapshot();
+
+    let e = interior_edge(&triangulation);
+    let mut edit = triangulation.edit_with_journal(&mut journal);
+    edit.split_edge(e, 18).unwrap();
+    let e = edit
+        .view()
+        .half_edges()
+        .find(|e| e.end().id() == 18)
+        .unwrap()
+        .id();
+    edit.collapse_edge(e).unwrap();
+    let e = interior_edge(&triangulation);
+    triangulation
+        .edit_with_journal(&mut journal)
+        .flip_edge(e)
+        .unwrap();
+    assert!(state(&triangulation) != split);
+
+    journal.rollback(&mut triangulation, middle);
+    assert!(state(&triangulation) == split);
+    journal.rollback(&mut triangulation, start);
+    assert!(state(&triangulation) == original);
+    assert!(journal.is_empty());
+
+    #[cfg(feature = "vertices")]
+    assert_eq!(triangulation.vertices, vertices);
+}
+
+#[test]
+fn kinetic_repair() {
+    use delaunator::kinetic::Repair;
+
+    // a disk of points under differential rotation, faster near the center
+    let mut points: Vec<Point<f64>> = (0..400)
+        .map(|i| {
+            let (r, angle) = ((i as f64 / 400.).sqrt(), i as f64 * 2.399_963);
+            Point::new(r * angle.cos(), r * angle.sin())
+        })
+        .collect();
+    let mut triangulation = Triangulation::<Index>::new(&points).unwrap();
+
+    let mut flips = 0;
+    for _ in 0..20 {
+        for p in &mut points {
+            let r = p.length();
+            let (sin, cos) = (0.02 / (0.2 + r)).sin_cos();
+            *p = Point::new(p.x * cos - p.y * sin, p.x * sin + p.y * cos);
+        }
+        match triangulation.repair(&points).unwrap() {
+            Repair::Flipped(n) => flips += n,
+            Repair::Rebuilt => panic!("small steps should be repaired in place"),
+        }
+        validate_triangulation(&points, &triangulation);
+        assert!(triangulation.is_delaunay(&points));
+        let expected = Triangulation::<Index>::new(&points).unwrap();
+        assert_eq!(triangulation.len(), expected.len());
+        assert_eq!(triangulation.hull.len(), expected.hull.len());
+    }
+    assert!(flips > 0);
+
+    // a large jump turns triangles inside out
+    points.swap(0, 399);
+    assert_eq!(triangulation.repair(&points), Some(Repair::Rebuilt));
+    validate_triangulation(&points, &triangulation);
+}
+
+#[test]
+fn nearest() {
+    let points = load_fixture(include_str!("fixtures/ukraine.json"));
+    let triangulation = Triangulation::<Index>::new(&points).unwrap();
+
+    // queries only read the triangulation, so threads may share it
+    fn shared<S: Send + Sync>(_: &S) {}
+    shared(&triangulation);
+
+    let (min, max) = points.iter().fold(
+        (
+            Point::new(f64::INFINITY, f64::INFINITY),
+            Point::new(-f64::INFINITY, -f64::INFINITY),
+        ),
+        |(min, max), p| {
+            (
+                Point::new(min.x.min(p.x), min.y.min(p.y)),
+                Point::new(max.x.max(p.x), max.y.max(p.y)),
+            )
+        },
+    );
+    for i in 0..400 {
+        // a grid reaching past the hull
+        let (u, v) = (
+            (i % 20) as f64 / 19. * 1.2 - 0.1,
+            (i / 20) as f64 / 19. * 1.2 - 0.1,
+        );
+        let q = Point::new(min.x + u * (max.x - min.x), min.y + v * (max.y - min.y));
+        let found = triangulation.nearest(&points, q).unwrap();
+        let best = points
+            .iter()
+            .map(|p| p.distance_squared(q))
+            .fold(f64::INFINITY, f64::min);
+        assert_eq!(points[found].distance_squared(q), best);
+    }
+}
+
+#[test]
+fn boolean_overlay() {
+    use delaunator::overlay::{self, Operation, Region};
+
+    let ring = |corners: &[(f64, f64)]| -> Vec<Point<f64>> {
+        corners.iter().map(|&(x, y)| Point::new(x, y)).collect()
+    };
+    // an L of area 20 and a far away triangle of area 2, against a clockwise
+    // square of area 16 that covers 7 of the L
+    let first = [
+        ring(&[(0., 0.), (6., 0.), (6., 2.), (2., 2.), (2., 6.), (0., 6.)]),
+        ring(&[(10., 10.), (12., 10.), (10., 12.)]),
+    ];
+    let second = [ring(&[(1., 1.), (1., 5.), (5., 5.), (5., 1.)])];
+
+    let union = overlay::boolean(&first, &second, Operation::Union).unwrap();
+    let triangulation = &union.triangulation;
+    assert_eq!(union.regions.len(), triangulation.len());
+    for (e, twin) in triangulation.halfedges.iter().enumerate() {
+        if let Some(twin) = twin.get() {
+            assert_eq!(triangulation.halfedges[twin].get(), Some(e));
+        }
+    }
+    for t in union.triangles().chunks_exact(3) {
+        let [a, b, c] = [t[0], t[1], t[2]].map(|i| union.points[i]);
+        assert!((b - a).perp_dot(c - a) > 0.);
+    }
+    // the square crosses the L twice
+    assert_eq!(union.points.len(), 9 + 4 + 2);
+    let close = |a: f64, b: f64| (a - b).abs() < 1e-9;
+    assert!(close(union.area(), 31.));
+    assert!(close(union.area_of(Region::First), 15.));
+    assert!(close(union.area_of(Region::Second), 9.));
+    assert!(close(union.area_of(Region::Both), 7.));
+
+    let expected = [
+        (Operation::Intersection, 7.),
+        (Operation::Difference, 15.),
+        (Operation::SymmetricDifference, 24.),
+    ];
+    for &(operation, area) in &expected {
+        let mesh = overlay::boolean(&first, &second, operation).unwrap();
+        assert!(close(mesh.area(), area));
+        assert_eq!(mesh.regions, union.regions);
+    }
+
+    // shared triangles lie inside the square
+    for t in 0..triangulation.len() {
+        if union.regions[t] == Some(Region::Both) {
+            for &i in &triangulation.triangles[3 * t..3 * t + 3] {
+                let p = union.points[i];
+                assert!((1.0..=5.).contains(&p.x) && (1.0..=5.).contains(&p.y));
+            }
+        }
+    }
+
+    // an empty operand leaves the other one as it is
+    let none: [Vec<Point<f64>>; 0] = [];
+    let difference = overlay::boolean(&first, &none, Operation::Difference).unwrap();
+    assert!(close(difference.area(), 22.));
+    assert!(overlay::boolean(&none, &second, Operation::Intersection)
+        .unwrap()
+        .triangles()
+        .is_empty());
+
+    // a hole, and boundaries that touch and run along each other
+    let frame = [
+        ring(&[(0., 0.), (4., 0.), (4., 4.), (0., 4.)]),
+        ring(&[(1., 1.), (3., 1.), (3., 3.), (1., 3.)]),
+    ];
+    let strip = [ring(&[(0., 0.), (4., 0.), (4., 1.), (0., 1.)])];
+    let intersection = overlay::boolean(&frame, &strip, Operation::Intersection).unwrap();
+    assert!(close(intersection.area(), 4.));
+    assert!(close(intersection.area_of(Region::First), 8.));
 }
 
 #[test]
-fn js_issues() {
-    validate(&load_fixture(include_str!("fixtures/issue5.json")));
-    validate(&load_fixture(include_str!("fixtures/issue11.json")));
-    validate(&load_fixture(include_str!("fixtures/issue13.json")));
-    validate(&load_fixture(include_str!("fixtures/issue24.json")));
-    validate(&load_fixture(include_str!("fixtures/issue43.json")));
-    validate(&load_fixture(include_str!("fixtures/issue44.json")));
+fn triangulation_overlay() {
+    use delaunator::{overlay, sampling::PoissonDisk};
+
+    // two unrelated triangulations of the same square
+    let corners = [(0., 0.), (10., 0.), (10., 10.), (0., 10.)].map(|(x, y)| Point::new(x, y));
+    let mut first: Vec<Point<f64>> = (0..11)
+        .flat_map(|i| (0..11).map(move |j| Point::new(i as f64, j as f64)))
+        .collect();
+    first.extend(corners);
+    let mut second = PoissonDisk::new(Point::new(0., 0.), Point::new(10., 10.), 1.3)
+        .seed(5)
+        .sample();
+    second.extend(corners);
+    let a = Triangulation::<Index>::new(&first).unwrap();
+    let b = Triangulation::<Index>::new(&second).unwrap();
+
+    let arrangement = overlay::overlay(&a, &first, &b, &second);
+    assert_eq!(arrangement.faces.len(), arrangement.sources.len());
+    let areas = arrangement.areas();
+    assert!((areas.iter().sum::<f64>() - 100.).abs() < 1e-9);
+    assert!(areas.iter().all(|&area| area > 0.));
+
+    // the faces in each triangle add up to it
+    let triangle_area = |t: &Triangulation<Index>, points: &[Point<f64>], i: usize| {
+        let [p, q, r] = [0, 1, 2].map(|k| points[t.triangles[3 * i + k]]);
+        (q - p).perp_dot(r - p) / 2.
+    };
+    let mut in_first = vec![0.; a.len()];
+    let mut in_second = vec![0.; b.len()];
+    for (&(i, j), area) in arrangement.sources.iter().zip(&areas) {
+        in_first[i] += area;
+        in_second[j] += area;
+    }
+    for (i, area) in in_first.iter().enumerate() {
+        assert!((area - triangle_area(&a, &first, i)).abs() < 1e-9);
+    }
+    for (j, area) in in_second.iter().enumerate() {
+        assert!((area - triangle_area(&b, &second, j)).abs() < 1e-9);
+    }
+
+    // remapping conserves the integral of a field
+    let values: Vec<f64> = (0..a.len()).map(|i| (i % 7) as f64).collect();
+    let remapped = arrangement.remap(&values, b.len());
+    let integral = |values: &[f64], t: &Triangulation<Index>, points: &[Point<f64>]| {
+        (0..t.len())
+            .map(|i| values[i] * triangle_area(t, points, i))
+            .sum::<f64>()
+    };
+    let remapped: Vec<f64> = remapped.into_iter().map(Option::unwrap).collect();
+    assert!((integral(&values, &a, &first) - integral(&remapped, &b, &second)).abs() < 1e-6);
 }
 
 #[test]
-fn robustness() {
-    let robustness1 = load_fixture(include_str!("fixtures/robustness1.json"));
+fn extrude() {
+    use delaunator::export;
+    use std::collections::HashMap;
 
-    validate(&robustness1);
-    validate(&(scale_points(&robustness1, 1e-9)));
-    validate(&(scale_points(&robustness1, 1e-2)));
-    validate(&(scale_points(&robustness1, 100.0)));
-    validate(&(scale_points(&robustness1, 1e9)));
+    let points = load_fixture(include_str!("fixtures/ukraine.json"));
+    let triangulation = Triangulation::<Index>::new(&points).unwrap();
+    let area: f64 = triangulation
+        .triangles
+        .chunks_exact(3)
+        .map(|t| (points[t[1]] - points[t[0]]).perp_dot(points[t[2]] - points[t[0]]) / 2.)
+        .sum();
 
-    let robustness2 = load_fixture(include_str!("fixtures/robustness2.json"));
-    validate(&robustness2[0..100]);
-    validate(&robustness2);
+    for &height in &[3., -3.] {
+        let mesh = export::extrude(&points, &triangulation, height);
+        assert_eq!(mesh.positions.len(), mesh.normals.len());
+        assert_eq!(
+            mesh.indices.len(),
+            3 * (2 * triangulation.len() + 2 * triangulation.hull.len())
+        );
+        let position = |i: u32| {
+            let i = 3 * i as usize;
+            [0, 1, 2].map(|k| mesh.positions[i + k])
+        };
+
+        // watertight: every edge between welded positions is used once in
+        // each direction
+        let mut edges = HashMap::new();
+        for t in mesh.indices.chunks_exact(3) {
+            for k in 0..3 {
+                let (a, b) = (position(t[k]), position(t[(k + 1) % 3]));
+                let key = |p: [f32; 3]| p.map(f32::to_bits);
+                *edges.entry((key(a), key(b))).or_insert(0) += 1;
+            }
+        }
+        for (&(a, b), &count) in &edges {
+            assert_eq!(count, 1);
+            assert_eq!(edges.get(&(b, a)), Some(&1));
+        }
+
+        // outward orientation gives a positive volume, by the divergence theorem
+        let volume: f64 = mesh
+            .indices
+            .chunks_exact(3)
+            .map(|t| {
+                let [a, b, c] = [t[0], t[1], t[2]].map(|i| position(i).map(f64::from));
+                let cross = [
+                    b[1] * c[2] - b[2] * c[1],
+                    b[2] * c[0] - b[0] * c[2],
+                    b[0] * c[1] - b[1] * c[0],
+                ];
+                (a[0] * cross[0] + a[1] * cross[1] + a[2] * cross[2]) / 6.
+            })
+            .sum();
+        assert!((volume - 3. * area).abs() < 1e-3 * area);
+    }
 }
 
 #[test]
-fn bad_input() {
-    let mut points = vec![Point { x: 0., y: 0. }];
-    assert!(
-        Triangulation::<Index>::new(&points).is_none(),
-        "Expected empty triangulation (1 point)"
+fn mesh_sinks() {
+    use delaunator::export::{self, MeshSink, PlyWriter, VtkWriter};
+    use std::io;
+
+    // a custom format that records what it receives
+    #[derive(Default)]
+    struct Recorder {
+        calls: Vec<String>,
+    }
+    impl MeshSink for Recorder {
+        fn begin(&mut self, vertices: usize, triangles: usize) -> io::Result<()> {
+            self.calls.push(format!("begin {} {}", vertices, triangles));
+            Ok(())
+        }
+        fn add_vertex(&mut self, [x, y, z]: [f64; 3]) -> io::Result<()> {
+            self.calls.push(format!("v {} {} {}", x, y, z));
+            Ok(())
+        }
+        fn add_triangle(&mut self, [a, b, c]: [usize; 3]) -> io::Result<()> {
+            self.calls.push(format!("t {} {} {}", a, b, c));
+            Ok(())
+        }
+        fn add_attribute(&mut self, name: &str, values: &[f64]) -> io::Result<()> {
+            self.calls.push(format!("a {} {:?}", name, values));
+            Ok(())
+        }
+        fn finish(&mut self) -> io::Result<()> {
+            self.calls.push("finish".to_string());
+            Ok(())
+        }
+    }
+
+    let points = vec![
+        Point::new(0., 0.),
+        Point::new(1., 0.),
+        Point::new(1., 1.),
+        Point::new(0., 1.),
+    ];
+    let z = [0., 1., 2., 3.];
+    let triangulation = Triangulation::<Index>::new(&points).unwrap();
+    let slope = [0.5; 4];
+
+    let mut recorder = Recorder::default();
+    export::write_triangulation(
+        &mut recorder,
+        &triangulation,
+        &points,
+        Some(&z[..]),
+        &[("slope", &slope[..])],
+    )
+    .unwrap();
+    assert_eq!(
+        recorder.calls,
+        [
+            "begin 4 2",
+            "v 0 0 0",
+            "v 1 0 1",
+            "v 1 1 2",
+            "v 0 1 3",
+            "t 0 1 2",
+            "t 2 3 0",
+            "a slope [0.5, 0.5, 0.5, 0.5]",
+            "finish",
+        ]
     );
 
-    points.push(Point { x: 1., y: 0. });
-    assert!(
-        Triangulation::<Index>::new(&points).is_none(),
-        "Expected empty triangulation (2 point)"
+    let mut ply = PlyWriter::new(Vec::new());
+    export::write_triangulation(
+        &mut ply,
+        &triangulation,
+        &points,
+        Some(&z[..]),
+        &[("slope", &slope[..])],
+    )
+    .unwrap();
+    let ply = String::from_utf8(ply.into_inner()).unwrap();
+    let body: Vec<&str> = ply.split("end_header\n").collect();
+    assert!(body[0].contains("element vertex 4\n") && body[0].contains("property double slope\n"));
+    assert_eq!(
+        body[1].lines().collect::<Vec<_>>()[..2],
+        ["0 0 0 0.5", "1 0 1 0.5"]
     );
+    assert_eq!(body[1].lines().last(), Some("3 2 3 0"));
 
-    points.push(Point { x: 2., y: 0. });
-    assert!(
-        Triangulation::<Index>::new(&points).is_none(),
-        "Expected empty triangulation (collinear points)"
+    // the extruded cube, with its normals as point data
+    let cube = export::extrude(&points, &triangulation, 1.);
+    let mut vtk = VtkWriter::new(Vec::new());
+    cube.write_to(&mut vtk).unwrap();
+    let vtk = String::from_utf8(vtk.into_inner()).unwrap();
+    let vertices = cube.positions.len() / 3;
+    assert!(vtk.contains(&format!("POINTS {} double\n", vertices)));
+    assert!(vtk.contains("POLYGONS 12 48\n"));
+    assert!(vtk.contains(&format!("POINT_DATA {}\nSCALARS nx double", vertices)));
+    assert_eq!(vtk.matches("SCALARS").count(), 3);
+}
+
+#[test]
+fn estimate_sizes() {
+    let points = load_fixture(include_str!("fixtures/ukraine.json"));
+    let triangulation = Triangulation::<Index>::new(&points).unwrap();
+    let sizes = Triangulation::<Index>::estimate_sizes(points.len());
+    assert!(triangulation.len() <= sizes.triangles);
+    assert!(triangulation.halfedges.len() <= sizes.halfedges);
+    assert!(triangulation.hull.len() <= sizes.hull);
+    assert!(triangulation.triangles.capacity() >= triangulation.triangles.len());
+
+    let sizes = Triangulation::<u16>::estimate_sizes(4);
+    assert_eq!((sizes.triangles, sizes.halfedges, sizes.hull), (3, 9, 4));
+    assert_eq!(sizes.total_bytes(), 9 * 2 + 9 * 2 + 4 * 2);
+    assert_eq!(Triangulation::<u32>::estimate_sizes(2).triangles, 0);
+}
+
+#[test]
+fn dot_graph() {
+    let points = [
+        Point { x: 0., y: 0. },
+        Point { x: 1., y: 0. },
+        Point { x: 1., y: 1. },
+        Point { x: 0., y: 1. },
+    ];
+    let mut triangulation = Triangulation::<Index>::new(&points).unwrap();
+    let dot = triangulation.to_dot();
+    assert!(dot.starts_with("digraph triangulation {"));
+    assert_eq!(dot.matches("[label=").count(), 2);
+    assert_eq!(dot.matches(" hull").count(), 4);
+    assert_eq!(dot.matches("dir=none").count(), 1);
+    assert!(!dot.contains("red"));
+
+    let around = triangulation.to_dot_around(0, 0);
+    assert_eq!(around.matches("[label=").count(), 1);
+    assert!(around.contains("t1 [shape=ellipse, style=dashed]"));
+    assert_eq!(triangulation.to_dot_around(0, 1), dot);
+
+    let e = (0..6)
+        .find(|&e| triangulation.halfedges[e].is_some())
+        .unwrap();
+    triangulation.halfedges[e] = (17 as Index).into();
+    assert!(triangulation.to_dot().contains("-> \"17?\" [color=red]"));
+}
+
+#[test]
+fn tri_file() {
+    use delaunator::tri::{self, TriError};
+
+    let points = load_fixture(include_str!("fixtures/ukraine.json"));
+    let triangulation = Triangulation::<Index>::new(&points).unwrap();
+    let values: Vec<f64> = points.iter().map(|p| p.x - p.y).collect();
+    let mut file = Vec::new();
+    tri::write(&mut file, &triangulation, Some(&points), Some(&values)).unwrap();
+    assert_eq!(&file[..4], b"DTRI");
+    assert_eq!(file.len() % 8, 0);
+
+    let loaded = tri::read::<_, u32>(&file[..]).unwrap();
+    let triangles: Vec<u32> = triangulation.triangles.iter().map(|&i| i as u32).collect();
+    assert_eq!(loaded.triangulation.triangles, triangles);
+    assert_eq!(loaded.triangulation.hull.len(), triangulation.hull.len());
+    for (a, b) in loaded
+        .triangulation
+        .halfedges
+        .iter()
+        .zip(&triangulation.halfedges)
+    {
+        assert_eq!(a.map(|e| e as usize), b.map(|e| e as usize));
+    }
+    assert_eq!(loaded.points.unwrap(), points);
+    assert_eq!(loaded.values.unwrap(), values);
+    // the indices are narrowed as long as they fit
+    let narrow = tri::read::<_, u16>(&file[..]).unwrap();
+    assert_eq!(narrow.triangulation.len(), triangulation.len());
+    // a point beyond the points of the file, or a twin that doesn't point back
+    let width = usize::from(file[6]);
+    let halfedges = 32 + (triangulation.triangles.len() * width + 7) / 8 * 8;
+    let mut broken = file.clone();
+    broken[32..32 + width].copy_from_slice(&(points.len() as u64).to_le_bytes()[..width]);
+    assert!(matches!(
+        tri::read::<_, u32>(&broken[..]),
+        Err(TriError::Malformed)
+    ));
+    let mut broken = file.clone();
+    broken[halfedges..halfedges + width].fill(0);
+    assert!(matches!(
+        tri::read::<_, u32>(&broken[..]),
+        Err(TriError::Malformed)
+    ));
+    file[6] = 3;
+    assert!(matches!(
+        tri::read::<_, u32>(&file[..]),
+        Err(TriError::IndexWidth(3))
+    ));
+
+    let square = [
+        Point { x: 0., y: 0. },
+        Point { x: 1., y: 0. },
+        Point { x: 1., y: 1. },
+        Point { x: 0., y: 1. },
+    ];
+    let small = Triangulation::<u16>::new(&square).unwrap();
+    let mut file = Vec::new();
+    tri::write(&mut file, &small, None, None).unwrap();
+    let loaded = tri::read::<_, Index>(&file[..]).unwrap();
+    assert_eq!(loaded.triangulation.len(), small.len());
+    assert!(loaded.points.is_none() && loaded.values.is_none());
+    assert!(matches!(
+        tri::read::<_, Index>(&file[..file.len() - 8]),
+        Err(TriError::Truncated)
+    ));
+    file[0] = b'X';
+    assert!(matches!(
+        tri::read::<_, Index>(&file[..]),
+        Err(TriError::Magic)
+    ));
+}
+
+#[test]
+fn attribute_propagation() {
+    use delaunator::attributes::{Attributes, Lerp, Parent};
+
+    let mut points = vec![
+        Point { x: 0., y: 0. },
+        Point { x: 4., y: 0. },
+        Point { x: 4., y: 4. },
+        Point { x: 0., y: 4. },
+    ];
+    let mut triangulation = Triangulation::<Index>::new(&points).unwrap();
+    // a height and a color per point, both linear in x and y
+    let value = |p: Point<f64>| (p.x + 2. * p.y, [p.x / 4., p.y / 4., 1.]);
+    let mut attributes = Attributes::new(points.iter().map(|&p| value(p)).collect());
+
+    points.push(Point { x: 1., y: 2. });
+    let t = triangulation
+        .triangles()
+        .position(|t| {
+            let [a, b, c] = [t.a(), t.b(), t.c()].map(|v| points[v.id()]);
+            let inside = |p: Point<f64>, q: Point<f64>| (q - p).perp_dot(points[4] - p) > 0.;
+            inside(a, b) && inside(b, c) && inside(c, a)
+        })
+        .unwrap();
+    let mut edit = triangulation.edit();
+    attributes.split_triangle(&mut edit, &points, t, 4).unwrap();
+
+    points.push(Point { x: 4., y: 3. });
+    let e = edit
+        .view()
+        .half_edges()
+        .find(|e| e.start().id() == 1 && e.end().id() == 2)
+        .unwrap()
+        .id();
+    attributes.split_edge(&mut edit, &points, e, 5).unwrap();
+
+    for (i, &p) in points.iter().enumerate() {
+        let (height, color) = value(p);
+        assert!((attributes.values[i].0 - height).abs() < 1e-12);
+        for (&a, b) in attributes.values[i].1.iter().zip(color) {
+            assert!((a - b).abs() < 1e-12);
+        }
+    }
+    assert_eq!(attributes.parents().len(), 2);
+    assert!(matches!(
+        attributes.parents()[0],
+        (4, Parent::Triangle { .. })
+    ));
+    assert_eq!(
+        attributes.parents()[1],
+        (
+            5,
+            Parent::Edge {
+                a: 1,
+                b: 2,
+                t: 0.75
+            }
+        )
     );
+    assert_eq!(triangulation.len(), 5);
+    assert_eq!(
+        Point::new(0f64, 2.).lerp(&Point::new(4., 2.), 0.25),
+        Point::new(1., 2.)
+    );
+}
 
-    points.push(Point { x: 1., y: 1. });
-    validate(&points);
+#[test]
+fn constrained_delaunay() {
+    use delaunator::constrained::{ConstrainedTriangulation, ConstraintError};
+
+    let points = load_fixture(include_str!("fixtures/ukraine.json"));
+    let triangulation = Triangulation::<Index>::new(&points).unwrap();
+    let len = triangulation.len();
+    let mut cdt = ConstrainedTriangulation::new(triangulation, points.len());
+
+    // long segments across the fixture, skipping those that cross earlier ones
+    let n = points.len();
+    let mut crossing = 0;
+    for i in (0..n).step_by(7) {
+        match cdt.insert(&points, i, (i * 31 + 17) % n) {
+            Ok(()) => {}
+            Err(ConstraintError::Crossing { .. }) => crossing += 1,
+            Err(ConstraintError::NotTriangulated(_)) => {}
+            Err(err) => panic!("{}", err),
+        }
+    }
+    assert!(crossing > 0 && cdt.segments().len() > 10);
+
+    let t = cdt.triangulation();
+    assert_eq!(t.len(), len);
+    let pos = |e: usize| points[t.triangles[e] as usize];
+    for (e, twin) in t.halfedges.iter().enumerate() {
+        if e % 3 == 0 {
+            let (a, b, c) = (pos(e), pos(e + 1), pos(e + 2));
+            assert!((b - a).perp_dot(c - a) > 0.);
+        }
+        if let Some(f) = twin.get() {
+            assert_eq!(t.halfedges[f].get(), Some(e));
+            // edges other than segments are locally Delaunay
+            if !cdt.is_constrained(e) {
+                let opposite = pos(f - f % 3 + (f + 2) % 3);
+                let apex = pos(e - e % 3 + (e + 2) % 3);
+                assert!(!opposite.is_in_circle(apex, pos(e), pos(f)));
+            }
+        }
+    }
+    for (a, b) in cdt.segments() {
+        assert!(cdt
+            .find_edge(a, b)
+            .or_else(|| cdt.find_edge(b, a))
+            .is_some());
+    }
+
+    // the long diagonal of a thin rhombus, and a segment crossing it
+    let rhombus = [
+        Point { x: 0., y: 0. },
+        Point { x: 2., y: -1. },
+        Point { x: 4., y: 0. },
+        Point { x: 2., y: 1. },
+    ];
+    let cdt = Triangulation::<Index>::with_constraints(&rhombus, &[(0, 2)]).unwrap();
+    assert_eq!(cdt.segments(), vec![(0, 2)]);
+    assert!(cdt
+        .find_edge(1, 3)
+        .or_else(|| cdt.find_edge(3, 1))
+        .is_none());
+    assert!(matches!(
+        Triangulation::<Index>::with_constraints(&rhombus, &[(0, 2), (3, 1)]),
+        Err(ConstraintError::Crossing {
+            segment: (1, 3),
+            other: (0, 2)
+        })
+    ));
+    assert!(matches!(
+        Triangulation::<Index>::with_constraints(&rhombus, &[(0, 4)]),
+        Err(ConstraintError::OutOfBounds(4))
+    ));
+
+    // a segment through a point is split there
+    let line = [
+        Point { x: 0., y: 0. },
+        Point { x: 1., y: 0.1 },
+        Point { x: 2., y: 0. },
+        Point { x: 1., y: -0.1 },
+        Point { x: 1., y: 1. },
+        Point { x: 1., y: -1. },
+    ];
+    let cdt = Triangulation::<Index>::with_constraints(&line, &[(4, 5)]).unwrap();
+    assert_eq!(cdt.segments(), vec![(1, 3), (1, 4), (3, 5)]);
+}
+
+#[test]
+fn conforming_delaunay() {
+    use delaunator::{
+        attributes::{Attributes, Parent},
+        constrained::ConstrainedTriangulation,
+    };
+
+    let mut points = load_fixture(include_str!("fixtures/ukraine.json"));
+    let triangulation = Triangulation::<Index>::new(&points).unwrap();
+    let mut cdt = ConstrainedTriangulation::new(triangulation, points.len());
+    let n = points.len();
+    for i in (0..n).step_by(7) {
+        let _ = cdt.insert(&points, i, (i * 31 + 17) % n);
+    }
+    let segments = cdt.segments().len();
+    assert!(!cdt.triangulation().is_delaunay(&points));
+
+    let mut heights = Attributes::new(points.iter().map(|p| p.x + 2. * p.y).collect());
+    let inserted = cdt.conform(&mut points, 10_000);
+    assert!(!inserted.is_empty() && inserted.len() < 10_000);
+    assert_eq!(points.len(), n + inserted.len());
+    assert!(cdt.triangulation().is_delaunay(&points));
+    // every split adds a segment, and segments stay edges
+    assert_eq!(cdt.segments().len(), segments + inserted.len());
+    for (a, b) in cdt.segments() {
+        assert!(cdt
+            .find_edge(a, b)
+            .or_else(|| cdt.find_edge(b, a))
+            .is_some());
+    }
+    for &(m, parent) in &inserted {
+        let (a, b, t) = match parent {
+            Parent::Edge { a, b, t } => (a, b, t),
+            _ => unreachable!(),
+        };
+        assert!(a < m && b < m && t > 0. && t < 1.);
+        let expected = points[a] + (points[b] - points[a]) * t;
+        assert!(points[m].distance(expected) < 1e-9);
+        // a linear function is interpolated exactly
+        let height = *heights.record(m, parent);
+        assert!((height - (points[m].x + 2. * points[m].y)).abs() < 1e-6);
+    }
+
+    // segments meeting at a small angle
+    let mut points = vec![
+        Point { x: 0., y: 0. },
+        Point { x: 10., y: 0. },
+        Point { x: 10., y: 0.5 },
+        Point { x: 5., y: -3. },
+        Point { x: 5., y: 3. },
+    ];
+    let mut cdt = Triangulation::<Index>::with_constraints(&points, &[(0, 1), (0, 2)]).unwrap();
+    let inserted = cdt.conform(&mut points, 1000);
+    assert!(inserted.len() < 1000);
+    assert!(cdt.triangulation().is_delaunay(&points));
+}
+
+#[test]
+fn pslg_domain() {
+    // an L-shaped outer ring with a square hole, an interior segment and
+    // points scattered over its bounding box
+    let mut points = vec![
+        Point { x: 0., y: 0. },
+        Point { x: 4., y: 0. },
+        Point { x: 4., y: 2. },
+        Point { x: 2., y: 2. },
+        Point { x: 2., y: 4. },
+        Point { x: 0., y: 4. },
+        Point { x: 0.5, y: 0.5 },
+        Point { x: 1.5, y: 0.5 },
+        Point { x: 1.5, y: 1.5 },
+        Point { x: 0.5, y: 1.5 },
+        Point { x: 3., y: 0.5 },
+        Point { x: 3.5, y: 1.5 },
+    ];
+    for i in 0..7 {
+        for j in 0..7 {
+            points.push(Point {
+                x: 0.3 + 0.55 * i as f64,
+                y: 0.2 + 0.6 * j as f64,
+            });
+        }
+    }
+    let rings = [vec![0, 1, 2, 3, 4, 5, 0], vec![9, 8, 7, 6]];
+    let cdt = Triangulation::<Index>::from_pslg(&points, &rings, &[(10, 11)]).unwrap();
+    let mut segments = cdt.segments();
+    segments.retain(|&s| s == (10, 11));
+    assert_eq!(segments.len(), 1);
+
+    let inside = cdt.inside();
+    let triangles = cdt.domain_triangles();
+    assert_eq!(triangles.len(), 3 * inside.iter().filter(|&&i| i).count());
+    assert!(triangles.len() < cdt.triangulation().triangles.len());
+    let mut total = 0.;
+    for t in triangles.chunks_exact(3) {
+        let [a, b, c] = [t[0], t[1], t[2]].map(|i| points[i as usize]);
+        total += (b - a).perp_dot(c - a) / 2.;
+        let centroid = Point {
+            x: (a.x + b.x + c.x) / 3.,
+            y: (a.y + b.y + c.y) / 3.,
+        };
+        let in_hole = (0.5..1.5).contains(&centroid.x) && (0.5..1.5).contains(&centroid.y);
+        let in_notch = centroid.x > 2. && centroid.y > 2.;
+        assert!(!in_hole && !in_notch);
+    }
+    assert!((total - (12. - 1.)).abs() < 1e-9);
+
+    // without rings, the domain is the whole convex hull
+    let cdt = Triangulation::<Index>::with_constraints(&points, &[(10, 11)]).unwrap();
+    assert_eq!(cdt.domain_triangles(), cdt.triangulation().triangles);
+}
+
+#[test]
+fn refinement() {
+    use delaunator::{attributes::Attributes, refine::Refinement};
+
+    // the smallest angle of every triangle in the domain, in degrees
+    fn smallest_angle(points: &[Point<f64>], triangles: &[Index]) -> f64 {
+        let mut smallest = 180f64;
+        for t in triangles.chunks_exact(3) {
+            let [a, b, c] = [t[0], t[1], t[2]].map(|i| points[i]);
+            for (p, q, r) in [(a, b, c), (b, c, a), (c, a, b)] {
+                let (u, v) = (q - p, r - p);
+                let angle = u.perp_dot(v).atan2(u.x * v.x + u.y * v.y);
+                smallest = smallest.min(angle.to_degrees());
+            }
+        }
+        smallest
+    }
+
+    // an L-shaped outer ring with a square hole and an interior segment
+    let mut points = vec![
+        Point { x: 0., y: 0. },
+        Point { x: 4., y: 0. },
+        Point { x: 4., y: 2. },
+        Point { x: 2., y: 2. },
+        Point { x: 2., y: 4. },
+        Point { x: 0., y: 4. },
+        Point { x: 0.5, y: 0.5 },
+        Point { x: 1.5, y: 0.5 },
+        Point { x: 1.5, y: 1.5 },
+        Point { x: 0.5, y: 1.5 },
+        Point { x: 3., y: 0.5 },
+        Point { x: 3.5, y: 1.5 },
+    ];
+    let n = points.len();
+    let rings = [vec![0, 1, 2, 3, 4, 5], vec![9, 8, 7, 6]];
+    let mut cdt = Triangulation::<Index>::from_pslg(&points, &rings, &[(10, 11)]).unwrap();
+    assert!(smallest_angle(&points, &cdt.domain_triangles()) < 30.);
+
+    let mut heights = Attributes::new(points.iter().map(|p| p.x - 3. * p.y).collect());
+    let inserted = Refinement::new()
+        .min_angle(30.)
+        .max_points(10_000)
+        .refine(&mut cdt, &mut points);
+    assert!(!inserted.is_empty() && inserted.len() < 10_000);
+    assert_eq!(points.len(), n + inserted.len());
+    let triangles = cdt.domain_triangles();
+    assert!(smallest_angle(&points, &triangles) >= 30. - 1e-9);
+
+    // the domain is unchanged and the segments are still edges
+    let area: f64 = triangles
+        .chunks_exact(3)
+        .map(|t| (points[t[1]] - points[t[0]]).perp_dot(points[t[2]] - points[t[0]]) / 2.)
+        .sum();
+    assert!((area - (12. - 1.)).abs() < 1e-9);
+    for (a, b) in cdt.segments() {
+        assert!(cdt
+            .find_edge(a, b)
+            .or_else(|| cdt.find_edge(b, a))
+            .is_some());
+    }
+    // a linear function is interpolated exactly
+    for &(m, parent) in &inserted {
+        let height = *heights.record(m, parent);
+        assert!((height - (points[m].x - 3. * points[m].y)).abs() < 1e-9);
+    }
+
+    // without rings, the whole convex hull is refined
+    let mut points = load_fixture(include_str!("fixtures/ukraine.json"));
+    let triangulation = Triangulation::<Index>::new(&points).unwrap();
+    let mut cdt =
+        delaunator::constrained::ConstrainedTriangulation::new(triangulation, points.len());
+    let inserted = Refinement::new().refine(&mut cdt, &mut points);
+    assert!(!inserted.is_empty());
+    assert!(smallest_angle(&points, &cdt.triangulation().triangles) >= 20. - 1e-9);
+    assert!(cdt.triangulation().is_delaunay(&points));
+}
+
+#[test]
+fn area_refinement() {
+    use delaunator::refine::Refinement;
+
+    // a square split in two by a segment, with a finer left half
+    let mut points = vec![
+        Point { x: 0., y: 0. },
+        Point { x: 2., y: 0. },
+        Point { x: 2., y: 1. },
+        Point { x: 0., y: 1. },
+        Point { x: 1., y: 0. },
+        Point { x: 1., y: 1. },
+    ];
+    let mut cdt =
+        Triangulation::<Index>::from_pslg(&points, &[[0, 4, 1, 2, 5, 3]], &[(4, 5)]).unwrap();
+    let inserted = Refinement::new()
+        .min_angle(0.)
+        .max_area(0.05)
+        .region(Point { x: 0.5, y: 0.5 }, 0.005)
+        .region(Point { x: 5., y: 5. }, 1e-9)
+        .refine(&mut cdt, &mut points);
+    assert!(!inserted.is_empty());
+
+    let (mut left, mut right) = (0, 0);
+    for t in cdt.domain_triangles().chunks_exact(3) {
+        let [a, b, c] = [t[0], t[1], t[2]].map(|i| points[i]);
+        let area = (b - a).perp_dot(c - a) / 2.;
+        if a.x + b.x + c.x < 3. {
+            assert!(area <= 0.005);
+            left += 1;
+        } else {
+            assert!(area <= 0.05);
+            right += 1;
+        }
+    }
+    assert!(left >= 200 && right >= 20 && left > 5 * right);
+    // the dividing segment is split, but its parts are still edges
+    for (a, b) in cdt.segments() {
+        assert!(cdt
+            .find_edge(a, b)
+            .or_else(|| cdt.find_edge(b, a))
+            .is_some());
+    }
+}
+
+#[test]
+fn index_overflow() {
+    let grid = |side: usize| -> Vec<Point<f64>> {
+        (0..side * side)
+            .map(|i| Point {
+                x: (i % side) as f64,
+                y: (i / side) as f64 + (i % side) as f64 * 1e-3,
+            })
+            .collect()
+    };
+    // 6n - 15 half-edges: 59985 fit in a u16, 90759 don't
+    assert!(Triangulation::<u16>::new(&grid(100)).is_some());
+    assert!(Triangulation::<u16>::new(&grid(123)).is_none());
+    assert!(Triangulation::<u32>::new(&grid(123)).is_some());
+}
+
+#[test]
+fn promoted_kernel() {
+    use delaunator::kernel::PromotedKernel;
+
+    let fixtures = [
+        load_fixture(include_str!("fixtures/ukraine.json")),
+        load_fixture(include_str!("fixtures/issue13.json")),
+        load_fixture(include_str!("fixtures/issue44.json")),
+    ];
+    for points in &fixtures {
+        // round to f32, then check the result against the exact f32 values
+        let points32: Vec<Point<f32>> = points
+            .iter()
+            .map(|p| Point::new(p.x as f32, p.y as f32))
+            .collect();
+        let points: Vec<Point<f64>> = points32
+            .iter()
+            .map(|p| Point::new(p.x.into(), p.y.into()))
+            .collect();
+
+        let triangulation = TriangulationBuilder::new()
+            .kernel::<PromotedKernel>()
+            .build::<Index, _, _>(&points32)
+            .unwrap();
+        validate_triangulation(&points, &triangulation);
+        assert!(triangulation.is_delaunay(&points));
+    }
+}
+
+#[cfg(feature = "robust")]
+#[test]
+fn robust_kernel() {
+    use delaunator::kernel::RobustKernel;
+
+    let fixtures = [
+        load_fixture(include_str!("fixtures/ukraine.json")),
+        load_fixture(include_str!("fixtures/issue5.json")),
+        load_fixture(include_str!("fixtures/issue11.json")),
+        load_fixture(include_str!("fixtures/issue13.json")),
+        load_fixture(include_str!("fixtures/issue24.json")),
+        load_fixture(include_str!("fixtures/issue43.json")),
+        load_fixture(include_str!("fixtures/issue44.json")),
+        load_fixture(include_str!("fixtures/robustness1.json")),
+        load_fixture(include_str!("fixtures/robustness2.json")),
+    ];
+    for points in &fixtures {
+        let triangulation = TriangulationBuilder::new()
+            .kernel::<RobustKernel>()
+            .build::<Index, _, _>(points)
+            .unwrap();
+
+        // exactly collinear hull points end up inside the hull with zero-area
+        // triangles, so the area only matches up to rounding
+        for (i, &h) in triangulation.halfedges.iter().enumerate() {
+            if let Some(h) = h.get() {
+                assert_eq!(triangulation.halfedges[h as usize], (i as Index).into());
+            }
+        }
+        let triangles_area: f64 = triangulation
+            .triangles()
+            .map(|t| {
+                let [a, b, c] = [t.a(), t.b(), t.c()].map(|v| points[v.id()]);
+                ((b.y - a.y) * (c.x - b.x) - (b.x - a.x) * (c.y - b.y)).abs() / 2.
+            })
+            .sum();
+        let hull_area = triangulation.hull_area(points);
+        assert!(((hull_area - triangles_area) / hull_area).abs() < 1e-12);
+    }
+}
+
+#[cfg(feature = "robust")]
+#[test]
+fn interval_kernel() {
+    use delaunator::kernel::{IntervalKernel, Kernel, RobustKernel};
+
+    let fixtures = [
+        load_fixture(include_str!("fixtures/ukraine.json")),
+        load_fixture(include_str!("fixtures/issue13.json")),
+        load_fixture(include_str!("fixtures/issue43.json")),
+        load_fixture(include_str!("fixtures/robustness1.json")),
+        load_fixture(include_str!("fixtures/robustness2.json")),
+    ];
+    for points in &fixtures {
+        let robust = TriangulationBuilder::new()
+            .kernel::<RobustKernel>()
+            .build::<Index, _, _>(points)
+            .unwrap();
+        let interval = TriangulationBuilder::new()
+            .kernel::<IntervalKernel>()
+            .build::<Index, _, _>(points)
+            .unwrap();
+        assert_eq!(interval.triangles, robust.triangles);
+        assert_eq!(interval.halfedges, robust.halfedges);
+    }
+
+    // nearly collinear and exactly cocircular points need the exact fallback
+    let (p, q) = (Point::new(0.5f64, 0.5), Point::new(12.0, 12.0));
+    let r = Point::new(24.0, 24.0 + 2f64.powi(-48));
+    assert!(!<IntervalKernel as Kernel<f64>>::is_clockwise(p, q, r));
+    assert!(<IntervalKernel as Kernel<f64>>::is_clockwise(p, r, q));
+    let square = [(0., 0.), (1., 0.), (1., 1.), (0., 1.)].map(|(x, y)| Point::new(x, y));
+    assert_eq!(
+        <IntervalKernel as Kernel<f64>>::in_circle(square[3], square[0], square[1], square[2]),
+        std::cmp::Ordering::Equal
+    );
+    assert_eq!(
+        <IntervalKernel as Kernel<f64>>::in_circle(
+            Point::new(0.5, 0.5),
+            square[0],
+            square[1],
+            square[2]
+        ),
+        std::cmp::Ordering::Greater
+    );
+}
+
+#[test]
+fn to_soa() {
+    let points = load_fixture(include_str!("fixtures/ukraine.json"));
+    let triangulation = Triangulation::<Index>::new(&points).unwrap();
+    let soa = triangulation.to_soa();
+    assert_eq!(soa.len(), triangulation.len());
+
+    for t in triangulation.triangles() {
+        let id = t.id();
+        let vertices: Vec<usize> = t.vertices().map(|v| v.id()).collect();
+        assert_eq!(
+            vertices,
+            [soa.a[id] as usize, soa.b[id] as usize, soa.c[id] as usize]
+        );
+
+        let neighbors: Vec<Option<usize>> = t
+            .edges()
+            .map(|e| e.twin().map(|twin| twin.left().id()))
+            .collect();
+        let soa_neighbors: Vec<Option<usize>> = [soa.ab[id], soa.bc[id], soa.ca[id]]
+            .iter()
+            .map(|n| n.get().map(|n| n as usize))
+            .collect();
+        assert_eq!(neighbors, soa_neighbors);
+    }
 }
 
 fn scale_points(points: &[Point<f64>], scale: f64) -> Vec<Point<f64>> {
@@ -72,13 +3775,19 @@ fn load_fixture(json: &str) -> Vec<Point<f64>> {
     u.iter().map(|p| Point { x: p.0, y: p.1 }).collect()
 }
 
+#[allow(clippy::needless_borrow)]
 fn validate(points: &[Point<f64>]) {
+    let triangulation =
+        Triangulation::<Index>::new(&points).expect("No triangulation exists for this input");
+    validate_triangulation(points, &triangulation);
+}
+
+fn validate_triangulation(points: &[Point<f64>], triangulation: &Triangulation<Index>) {
     let Triangulation {
         triangles,
         halfedges,
-        hull,
         ..
-    } = Triangulation::<Index>::new(&points).expect("No triangulation exists for this input");
+    } = triangulation;
 
     // validate halfedges
     for (i, &h) in halfedges.iter().enumerate() {
@@ -91,19 +3800,7 @@ fn validate(points: &[Point<f64>]) {
     }
 
     // validate triangulation
-    let hull_area = {
-        let mut hull_areas = Vec::new();
-        let mut i = 0;
-        let mut j = hull.len() - 1;
-        while i < hull.len() {
-            let p0 = &points[hull[j] as usize];
-            let p = &points[hull[i] as usize];
-            hull_areas.push((p.x + p0.x) * (p.y - p0.y));
-            j = i;
-            i += 1;
-        }
-        sum(&hull_areas)
-    };
+    let hull_area = 2.0 * triangulation.hull_area(points);
     let triangles_area = {
         let mut triangle_areas = Vec::new();
         let mut i = 0;
@@ -118,6 +3815,7 @@ fn validate(points: &[Point<f64>]) {
     };
 
     let err = ((hull_area - triangles_area) / hull_area).abs();
+    assert!(((2.0 * triangulation.area(points) - triangles_area) / triangles_area).abs() < 1e-12);
     // const EPSILON: f32 = f32::EPSILON * 4.0;
     const EPSILON: f64 = f64::EPSILON * 2.0;
     assert!(
@@ -143,3 +3841,330 @@ fn sum(x: &[f64]) -> f64 {
     }
     sum + err
 }
+
+#[cfg(feature = "spade")]
+#[test]
+fn spade_interop() {
+    use spade::Triangulation as _;
+
+    let points = load_fixture(include_str!("fixtures/ukraine.json"));
+    let mut unique = points.clone();
+    unique.sort_by(|a, b| (a.x, a.y).partial_cmp(&(b.x, b.y)).unwrap());
+    unique.dedup_by(|a, b| a == b);
+
+    let triangulation = Triangulation::<Index>::new(&unique).unwrap();
+    let spade = triangulation.to_spade(&unique).unwrap();
+    assert_eq!(spade.num_vertices(), unique.len());
+    assert_eq!(spade.num_inner_faces(), triangulation.len());
+
+    let (back, back_points) = Triangulation::<Index>::from_spade(&spade).unwrap();
+    assert!(back_points == unique);
+    validate_triangulation(&back_points, &back);
+    assert!(back.is_delaunay(&back_points));
+    assert_eq!(back.len(), triangulation.len());
+    assert_eq!(back.hull.len(), triangulation.hull.len());
+}
+
+#[cfg(feature = "geo")]
+#[test]
+fn geo_traits() {
+    use delaunator::geo::{TriangulateDelaunay, VoronoiDiagram};
+    use geo_types::{polygon, MultiPoint};
+
+    let points = load_fixture(include_str!("fixtures/ukraine.json"));
+    let multi_point: MultiPoint<f64> = points
+        .iter()
+        .map(|p| geo_types::Point::new(p.x, p.y))
+        .collect();
+
+    let expected = Triangulation::<usize>::new(&points).unwrap();
+    let triangulation = multi_point.delaunay_triangulation().unwrap();
+    assert_eq!(triangulation.triangles, expected.triangles);
+    let triangles = multi_point.delaunay_triangles();
+    assert_eq!(triangles.len(), expected.len());
+    assert_eq!(triangles[0].v1().x, points[expected.triangles[0]].x);
+
+    let (min, max) = (-1e4, 1e4);
+    let bounds = polygon![(x: min, y: min), (x: max, y: min), (x: max, y: max), (x: min, y: max)];
+    let cells = multi_point.voronoi_cells(&bounds);
+    assert_eq!(cells.len(), points.len());
+    let area: f64 = cells
+        .iter()
+        .map(|cell| {
+            let ring = &cell.exterior().0;
+            ring.windows(2)
+                .map(|w| w[0].x * w[1].y - w[1].x * w[0].y)
+                .sum::<f64>()
+                / 2.
+        })
+        .sum();
+    assert!((area - (max - min) * (max - min)).abs() < 1e-3);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn sentinel_serde() {
+    use delaunator::{
+        sentinel::{max_value, minus_one, Sentinel},
+        util::OptionIndex,
+    };
+
+    let points = load_fixture(include_str!("fixtures/ukraine.json"));
+    let triangulation = Triangulation::<u32>::new(&points).unwrap();
+
+    let json = serde_json::to_value(Sentinel(&triangulation)).unwrap();
+    let halfedges = json["halfedges"].as_array().unwrap();
+    assert_eq!(halfedges.len(), triangulation.halfedges.len());
+    assert_eq!(
+        halfedges.iter().filter(|v| v.as_i64() == Some(-1)).count(),
+        triangulation.hull.len()
+    );
+
+    let mut json = Vec::new();
+    minus_one::serialize(
+        &triangulation.halfedges,
+        &mut serde_json::Serializer::new(&mut json),
+    )
+    .unwrap();
+    let back: Vec<OptionIndex<u32>> =
+        minus_one::deserialize(&mut serde_json::Deserializer::from_slice(&json)).unwrap();
+    assert_eq!(back, triangulation.halfedges);
+
+    let mut json = Vec::new();
+    max_value::serialize(
+        &triangulation.halfedges,
+        &mut serde_json::Serializer::new(&mut json),
+    )
+    .unwrap();
+    assert!(String::from_utf8_lossy(&json).contains("4294967295"));
+    let back: Vec<OptionIndex<u32>> =
+        max_value::deserialize(&mut serde_json::Deserializer::from_slice(&json)).unwrap();
+    assert_eq!(back, triangulation.halfedges);
+
+    let invalid = minus_one::deserialize::<u32, _>(&mut serde_json::Deserializer::from_str("[-2]"));
+    assert!(invalid.is_err());
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn batched_queries() {
+    let points = load_fixture(include_str!("fixtures/ukraine.json"));
+    let triangulation = Triangulation::<Index>::new(&points).unwrap();
+
+    let (min, max) = points.iter().fold(
+        (
+            Point::new(f64::INFINITY, f64::INFINITY),
+            Point::new(-f64::INFINITY, -f64::INFINITY),
+        ),
+        |(min, max), p| {
+            (
+                Point::new(min.x.min(p.x), min.y.min(p.y)),
+                Point::new(max.x.max(p.x), max.y.max(p.y)),
+            )
+        },
+    );
+    // raster order over the bounding box, so neighboring queries are close
+    let queries: Vec<Point<f64>> = (0..10_000)
+        .map(|i| {
+            let (u, v) = ((i % 100) as f64 / 99., (i / 100) as f64 / 99.);
+            Point::new(min.x + u * (max.x - min.x), min.y + v * (max.y - min.y))
+        })
+        .collect();
+    let triangles = triangulation.locate_many(&points, &queries);
+    let nearest = triangulation.nearest_many(&points, &queries);
+    assert!(triangles.iter().any(|t| t.is_some()) && triangles.iter().any(|t| t.is_none()));
+    for (k, &q) in queries.iter().enumerate() {
+        let expected = triangulation.locate(&points, q);
+        assert_eq!(triangles[k].is_some(), expected.is_some());
+        if let Some(t) = &triangles[k] {
+            let corners = t.vertices().map(|v| points[v.id()]).collect::<Vec<_>>();
+            assert!((0..3).all(|j| !corners[j].is_clockwise(corners[(j + 1) % 3], q)));
+        }
+        let best = triangulation.nearest(&points, q).unwrap();
+        assert_eq!(
+            points[nearest[k].unwrap()].distance_squared(q),
+            points[best].distance_squared(q)
+        );
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+#[test]
+fn fuzz_entry_point() {
+    use arbitrary::{Arbitrary, Unstructured};
+    use delaunator::fuzz::{self, Violation};
+
+    // pseudo-random inputs, including NaNs, infinities and repeated bytes
+    let mut state = 0x9e37_79b9_7f4a_7c15_u64;
+    for len in (0..4096).step_by(97) {
+        let data: Vec<u8> = (0..len)
+            .map(|i| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                if i % 7 < 3 {
+                    (i % 5) as u8
+                } else {
+                    state as u8
+                }
+            })
+            .collect();
+        fuzz::triangulate(&data);
+    }
+
+    let data = [1u8; 16];
+    let p = Point::<f64>::arbitrary(&mut Unstructured::new(&data)).unwrap();
+    assert_eq!(p, Point::new(f64::from_bits(0x0101_0101_0101_0101), p.x));
+
+    let points = load_fixture(include_str!("fixtures/ukraine.json"));
+    let mut triangulation = Triangulation::<Index>::new(&points).unwrap();
+    assert_eq!(fuzz::validate(&triangulation, points.len()), Ok(()));
+    assert_eq!(
+        fuzz::validate(&triangulation, 10),
+        Err(Violation::IndexOutOfBounds {
+            halfedge: (0..triangulation.triangles.len())
+                .find(|&e| triangulation.triangles[e] >= 10)
+                .unwrap()
+        })
+    );
+    let e = (0..triangulation.halfedges.len())
+        .find(|&e| triangulation.halfedges[e].is_some())
+        .unwrap();
+    let twin = triangulation.halfedges[e];
+    triangulation.halfedges[e] = triangulation.halfedges[e + 1];
+    assert!(fuzz::validate(&triangulation, points.len()).is_err());
+    triangulation.halfedges[e] = twin;
+    triangulation.hull.pop();
+    assert_eq!(
+        fuzz::validate(&triangulation, points.len()),
+        Err(Violation::BrokenHull)
+    );
+}
+
+#[cfg(feature = "proptest")]
+#[test]
+fn proptest_strategies() {
+    use delaunator::strategy;
+    use proptest::{strategy::Strategy, test_runner::TestRunner};
+
+    fn check(strategy: impl Strategy<Value = Vec<Point<f64>>>) {
+        let mut runner = TestRunner::deterministic();
+        runner
+            .run(&strategy, |points| {
+                // the area check of validate_triangulation is relative to the
+                // hull area, which is too strict for slivers
+                if let Some(t) = Triangulation::<Index>::new(&points) {
+                    for (e, h) in t.halfedges.iter().enumerate() {
+                        if let Some(h) = h.get() {
+                            assert_eq!(t.halfedges[h as usize], (e as Index).into());
+                        }
+                    }
+                    let extent = points
+                        .iter()
+                        .fold(0f64, |m, p| m.max(p.x.abs()).max(p.y.abs()));
+                    let (area, hull_area) = (t.area(&points), t.hull_area(&points));
+                    assert!((area - hull_area).abs() <= 1e-12 * extent * extent);
+                }
+                Ok(())
+            })
+            .unwrap();
+    }
+    check(strategy::grid(8));
+    check(strategy::cocircular(40));
+    check(strategy::near_duplicates(20));
+    check(strategy::clusters(3, 60));
+    check(strategy::collinear(3, 10));
+    check(strategy::adversarial(60));
+
+    let mut runner = TestRunner::deterministic();
+    runner
+        .run(&strategy::grid(5), |points| {
+            let mut xs: Vec<f64> = points.iter().map(|p| p.x).collect();
+            xs.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            xs.dedup();
+            assert_eq!(points.len() % xs.len(), 0);
+            Ok(())
+        })
+        .unwrap();
+    runner
+        .run(&strategy::collinear(1, 10), |points| {
+            assert!(points.len() >= 2);
+            Ok(())
+        })
+        .unwrap();
+}
+
+#[cfg(feature = "mmap")]
+#[test]
+fn mapped_tri_file() {
+    use delaunator::tri::{self, MappedTriangulation};
+
+    let points = load_fixture(include_str!("fixtures/ukraine.json"));
+    let triangulation = Triangulation::<u32>::new(&points).unwrap();
+    let path = std::env::temp_dir().join(format!("delaunator-{}.tri", std::process::id()));
+    tri::save(&path, &triangulation, Some(&points), None).unwrap();
+
+    let mapped = unsafe { MappedTriangulation::<u32>::open(&path) }.unwrap();
+    let view = mapped.view();
+    assert_eq!(view.triangles, &triangulation.triangles[..]);
+    assert_eq!(view.halfedges, &triangulation.halfedges[..]);
+    assert_eq!(view.hull, &triangulation.hull[..]);
+    let coords = mapped.coords().unwrap();
+    assert_eq!(coords.len(), 2 * points.len());
+    assert_eq!((coords[2], coords[3]), (points[1].x, points[1].y));
+    assert!(mapped.values().is_none());
+    assert!(matches!(
+        unsafe { MappedTriangulation::<u16>::open(&path) },
+        Err(tri::TriError::IndexWidth(4))
+    ));
+    drop(mapped);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn voronoi_svg() {
+    use delaunator::{
+        svg::{CellStyle, VoronoiSvg},
+        voronoi::Voronoi,
+    };
+
+    let points = load_fixture(include_str!("fixtures/ukraine.json"));
+    let triangulation = Triangulation::<Index>::new(&points).unwrap();
+    let voronoi = Voronoi::new(&triangulation, &points);
+    let (mut min, mut max) = (points[0], points[0]);
+    for p in &points {
+        min = Point::new(min.x.min(p.x), min.y.min(p.y));
+        max = Point::new(max.x.max(p.x), max.y.max(p.y));
+    }
+    let cells = voronoi
+        .clip_to_rect(min, max)
+        .iter()
+        .filter(|cell| cell.len() >= 3)
+        .count();
+
+    let plain = VoronoiSvg::new().render(&voronoi, min, max);
+    assert!(plain.starts_with("<svg") && plain.ends_with("</svg>\n"));
+    assert_eq!(plain.matches("<path").count(), cells);
+    assert_eq!(plain.matches("<circle").count(), 0);
+
+    let styled = VoronoiSvg::new()
+        .width(200.)
+        .delaunay("gray")
+        .sites(1.5)
+        .style(|i| CellStyle {
+            fill: if i % 2 == 0 { "\"even\"" } else { "odd" }.to_string(),
+            stroke_width: 0.5,
+            ..CellStyle::default()
+        })
+        .render(&voronoi, min, max);
+    assert!(styled.contains("width=\"200\""));
+    assert_eq!(styled.matches("<path").count(), cells + 1);
+    assert!(styled.contains("fill=\"&quot;even&quot;\""));
+    assert!(styled.contains("stroke=\"gray\""));
+    let sites = voronoi
+        .cells()
+        .filter(|cell| cell.vertex_ids().next().is_some())
+        .count();
+    assert_eq!(styled.matches("<circle").count(), sites);
+}